@@ -0,0 +1,86 @@
+//! Screen-reader friendly response shapes
+//!
+//! When the accessibility setting is on, commands attach these alongside their
+//! usual data: transcripts as plain sequential text with speaker labels, and
+//! chart data as labelled tables, so the frontend needs no extra calls.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataTable {
+    pub title: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+fn speaker(message: &crate::Message) -> &'static str {
+    if message.is_meta {
+        "Command"
+    } else if message.is_tool && message.content.is_empty() {
+        "Tool"
+    } else if message.role == "user" {
+        "User"
+    } else {
+        "Assistant"
+    }
+}
+
+/// Messages as "Speaker (time): text" paragraphs in order
+pub fn transcript(messages: &[crate::Message]) -> String {
+    let mut out = String::new();
+    for message in messages {
+        let time = chrono::DateTime::parse_from_rfc3339(&message.timestamp)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let text = if message.content.is_empty() {
+            let names: Vec<&str> = message.tools.iter().map(|t| t.name.as_str()).collect();
+            format!("Used {}", names.join(", "))
+        } else {
+            message.content.trim().to_string()
+        };
+
+        if time.is_empty() {
+            out.push_str(&format!("{}:\n{}\n\n", speaker(message), text));
+        } else {
+            out.push_str(&format!("{} ({}):\n{}\n\n", speaker(message), time, text));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// Daily and hourly activity as tables, newest day first
+pub fn activity_tables(
+    daily: &HashMap<String, usize>,
+    hourly: &HashMap<u32, usize>,
+) -> Vec<DataTable> {
+    let mut days: Vec<(&String, &usize)> = daily.iter().collect();
+    days.sort_by(|a, b| b.0.cmp(a.0));
+
+    vec![
+        DataTable {
+            title: "Messages per day".to_string(),
+            columns: vec!["Date".to_string(), "Messages".to_string()],
+            rows: days
+                .into_iter()
+                .map(|(date, count)| vec![date.clone(), count.to_string()])
+                .collect(),
+        },
+        DataTable {
+            title: "Messages by hour of day".to_string(),
+            columns: vec!["Hour".to_string(), "Messages".to_string()],
+            rows: (0..24)
+                .map(|hour| {
+                    vec![
+                        format!("{:02}:00", hour),
+                        hourly.get(&hour).copied().unwrap_or(0).to_string(),
+                    ]
+                })
+                .collect(),
+        },
+    ]
+}
@@ -0,0 +1,423 @@
+//! Creating, editing, renaming and deleting agent files
+//!
+//! Agents live in ~/.claude/agents (global) or <project>/.claude/agents, one
+//! <name>.md per agent whose frontmatter `name` matches the file name. New
+//! agents get validated name, description, tools, model and color fields;
+//! edits rewrite only the changed fields through `frontmatter` and snapshot
+//! the previous content like commands do (see `command_versions`).
+//!
+//! Deprecating an agent moves it (and its .changelog) to .agents/archived
+//! next to the agents dir, recording `replaced-by` and `deprecation-note`,
+//! which restoring clears again. Changelogs use the command format.
+//!
+//! Global agents also include commands with a `model` field, an older way of
+//! defining them. `list_by_scope` labels each agent with where it comes from:
+//! global, or one of the workspace projects.
+//!
+//! Deleted agents go to ~/.lovstudio/lovcode/agent-trash/, which works like
+//! the command trash and expires on the same schedule.
+
+use crate::command_files::{CommandScope, DeletedCommand};
+use crate::LocalAgent;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Models an agent can name besides a full model id
+pub const MODEL_ALIASES: &[&str] = &["sonnet", "opus", "haiku", "inherit"];
+pub const COLORS: &[&str] = &[
+    "red", "blue", "green", "yellow", "purple", "orange", "pink", "cyan",
+];
+
+pub type DeletedAgent = DeletedCommand;
+
+/// A new agent; optional fields are left out of the frontmatter when None or empty
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgentDraft {
+    pub name: String,
+    pub description: String,
+    pub tools: Option<String>,
+    pub model: Option<String>,
+    pub color: Option<String>,
+    pub body: String,
+}
+
+/// Changes to an agent; None leaves a field as is, an empty string removes it
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AgentUpdate {
+    pub description: Option<String>,
+    pub tools: Option<String>,
+    pub model: Option<String>,
+    pub color: Option<String>,
+    pub body: Option<String>,
+}
+
+pub fn agents_dir(scope: &CommandScope) -> Result<PathBuf, String> {
+    Ok(scope.claude_dir()?.join("agents"))
+}
+
+/// Where deprecated agents go, outside the dir Claude Code loads
+pub fn archived_dir(scope: &CommandScope) -> Result<PathBuf, String> {
+    Ok(scope.claude_dir()?.join(".agents").join("archived"))
+}
+
+fn get_trash_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("agent-trash")
+}
+
+/// Agents of one scope, labelled for display
+#[derive(Debug, Serialize)]
+pub struct ScopedAgents {
+    pub scope: CommandScope,
+    /// "Global", or the workspace project's name
+    pub label: String,
+    pub agents: Vec<LocalAgent>,
+}
+
+/// Active and deprecated agents of a scope, sorted by name
+pub fn list(scope: &CommandScope) -> Result<Vec<LocalAgent>, String> {
+    let mut agents = Vec::new();
+    for dir in [agents_dir(scope)?, archived_dir(scope)?] {
+        if dir.is_dir() {
+            crate::collect_agents(&dir, &dir, &mut agents, false)?;
+        }
+    }
+    let commands_dir = crate::get_claude_dir().join("commands");
+    if matches!(scope, CommandScope::Global) && commands_dir.is_dir() {
+        crate::collect_agents(&commands_dir, &commands_dir, &mut agents, true)?;
+    }
+    agents.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(agents)
+}
+
+/// Global agents, then those of each (non-archived) workspace project that has any
+pub fn list_by_scope() -> Result<Vec<ScopedAgents>, String> {
+    let mut groups = vec![ScopedAgents {
+        label: "Global".to_string(),
+        agents: list(&CommandScope::Global)?,
+        scope: CommandScope::Global,
+    }];
+
+    let workspace = crate::workspace_store::load_workspace()?;
+    let mut seen = std::collections::HashSet::new();
+    for project in workspace.projects {
+        if project.archived == Some(true) || !seen.insert(project.path.clone()) {
+            continue;
+        }
+        let scope = CommandScope::Project {
+            project_path: project.path,
+        };
+        // Projects whose directory is gone have nothing to list
+        let Ok(agents) = list(&scope) else {
+            continue;
+        };
+        if !agents.is_empty() {
+            groups.push(ScopedAgents {
+                scope,
+                label: project.name,
+                agents,
+            });
+        }
+    }
+    Ok(groups)
+}
+
+/// Lowercase letters, digits and hyphens, as Claude Code expects
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(format!(
+            "Invalid agent name '{}': use lowercase letters, digits and hyphens",
+            name
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Check the optional fields shared by drafts and updates; empty values pass (they remove the field)
+fn validate_fields(
+    description: Option<&str>,
+    tools: Option<&str>,
+    model: Option<&str>,
+    color: Option<&str>,
+) -> Result<(), String> {
+    if description.is_some_and(|d| d.trim().is_empty()) {
+        return Err("An agent needs a description".to_string());
+    }
+    if let Some(tools) = tools.map(str::trim).filter(|t| !t.is_empty()) {
+        let entries =
+            crate::command_lint::split_tools(tools).ok_or("tools has unbalanced parentheses")?;
+        if let Some(bad) = entries.iter().find(|t| !crate::command_lint::is_tool(t)) {
+            return Err(format!("'{}' is not a tool name or Tool(pattern)", bad));
+        }
+    }
+    if let Some(model) = model.map(str::trim).filter(|m| !m.is_empty()) {
+        if !MODEL_ALIASES.contains(&model) && !model.starts_with("claude-") {
+            return Err(format!(
+                "Unknown model '{}': use {} or a claude-* model id",
+                model,
+                MODEL_ALIASES.join(", ")
+            ));
+        }
+    }
+    if let Some(color) = color.map(str::trim).filter(|c| !c.is_empty()) {
+        if !COLORS.contains(&color) {
+            return Err(format!(
+                "Unknown color '{}': use one of {}",
+                color,
+                COLORS.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The agents dir (or deprecated archive) an agent file is in
+fn agents_base(path: &Path) -> Option<PathBuf> {
+    let dir = path.parent()?;
+    let archived = Path::new(".agents").join("archived");
+    let in_claude_dir = |tail: &Path| {
+        dir.ends_with(tail)
+            && dir
+                .ancestors()
+                .nth(tail.components().count())
+                .is_some_and(|d| d.ends_with(".claude"))
+    };
+    let is_agent = path.extension().is_some_and(|e| e == "md")
+        && (in_claude_dir(Path::new("agents")) || in_claude_dir(&archived));
+    is_agent.then(|| dir.to_path_buf())
+}
+
+/// The scope whose agents dir or archive holds `base`
+fn scope_of_base(base: &Path) -> Option<CommandScope> {
+    let claude_dir = base.ancestors().find(|d| d.ends_with(".claude"))?;
+    if claude_dir == crate::get_claude_dir() {
+        return Some(CommandScope::Global);
+    }
+    Some(CommandScope::Project {
+        project_path: claude_dir.parent()?.to_string_lossy().to_string(),
+    })
+}
+
+/// Move an agent file and its .changelog from `from` into `to`
+fn move_with_changelog(file: &Path, from: &Path, to: &Path) -> Result<PathBuf, String> {
+    let relative = file.strip_prefix(from).map_err(|e| e.to_string())?;
+    let dest = to.join(relative);
+    if dest.exists() {
+        return Err(format!("{} already exists", dest.display()));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(file, &dest).map_err(|e| e.to_string())?;
+    let changelog = file.with_extension("changelog");
+    if changelog.exists() {
+        let _ = fs::rename(&changelog, dest.with_extension("changelog"));
+    }
+    Ok(dest)
+}
+
+/// Retire an agent: record its replacement and/or a note, then move it to the archive
+pub fn deprecate(
+    path: &str,
+    replaced_by: Option<&str>,
+    note: Option<&str>,
+) -> Result<LocalAgent, String> {
+    let (file, base) = agent_file(path)?;
+    let scope = scope_of_base(&base).ok_or_else(|| format!("Not an agent file: {}", path))?;
+    let agents_dir = agents_dir(&scope)?;
+    let archived_dir = archived_dir(&scope)?;
+    if base == archived_dir {
+        return Err("Agent is already deprecated".to_string());
+    }
+
+    let content = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+    let mut updated = content.clone();
+    for (key, value) in [("replaced-by", replaced_by), ("deprecation-note", note)] {
+        if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+            updated = crate::frontmatter::set_field(&updated, key, Some(value))?;
+        }
+    }
+    let dest = move_with_changelog(&file, &agents_dir, &archived_dir)?;
+    if updated != content {
+        fs::write(&dest, updated).map_err(|e| e.to_string())?;
+    }
+    Ok(crate::read_agent_file(&archived_dir, &dest))
+}
+
+/// Bring a deprecated agent back into the agents dir
+pub fn restore(path: &str) -> Result<LocalAgent, String> {
+    let (file, base) = agent_file(path)?;
+    let scope = scope_of_base(&base).ok_or_else(|| format!("Not an agent file: {}", path))?;
+    let agents_dir = agents_dir(&scope)?;
+    let archived_dir = archived_dir(&scope)?;
+    if base != archived_dir {
+        return Err("Agent is not deprecated".to_string());
+    }
+
+    let dest = move_with_changelog(&file, &archived_dir, &agents_dir)?;
+    let content = fs::read_to_string(&dest).map_err(|e| e.to_string())?;
+    let mut updated = content.clone();
+    for key in ["replaced-by", "deprecation-note"] {
+        updated = crate::frontmatter::set_field(&updated, key, None)?;
+    }
+    if updated != content {
+        fs::write(&dest, updated).map_err(|e| e.to_string())?;
+    }
+    Ok(crate::read_agent_file(&agents_dir, &dest))
+}
+
+/// Add a dated entry to an agent's .changelog, optionally setting its frontmatter version
+pub fn append_changelog(
+    path: &str,
+    version: &str,
+    note: &str,
+    bump_version: bool,
+) -> Result<LocalAgent, String> {
+    let (file, base) = agent_file(path)?;
+    let version = crate::command_files::write_changelog_entry(&file, version, note)?;
+
+    if bump_version {
+        let content = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+        let updated = crate::frontmatter::set_field(&content, "version", Some(version))?;
+        if updated != content {
+            crate::command_versions::snapshot(&file)?;
+            fs::write(&file, updated).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(crate::read_agent_file(&base, &file))
+}
+
+fn agent_file(path: &str) -> Result<(PathBuf, PathBuf), String> {
+    let file = PathBuf::from(path);
+    if !file.is_file() {
+        return Err(format!("Agent file not found: {}", path));
+    }
+    let base = agents_base(&file).ok_or_else(|| format!("Not an agent file: {}", path))?;
+    Ok((file, base))
+}
+
+/// Write a new agent file from `draft`
+pub fn create(draft: &AgentDraft, scope: &CommandScope) -> Result<LocalAgent, String> {
+    let name = validate_name(&draft.name)?;
+    validate_fields(
+        Some(&draft.description),
+        draft.tools.as_deref(),
+        draft.model.as_deref(),
+        draft.color.as_deref(),
+    )?;
+
+    let dir = agents_dir(scope)?;
+    let path = dir.join(format!("{}.md", name));
+    if path.exists() || list(scope)?.iter().any(|a| a.name == name) {
+        return Err(format!("An agent named '{}' already exists", name));
+    }
+
+    let mut content = format!("{}\n", draft.body.trim());
+    for (key, value) in [
+        ("name", Some(name.as_str())),
+        ("description", Some(draft.description.trim())),
+        ("tools", draft.tools.as_deref().map(str::trim)),
+        ("model", draft.model.as_deref().map(str::trim)),
+        ("color", draft.color.as_deref().map(str::trim)),
+    ] {
+        content = crate::frontmatter::set_field(&content, key, value)?;
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(crate::read_agent_file(&dir, &path))
+}
+
+/// Apply `update` to an agent file, leaving the rest of its frontmatter untouched
+pub fn update(path: &str, update: &AgentUpdate) -> Result<LocalAgent, String> {
+    let (file, base) = agent_file(path)?;
+    validate_fields(
+        update.description.as_deref(),
+        update.tools.as_deref(),
+        update.model.as_deref(),
+        update.color.as_deref(),
+    )?;
+
+    let original = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+    let mut content = original.clone();
+    for (key, value) in [
+        ("description", &update.description),
+        ("tools", &update.tools),
+        ("model", &update.model),
+        ("color", &update.color),
+    ] {
+        if let Some(value) = value {
+            content = crate::frontmatter::set_field(&content, key, Some(value.trim()))?;
+        }
+    }
+    if let Some(body) = &update.body {
+        content = crate::frontmatter::set_body(&content, body);
+    }
+    if content != original {
+        crate::command_versions::snapshot(&file)?;
+        fs::write(&file, &content).map_err(|e| e.to_string())?;
+    }
+
+    Ok(crate::read_agent_file(&base, &file))
+}
+
+/// Rename an agent: its file and the `name` field move together
+pub fn rename(path: &str, new_name: &str) -> Result<LocalAgent, String> {
+    let (file, base) = agent_file(path)?;
+    let new_name = validate_name(new_name)?;
+    let dest = base.join(format!("{}.md", new_name));
+    if dest != file && dest.exists() {
+        return Err(format!("An agent named '{}' already exists", new_name));
+    }
+
+    let content = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+    let updated = crate::frontmatter::set_field(&content, "name", Some(&new_name))?;
+    if updated != content {
+        fs::write(&file, &updated).map_err(|e| e.to_string())?;
+    }
+    if dest != file {
+        fs::rename(&file, &dest).map_err(|e| e.to_string())?;
+        let changelog = file.with_extension("changelog");
+        if changelog.exists() {
+            let _ = fs::rename(&changelog, dest.with_extension("changelog"));
+        }
+    }
+    Ok(crate::read_agent_file(&base, &dest))
+}
+
+/// Move an agent file and its .changelog into a new trash entry
+pub fn delete(path: &str) -> Result<DeletedAgent, String> {
+    let (file, base) = agent_file(path)?;
+    let name = crate::read_agent_file(&base, &file).name;
+    let changelog = file.with_extension("changelog");
+    crate::command_files::move_to_trash(&get_trash_dir(), name, &file, &[&file, &changelog])
+}
+
+/// Deleted agents, most recently deleted first
+pub fn list_deleted() -> Vec<DeletedAgent> {
+    crate::command_files::list_trash(&get_trash_dir())
+}
+
+/// Move a deleted agent back to where it was
+pub fn restore_deleted(id: &str) -> Result<DeletedAgent, String> {
+    crate::command_files::restore_from_trash(&get_trash_dir(), id)
+}
+
+/// Permanently delete a trashed agent
+pub fn purge_deleted(id: &str) -> Result<(), String> {
+    crate::command_files::purge_from_trash(&get_trash_dir(), id)
+}
+
+/// Permanently delete trashed agents past their expiry, returns how many were removed
+pub fn purge_expired() -> usize {
+    crate::command_files::purge_expired_in(&get_trash_dir())
+}
@@ -0,0 +1,258 @@
+//! Checks for agent files
+//!
+//! Uses the command linter's frontmatter parsing and diagnostics. Claude Code
+//! skips an agent without a name or description, so those are errors, as is
+//! a model it can't resolve; tools it doesn't know are warnings (MCP tools
+//! are taken on trust). Hints cover descriptions too vague for Claude to
+//! decide when to delegate.
+
+use crate::command_lint::{self, CommandValidation, Lint, Severity};
+use std::fs;
+use std::path::Path;
+
+/// Frontmatter keys read by Claude Code for agents
+const KNOWN_KEYS: &[&str] = &[
+    "name",
+    "description",
+    "tools",
+    "model",
+    "color",
+    "permissionMode",
+    "skills",
+    // Maintained by lovcode
+    "version",
+    "replaced-by",
+    "deprecation-note",
+];
+
+/// Built-in tools an agent can be given
+const KNOWN_TOOLS: &[&str] = &[
+    "Agent",
+    "Bash",
+    "BashOutput",
+    "Edit",
+    "ExitPlanMode",
+    "Glob",
+    "Grep",
+    "KillShell",
+    "LS",
+    "MultiEdit",
+    "NotebookEdit",
+    "NotebookRead",
+    "Read",
+    "SlashCommand",
+    "Skill",
+    "Task",
+    "TodoWrite",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// Shorter descriptions rarely say enough to pick the agent
+const MIN_DESCRIPTION_CHARS: usize = 40;
+const MAX_DESCRIPTION_CHARS: usize = 1024;
+
+fn check_tools(lint: &mut Lint, value: &serde_yaml_ng::Value, line: Option<usize>) {
+    let entries = match value {
+        serde_yaml_ng::Value::String(s) => command_lint::split_tools(s),
+        serde_yaml_ng::Value::Sequence(items) => Some(
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.trim().to_string()))
+                .collect(),
+        ),
+        _ => None,
+    };
+    let Some(entries) = entries else {
+        lint.push(
+            Severity::Error,
+            "invalid_tools",
+            line,
+            "tools must be a comma-separated string or a list of tool names".to_string(),
+        );
+        return;
+    };
+    for tool in entries {
+        let base = tool.split('(').next().unwrap_or(&tool);
+        if !command_lint::is_tool(&tool) {
+            lint.push(
+                Severity::Error,
+                "invalid_tools",
+                line,
+                format!("'{}' is not a tool name or Tool(pattern)", tool),
+            );
+        } else if !base.starts_with("mcp__") && !KNOWN_TOOLS.contains(&base) {
+            lint.push(
+                Severity::Warning,
+                "unknown_tool",
+                line,
+                format!("'{}' is not a Claude Code tool", base),
+            );
+        }
+    }
+}
+
+fn check_description(lint: &mut Lint, description: &str, line: Option<usize>) {
+    let length = description.chars().count();
+    if length < MIN_DESCRIPTION_CHARS {
+        lint.push(
+            Severity::Hint,
+            "short_description",
+            line,
+            "Describe what the agent does and when to use it; Claude picks agents by this text"
+                .to_string(),
+        );
+    } else if length > MAX_DESCRIPTION_CHARS {
+        lint.push(
+            Severity::Warning,
+            "long_description",
+            line,
+            format!(
+                "Description is {} characters; keep it under {}",
+                length, MAX_DESCRIPTION_CHARS
+            ),
+        );
+    }
+    let lower = description.to_lowercase();
+    let says_when = ["use ", "when", "proactively", "after ", "before "]
+        .iter()
+        .any(|cue| lower.contains(cue));
+    if length >= MIN_DESCRIPTION_CHARS && !says_when {
+        lint.push(
+            Severity::Hint,
+            "description_without_trigger",
+            line,
+            "Say when to use the agent (e.g. \"Use proactively after code changes\")".to_string(),
+        );
+    }
+}
+
+/// Validate the agent file at `path`
+pub fn validate(path: &Path) -> Result<CommandValidation, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut lint = Lint { issues: Vec::new() };
+    let lines: Vec<&str> = content.lines().collect();
+    let frontmatter = command_lint::read_frontmatter(&mut lint, &lines);
+
+    let has_fence = lines.first().is_some_and(|l| l.trim_end() == "---");
+    if !has_fence {
+        lint.push(
+            Severity::Error,
+            "missing_frontmatter",
+            Some(1),
+            "Agents need frontmatter with a name and description".to_string(),
+        );
+    }
+
+    if let Some(map) = &frontmatter.yaml {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let line_of = |key: &str| command_lint::key_line(&frontmatter.text, key);
+        for (key, value) in map {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            let line = line_of(key);
+            if !KNOWN_KEYS.contains(&key) {
+                lint.push(
+                    Severity::Warning,
+                    "unknown_key",
+                    line,
+                    format!("Unknown frontmatter key '{}'", key),
+                );
+            }
+            match key {
+                "name" => {
+                    let name = value.as_str().map(str::trim).unwrap_or("");
+                    let valid = !name.is_empty()
+                        && name
+                            .chars()
+                            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+                    if !valid {
+                        lint.push(
+                            Severity::Error,
+                            "invalid_name",
+                            line,
+                            "name must be lowercase letters, digits and hyphens".to_string(),
+                        );
+                    } else if name != stem {
+                        lint.push(
+                            Severity::Warning,
+                            "name_mismatch",
+                            line,
+                            format!("name '{}' differs from the file name '{}'", name, stem),
+                        );
+                    }
+                }
+                "description" => match value.as_str() {
+                    Some(description) => check_description(&mut lint, description.trim(), line),
+                    None => lint.push(
+                        Severity::Error,
+                        "invalid_value",
+                        line,
+                        "description should be a single string".to_string(),
+                    ),
+                },
+                "tools" => check_tools(&mut lint, value, line),
+                "model" => {
+                    let model = value.as_str().map(str::trim).unwrap_or("");
+                    let known = crate::agent_files::MODEL_ALIASES.contains(&model)
+                        || model.starts_with("claude-");
+                    if !known {
+                        lint.push(
+                            Severity::Error,
+                            "invalid_model",
+                            line,
+                            format!(
+                                "model must be {} or a claude-* model id",
+                                crate::agent_files::MODEL_ALIASES.join(", ")
+                            ),
+                        );
+                    }
+                }
+                "color"
+                    if !value
+                        .as_str()
+                        .is_some_and(|c| crate::agent_files::COLORS.contains(&c.trim())) =>
+                {
+                    lint.push(
+                        Severity::Warning,
+                        "invalid_value",
+                        line,
+                        format!(
+                            "color should be one of {}",
+                            crate::agent_files::COLORS.join(", ")
+                        ),
+                    )
+                }
+                _ => {}
+            }
+        }
+
+        for required in ["name", "description"] {
+            if !map.contains_key(required) {
+                lint.push(
+                    Severity::Error,
+                    "missing_field",
+                    Some(1),
+                    format!("Agents need a '{}' field", required),
+                );
+            }
+        }
+    }
+
+    let body_empty = lines
+        .iter()
+        .skip(frontmatter.body_start)
+        .all(|l| l.trim().is_empty());
+    if has_fence && body_empty {
+        lint.push(
+            Severity::Warning,
+            "empty_prompt",
+            None,
+            "The agent has no system prompt below the frontmatter".to_string(),
+        );
+    }
+
+    Ok(lint.finish(path))
+}
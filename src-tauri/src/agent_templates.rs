@@ -0,0 +1,188 @@
+//! Marketplace agents installed into an agents dir
+//!
+//! Each install is recorded in ~/.lovstudio/lovcode/agent-installs.json by
+//! template name and scope, so installing the same template again updates the
+//! file it wrote (snapshotting the old content) and uninstalling finds it.
+//! When another agent already has the template's name, the new one gets the
+//! first free `<name>-<n>`, and its frontmatter `name` follows the file name.
+
+use crate::command_files::CommandScope;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentInstall {
+    /// Marketplace template name
+    pub template: String,
+    /// Agent name it was installed as
+    pub name: String,
+    pub path: String,
+    pub scope: CommandScope,
+    /// Hash of the content as written, to tell whether it was edited since
+    pub content_hash: u64,
+    pub installed_at: u64,
+    pub updated_at: u64,
+    /// The file no longer matches what was installed
+    #[serde(skip_deserializing)]
+    pub modified: bool,
+}
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("agent-installs.json")
+}
+
+/// Install records; a missing file is empty, an unreadable one an error
+fn load() -> Result<Vec<AgentInstall>, String> {
+    let path = get_store_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read agent installs: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse agent installs: {}", e))
+}
+
+fn save(installs: &[AgentInstall]) -> Result<(), String> {
+    let path = get_store_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(installs).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn same_scope(a: &CommandScope, b: &CommandScope) -> bool {
+    match (a, b) {
+        (CommandScope::Global, CommandScope::Global) => true,
+        (CommandScope::Project { project_path: a }, CommandScope::Project { project_path: b }) => {
+            Path::new(a) == Path::new(b)
+        }
+        _ => false,
+    }
+}
+
+/// Template names as agent names: lowercase, anything else becomes a hyphen
+fn agent_name(template: &str) -> String {
+    let mut name = String::new();
+    for c in template.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_lowercase());
+        } else if !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    name.trim_matches('-').to_string()
+}
+
+/// Install `content` as an agent, or update the agent a previous install of `template` wrote
+pub fn install(
+    template: &str,
+    content: &str,
+    scope: &CommandScope,
+) -> Result<AgentInstall, String> {
+    let base_name = agent_name(template);
+    if base_name.is_empty() {
+        return Err(format!("Invalid template name: {}", template));
+    }
+    let dir = crate::agent_files::agents_dir(scope)?;
+
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut installs = load()?;
+    let previous = installs
+        .iter()
+        .position(|i| i.template == template && same_scope(&i.scope, scope))
+        .filter(|&i| Path::new(&installs[i].path).is_file());
+
+    let name = match previous {
+        Some(index) => installs[index].name.clone(),
+        None => {
+            let taken: Vec<String> = crate::agent_files::list(scope)?
+                .into_iter()
+                .map(|a| a.name)
+                .collect();
+            let mut name = base_name.clone();
+            let mut n = 2;
+            while taken.contains(&name) || dir.join(format!("{}.md", name)).exists() {
+                name = format!("{}-{}", base_name, n);
+                n += 1;
+            }
+            name
+        }
+    };
+
+    let content = crate::frontmatter::set_field(content, "name", Some(&name))?;
+    let path = dir.join(format!("{}.md", name));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    if previous.is_some() {
+        crate::command_versions::snapshot(&path)?;
+    }
+    fs::write(&path, &content).map_err(|e| e.to_string())?;
+
+    let now = now_secs();
+    let install = AgentInstall {
+        template: template.to_string(),
+        name,
+        path: path.to_string_lossy().to_string(),
+        scope: scope.clone(),
+        content_hash: crate::attachments::fnv1a(&content),
+        installed_at: previous.map(|i| installs[i].installed_at).unwrap_or(now),
+        updated_at: now,
+        modified: false,
+    };
+    installs.retain(|i| !(i.template == template && same_scope(&i.scope, scope)));
+    installs.push(install.clone());
+    save(&installs)?;
+    Ok(install)
+}
+
+/// Recorded installs whose file still exists
+pub fn list() -> Result<Vec<AgentInstall>, String> {
+    Ok(load()?
+        .into_iter()
+        .filter_map(|mut install| {
+            let content = fs::read_to_string(&install.path).ok()?;
+            install.modified = crate::attachments::fnv1a(&content) != install.content_hash;
+            Some(install)
+        })
+        .collect())
+}
+
+/// Move the agent installed from `template` to the agent trash and forget the
+/// install; None when its file was already gone
+pub fn uninstall(
+    template: &str,
+    scope: &CommandScope,
+) -> Result<Option<crate::agent_files::DeletedAgent>, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut installs = load()?;
+    let install = installs
+        .iter()
+        .find(|i| i.template == template && same_scope(&i.scope, scope))
+        .cloned()
+        .ok_or_else(|| format!("Agent template '{}' is not installed", template))?;
+
+    let deleted = if Path::new(&install.path).exists() {
+        Some(crate::agent_files::delete(&install.path)?)
+    } else {
+        None
+    };
+    installs.retain(|i| !(i.template == template && same_scope(&i.scope, scope)));
+    save(&installs)?;
+    Ok(deleted)
+}
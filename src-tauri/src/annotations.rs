@@ -0,0 +1,193 @@
+//! Per-message review comments
+//!
+//! Annotations live in sidecar files at
+//! ~/.lovstudio/lovcode/annotations/<project_id>/<session_id>.json so the
+//! session `.jsonl` written by Claude Code is never touched. A sidecar is
+//! removed once its last annotation is deleted.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on sidecar files
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: String,
+    /// uuid of the annotated message
+    pub message_uuid: String,
+    pub text: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+fn get_annotations_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("annotations")
+}
+
+fn sidecar_path(project_id: &str, session_id: &str) -> PathBuf {
+    get_annotations_dir()
+        .join(project_id)
+        .join(format!("{}.json", session_id))
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A session's annotations; a missing sidecar is empty, an unreadable one an error
+fn load(project_id: &str, session_id: &str) -> Result<Vec<Annotation>, String> {
+    let path = sidecar_path(project_id, session_id);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read annotations: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse annotations: {}", e))
+}
+
+fn save(project_id: &str, session_id: &str, annotations: &[Annotation]) -> Result<(), String> {
+    let path = sidecar_path(project_id, session_id);
+    if annotations.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(annotations).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn clean_text(text: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Annotation text is empty".to_string());
+    }
+    Ok(text.to_string())
+}
+
+/// Annotations of a session, oldest first, optionally only those on one message
+pub fn list(
+    project_id: &str,
+    session_id: &str,
+    message_uuid: Option<&str>,
+) -> Result<Vec<Annotation>, String> {
+    let mut annotations = load(project_id, session_id)?;
+    if let Some(uuid) = message_uuid {
+        annotations.retain(|a| a.message_uuid == uuid);
+    }
+    annotations.sort_by_key(|a| a.created_at);
+    Ok(annotations)
+}
+
+pub fn add(
+    project_id: &str,
+    session_id: &str,
+    message_uuid: &str,
+    text: &str,
+) -> Result<Annotation, String> {
+    let text = clean_text(text)?;
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut annotations = load(project_id, session_id)?;
+    let timestamp = now();
+    let annotation = Annotation {
+        id: uuid::Uuid::new_v4().to_string(),
+        message_uuid: message_uuid.to_string(),
+        text,
+        created_at: timestamp,
+        updated_at: timestamp,
+    };
+    annotations.push(annotation.clone());
+    save(project_id, session_id, &annotations)?;
+    Ok(annotation)
+}
+
+pub fn update(
+    project_id: &str,
+    session_id: &str,
+    id: &str,
+    text: &str,
+) -> Result<Annotation, String> {
+    let text = clean_text(text)?;
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut annotations = load(project_id, session_id)?;
+    let annotation = annotations
+        .iter_mut()
+        .find(|a| a.id == id)
+        .ok_or_else(|| format!("Annotation '{}' not found", id))?;
+    annotation.text = text;
+    annotation.updated_at = now();
+    let updated = annotation.clone();
+    save(project_id, session_id, &annotations)?;
+    Ok(updated)
+}
+
+pub fn delete(project_id: &str, session_id: &str, id: &str) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut annotations = load(project_id, session_id)?;
+    let before = annotations.len();
+    annotations.retain(|a| a.id != id);
+    if annotations.len() == before {
+        return Err(format!("Annotation '{}' not found", id));
+    }
+    save(project_id, session_id, &annotations)
+}
+
+/// Annotation count per `<project_id>/<session_id>` (the session-meta key)
+pub fn counts() -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for project in fs::read_dir(get_annotations_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+    {
+        let project_id = project.file_name().to_string_lossy().to_string();
+        for entry in fs::read_dir(project.path()).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(session_id) = name.strip_suffix(".json") else {
+                continue;
+            };
+            let count = load(&project_id, session_id).map_or(0, |a| a.len());
+            if count > 0 {
+                counts.insert(crate::session_meta::key(&project_id, session_id), count);
+            }
+        }
+    }
+    counts
+}
+
+/// Move the sidecars of `session_ids` to another project id; where the new id
+/// already has a sidecar, both are kept
+pub fn rekey_sessions(
+    old_project_id: &str,
+    new_project_id: &str,
+    session_ids: &[String],
+) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    for session_id in session_ids {
+        let source = sidecar_path(old_project_id, session_id);
+        let target = sidecar_path(new_project_id, session_id);
+        if !source.exists() || target.exists() {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::rename(&source, &target).map_err(|e| e.to_string())?;
+    }
+    let _ = fs::remove_dir(get_annotations_dir().join(old_project_id));
+    Ok(())
+}
@@ -0,0 +1,232 @@
+//! Images and files attached to messages
+//!
+//! Pasted screenshots and images read by tools are stored inline as base64
+//! `image` blocks (PDFs as `document` blocks). They are decoded once into
+//! ~/.lovstudio/lovcode/attachments/, named by a hash of their data, so the
+//! viewer can load them as files instead of shipping megabytes over IPC.
+//! `@path` mentions in prompts are returned as file references, resolved
+//! against the session's working directory.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    Image,
+    Document,
+    /// `@path` mention of a file
+    File,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    /// e.g. "image/png"; None for file references
+    pub media_type: Option<String>,
+    /// Cached copy of inline data, or the referenced file
+    pub path: Option<String>,
+    /// File name for display
+    pub name: String,
+    pub size_bytes: Option<u64>,
+    /// Inside a tool result (e.g. an image read by a tool) rather than the message itself
+    pub from_tool: bool,
+    /// The referenced file or cached copy is on disk
+    pub exists: bool,
+}
+
+fn get_cache_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("attachments")
+}
+
+/// FNV-1a, stable across runs so the same data always maps to the same file
+pub fn fnv1a(data: &str) -> u64 {
+    data.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn extension(media_type: &str) -> &str {
+    match media_type {
+        "image/jpeg" => "jpg",
+        "image/svg+xml" => "svg",
+        "application/pdf" => "pdf",
+        other => other
+            .rsplit('/')
+            .next()
+            .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("bin"),
+    }
+}
+
+/// Decode a base64 block into the cache unless it is there already. The data
+/// goes to a temp file first, so a crash or a concurrent reader never sees a
+/// truncated copy under the final name.
+fn cache_inline(kind: AttachmentKind, media_type: &str, data: &str, from_tool: bool) -> Attachment {
+    let name = format!("{:016x}.{}", fnv1a(data), extension(media_type));
+    let path = get_cache_dir().join(&name);
+    let size_bytes = match fs::metadata(&path) {
+        Ok(meta) => Some(meta.len()),
+        Err(_) => STANDARD.decode(data.trim()).ok().and_then(|bytes| {
+            fs::create_dir_all(get_cache_dir()).ok()?;
+            let tmp = get_cache_dir().join(format!(".{}.{}.tmp", name, std::process::id()));
+            if fs::write(&tmp, &bytes)
+                .and_then(|_| fs::rename(&tmp, &path))
+                .is_err()
+            {
+                let _ = fs::remove_file(&tmp);
+                return None;
+            }
+            Some(bytes.len() as u64)
+        }),
+    };
+    Attachment {
+        kind,
+        media_type: Some(media_type.to_string()),
+        path: Some(path.to_string_lossy().to_string()),
+        name,
+        size_bytes,
+        from_tool,
+        exists: size_bytes.is_some(),
+    }
+}
+
+fn collect_blocks(blocks: &[serde_json::Value], from_tool: bool, out: &mut Vec<Attachment>) {
+    for block in blocks {
+        let kind = match block.get("type").and_then(|t| t.as_str()) {
+            Some("image") => AttachmentKind::Image,
+            Some("document") => AttachmentKind::Document,
+            Some("tool_result") => {
+                if let Some(nested) = block.get("content").and_then(|c| c.as_array()) {
+                    collect_blocks(nested, true, out);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let Some(source) = block.get("source") else {
+            continue;
+        };
+        if source.get("type").and_then(|t| t.as_str()) != Some("base64") {
+            continue;
+        }
+        let media_type = source
+            .get("media_type")
+            .and_then(|m| m.as_str())
+            .unwrap_or("application/octet-stream");
+        if let Some(data) = source.get("data").and_then(|d| d.as_str()) {
+            out.push(cache_inline(kind, media_type, data, from_tool));
+        }
+    }
+}
+
+/// Whether blocks hold inline data that `collect_blocks` would cache
+fn has_inline_blocks(blocks: &[serde_json::Value]) -> bool {
+    blocks
+        .iter()
+        .any(|block| match block.get("type").and_then(|t| t.as_str()) {
+            Some("image") | Some("document") => block.get("source").is_some_and(|source| {
+                source.get("type").and_then(|t| t.as_str()) == Some("base64")
+                    && source.get("data").is_some_and(|d| d.is_string())
+            }),
+            Some("tool_result") => block
+                .get("content")
+                .and_then(|c| c.as_array())
+                .is_some_and(|nested| has_inline_blocks(nested)),
+            _ => false,
+        })
+}
+
+pub fn mention_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:^|\s)@((?:~|\.{1,2})?/?[\w.\-]+(?:/[\w.\-]+)*)").unwrap())
+}
+
+/// `@path` mentions in prompt text that look like files (have an extension or a slash)
+fn collect_mentions(text: &str, cwd: Option<&str>, out: &mut Vec<Attachment>) {
+    for capture in mention_regex().captures_iter(text) {
+        let mention = capture[1].trim_end_matches('.');
+        if !mention.contains(['/', '.']) || out.iter().any(|a| a.name == mention) {
+            continue;
+        }
+        let resolved = match mention.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None if Path::new(mention).is_absolute() => Some(PathBuf::from(mention)),
+            None => cwd.map(|cwd| Path::new(cwd).join(mention)),
+        };
+        let size_bytes = resolved
+            .as_ref()
+            .and_then(|p| fs::metadata(p).ok())
+            .filter(|m| m.is_file())
+            .map(|m| m.len());
+        out.push(Attachment {
+            kind: AttachmentKind::File,
+            media_type: None,
+            path: resolved.map(|p| p.to_string_lossy().to_string()),
+            name: mention.to_string(),
+            size_bytes,
+            from_tool: false,
+            exists: size_bytes.is_some(),
+        });
+    }
+}
+
+/// Attachments of a message's content; `prompt_text` is scanned for `@path` mentions
+pub fn extract(
+    content: &Option<serde_json::Value>,
+    prompt_text: Option<&str>,
+    cwd: Option<&str>,
+) -> Vec<Attachment> {
+    let mut attachments = Vec::new();
+    if let Some(serde_json::Value::Array(blocks)) = content {
+        collect_blocks(blocks, false, &mut attachments);
+    }
+    if let Some(text) = prompt_text {
+        collect_mentions(text, cwd, &mut attachments);
+    }
+    attachments
+}
+
+/// Whether a message's content has inline images or documents, without decoding them
+pub fn has_inline(content: &Option<serde_json::Value>) -> bool {
+    matches!(content, Some(serde_json::Value::Array(blocks)) if has_inline_blocks(blocks))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+pub fn cache_stats() -> CacheStats {
+    let sizes: Vec<u64> = fs::read_dir(get_cache_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .collect();
+    CacheStats {
+        files: sizes.len(),
+        bytes: sizes.iter().sum(),
+    }
+}
+
+/// Delete cached copies; they are re-created when their sessions are viewed again
+pub fn clear_cache() -> Result<CacheStats, String> {
+    let stats = cache_stats();
+    let dir = get_cache_dir();
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    Ok(stats)
+}
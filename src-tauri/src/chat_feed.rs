@@ -0,0 +1,128 @@
+//! Newest-first feed of chat messages across all sessions
+//!
+//! A page is built from the fewest session files that can hold it: files are
+//! visited newest mtime first, and since no message in a file is newer than
+//! the file's mtime, the walk stops once the page is full with messages newer
+//! than the next file. Pages continue from an opaque cursor (timestamp and uuid
+//! of the last item) instead of an offset, so deep pages don't re-sort all of
+//! history.
+
+use crate::{ChatMessage, ChatsResponse, RawLine};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Position in the feed: (timestamp in ms, uuid), compared newest first
+type FeedKey = (i64, String);
+
+fn parse_cursor(cursor: &str) -> Result<FeedKey, String> {
+    let (millis, uuid) = cursor
+        .split_once(':')
+        .ok_or_else(|| "Invalid cursor".to_string())?;
+    let millis = millis.parse().map_err(|_| "Invalid cursor".to_string())?;
+    Ok((millis, uuid.to_string()))
+}
+
+fn timestamp_millis(timestamp: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.timestamp_millis())
+        .unwrap_or(0)
+}
+
+/// Non-meta messages with text in one session file
+fn read_chats(
+    path: &Path,
+    cached: &crate::listing_cache::CachedSession,
+    project_path: &str,
+) -> Vec<(FeedKey, ChatMessage)> {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    let mut summary = None;
+    let mut chats = Vec::new();
+    for line in content.lines() {
+        let Ok(parsed) = serde_json::from_str::<RawLine>(line) else {
+            continue;
+        };
+        match parsed.line_type.as_deref() {
+            Some("summary") => summary = parsed.summary,
+            Some("user") | Some("assistant") => {
+                let Some(msg) = &parsed.message else {
+                    continue;
+                };
+                let (text, _is_tool) = crate::extract_content_with_meta(&msg.content);
+                if parsed.is_meta.unwrap_or(false) || text.is_empty() {
+                    continue;
+                }
+                let timestamp = parsed.timestamp.unwrap_or_default();
+                let uuid = parsed.uuid.unwrap_or_default();
+                chats.push((
+                    (timestamp_millis(&timestamp), uuid.clone()),
+                    ChatMessage {
+                        uuid,
+                        role: msg.role.clone().unwrap_or_default(),
+                        content: text,
+                        timestamp,
+                        project_id: cached.project_id.clone(),
+                        project_path: project_path.to_string(),
+                        session_id: cached.session_id.clone(),
+                        session_summary: None,
+                    },
+                ));
+            }
+            _ => {}
+        }
+    }
+    let summary = summary.or_else(|| cached.summary.clone());
+    for (_, chat) in &mut chats {
+        chat.session_summary = summary.clone();
+    }
+    chats
+}
+
+/// Up to `limit` messages older than `cursor` (from the start when None)
+pub fn page(limit: usize, cursor: Option<&str>) -> Result<ChatsResponse, String> {
+    let after = cursor.map(parse_cursor).transpose()?;
+    let projects_dir = crate::get_claude_dir().join("projects");
+
+    let mut sessions: Vec<_> = crate::listing_cache::all_sessions()
+        .into_values()
+        .flatten()
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.mtime));
+    // Approximate: counts include meta and empty entries that the feed skips
+    let total = sessions.iter().map(|s| s.message_count).sum();
+
+    let mut project_paths: HashMap<String, String> = HashMap::new();
+    let mut items: Vec<(FeedKey, ChatMessage)> = Vec::new();
+    for session in &sessions {
+        // Nothing in this or any later file is newer than its mtime
+        let newest_possible = session.mtime as i64 * 1000 + 999;
+        if items.len() >= limit && items.last().is_none_or(|(key, _)| key.0 > newest_possible) {
+            break;
+        }
+        let project_path = project_paths
+            .entry(session.project_id.clone())
+            .or_insert_with(|| crate::decode_project_path(&session.project_id));
+        let path = projects_dir
+            .join(&session.project_id)
+            .join(format!("{}.jsonl", session.session_id));
+        items.extend(
+            read_chats(&path, session, project_path)
+                .into_iter()
+                .filter(|(key, _)| after.as_ref().is_none_or(|after| key < after)),
+        );
+        items.sort_by(|a, b| b.0.cmp(&a.0));
+        items.truncate(limit);
+    }
+
+    let next_cursor = (items.len() == limit && limit > 0)
+        .then(|| {
+            items
+                .last()
+                .map(|((millis, uuid), _)| format!("{}:{}", millis, uuid))
+        })
+        .flatten();
+    Ok(ChatsResponse {
+        items: items.into_iter().map(|(_, chat)| chat).collect(),
+        total,
+        next_cursor,
+    })
+}
@@ -0,0 +1,237 @@
+//! Curated session collections
+//!
+//! Named groups of exemplary sessions (good prompt patterns, reference
+//! solutions) with a description and a per-session note on why it was picked.
+//! Collections only reference sessions by id; they are shared by exporting them
+//! as an archive. Persisted to ~/.lovstudio/lovcode/collections.json
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionEntry {
+    pub project_id: String,
+    pub session_id: String,
+    /// Why the session is in the collection
+    #[serde(default)]
+    pub note: Option<String>,
+    pub added_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub entries: Vec<CollectionEntry>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionSummary {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub session_count: usize,
+    pub updated_at: u64,
+}
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("collections.json")
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// All collections; a missing file is empty, an unreadable one an error
+fn load() -> Result<Vec<Collection>, String> {
+    let path = get_store_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read collections: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse collections: {}", e))
+}
+
+fn save(collections: &[Collection]) -> Result<(), String> {
+    let path = get_store_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(collections).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn clean(text: Option<String>) -> Option<String> {
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Apply `change` to one collection and persist it
+fn modify(id: &str, change: impl FnOnce(&mut Collection)) -> Result<Collection, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut collections = load()?;
+    let collection = collections
+        .iter_mut()
+        .find(|c| c.id == id)
+        .ok_or_else(|| format!("Collection '{}' not found", id))?;
+    change(collection);
+    collection.updated_at = now();
+    let updated = collection.clone();
+    save(&collections)?;
+    Ok(updated)
+}
+
+/// All collections, most recently updated first
+pub fn list() -> Result<Vec<CollectionSummary>, String> {
+    let mut collections: Vec<CollectionSummary> = load()?
+        .into_iter()
+        .map(|c| CollectionSummary {
+            session_count: c.entries.len(),
+            id: c.id,
+            name: c.name,
+            description: c.description,
+            updated_at: c.updated_at,
+        })
+        .collect();
+    collections.sort_by_key(|c| std::cmp::Reverse(c.updated_at));
+    Ok(collections)
+}
+
+pub fn get(id: &str) -> Result<Collection, String> {
+    load()?
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| format!("Collection '{}' not found", id))
+}
+
+pub fn create(name: &str, description: Option<String>) -> Result<Collection, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Collection name is empty".to_string());
+    }
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut collections = load()?;
+    let timestamp = now();
+    let collection = Collection {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        description: clean(description),
+        entries: Vec::new(),
+        created_at: timestamp,
+        updated_at: timestamp,
+    };
+    collections.push(collection.clone());
+    save(&collections)?;
+    Ok(collection)
+}
+
+/// Rename or re-describe a collection; None leaves a field unchanged
+pub fn update(
+    id: &str,
+    name: Option<String>,
+    description: Option<String>,
+) -> Result<Collection, String> {
+    let name = clean(name);
+    modify(id, |collection| {
+        if let Some(name) = name {
+            collection.name = name;
+        }
+        if let Some(description) = description {
+            collection.description = clean(Some(description));
+        }
+    })
+}
+
+pub fn delete(id: &str) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut collections = load()?;
+    let before = collections.len();
+    collections.retain(|c| c.id != id);
+    if collections.len() == before {
+        return Err(format!("Collection '{}' not found", id));
+    }
+    save(&collections)
+}
+
+/// Add a session, or update its note when it is already in the collection
+pub fn add_session(
+    id: &str,
+    project_id: &str,
+    session_id: &str,
+    note: Option<String>,
+) -> Result<Collection, String> {
+    let note = clean(note);
+    modify(id, |collection| {
+        match collection
+            .entries
+            .iter_mut()
+            .find(|e| e.project_id == project_id && e.session_id == session_id)
+        {
+            Some(entry) => entry.note = note,
+            None => collection.entries.push(CollectionEntry {
+                project_id: project_id.to_string(),
+                session_id: session_id.to_string(),
+                note,
+                added_at: now(),
+            }),
+        }
+    })
+}
+
+pub fn remove_session(id: &str, project_id: &str, session_id: &str) -> Result<Collection, String> {
+    modify(id, |collection| {
+        collection
+            .entries
+            .retain(|e| !(e.project_id == project_id && e.session_id == session_id));
+    })
+}
+
+/// Point entries of `session_ids` at another project id; where the new id is
+/// already listed, both entries are kept
+pub fn rekey_sessions(
+    old_project_id: &str,
+    new_project_id: &str,
+    session_ids: &[String],
+) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut collections = load()?;
+    let mut changed = false;
+    for collection in &mut collections {
+        for session_id in session_ids {
+            let listed = |project_id: &str| {
+                collection
+                    .entries
+                    .iter()
+                    .position(|e| e.project_id == project_id && &e.session_id == session_id)
+            };
+            if listed(new_project_id).is_some() {
+                continue;
+            }
+            if let Some(index) = listed(old_project_id) {
+                collection.entries[index].project_id = new_project_id.to_string();
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        save(&collections)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Commands that shadow each other
+//!
+//! Claude Code invokes a command by its file name alone: subdirectories only
+//! show up in its description, so /git/review and /pr/review are both
+//! `/review`. When project commands, global ones and installed plugins share
+//! such a name, the project command wins, then the global one; a shadowed
+//! plugin command stays reachable as `/plugin:name`.
+
+use crate::command_files::CommandScope;
+use crate::LocalCommand;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    Project,
+    Global,
+    Plugin,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandSource {
+    pub kind: SourceKind,
+    /// Plugin name, for plugin commands
+    pub plugin: Option<String>,
+    /// Name as lovcode lists it, e.g. /git/review
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandConflict {
+    /// What the user types, e.g. /review
+    pub invoked_as: String,
+    /// Every command answering to it, the one Claude Code runs first
+    pub sources: Vec<CommandSource>,
+}
+
+/// Install dirs of plugins in ~/.claude/plugins/installed_plugins.json, by plugin name
+fn installed_plugins() -> Vec<(String, PathBuf)> {
+    let path = crate::get_claude_dir()
+        .join("plugins")
+        .join("installed_plugins.json");
+    let Some(value) = fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+    else {
+        return Vec::new();
+    };
+    let Some(plugins) = value.get("plugins").and_then(|p| p.as_object()) else {
+        return Vec::new();
+    };
+
+    let mut installed = Vec::new();
+    for (key, entry) in plugins {
+        // "name@marketplace"; entries are an object, or a list of installs in newer files
+        let name = key.split('@').next().unwrap_or(key).to_string();
+        let installs = match entry {
+            serde_json::Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        };
+        for install in installs {
+            if let Some(dir) = install.get("installPath").and_then(|p| p.as_str()) {
+                installed.push((name.clone(), PathBuf::from(dir)));
+            }
+        }
+    }
+    installed
+}
+
+fn invoked_as(command: &LocalCommand) -> String {
+    let name = command.name.rsplit('/').next().unwrap_or(&command.name);
+    format!("/{}", name)
+}
+
+fn active(commands: Vec<LocalCommand>) -> impl Iterator<Item = LocalCommand> {
+    commands.into_iter().filter(|c| c.status == "active")
+}
+
+/// Names answered by more than one command, among global commands, installed
+/// plugins and the commands of `project_path`
+pub fn find(project_path: Option<&str>) -> Result<Vec<CommandConflict>, String> {
+    let mut sources: Vec<(CommandSource, String)> = Vec::new();
+    let mut add = |kind: SourceKind, plugin: Option<String>, command: LocalCommand| {
+        let invoked_as = invoked_as(&command);
+        let source = CommandSource {
+            kind,
+            plugin,
+            name: command.name,
+            path: command.path,
+        };
+        sources.push((source, invoked_as));
+    };
+
+    if let Some(project_path) = project_path {
+        let scope = CommandScope::Project {
+            project_path: project_path.to_string(),
+        };
+        for command in active(crate::command_files::list(&scope)?) {
+            add(SourceKind::Project, None, command);
+        }
+    }
+    for command in active(crate::collect_local_commands()?) {
+        add(SourceKind::Global, None, command);
+    }
+    for (plugin, dir) in installed_plugins() {
+        let commands_dir = dir.join("commands");
+        if !commands_dir.is_dir() {
+            continue;
+        }
+        let mut commands = Vec::new();
+        crate::collect_commands_from_dir(&commands_dir, &commands_dir, &mut commands, "active")?;
+        for command in active(commands) {
+            add(SourceKind::Plugin, Some(plugin.clone()), command);
+        }
+    }
+
+    // Sources were added in shadowing order, which grouping keeps
+    let mut by_name: BTreeMap<String, Vec<CommandSource>> = BTreeMap::new();
+    for (source, invoked_as) in sources {
+        by_name.entry(invoked_as).or_default().push(source);
+    }
+    Ok(by_name
+        .into_iter()
+        .filter(|(_, sources)| sources.len() > 1)
+        .map(|(invoked_as, sources)| CommandConflict {
+            invoked_as,
+            sources,
+        })
+        .collect())
+}
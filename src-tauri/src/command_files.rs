@@ -0,0 +1,648 @@
+//! Creating, editing, duplicating and deleting command files
+//!
+//! Commands live in ~/.claude/commands (global) or <project>/.claude/commands
+//! (project), named by their path relative to that dir: `/foo/bar` is
+//! foo/bar.md. Deprecated ones move to .commands/archived next to that dir.
+//! A new name must not clash with an existing command or with an alias one
+//! keeps from a previous rename, since stats are aggregated by both.
+//! Folders (namespaces) are renamed and moved whole, and every command in one
+//! keeps its previous name as an alias, as a single rename would.
+//! Edits go through `frontmatter`, which rewrites only the changed fields, and
+//! snapshot the previous content (see `command_versions`).
+//! Changelog entries are kept newest first in <name>.changelog next to the
+//! command, under `## <version> - <date>` headings.
+//! A duplicate starts its own history: aliases, version and deprecation
+//! fields are dropped and the changelog is not copied.
+//!
+//! Deleted commands are moved with their .changelog into
+//! ~/.lovstudio/lovcode/command-trash/<id>/ and can be restored to where they
+//! were until they expire, like trashed sessions.
+
+use crate::LocalCommand;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedCommand {
+    pub id: String,
+    /// Command name as listed when it was deleted, e.g. /foo/bar
+    pub name: String,
+    /// Where the .md file was; restore puts it back here
+    pub path: String,
+    /// The moved files (the .md and its .changelog)
+    pub files: Vec<TrashedFile>,
+    pub deleted_at: u64,
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedFile {
+    /// Original path; restore puts the file back here
+    pub path: String,
+    /// Name inside the trash entry dir
+    pub stored: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommandScope {
+    Global,
+    Project { project_path: String },
+}
+
+impl CommandScope {
+    /// ~/.claude, or the project's .claude dir
+    pub fn claude_dir(&self) -> Result<PathBuf, String> {
+        match self {
+            CommandScope::Global => Ok(crate::get_claude_dir()),
+            CommandScope::Project { project_path } => {
+                let project_dir = Path::new(project_path);
+                if !project_dir.is_absolute() || !project_dir.is_dir() {
+                    return Err(format!("Directory not found: {}", project_path));
+                }
+                Ok(project_dir.join(".claude"))
+            }
+        }
+    }
+
+    pub fn commands_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.claude_dir()?.join("commands"))
+    }
+
+    /// Where deprecated commands go, outside the dir Claude Code loads
+    pub fn archived_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.claude_dir()?.join(".commands").join("archived"))
+    }
+}
+
+/// Active and deprecated commands of a scope, sorted by name
+pub fn list(scope: &CommandScope) -> Result<Vec<LocalCommand>, String> {
+    if let CommandScope::Global = scope {
+        return crate::collect_local_commands();
+    }
+    let mut commands = Vec::new();
+    let commands_dir = scope.commands_dir()?;
+    if commands_dir.is_dir() {
+        crate::collect_commands_from_dir(&commands_dir, &commands_dir, &mut commands, "active")?;
+    }
+    let archived_dir = scope.archived_dir()?;
+    if archived_dir.is_dir() {
+        crate::collect_commands_from_dir(
+            &archived_dir,
+            &archived_dir,
+            &mut commands,
+            "deprecated",
+        )?;
+    }
+    commands.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(commands)
+}
+
+/// Normalize a command name to its path form (`foo/bar`, no leading slash or .md)
+pub fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.trim().trim_start_matches('/');
+    let name = name.strip_suffix(".md").unwrap_or(name);
+    if name.is_empty() {
+        return Err("Command name cannot be empty".to_string());
+    }
+    for segment in name.split('/') {
+        let valid = !segment.is_empty()
+            && !segment.starts_with('.')
+            && segment
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+        if !valid {
+            return Err(format!("Invalid command name: /{}", name));
+        }
+    }
+    Ok(name.to_string())
+}
+
+/// Fail when `/name` is already a command or an alias of one in `scope`
+fn check_available(scope: &CommandScope, name: &str) -> Result<(), String> {
+    let slash_name = format!("/{}", name);
+    for command in list(scope)? {
+        if command.name == slash_name {
+            return Err(format!("A command named '{}' already exists", slash_name));
+        }
+        if command.aliases.contains(&slash_name) {
+            return Err(format!(
+                "'{}' is an alias of {}; pick another name",
+                slash_name, command.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Write `frontmatter` (raw YAML, without the --- fences) and `body` as a new command
+pub fn create(
+    name: &str,
+    frontmatter: Option<&str>,
+    body: &str,
+    scope: &CommandScope,
+) -> Result<LocalCommand, String> {
+    let name = normalize_name(name)?;
+    check_available(scope, &name)?;
+
+    let commands_dir = scope.commands_dir()?;
+    let path = commands_dir.join(format!("{}.md", name));
+    if path.exists() {
+        return Err(format!("File already exists: {}", path.display()));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let frontmatter = frontmatter.map(str::trim).filter(|f| !f.is_empty());
+    if frontmatter.is_some_and(|f| f.lines().any(|line| line.trim() == "---")) {
+        return Err("Frontmatter cannot contain a --- line".to_string());
+    }
+    let content = match frontmatter {
+        Some(frontmatter) => format!("---\n{}\n---\n\n{}", frontmatter, body.trim_start()),
+        None => body.to_string(),
+    };
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+
+    Ok(crate::read_command_file(&commands_dir, &path, "active"))
+}
+
+/// Changes to a command; None leaves a field as is, an empty string removes it
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CommandUpdate {
+    pub description: Option<String>,
+    pub allowed_tools: Option<String>,
+    pub argument_hint: Option<String>,
+    pub version: Option<String>,
+    pub body: Option<String>,
+}
+
+/// Apply `update` to a command file, leaving the rest of its frontmatter untouched
+pub fn update(path: &str, update: &CommandUpdate) -> Result<LocalCommand, String> {
+    let file = Path::new(path);
+    if !file.is_file() {
+        return Err(format!("Command file not found: {}", path));
+    }
+    let base = commands_base(file)
+        .filter(|_| path.ends_with(".md") || path.ends_with(".md.archived"))
+        .ok_or_else(|| format!("Not a command file: {}", path))?;
+
+    let original = fs::read_to_string(file).map_err(|e| e.to_string())?;
+    let mut content = original.clone();
+    for (key, value) in [
+        ("description", &update.description),
+        ("allowed-tools", &update.allowed_tools),
+        ("argument-hint", &update.argument_hint),
+        ("version", &update.version),
+    ] {
+        if let Some(value) = value {
+            content = crate::frontmatter::set_field(&content, key, Some(value.trim()))?;
+        }
+    }
+    if let Some(body) = &update.body {
+        content = crate::frontmatter::set_body(&content, body);
+    }
+    if content != original {
+        if path.ends_with(".md") {
+            crate::command_versions::snapshot(file)?;
+        }
+        fs::write(file, &content).map_err(|e| e.to_string())?;
+    }
+
+    Ok(crate::read_command_file(&base, file, "active"))
+}
+
+const CHANGELOG_TITLE: &str = "# Changelog";
+
+/// `existing` with `note` added under `version`: to the latest entry when it is
+/// for the same version, otherwise as a new entry on top
+fn changelog_with_entry(existing: &str, version: &str, date: &str, note: &str) -> String {
+    let item = format!("- {}", note.lines().collect::<Vec<_>>().join("\n  "));
+    let existing = existing.trim();
+    let entries = existing
+        .strip_prefix(CHANGELOG_TITLE)
+        .unwrap_or(existing)
+        .trim_start();
+
+    let mut out = format!("{}\n\n", CHANGELOG_TITLE);
+    if entries.starts_with(&format!("## {} - ", version)) {
+        let end = entries[1..]
+            .find("\n## ")
+            .map(|i| i + 1)
+            .unwrap_or(entries.len());
+        out.push_str(entries[..end].trim_end());
+        out.push('\n');
+        out.push_str(&item);
+        out.push_str("\n\n");
+        out.push_str(entries[end..].trim_start());
+    } else {
+        out.push_str(&format!(
+            "## {} - {}\n\n{}\n\n{}",
+            version, date, item, entries
+        ));
+    }
+    format!("{}\n", out.trim_end())
+}
+
+/// Add a dated entry to the .changelog next to `file`, creating it if needed.
+/// Returns the trimmed version.
+pub fn write_changelog_entry<'a>(
+    file: &Path,
+    version: &'a str,
+    note: &str,
+) -> Result<&'a str, String> {
+    let version = version.trim();
+    let note = note.trim();
+    if version.is_empty() || version.contains(['\n', '\r']) {
+        return Err("Version must be a single non-empty line".to_string());
+    }
+    if note.is_empty() {
+        return Err("Changelog note cannot be empty".to_string());
+    }
+
+    let changelog_path = file.with_extension("changelog");
+    // Anything but a missing file would lose the entries written so far
+    let existing = match fs::read_to_string(&changelog_path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read changelog: {}", e)),
+    };
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let content = changelog_with_entry(&existing, version, &date, note);
+    fs::write(&changelog_path, content).map_err(|e| e.to_string())?;
+    Ok(version)
+}
+
+/// Add a dated entry to a command's .changelog, creating it if needed, and
+/// optionally set the frontmatter version to `version`
+pub fn append_changelog(
+    path: &str,
+    version: &str,
+    note: &str,
+    bump_version: bool,
+) -> Result<LocalCommand, String> {
+    let file = Path::new(path);
+    if !file.is_file() {
+        return Err(format!("Command file not found: {}", path));
+    }
+    if !path.ends_with(".md") || commands_base(file).is_none() {
+        return Err(format!("Not a command file: {}", path));
+    }
+    let version = write_changelog_entry(file, version, note)?;
+
+    if bump_version {
+        let bump = CommandUpdate {
+            version: Some(version.to_string()),
+            ..Default::default()
+        };
+        return update(path, &bump);
+    }
+    read(file)
+}
+
+/// Frontmatter fields a duplicate doesn't inherit
+const DUPLICATE_CLEARED_FIELDS: &[&str] =
+    &["aliases", "version", "replaced-by", "deprecation-note"];
+
+/// The scope a command file belongs to, deprecated ones included
+pub fn scope_of(path: &Path) -> Option<CommandScope> {
+    let base = commands_base(path)?;
+    let claude_dir = base.ancestors().find(|d| d.ends_with(".claude"))?;
+    if claude_dir == crate::get_claude_dir() {
+        return Some(CommandScope::Global);
+    }
+    Some(CommandScope::Project {
+        project_path: claude_dir.parent()?.to_string_lossy().to_string(),
+    })
+}
+
+/// Copy a command to `new_name` in the same scope, without its history
+pub fn duplicate(path: &str, new_name: &str) -> Result<LocalCommand, String> {
+    let src = Path::new(path);
+    if !src.is_file() {
+        return Err(format!("Command file not found: {}", path));
+    }
+    let scope = scope_of(src)
+        .filter(|_| path.ends_with(".md") || path.ends_with(".md.archived"))
+        .ok_or_else(|| format!("Not a command file: {}", path))?;
+
+    let mut content = fs::read_to_string(src).map_err(|e| e.to_string())?;
+    for field in DUPLICATE_CLEARED_FIELDS {
+        content = crate::frontmatter::set_field(&content, field, None)?;
+    }
+
+    let (_, raw_frontmatter, body) = crate::parse_frontmatter(&content);
+    create(new_name, raw_frontmatter.as_deref(), &body, &scope)
+}
+
+/// Create an empty command folder (namespace), e.g. `lov/git`
+pub fn create_folder(name: &str, scope: &CommandScope) -> Result<String, String> {
+    let name = normalize_name(name)?;
+    let dir = scope.commands_dir()?.join(&name);
+    if dir.exists() {
+        return Err(format!("Folder already exists: /{}", name));
+    }
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.to_string_lossy().to_string())
+}
+
+/// Rename a command folder in place: `lovstudio` → `lov`
+pub fn rename_folder(path: &str, new_name: &str) -> Result<Vec<LocalCommand>, String> {
+    let new_name = normalize_name(new_name)?;
+    if new_name.contains('/') {
+        return Err("A folder name cannot contain '/'; move the folder instead".to_string());
+    }
+    let src = Path::new(path);
+    let dest = src.with_file_name(new_name);
+    relocate_folder(src, &dest)
+}
+
+/// Move a command folder under `new_parent` (a folder name, or empty for the top level)
+pub fn move_folder(path: &str, new_parent: &str) -> Result<Vec<LocalCommand>, String> {
+    let src = Path::new(path);
+    let scope = scope_of(src).ok_or_else(|| format!("Not a command folder: {}", path))?;
+    let mut dest = scope.commands_dir()?;
+    if !new_parent.trim().trim_matches('/').is_empty() {
+        dest = dest.join(normalize_name(new_parent)?);
+    }
+    relocate_folder(src, &dest.join(src.file_name().unwrap_or_default()))
+}
+
+/// Start of the names of commands in `dir`, e.g. `/lov/`
+fn name_prefix(commands_dir: &Path, dir: &Path) -> String {
+    let relative = dir.strip_prefix(commands_dir).unwrap_or(dir);
+    format!("/{}/", relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Move a folder with everything in it, adding each command's previous name to its aliases
+fn relocate_folder(src: &Path, dest: &Path) -> Result<Vec<LocalCommand>, String> {
+    let scope = scope_of(src)
+        .filter(|_| src.is_dir())
+        .ok_or_else(|| format!("Not a command folder: {}", src.display()))?;
+    let commands_dir = scope.commands_dir()?;
+    if src == commands_dir || !src.starts_with(&commands_dir) {
+        return Err(format!("Not a command folder: {}", src.display()));
+    }
+    if dest == src {
+        return Ok(Vec::new());
+    }
+    if dest.starts_with(src) {
+        return Err("Cannot move a folder into itself".to_string());
+    }
+    if dest.exists() {
+        return Err(format!("{} already exists", dest.display()));
+    }
+
+    let mut moved = Vec::new();
+    crate::collect_commands_from_dir(&commands_dir, &src.to_path_buf(), &mut moved, "active")?;
+    let src_prefix = name_prefix(&commands_dir, src);
+    let dest_prefix = name_prefix(&commands_dir, dest);
+    let renames: Vec<(String, String)> = moved
+        .iter()
+        .map(|c| {
+            let rest = c.name.strip_prefix(&src_prefix).unwrap_or(&c.name);
+            (c.name.clone(), format!("{}{}", dest_prefix, rest))
+        })
+        .collect();
+
+    // New names must be free among the commands staying where they are
+    let moved_paths: Vec<&str> = moved.iter().map(|c| c.path.as_str()).collect();
+    for other in list(&scope)?
+        .iter()
+        .filter(|c| !moved_paths.contains(&c.path.as_str()))
+    {
+        if let Some((_, new_name)) = renames
+            .iter()
+            .find(|(_, n)| *n == other.name || other.aliases.contains(n))
+        {
+            return Err(format!("'{}' is already used by {}", new_name, other.name));
+        }
+    }
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(src, dest).map_err(|e| e.to_string())?;
+
+    let mut result = Vec::new();
+    for (command, (old_name, new_name)) in moved.iter().zip(&renames) {
+        let relative = Path::new(&command.path)
+            .strip_prefix(src)
+            .unwrap_or(Path::new(""));
+        let file = dest.join(relative);
+        let content = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+        let updated = crate::update_aliases_on_rename(&content, old_name, new_name);
+        if updated != content {
+            fs::write(&file, &updated).map_err(|e| e.to_string())?;
+        }
+        result.push(crate::read_command_file(&commands_dir, &file, "active"));
+    }
+    Ok(result)
+}
+
+fn get_trash_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("command-trash")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The dir a command file's name is relative to: a commands dir or a deprecated archive
+fn commands_base(path: &Path) -> Option<PathBuf> {
+    let archived = Path::new(".commands").join("archived");
+    path.ancestors()
+        .skip(1)
+        .find(|dir| {
+            let in_claude_dir = |tail: &Path| {
+                dir.ends_with(tail)
+                    && dir
+                        .ancestors()
+                        .nth(tail.components().count())
+                        .is_some_and(|d| d.ends_with(".claude"))
+            };
+            in_claude_dir(Path::new("commands")) || in_claude_dir(&archived)
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Parse a command file, named relative to the commands dir it is in
+pub fn read(path: &Path) -> Result<LocalCommand, String> {
+    let base =
+        commands_base(path).ok_or_else(|| format!("Not a command file: {}", path.display()))?;
+    Ok(crate::read_command_file(&base, path, "active"))
+}
+
+/// Move a command file and its .changelog into a new trash entry
+pub fn delete(path: &str) -> Result<DeletedCommand, String> {
+    let src = PathBuf::from(path);
+    if !src.is_file() {
+        return Err(format!("Command file not found: {}", path));
+    }
+    let is_md = path.ends_with(".md") || path.ends_with(".md.archived");
+    let Some(base) = commands_base(&src).filter(|_| is_md) else {
+        return Err(format!("Not a command file: {}", path));
+    };
+    let name = crate::read_command_file(&base, &src, "active").name;
+
+    let file_name = src.file_name().unwrap_or_default().to_string_lossy();
+    let stem = file_name
+        .strip_suffix(".md.archived")
+        .or_else(|| file_name.strip_suffix(".md"))
+        .unwrap_or(&file_name);
+    let changelog = src.with_file_name(format!("{}.changelog", stem));
+    move_to_trash(&get_trash_dir(), name, &src, &[&src, &changelog])
+}
+
+/// Move `files` (those that exist) into a new entry of `trash_dir`; `src` is the main one
+pub fn move_to_trash(
+    trash_dir: &Path,
+    name: String,
+    src: &Path,
+    files: &[&Path],
+) -> Result<DeletedCommand, String> {
+    let deleted_at = now_secs();
+    let base_id = format!(
+        "{}-{:016x}",
+        deleted_at,
+        crate::attachments::fnv1a(&src.to_string_lossy())
+    );
+    fs::create_dir_all(trash_dir).map_err(|e| e.to_string())?;
+    // The same path trashed again within a second gets the next free suffix
+    let mut id = base_id.clone();
+    let mut n = 2;
+    let entry_dir = loop {
+        let dir = trash_dir.join(&id);
+        match fs::create_dir(&dir) {
+            Ok(()) => break dir,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                id = format!("{}-{}", base_id, n);
+                n += 1;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    let mut entry = DeletedCommand {
+        id,
+        name,
+        path: src.to_string_lossy().to_string(),
+        files: Vec::new(),
+        deleted_at,
+        expires_at: deleted_at + crate::trash::DEFAULT_RETENTION_DAYS * 24 * 60 * 60,
+    };
+
+    let mut result = Ok(());
+    for (index, file) in files.iter().enumerate() {
+        if !file.exists() {
+            continue;
+        }
+        // Stored by position, the original names may clash
+        let stored = index.to_string();
+        result = crate::trash::move_path(file, &entry_dir.join(&stored));
+        if result.is_err() {
+            break;
+        }
+        entry.files.push(TrashedFile {
+            path: file.to_string_lossy().to_string(),
+            stored,
+        });
+    }
+
+    // Record whatever was moved, even on failure, so nothing becomes unreachable
+    let manifest = serde_json::to_string_pretty(&entry).map_err(|e| e.to_string())?;
+    fs::write(entry_dir.join(MANIFEST), manifest).map_err(|e| e.to_string())?;
+    result?;
+
+    Ok(entry)
+}
+
+/// Deleted commands, most recently deleted first
+pub fn list_deleted() -> Vec<DeletedCommand> {
+    list_trash(&get_trash_dir())
+}
+
+/// Move a deleted command back to where it was
+pub fn restore_deleted(id: &str) -> Result<DeletedCommand, String> {
+    restore_from_trash(&get_trash_dir(), id)
+}
+
+/// Permanently delete a trashed command
+pub fn purge_deleted(id: &str) -> Result<(), String> {
+    purge_from_trash(&get_trash_dir(), id)
+}
+
+/// Permanently delete trashed commands past their expiry, returns how many were removed
+pub fn purge_expired() -> usize {
+    purge_expired_in(&get_trash_dir())
+}
+
+/// Entries of `trash_dir`, most recently deleted first
+pub fn list_trash(trash_dir: &Path) -> Vec<DeletedCommand> {
+    let Ok(dirs) = fs::read_dir(trash_dir) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<DeletedCommand> = dirs
+        .flatten()
+        .filter_map(|d| fs::read_to_string(d.path().join(MANIFEST)).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    entries
+}
+
+fn get_trashed(trash_dir: &Path, id: &str) -> Result<DeletedCommand, String> {
+    if id.contains(['/', '\\']) || id.contains("..") {
+        return Err(format!("Invalid trash id: {}", id));
+    }
+    let content = fs::read_to_string(trash_dir.join(id).join(MANIFEST))
+        .map_err(|_| format!("Deleted item '{}' not found", id))?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+/// Move the files of a trash entry back to where they were
+pub fn restore_from_trash(trash_dir: &Path, id: &str) -> Result<DeletedCommand, String> {
+    let entry = get_trashed(trash_dir, id)?;
+    let entry_dir = trash_dir.join(id);
+
+    if let Some(existing) = entry.files.iter().find(|f| Path::new(&f.path).exists()) {
+        return Err(format!("Cannot restore: {} already exists", existing.path));
+    }
+
+    for file in &entry.files {
+        let dest = Path::new(&file.path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        crate::trash::move_path(&entry_dir.join(&file.stored), dest)?;
+    }
+    fs::remove_dir_all(&entry_dir).map_err(|e| e.to_string())?;
+
+    Ok(entry)
+}
+
+pub fn purge_from_trash(trash_dir: &Path, id: &str) -> Result<(), String> {
+    get_trashed(trash_dir, id)?;
+    fs::remove_dir_all(trash_dir.join(id)).map_err(|e| e.to_string())
+}
+
+/// Drop entries of `trash_dir` past their expiry, returns how many were removed
+pub fn purge_expired_in(trash_dir: &Path) -> usize {
+    let now = now_secs();
+    list_trash(trash_dir)
+        .into_iter()
+        .filter(|e| e.expires_at <= now)
+        .filter(|e| purge_from_trash(trash_dir, &e.id).is_ok())
+        .count()
+}
@@ -0,0 +1,411 @@
+//! Checks for command files
+//!
+//! Finds what makes a command misbehave in Claude Code without an error of its
+//! own: frontmatter that isn't valid YAML or has misspelled keys, malformed
+//! allowed-tools, arguments used without an argument-hint, `@file` includes
+//! that don't resolve, `!` bash lines Bash isn't allowed for, and code fences
+//! left open. Line numbers are 1-based and count the whole file.
+
+use regex::Regex;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Frontmatter keys read by Claude Code, then the ones lovcode maintains
+const KNOWN_KEYS: &[&str] = &[
+    "description",
+    "allowed-tools",
+    "argument-hint",
+    "model",
+    "disable-model-invocation",
+    "version",
+    "aliases",
+    "tags",
+    "replaced-by",
+    "deprecation-note",
+];
+
+static ARGUMENT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$(ARGUMENTS\b|[1-9]\b)").expect("valid argument regex"));
+static TOOL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_\-]*(\(.+\))?$").expect("valid tool regex"));
+static BASH_LINE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!`[^`]+`").expect("valid bash line regex"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Claude Code will misread the command
+    Error,
+    /// Likely a mistake, the command still loads
+    Warning,
+    /// Works, but could be clearer
+    Hint,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandIssue {
+    pub severity: Severity,
+    /// Stable identifier, e.g. "unknown_key"
+    pub code: &'static str,
+    pub message: String,
+    pub line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandValidation {
+    pub path: String,
+    pub issues: Vec<CommandIssue>,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+pub struct Lint {
+    pub issues: Vec<CommandIssue>,
+}
+
+impl Lint {
+    pub fn push(
+        &mut self,
+        severity: Severity,
+        code: &'static str,
+        line: Option<usize>,
+        message: String,
+    ) {
+        self.issues.push(CommandIssue {
+            severity,
+            code,
+            message,
+            line,
+        });
+    }
+
+    /// Issues sorted by line, with their counts
+    pub fn finish(self, path: &Path) -> CommandValidation {
+        let mut issues = self.issues;
+        issues.sort_by_key(|i| i.line.unwrap_or(0));
+        CommandValidation {
+            path: path.to_string_lossy().to_string(),
+            errors: issues
+                .iter()
+                .filter(|i| i.severity == Severity::Error)
+                .count(),
+            warnings: issues
+                .iter()
+                .filter(|i| i.severity == Severity::Warning)
+                .count(),
+            issues,
+        }
+    }
+}
+
+/// Split entries at commas outside parentheses; None when parentheses don't balance
+pub fn split_tools(value: &str) -> Option<Vec<String>> {
+    let mut tools = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+    for c in value.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                tools.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if depth != 0 {
+        return None;
+    }
+    tools.push(current);
+    Some(
+        tools
+            .into_iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+    )
+}
+
+/// `Tool` or `Tool(pattern)`, as allowed-tools and agent tools expect
+pub fn is_tool(entry: &str) -> bool {
+    TOOL_RE.is_match(entry)
+}
+
+fn check_allowed_tools(
+    lint: &mut Lint,
+    value: &serde_yaml_ng::Value,
+    line: Option<usize>,
+) -> Vec<String> {
+    let entries: Vec<String> = match value {
+        serde_yaml_ng::Value::String(s) => match split_tools(s) {
+            Some(tools) => tools,
+            None => {
+                lint.push(
+                    Severity::Error,
+                    "invalid_allowed_tools",
+                    line,
+                    "allowed-tools has unbalanced parentheses".to_string(),
+                );
+                return Vec::new();
+            }
+        },
+        serde_yaml_ng::Value::Sequence(items) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.trim().to_string()))
+            .collect(),
+        _ => {
+            lint.push(
+                Severity::Error,
+                "invalid_allowed_tools",
+                line,
+                "allowed-tools must be a string or a list".to_string(),
+            );
+            return Vec::new();
+        }
+    };
+    for tool in &entries {
+        if !is_tool(tool) {
+            lint.push(
+                Severity::Error,
+                "invalid_allowed_tools",
+                line,
+                format!("'{}' is not a tool name or Tool(pattern)", tool),
+            );
+        }
+    }
+    entries
+}
+
+/// Line (1-based, in the file) of a top-level frontmatter key
+pub fn key_line(frontmatter: &str, key: &str) -> Option<usize> {
+    frontmatter
+        .lines()
+        .position(|l| {
+            !l.starts_with([' ', '\t']) && l.split_once(':').is_some_and(|(k, _)| k.trim() == key)
+        })
+        .map(|i| i + 2)
+}
+
+/// A run of three or more backticks or tildes opening a code block
+#[derive(Debug, Clone, Copy)]
+struct Fence {
+    marker: char,
+    len: usize,
+}
+
+impl Fence {
+    /// The fence a (left-trimmed) line starts with, if any
+    fn parse(trimmed: &str) -> Option<Fence> {
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+        let len = trimmed.chars().take_while(|c| *c == marker).count();
+        (len >= 3).then_some(Fence { marker, len })
+    }
+
+    /// Closes `open`: same character, at least as long, nothing after it
+    fn closes(self, open: Fence, trimmed: &str) -> bool {
+        self.marker == open.marker && self.len >= open.len && trimmed[self.len..].trim().is_empty()
+    }
+
+    fn marker(self) -> String {
+        self.marker.to_string().repeat(self.len)
+    }
+}
+
+/// Where `@` includes resolve from: the project for project commands, nowhere for global ones
+fn include_root(path: &Path) -> Option<PathBuf> {
+    match crate::command_files::scope_of(path)? {
+        crate::command_files::CommandScope::Project { project_path } => {
+            Some(PathBuf::from(project_path))
+        }
+        crate::command_files::CommandScope::Global => None,
+    }
+}
+
+/// Frontmatter of a markdown file as linted
+pub struct Frontmatter {
+    /// None when there is none or it isn't a valid mapping (already reported)
+    pub yaml: Option<serde_yaml_ng::Mapping>,
+    /// The lines between the fences
+    pub text: String,
+    /// Index of the first body line
+    pub body_start: usize,
+}
+
+/// Parse the lines between the opening and closing fences, reporting what's malformed
+pub fn read_frontmatter(lint: &mut Lint, lines: &[&str]) -> Frontmatter {
+    let mut body_start = 0;
+    let mut yaml: Option<serde_yaml_ng::Mapping> = None;
+    let mut frontmatter = String::new();
+    if lines.first().is_some_and(|l| l.trim_end() == "---") {
+        match lines.iter().skip(1).position(|l| l.trim_end() == "---") {
+            None => lint.push(
+                Severity::Error,
+                "unclosed_frontmatter",
+                Some(1),
+                "Frontmatter has no closing ---".to_string(),
+            ),
+            Some(end) => {
+                frontmatter = lines[1..end + 1].join("\n");
+                body_start = end + 2;
+                match serde_yaml_ng::from_str::<serde_yaml_ng::Value>(&frontmatter) {
+                    Ok(serde_yaml_ng::Value::Mapping(map)) => yaml = Some(map),
+                    Ok(serde_yaml_ng::Value::Null) => yaml = Some(serde_yaml_ng::Mapping::new()),
+                    Ok(_) => lint.push(
+                        Severity::Error,
+                        "invalid_yaml",
+                        Some(2),
+                        "Frontmatter must be a mapping of keys to values".to_string(),
+                    ),
+                    Err(e) => lint.push(
+                        Severity::Error,
+                        "invalid_yaml",
+                        e.location().map(|l| l.line() + 1),
+                        format!("Frontmatter is not valid YAML: {}", e),
+                    ),
+                }
+            }
+        }
+    }
+    Frontmatter {
+        yaml,
+        text: frontmatter,
+        body_start,
+    }
+}
+
+/// Validate the command file at `path`
+pub fn validate(path: &Path) -> Result<CommandValidation, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut lint = Lint { issues: Vec::new() };
+    let lines: Vec<&str> = content.lines().collect();
+    let Frontmatter {
+        yaml,
+        text: frontmatter,
+        body_start,
+    } = read_frontmatter(&mut lint, &lines);
+
+    let mut allowed_tools: Option<Vec<String>> = None;
+    let mut has_argument_hint = false;
+    if let Some(map) = &yaml {
+        for (key, value) in map {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            let line = key_line(&frontmatter, key);
+            if !KNOWN_KEYS.contains(&key) {
+                lint.push(
+                    Severity::Warning,
+                    "unknown_key",
+                    line,
+                    format!("Unknown frontmatter key '{}'", key),
+                );
+            }
+            match key {
+                "allowed-tools" => {
+                    allowed_tools = Some(check_allowed_tools(&mut lint, value, line))
+                }
+                // `argument-hint: [message]` parses as a list, which is fine too
+                "argument-hint" => {
+                    has_argument_hint =
+                        !value.is_null() && value.as_str().is_none_or(|s| !s.trim().is_empty())
+                }
+                "description" if !value.is_string() => lint.push(
+                    Severity::Warning,
+                    "invalid_value",
+                    line,
+                    "description should be a single string".to_string(),
+                ),
+                "disable-model-invocation" if !value.is_bool() => lint.push(
+                    Severity::Warning,
+                    "invalid_value",
+                    line,
+                    "disable-model-invocation should be true or false".to_string(),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    // Body, skipping fenced code for everything but the fence check itself
+    let root = include_root(path);
+    let mut open_fence: Option<(usize, Fence)> = None;
+    let mut first_argument: Option<usize> = None;
+    let mut first_bash_line: Option<usize> = None;
+    for (index, line) in lines.iter().enumerate().skip(body_start) {
+        let line_no = index + 1;
+        let trimmed = line.trim_start();
+        let fence = Fence::parse(trimmed);
+        match (open_fence, fence) {
+            (None, Some(fence)) => {
+                open_fence = Some((line_no, fence));
+                continue;
+            }
+            (Some((_, open)), Some(fence)) if fence.closes(open, trimmed) => {
+                open_fence = None;
+                continue;
+            }
+            (Some(_), _) => continue,
+            _ => {}
+        }
+
+        if first_argument.is_none() && ARGUMENT_RE.is_match(line) {
+            first_argument = Some(line_no);
+        }
+        if first_bash_line.is_none() && BASH_LINE_RE.is_match(line) {
+            first_bash_line = Some(line_no);
+        }
+        for capture in crate::attachments::mention_regex().captures_iter(line) {
+            let mention = capture[1].trim_end_matches('.');
+            if !mention.contains(['/', '.']) {
+                continue;
+            }
+            let resolved = match mention.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+                None if Path::new(mention).is_absolute() => Some(PathBuf::from(mention)),
+                None => root.as_ref().map(|root| root.join(mention)),
+            };
+            if resolved.is_some_and(|p| !p.exists()) {
+                lint.push(
+                    Severity::Warning,
+                    "broken_include",
+                    Some(line_no),
+                    format!("@{} does not exist", mention),
+                );
+            }
+        }
+    }
+
+    if let Some((line, fence)) = open_fence {
+        lint.push(
+            Severity::Error,
+            "unclosed_code_fence",
+            Some(line),
+            format!("Code fence {} is never closed", fence.marker()),
+        );
+    }
+    if let Some(line) = first_argument.filter(|_| !has_argument_hint) {
+        lint.push(
+            Severity::Warning,
+            "missing_argument_hint",
+            Some(line),
+            "Arguments are used but there is no argument-hint".to_string(),
+        );
+    }
+    let bash_allowed = allowed_tools
+        .as_ref()
+        .is_none_or(|tools| tools.iter().any(|t| t == "Bash" || t.starts_with("Bash(")));
+    if let Some(line) = first_bash_line.filter(|_| !bash_allowed) {
+        lint.push(
+            Severity::Warning,
+            "bash_not_allowed",
+            Some(line),
+            "!`...` runs Bash, which allowed-tools doesn't include".to_string(),
+        );
+    }
+
+    Ok(lint.finish(path))
+}
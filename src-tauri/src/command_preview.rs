@@ -0,0 +1,304 @@
+//! What Claude Code would send for a command
+//!
+//! Mirrors how a slash command is expanded: `$ARGUMENTS` and `$1`..`$9` are
+//! substituted first (arguments are appended when the body uses neither),
+//! then each !`...` snippet is replaced by its output, run with the user's
+//! shell in the chosen project. `@file` references stay in the prompt and the
+//! files are returned alongside, as Claude Code attaches them.
+
+use regex::Regex;
+use serde::Serialize;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+/// How long a shell snippet may run before it is killed
+const SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long to keep reading output once the shell is gone; background jobs
+/// it started may hold the pipes open
+const OUTPUT_GRACE: Duration = Duration::from_millis(500);
+/// Larger included files are returned truncated
+const MAX_INCLUDE_BYTES: usize = 256 * 1024;
+
+static ARGUMENT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$(ARGUMENTS\b|[1-9]\b)").expect("valid argument regex"));
+static SHELL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!`([^`]+)`").expect("valid shell snippet regex"));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShellSnippet {
+    pub command: String,
+    /// stdout followed by stderr; None in a dry run
+    pub output: Option<String>,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IncludedFile {
+    /// As written after the @
+    pub reference: String,
+    pub path: Option<String>,
+    pub content: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandPreview {
+    pub name: String,
+    pub prompt: String,
+    pub shell: Vec<ShellSnippet>,
+    pub includes: Vec<IncludedFile>,
+    pub dry_run: bool,
+}
+
+/// Split arguments at whitespace, keeping quoted parts together
+fn split_arguments(arguments: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_part = false;
+    for c in arguments.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_part = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_part {
+                    parts.push(std::mem::take(&mut current));
+                    in_part = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_part = true;
+            }
+        }
+    }
+    if in_part {
+        parts.push(current);
+    }
+    parts
+}
+
+fn substitute_arguments(body: &str, arguments: &str) -> String {
+    let arguments = arguments.trim();
+    if !ARGUMENT_RE.is_match(body) {
+        if arguments.is_empty() {
+            return body.to_string();
+        }
+        return format!("{}\n\nARGUMENTS: {}", body.trim_end(), arguments);
+    }
+    let positional = split_arguments(arguments);
+    ARGUMENT_RE
+        .replace_all(body, |caps: &regex::Captures| match &caps[1] {
+            "ARGUMENTS" => arguments.to_string(),
+            n => n
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| positional.get(n - 1).cloned())
+                .unwrap_or_default(),
+        })
+        .to_string()
+}
+
+/// Kill the snippet's shell and, on unix, every process in its group
+fn kill_snippet(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    unsafe {
+        // The shell leads its own group (see `run_snippet`), so -pid is all of it
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+/// Run `command` with the user's shell in `cwd`, killing it and whatever it
+/// started after SHELL_TIMEOUT
+fn run_snippet(command: &str, cwd: &Path) -> ShellSnippet {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let mut builder = std::process::Command::new(&shell);
+    builder
+        .args(["-lc", command])
+        .current_dir(cwd)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Own process group, so pipelines and background jobs can be killed with it
+        builder.process_group(0);
+    }
+    let child = builder.spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            return ShellSnippet {
+                command: command.to_string(),
+                output: Some(format!("Failed to run {}: {}", shell, e)),
+                exit_code: None,
+                timed_out: false,
+            }
+        }
+    };
+
+    // Drain both pipes while waiting so a chatty command can't block on a full pipe
+    let (sender, outputs) = std::sync::mpsc::channel::<(usize, String)>();
+    let pipes: [Option<Box<dyn Read + Send>>; 2] = [
+        child
+            .stdout
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    ];
+    for (index, pipe) in pipes.into_iter().enumerate() {
+        let sender = sender.clone();
+        std::thread::spawn(move || {
+            let mut buf = String::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_string(&mut buf);
+            }
+            let _ = sender.send((index, buf));
+        });
+    }
+    drop(sender);
+
+    let deadline = Instant::now() + SHELL_TIMEOUT;
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() >= deadline => {
+                kill_snippet(&mut child);
+                timed_out = true;
+                break child.wait().ok();
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(_) => break None,
+        }
+    };
+
+    // Don't wait on readers past the grace period: a leftover background job
+    // keeps its pipe open, so it is killed, which lets the readers finish
+    let mut reads_until = Instant::now() + OUTPUT_GRACE;
+    let mut killed = false;
+    let mut parts = [String::new(), String::new()];
+    let mut received = 0;
+    while received < parts.len() {
+        match outputs.recv_timeout(reads_until.saturating_duration_since(Instant::now())) {
+            Ok((index, text)) => {
+                parts[index] = text;
+                received += 1;
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) if !killed => {
+                kill_snippet(&mut child);
+                killed = true;
+                reads_until = Instant::now() + OUTPUT_GRACE;
+            }
+            Err(_) => break,
+        }
+    }
+    let [mut output, errors] = parts;
+    output.push_str(&errors);
+    ShellSnippet {
+        command: command.to_string(),
+        output: Some(output.trim_end().to_string()),
+        exit_code: status.and_then(|s| s.code()),
+        timed_out,
+    }
+}
+
+fn include(reference: &str, root: Option<&Path>) -> IncludedFile {
+    let resolved = match reference.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+        None if Path::new(reference).is_absolute() => Some(PathBuf::from(reference)),
+        None => root.map(|root| root.join(reference)),
+    };
+    let mut file = IncludedFile {
+        reference: reference.to_string(),
+        path: resolved.as_ref().map(|p| p.to_string_lossy().to_string()),
+        content: None,
+        error: None,
+    };
+    let Some(path) = resolved else {
+        file.error = Some("Choose a project to resolve relative paths".to_string());
+        return file;
+    };
+    match std::fs::read(&path) {
+        Ok(bytes) => {
+            let end = bytes.len().min(MAX_INCLUDE_BYTES);
+            file.content = Some(String::from_utf8_lossy(&bytes[..end]).to_string());
+        }
+        Err(e) => file.error = Some(e.to_string()),
+    }
+    file
+}
+
+/// Expand the command at `path` with `arguments`; with `dry_run` shell snippets
+/// are listed but not run and stay as written in the prompt
+pub fn render(
+    path: &Path,
+    arguments: &str,
+    project_path: Option<&str>,
+    dry_run: bool,
+) -> Result<CommandPreview, String> {
+    let command = crate::command_files::read(path)?;
+    let root = project_path.map(PathBuf::from);
+    if root.as_ref().is_some_and(|r| !r.is_dir()) {
+        return Err(format!(
+            "Directory not found: {}",
+            project_path.unwrap_or("")
+        ));
+    }
+    let cwd = root
+        .clone()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let mut prompt = substitute_arguments(&command.content, arguments);
+
+    let mut shell = Vec::new();
+    if dry_run {
+        for caps in SHELL_RE.captures_iter(&prompt) {
+            shell.push(ShellSnippet {
+                command: caps[1].to_string(),
+                output: None,
+                exit_code: None,
+                timed_out: false,
+            });
+        }
+    } else {
+        prompt = SHELL_RE
+            .replace_all(&prompt, |caps: &regex::Captures| {
+                let snippet = run_snippet(&caps[1], &cwd);
+                let output = snippet.output.clone().unwrap_or_default();
+                shell.push(snippet);
+                output
+            })
+            .to_string();
+    }
+
+    let mut includes: Vec<IncludedFile> = Vec::new();
+    for caps in crate::attachments::mention_regex().captures_iter(&prompt) {
+        let reference = caps[1].trim_end_matches('.');
+        if !reference.contains(['/', '.']) || includes.iter().any(|i| i.reference == reference) {
+            continue;
+        }
+        includes.push(include(reference, root.as_deref()));
+    }
+
+    Ok(CommandPreview {
+        name: command.name,
+        prompt,
+        shell,
+        includes,
+        dry_run,
+    })
+}
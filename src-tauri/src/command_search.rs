@@ -0,0 +1,163 @@
+//! Full-text search over commands
+//!
+//! Commands are few and small, so they get their own in-memory tantivy index
+//! rather than documents in the session index. It covers name, description,
+//! tags and body with the same jieba tokenizer, and is rebuilt only when the
+//! set of commands or their content changes.
+
+use crate::LocalCommand;
+use serde::Serialize;
+use std::sync::Mutex;
+use tantivy::collector::TopDocs;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, STORED};
+use tantivy::{doc, Index, SnippetGenerator};
+
+static INDEX: Mutex<Option<CommandIndex>> = Mutex::new(None);
+
+struct CommandIndex {
+    /// Hash of every indexed command's path and content
+    fingerprint: u64,
+    index: Index,
+    commands: Vec<LocalCommand>,
+    fields: Fields,
+}
+
+#[derive(Clone, Copy)]
+struct Fields {
+    /// Position in `CommandIndex::commands`
+    position: Field,
+    name: Field,
+    description: Field,
+    tags: Field,
+    body: Field,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandSearchResult {
+    pub command: LocalCommand,
+    pub score: f32,
+    /// Best matching passage of the body, empty when only other fields matched
+    pub snippet: String,
+}
+
+fn fingerprint(commands: &[LocalCommand]) -> u64 {
+    let mut key = String::new();
+    for command in commands {
+        key.push_str(&command.path);
+        key.push('\0');
+        key.push_str(command.frontmatter.as_deref().unwrap_or(""));
+        key.push('\0');
+        key.push_str(&command.content);
+        key.push('\0');
+    }
+    crate::attachments::fnv1a(&key)
+}
+
+fn build(commands: Vec<LocalCommand>, fingerprint: u64) -> Result<CommandIndex, String> {
+    let text_options = TextOptions::default()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(crate::JIEBA_TOKENIZER_NAME)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        )
+        .set_stored();
+    let mut schema_builder = Schema::builder();
+    let fields = Fields {
+        position: schema_builder.add_u64_field("position", STORED),
+        name: schema_builder.add_text_field("name", text_options.clone()),
+        description: schema_builder.add_text_field("description", text_options.clone()),
+        tags: schema_builder.add_text_field("tags", text_options.clone()),
+        body: schema_builder.add_text_field("body", text_options),
+    };
+
+    let index = Index::create_in_ram(schema_builder.build());
+    crate::register_jieba_tokenizer(&index);
+    let mut writer = index
+        .writer::<tantivy::TantivyDocument>(15_000_000)
+        .map_err(|e| e.to_string())?;
+    for (position, command) in commands.iter().enumerate() {
+        // Index "git/commit" as "git commit" so each segment is a word
+        let name = command
+            .name
+            .trim_start_matches('/')
+            .replace(['/', ':'], " ");
+        writer
+            .add_document(doc!(
+                fields.position => position as u64,
+                fields.name => name,
+                fields.description => command.description.clone().unwrap_or_default(),
+                fields.tags => command.tags.join(" "),
+                fields.body => command.content.clone(),
+            ))
+            .map_err(|e| e.to_string())?;
+    }
+    writer.commit().map_err(|e| e.to_string())?;
+
+    Ok(CommandIndex {
+        fingerprint,
+        index,
+        commands,
+        fields,
+    })
+}
+
+/// Commands matching `query`, best first. Project commands of `project_path` are
+/// searched along with the global ones.
+pub fn search(
+    query: &str,
+    limit: usize,
+    project_path: Option<&str>,
+) -> Result<Vec<CommandSearchResult>, String> {
+    let mut commands = crate::collect_local_commands()?;
+    if let Some(project_path) = project_path {
+        commands.extend(crate::project_assets::list(project_path)?.commands);
+    }
+    let fingerprint = fingerprint(&commands);
+
+    let mut guard = INDEX.lock().map_err(|e| e.to_string())?;
+    if guard.as_ref().is_none_or(|i| i.fingerprint != fingerprint) {
+        *guard = Some(build(commands, fingerprint)?);
+    }
+    let Some(command_index) = guard.as_ref() else {
+        return Ok(Vec::new());
+    };
+    let fields = command_index.fields;
+
+    let mut parser = QueryParser::for_index(
+        &command_index.index,
+        vec![fields.name, fields.description, fields.tags, fields.body],
+    );
+    parser.set_field_boost(fields.name, 3.0);
+    parser.set_field_boost(fields.description, 2.0);
+    parser.set_field_boost(fields.tags, 2.0);
+    // Stray quotes or colons in what the user typed shouldn't fail the search
+    let (parsed, _) = parser.parse_query_lenient(query);
+
+    let reader = command_index.index.reader().map_err(|e| e.to_string())?;
+    let searcher = reader.searcher();
+    let top_docs = searcher
+        .search(&parsed, &TopDocs::with_limit(limit.max(1)))
+        .map_err(|e| e.to_string())?;
+    let snippets =
+        SnippetGenerator::create(&searcher, &*parsed, fields.body).map_err(|e| e.to_string())?;
+
+    let mut results = Vec::new();
+    for (score, address) in top_docs {
+        let document: tantivy::TantivyDocument =
+            searcher.doc(address).map_err(|e| e.to_string())?;
+        let Some(command) = document
+            .get_first(fields.position)
+            .and_then(|v| tantivy::schema::Value::as_u64(&v))
+            .and_then(|position| command_index.commands.get(position as usize))
+        else {
+            continue;
+        };
+        results.push(CommandSearchResult {
+            command: command.clone(),
+            score,
+            snippet: snippets.snippet_from_doc(&document).fragment().to_string(),
+        });
+    }
+    Ok(results)
+}
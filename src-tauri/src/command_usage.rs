@@ -0,0 +1,406 @@
+//! Slash command usage over time
+//!
+//! Every `<command-name>` in session history is recorded as an occurrence
+//! (command, project, time), persisted per session file in
+//! ~/.lovstudio/lovcode/command-usage.json together with how far the file was
+//! read, so only lines appended since the last call are parsed. Counts per
+//! project, ISO week and month are derived from the occurrences.
+//!
+//! Names are resolved through command aliases when counting, so uses of a
+//! command under a previous name count for the command it was renamed to.
+//! The same resolution decides which commands are stale: unused altogether,
+//! or not used within a number of days.
+//!
+//! Subagent launches (Task tool calls and their `subagent_type`) are recorded
+//! in the same pass and reported the same way by `agent_report`.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Bump when the recorded occurrences change
+const CACHE_VERSION: u32 = 2;
+/// Tools that launch a subagent; newer Claude Code versions call it Agent
+const AGENT_TOOLS: &[&str] = &["Task", "Agent"];
+
+static CACHE: LazyLock<Mutex<Option<CacheData>>> = LazyLock::new(|| Mutex::new(None));
+static COMMAND_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<command-name>(/[^<]+)</command-name>").expect("valid command name regex")
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Occurrence {
+    /// Command name without the leading slash
+    name: String,
+    /// Unix seconds, 0 when the line had no timestamp
+    timestamp: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct FileUsage {
+    project_id: String,
+    /// Bytes read so far, always at a line boundary
+    offset: u64,
+    uses: Vec<Occurrence>,
+    /// Subagent launches, named by subagent_type
+    #[serde(default)]
+    agents: Vec<Occurrence>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheData {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    files: HashMap<String, FileUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandUsage {
+    /// Command name without the leading slash, as in get_command_stats
+    pub name: String,
+    pub total: usize,
+    /// Project id → uses
+    pub by_project: HashMap<String, usize>,
+    /// ISO week ("2025-W07") → uses, in local time
+    pub by_week: BTreeMap<String, usize>,
+    /// Month ("2025-02") → uses, in local time
+    pub by_month: BTreeMap<String, usize>,
+    /// Previous name (alias) → uses under that name, included in `total`
+    pub by_alias: HashMap<String, usize>,
+    pub first_used: u64,
+    pub last_used: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleCommand {
+    pub command: crate::LocalCommand,
+    /// Uses ever recorded, aliases included
+    pub total: usize,
+    /// Unix seconds of the last use, None when never used or its time is unknown
+    pub last_used: Option<u64>,
+}
+
+/// Launches of one subagent type; `by_alias` stays empty
+pub type AgentUsage = CommandUsage;
+
+fn get_cache_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("command-usage.json")
+}
+
+fn load_cache() -> CacheData {
+    let data = fs::read_to_string(get_cache_path())
+        .ok()
+        .and_then(|content| serde_json::from_str::<CacheData>(&content).ok())
+        .unwrap_or_default();
+    if data.version != CACHE_VERSION {
+        return CacheData {
+            version: CACHE_VERSION,
+            files: HashMap::new(),
+        };
+    }
+    data
+}
+
+fn save_cache(data: &CacheData) -> Result<(), String> {
+    let path = get_cache_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string(data).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn value_timestamp(value: &serde_json::Value) -> u64 {
+    value
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.timestamp().max(0) as u64)
+        .unwrap_or(0)
+}
+
+fn line_timestamp(line: &str) -> u64 {
+    serde_json::from_str::<serde_json::Value>(line)
+        .map(|v| value_timestamp(&v))
+        .unwrap_or(0)
+}
+
+/// Subagents launched by the tool_use blocks of an assistant line
+fn record_agents(line: &str, agents: &mut Vec<Occurrence>) {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+        return;
+    };
+    let Some(blocks) = value
+        .get("message")
+        .and_then(|m| m.get("content"))
+        .and_then(|c| c.as_array())
+    else {
+        return;
+    };
+    let timestamp = value_timestamp(&value);
+    for block in blocks {
+        let is_launch = block.get("type").and_then(|t| t.as_str()) == Some("tool_use")
+            && block
+                .get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|n| AGENT_TOOLS.contains(&n));
+        let agent = block
+            .get("input")
+            .and_then(|i| i.get("subagent_type"))
+            .and_then(|a| a.as_str())
+            .filter(|_| is_launch);
+        if let Some(agent) = agent {
+            agents.push(Occurrence {
+                name: agent.to_string(),
+                timestamp,
+            });
+        }
+    }
+}
+
+/// Record occurrences in the complete lines appended to `path` since `file.offset`
+fn read_new_lines(path: &Path, file: &mut FileUsage) {
+    let Ok(mut handle) = fs::File::open(path) else {
+        return;
+    };
+    if handle.seek(SeekFrom::Start(file.offset)).is_err() {
+        return;
+    }
+    let mut appended = Vec::new();
+    if handle.read_to_end(&mut appended).is_err() {
+        return;
+    }
+    // Leave a partly written last line for the next call
+    let Some(end) = appended.iter().rposition(|b| *b == b'\n') else {
+        return;
+    };
+    let appended = String::from_utf8_lossy(&appended[..=end]);
+    for line in appended.lines() {
+        if line.contains("\"subagent_type\"") {
+            record_agents(line, &mut file.agents);
+        }
+        if !line.contains("<command-name>") {
+            continue;
+        }
+        let timestamp = line_timestamp(line);
+        for capture in COMMAND_NAME_RE.captures_iter(line) {
+            file.uses.push(Occurrence {
+                name: capture[1].trim_start_matches('/').to_string(),
+                timestamp,
+            });
+        }
+    }
+    file.offset += end as u64 + 1;
+}
+
+/// Bring the cache up to date with every session file and return it
+fn refresh(guard: &mut Option<CacheData>) -> Result<&CacheData, String> {
+    let cache = guard.get_or_insert_with(load_cache);
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let mut changed = false;
+    let mut seen = std::collections::HashSet::new();
+
+    for project_entry in fs::read_dir(&projects_dir).into_iter().flatten().flatten() {
+        if !project_entry.path().is_dir() {
+            continue;
+        }
+        let project_id = project_entry.file_name().to_string_lossy().to_string();
+        for session_entry in fs::read_dir(project_entry.path())
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let name = session_entry.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".jsonl") || name.starts_with("agent-") {
+                continue;
+            }
+            let path = session_entry.path();
+            let key = path.to_string_lossy().to_string();
+            let size = session_entry.metadata().map(|m| m.len()).unwrap_or(0);
+            seen.insert(key.clone());
+
+            let file = cache.files.entry(key).or_insert_with(|| FileUsage {
+                project_id: project_id.clone(),
+                ..Default::default()
+            });
+            // Rewritten: start over
+            if size < file.offset {
+                *file = FileUsage {
+                    project_id: project_id.clone(),
+                    ..Default::default()
+                };
+                changed = true;
+            }
+            if size > file.offset {
+                let before = file.offset;
+                read_new_lines(&path, file);
+                changed |= file.offset != before;
+            }
+        }
+    }
+
+    let before = cache.files.len();
+    cache.files.retain(|key, _| seen.contains(key));
+    if changed || cache.files.len() != before {
+        save_cache(cache)?;
+    }
+    Ok(cache)
+}
+
+/// Alias → current name, both without the leading slash. An alias that is also
+/// the name of an existing command stays that command's own.
+fn alias_targets() -> HashMap<String, String> {
+    let commands = crate::collect_local_commands().unwrap_or_default();
+    let names: std::collections::HashSet<&str> = commands
+        .iter()
+        .map(|c| c.name.trim_start_matches('/'))
+        .collect();
+    let mut targets = HashMap::new();
+    for command in &commands {
+        let name = command.name.trim_start_matches('/');
+        for alias in &command.aliases {
+            let alias = alias.trim_start_matches('/');
+            if alias != name && !names.contains(alias) {
+                targets
+                    .entry(alias.to_string())
+                    .or_insert_with(|| name.to_string());
+            }
+        }
+    }
+    targets
+}
+
+/// Uses per command name (without the leading slash), aliases counted for their command
+pub fn counts() -> Result<HashMap<String, usize>, String> {
+    let aliases = alias_targets();
+    let mut guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = refresh(&mut guard)?;
+    let mut counts = HashMap::new();
+    for occurrence in cache.files.values().flat_map(|f| &f.uses) {
+        let name = aliases.get(&occurrence.name).unwrap_or(&occurrence.name);
+        *counts.entry(name.clone()).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// Usage of every command broken down by project, week and month, most used first
+pub fn report() -> Result<Vec<CommandUsage>, String> {
+    let aliases = alias_targets();
+    let mut guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = refresh(&mut guard)?;
+    Ok(build_report(cache, |file| &file.uses, &aliases))
+}
+
+/// Subagent launches per agent, broken down like `report`, most used first
+pub fn agent_report() -> Result<Vec<AgentUsage>, String> {
+    let mut guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = refresh(&mut guard)?;
+    Ok(build_report(cache, |file| &file.agents, &HashMap::new()))
+}
+
+fn build_report(
+    cache: &CacheData,
+    occurrences: impl Fn(&FileUsage) -> &Vec<Occurrence>,
+    aliases: &HashMap<String, String>,
+) -> Vec<CommandUsage> {
+    let mut by_name: HashMap<&str, CommandUsage> = HashMap::new();
+    for file in cache.files.values() {
+        for occurrence in occurrences(file) {
+            let alias_of = aliases.get(&occurrence.name);
+            let name = alias_of.unwrap_or(&occurrence.name);
+            let usage = by_name.entry(name).or_insert_with(|| CommandUsage {
+                name: name.clone(),
+                total: 0,
+                by_project: HashMap::new(),
+                by_week: BTreeMap::new(),
+                by_month: BTreeMap::new(),
+                by_alias: HashMap::new(),
+                first_used: 0,
+                last_used: 0,
+            });
+            usage.total += 1;
+            if alias_of.is_some() {
+                *usage.by_alias.entry(occurrence.name.clone()).or_insert(0) += 1;
+            }
+            *usage.by_project.entry(file.project_id.clone()).or_insert(0) += 1;
+
+            let Some(time) = chrono::DateTime::from_timestamp(occurrence.timestamp as i64, 0)
+                .filter(|_| occurrence.timestamp > 0)
+            else {
+                continue;
+            };
+            let local = time.with_timezone(&chrono::Local);
+            let week = chrono::Datelike::iso_week(&local);
+            *usage
+                .by_week
+                .entry(format!("{}-W{:02}", week.year(), week.week()))
+                .or_insert(0) += 1;
+            *usage
+                .by_month
+                .entry(local.format("%Y-%m").to_string())
+                .or_insert(0) += 1;
+            if usage.first_used == 0 || occurrence.timestamp < usage.first_used {
+                usage.first_used = occurrence.timestamp;
+            }
+            usage.last_used = usage.last_used.max(occurrence.timestamp);
+        }
+    }
+
+    let mut usages: Vec<CommandUsage> = by_name.into_values().collect();
+    usages.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+    usages
+}
+
+/// Active commands never used, or with `days` not used in that many days; never
+/// used ones first, then least recently used
+pub fn stale(days: Option<u64>) -> Result<Vec<StaleCommand>, String> {
+    let aliases = alias_targets();
+    let mut last_use: HashMap<String, (usize, u64)> = HashMap::new();
+    {
+        let mut guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        let cache = refresh(&mut guard)?;
+        for occurrence in cache.files.values().flat_map(|f| &f.uses) {
+            let name = aliases.get(&occurrence.name).unwrap_or(&occurrence.name);
+            let entry = last_use.entry(name.clone()).or_insert((0, 0));
+            entry.0 += 1;
+            entry.1 = entry.1.max(occurrence.timestamp);
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let cutoff = days.map(|days| now.saturating_sub(days.saturating_mul(24 * 60 * 60)));
+    let mut stale: Vec<StaleCommand> = crate::collect_local_commands()?
+        .into_iter()
+        .filter(|c| c.status == "active")
+        .filter_map(|command| {
+            let (total, last) = last_use
+                .get(command.name.trim_start_matches('/'))
+                .copied()
+                .unwrap_or((0, 0));
+            let is_stale = match cutoff {
+                Some(cutoff) => last < cutoff,
+                None => total == 0,
+            };
+            is_stale.then(|| StaleCommand {
+                command,
+                total,
+                last_used: Some(last).filter(|t| *t > 0),
+            })
+        })
+        .collect();
+    stale.sort_by(|a, b| {
+        (a.total > 0, a.last_used)
+            .cmp(&(b.total > 0, b.last_used))
+            .then_with(|| a.command.name.cmp(&b.command.name))
+    });
+    Ok(stale)
+}
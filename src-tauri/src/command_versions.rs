@@ -0,0 +1,149 @@
+//! Previous revisions of a command
+//!
+//! Saving a command through lovcode first snapshots the content it replaces
+//! into the `versions/` dir next to it, as <name>.<unix ms>.md.snapshot. The
+//! suffix keeps snapshots out of the command list and out of Claude Code;
+//! versions archived by hand (<name>.v<version>.md.archived) live in the same
+//! dir and are listed alongside them.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Snapshots kept per command; older ones are dropped on save
+const MAX_SNAPSHOTS: usize = 30;
+const SNAPSHOT_SUFFIX: &str = ".md.snapshot";
+const ARCHIVED_SUFFIX: &str = ".md.archived";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionKind {
+    /// Taken automatically on save
+    Snapshot,
+    /// Archived with archive_command
+    Archived,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandVersion {
+    pub path: String,
+    pub kind: VersionKind,
+    /// Version label of an archived version
+    pub version: Option<String>,
+    /// Unix ms the snapshot was taken, or the archived file's mtime
+    pub created_at: u64,
+    pub size_bytes: u64,
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// The versions dir and file name stem of an active command
+fn locate(path: &Path) -> Result<(PathBuf, String), String> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let Some(base) = name.strip_suffix(".md") else {
+        return Err(format!("Not an active command: {}", path.display()));
+    };
+    let dir = path.parent().ok_or("Cannot get parent directory")?;
+    Ok((dir.join("versions"), base.to_string()))
+}
+
+/// Copy the current content of `path` into its versions dir; None when it doesn't exist
+pub fn snapshot(path: &Path) -> Result<Option<CommandVersion>, String> {
+    let Ok(content) = fs::read(path) else {
+        return Ok(None);
+    };
+    let (versions_dir, base) = locate(path)?;
+    fs::create_dir_all(&versions_dir).map_err(|e| e.to_string())?;
+
+    let mut created_at = now_millis();
+    let snapshot_path = loop {
+        let candidate = versions_dir.join(format!("{}.{}{}", base, created_at, SNAPSHOT_SUFFIX));
+        if !candidate.exists() {
+            break candidate;
+        }
+        created_at += 1;
+    };
+    fs::write(&snapshot_path, &content).map_err(|e| e.to_string())?;
+
+    let snapshots = list(path)?
+        .into_iter()
+        .filter(|v| matches!(v.kind, VersionKind::Snapshot));
+    for old in snapshots.skip(MAX_SNAPSHOTS) {
+        let _ = fs::remove_file(&old.path);
+    }
+
+    Ok(Some(CommandVersion {
+        path: snapshot_path.to_string_lossy().to_string(),
+        kind: VersionKind::Snapshot,
+        version: None,
+        created_at,
+        size_bytes: content.len() as u64,
+    }))
+}
+
+/// Snapshots and archived versions of a command, newest first
+pub fn list(path: &Path) -> Result<Vec<CommandVersion>, String> {
+    let (versions_dir, base) = locate(path)?;
+    let snapshot_prefix = format!("{}.", base);
+    let archived_prefix = format!("{}.v", base);
+
+    let mut versions: Vec<CommandVersion> = fs::read_dir(&versions_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let metadata = entry.metadata().ok()?;
+            let created_at = name
+                .strip_prefix(&snapshot_prefix)
+                .and_then(|rest| rest.strip_suffix(SNAPSHOT_SUFFIX))
+                .and_then(|ms| ms.parse::<u64>().ok());
+            let (kind, version, created_at) = match created_at {
+                Some(created_at) => (VersionKind::Snapshot, None, created_at),
+                None => {
+                    let version = name
+                        .strip_prefix(&archived_prefix)?
+                        .strip_suffix(ARCHIVED_SUFFIX)?;
+                    let mtime = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|d| d.as_millis() as u64)
+                        .unwrap_or(0);
+                    (VersionKind::Archived, Some(version.to_string()), mtime)
+                }
+            };
+            Some(CommandVersion {
+                path: entry.path().to_string_lossy().to_string(),
+                kind,
+                version,
+                created_at,
+                size_bytes: metadata.len(),
+            })
+        })
+        .collect();
+    versions.sort_by_key(|v| std::cmp::Reverse(v.created_at));
+    Ok(versions)
+}
+
+/// Put a version's content back into the command; the content it replaces is snapshotted first
+pub fn restore(path: &Path, version_path: &str) -> Result<(), String> {
+    let version_path = Path::new(version_path);
+    let known = list(path)?
+        .iter()
+        .any(|v| Path::new(&v.path) == version_path);
+    if !known {
+        return Err(format!("Not a version of {}", path.display()));
+    }
+    let content = fs::read_to_string(version_path).map_err(|e| e.to_string())?;
+    if fs::read_to_string(path).is_ok_and(|current| current == content) {
+        return Ok(());
+    }
+    snapshot(path)?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
@@ -0,0 +1,202 @@
+//! Compaction boundaries inside a session
+//!
+//! When the context fills up (or on `/compact`) Claude Code writes a
+//! `system` line with subtype `compact_boundary`, followed by a user message
+//! flagged `isCompactSummary` that carries the summary the conversation continues
+//! from. Older versions wrote only the summary message. Each boundary starts a
+//! new segment; segments carry their own stats so the viewer can show where
+//! context was compacted and what each stretch of work cost.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Characters of the compact summary kept on the boundary
+const SUMMARY_EXCERPT_CHARS: usize = 280;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CompactMetadata {
+    pub trigger: Option<String>,
+    #[serde(rename = "preTokens")]
+    pub pre_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactBoundary {
+    pub line_number: usize,
+    pub timestamp: Option<String>,
+    /// "auto" or "manual"
+    pub trigger: Option<String>,
+    /// Context size when it was compacted
+    pub pre_tokens: Option<u64>,
+    /// Start of the summary the conversation continued from
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSegment {
+    pub index: usize,
+    /// None for the first segment
+    pub boundary: Option<CompactBoundary>,
+    /// Index (in the filtered message list) of the first message of the segment
+    pub start_index: usize,
+    pub start_line: usize,
+    pub message_count: usize,
+    /// User messages with a prompt, not tool results or meta
+    pub user_prompts: usize,
+    pub tool_calls: usize,
+    pub output_tokens: u64,
+    pub first_timestamp: Option<String>,
+    pub last_timestamp: Option<String>,
+}
+
+impl SessionSegment {
+    fn new(
+        index: usize,
+        boundary: Option<CompactBoundary>,
+        start_index: usize,
+        start_line: usize,
+    ) -> Self {
+        SessionSegment {
+            index,
+            boundary,
+            start_index,
+            start_line,
+            message_count: 0,
+            user_prompts: 0,
+            tool_calls: 0,
+            output_tokens: 0,
+            first_timestamp: None,
+            last_timestamp: None,
+        }
+    }
+}
+
+/// Splits a session into segments while its lines are read in order
+#[derive(Default)]
+pub struct SegmentTracker {
+    segments: Vec<SessionSegment>,
+    /// A compact_boundary line was seen and its summary message not yet
+    awaiting_summary: bool,
+    /// Assistant message ids already counted (one response spans several lines)
+    counted_responses: HashSet<String>,
+}
+
+fn excerpt(text: &str) -> String {
+    let mut excerpt: String = text.chars().take(SUMMARY_EXCERPT_CHARS).collect();
+    if text.chars().count() > SUMMARY_EXCERPT_CHARS {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+impl SegmentTracker {
+    fn current(&mut self, next_index: usize, line_number: usize) -> &mut SessionSegment {
+        if self.segments.is_empty() {
+            self.segments
+                .push(SessionSegment::new(0, None, next_index, line_number));
+        }
+        let last = self.segments.len() - 1;
+        &mut self.segments[last]
+    }
+
+    fn start_segment(&mut self, boundary: CompactBoundary, next_index: usize) {
+        let index = self.segments.len();
+        let line_number = boundary.line_number;
+        self.segments.push(SessionSegment::new(
+            index,
+            Some(boundary),
+            next_index,
+            line_number,
+        ));
+    }
+
+    /// Feed a parsed line; `next_index` is the index the next returned message gets
+    pub fn observe(&mut self, line: &crate::RawLine, line_number: usize, next_index: usize) {
+        let line_type = line.line_type.as_deref();
+        if line_type == Some("system") && line.subtype.as_deref() == Some("compact_boundary") {
+            let metadata = line.compact_metadata.clone().unwrap_or_default();
+            self.start_segment(
+                CompactBoundary {
+                    line_number,
+                    timestamp: line.timestamp.clone(),
+                    trigger: metadata.trigger,
+                    pre_tokens: metadata.pre_tokens,
+                    summary: None,
+                },
+                next_index,
+            );
+            self.awaiting_summary = true;
+            return;
+        }
+        if line_type != Some("user") && line_type != Some("assistant") {
+            return;
+        }
+        let Some(message) = line.message.as_ref() else {
+            return;
+        };
+
+        if line.is_compact_summary.unwrap_or(false) {
+            let (text, _) = crate::extract_content_with_meta(&message.content);
+            let summary = (!text.trim().is_empty()).then(|| excerpt(text.trim()));
+            let has_messages = self.segments.last().is_some_and(|s| s.message_count > 0);
+            if self.awaiting_summary {
+                if let Some(boundary) = self.current(next_index, line_number).boundary.as_mut() {
+                    boundary.summary = summary;
+                }
+            } else if has_messages {
+                // Summary without a boundary line (older Claude Code)
+                self.start_segment(
+                    CompactBoundary {
+                        line_number,
+                        timestamp: line.timestamp.clone(),
+                        trigger: None,
+                        pre_tokens: None,
+                        summary,
+                    },
+                    next_index,
+                );
+            }
+            self.awaiting_summary = false;
+            return;
+        }
+
+        let is_prompt = line_type == Some("user")
+            && !line.is_meta.unwrap_or(false)
+            && match &message.content {
+                Some(serde_json::Value::String(_)) => true,
+                Some(serde_json::Value::Array(blocks)) => blocks
+                    .iter()
+                    .any(|b| b.get("type").and_then(|t| t.as_str()) == Some("text")),
+                _ => false,
+            };
+        let tool_calls = match &message.content {
+            Some(serde_json::Value::Array(blocks)) if line_type == Some("assistant") => blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+                .count(),
+            _ => 0,
+        };
+        let output_tokens = match (&message.id, &message.usage) {
+            (Some(id), Some(usage)) if self.counted_responses.insert(id.clone()) => {
+                usage.output_tokens
+            }
+            _ => 0,
+        };
+
+        let segment = self.current(next_index, line_number);
+        segment.message_count += 1;
+        segment.user_prompts += usize::from(is_prompt);
+        segment.tool_calls += tool_calls;
+        segment.output_tokens += output_tokens;
+        if let Some(timestamp) = &line.timestamp {
+            if segment.first_timestamp.is_none() {
+                segment.first_timestamp = Some(timestamp.clone());
+            }
+            segment.last_timestamp = Some(timestamp.clone());
+        }
+    }
+
+    pub fn finish(self) -> Vec<SessionSegment> {
+        self.segments
+    }
+}
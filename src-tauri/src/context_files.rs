@@ -0,0 +1,175 @@
+//! Editing CLAUDE.md and other context files
+//!
+//! Before a context file is overwritten, its current content is copied to
+//! ~/.lovstudio/lovcode/backups/context/<hash of path>/<unix ms>.md, so every
+//! save and restore can be undone. Only memory files (CLAUDE.md,
+//! CLAUDE.local.md) and project commands (.claude/commands/*.md) are writable.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Backups kept per file; older ones are dropped on save
+const MAX_BACKUPS: usize = 50;
+const MEMORY_FILE_NAMES: &[&str] = &["CLAUDE.md", "CLAUDE.local.md"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextBackup {
+    /// Unix ms the backup was taken; identifies it for restore
+    pub id: String,
+    pub path: String,
+    pub created_at: u64,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedContextFile {
+    pub path: String,
+    pub last_modified: u64,
+    /// Backup of the content that was replaced; None for a new file or no change
+    pub backup: Option<ContextBackup>,
+}
+
+fn get_backups_root() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("backups")
+        .join("context")
+}
+
+fn backup_dir(path: &Path) -> PathBuf {
+    let key = path.to_string_lossy();
+    get_backups_root().join(format!("{:016x}", crate::attachments::fnv1a(&key)))
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn last_modified(path: &Path) -> u64 {
+    fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Memory files anywhere, and markdown commands under .claude/commands
+fn check_writable(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!("Not an absolute path: {}", path.display()));
+    }
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let in_commands = path.extension().is_some_and(|e| e == "md")
+        && path
+            .parent()
+            .is_some_and(|dir| dir.ends_with(Path::new(".claude").join("commands")));
+    if MEMORY_FILE_NAMES.contains(&name) || in_commands {
+        Ok(())
+    } else {
+        Err(format!("Not a context file: {}", path.display()))
+    }
+}
+
+/// Copy the current content into the backup dir; None when there is no file
+fn backup(path: &Path) -> Result<Option<ContextBackup>, String> {
+    let Ok(content) = fs::read(path) else {
+        return Ok(None);
+    };
+    let dir = backup_dir(path);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    // The original path, since the dir name is only a hash of it
+    fs::write(dir.join("path.txt"), path.to_string_lossy().as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut created_at = now_millis();
+    while dir.join(format!("{}.md", created_at)).exists() {
+        created_at += 1;
+    }
+    fs::write(dir.join(format!("{}.md", created_at)), &content).map_err(|e| e.to_string())?;
+
+    let backups = history(&path.to_string_lossy());
+    for old in backups.iter().skip(MAX_BACKUPS) {
+        let _ = fs::remove_file(dir.join(format!("{}.md", old.id)));
+    }
+    Ok(Some(ContextBackup {
+        id: created_at.to_string(),
+        path: path.to_string_lossy().to_string(),
+        created_at,
+        size_bytes: content.len() as u64,
+    }))
+}
+
+/// Write `content`, backing up what it replaces; unchanged content is not written
+pub fn save(path: &str, content: &str) -> Result<SavedContextFile, String> {
+    let path = Path::new(path);
+    check_writable(path)?;
+    if let Some(parent) = path.parent() {
+        if !parent.is_dir() {
+            return Err(format!("Directory not found: {}", parent.display()));
+        }
+    }
+
+    let unchanged = fs::read_to_string(path).is_ok_and(|current| current == content);
+    let backup = if unchanged {
+        None
+    } else {
+        let backup = backup(path)?;
+        fs::write(path, content).map_err(|e| e.to_string())?;
+        backup
+    };
+    Ok(SavedContextFile {
+        path: path.to_string_lossy().to_string(),
+        last_modified: last_modified(path),
+        backup,
+    })
+}
+
+/// Backups of a file, newest first
+pub fn history(path: &str) -> Vec<ContextBackup> {
+    let mut backups: Vec<ContextBackup> = fs::read_dir(backup_dir(Path::new(path)))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let created_at: u64 = name.strip_suffix(".md")?.parse().ok()?;
+            Some(ContextBackup {
+                id: created_at.to_string(),
+                path: path.to_string(),
+                created_at,
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    backups
+}
+
+fn backup_file(path: &str, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid backup id: {}", id));
+    }
+    let file = backup_dir(Path::new(path)).join(format!("{}.md", id));
+    if !file.is_file() {
+        return Err(format!("Backup '{}' not found", id));
+    }
+    Ok(file)
+}
+
+/// Content of one backup, e.g. to diff against the current file
+pub fn read_backup(path: &str, id: &str) -> Result<String, String> {
+    fs::read_to_string(backup_file(path, id)?).map_err(|e| e.to_string())
+}
+
+/// Put a backup's content back; the content it replaces is backed up first
+pub fn restore(path: &str, id: &str) -> Result<SavedContextFile, String> {
+    let content = read_backup(path, id)?;
+    save(path, &content)
+}
@@ -0,0 +1,161 @@
+//! Conversation tree from `parentUuid` links
+//!
+//! Every session line points at its parent; rewinding and re-prompting makes a
+//! second child under the same parent, so a session is a tree rather than a list.
+//! The active path is the chain ending at the last line written, which is what
+//! Claude Code resumes from.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+const PREVIEW_CHARS: usize = 160;
+
+#[derive(Debug, Deserialize)]
+struct TreeLine {
+    uuid: Option<String>,
+    #[serde(rename = "parentUuid")]
+    parent_uuid: Option<String>,
+    /// Set on compact boundaries, whose `parentUuid` is null
+    #[serde(rename = "logicalParentUuid")]
+    logical_parent_uuid: Option<String>,
+    timestamp: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeNode {
+    pub uuid: String,
+    pub parent_uuid: Option<String>,
+    pub children: Vec<String>,
+    /// "user" | "assistant" | "tool" | "meta" | "other"
+    pub kind: String,
+    pub preview: String,
+    pub timestamp: Option<String>,
+    pub line_number: usize,
+    pub on_active_path: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversationTree {
+    /// Nodes in file order
+    pub nodes: Vec<TreeNode>,
+    pub roots: Vec<String>,
+    /// Nodes with more than one child
+    pub branch_points: Vec<String>,
+    /// Last node of every branch
+    pub leaves: Vec<String>,
+    /// Root-to-leaf uuids of the branch that was written last
+    pub active_path: Vec<String>,
+}
+
+fn node_kind(message: Option<&crate::Message>) -> &'static str {
+    match message {
+        Some(m) if m.is_meta => "meta",
+        Some(m) if m.is_tool => "tool",
+        Some(m) if m.role == "user" => "user",
+        Some(m) if m.role == "assistant" => "assistant",
+        _ => "other",
+    }
+}
+
+fn preview(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= PREVIEW_CHARS {
+        line
+    } else {
+        let cut: String = line.chars().take(PREVIEW_CHARS).collect();
+        format!("{}…", cut)
+    }
+}
+
+pub fn build(path: &Path) -> Result<ConversationTree, String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+
+    let mut nodes: Vec<TreeNode> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let Ok(line) = line else { continue };
+        let Ok(parsed) = serde_json::from_str::<TreeLine>(&line) else {
+            continue;
+        };
+        let Some(uuid) = parsed.uuid else { continue };
+        // Resumed sessions may replay lines that were already seen
+        if index.contains_key(&uuid) {
+            continue;
+        }
+
+        let message = crate::parse_message_line(&line, idx + 1);
+        let preview = message
+            .as_ref()
+            .map(|m| {
+                if m.content.is_empty() {
+                    m.tools
+                        .iter()
+                        .map(|t| t.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                } else {
+                    preview(&m.content)
+                }
+            })
+            .unwrap_or_default();
+
+        index.insert(uuid.clone(), nodes.len());
+        nodes.push(TreeNode {
+            uuid,
+            parent_uuid: parsed.parent_uuid.or(parsed.logical_parent_uuid),
+            children: Vec::new(),
+            kind: node_kind(message.as_ref()).to_string(),
+            preview,
+            timestamp: parsed.timestamp,
+            line_number: idx + 1,
+            on_active_path: false,
+        });
+    }
+
+    // Parents missing from the file (e.g. trimmed history) make their children roots
+    let mut roots = Vec::new();
+    for i in 0..nodes.len() {
+        let parent = nodes[i]
+            .parent_uuid
+            .as_ref()
+            .and_then(|p| index.get(p).copied());
+        match parent {
+            Some(p) => {
+                let uuid = nodes[i].uuid.clone();
+                nodes[p].children.push(uuid);
+            }
+            None => roots.push(nodes[i].uuid.clone()),
+        }
+    }
+
+    let mut active_path = Vec::new();
+    let mut cursor = nodes.last().map(|n| n.uuid.clone());
+    while let Some(uuid) = cursor {
+        let Some(&i) = index.get(&uuid) else { break };
+        if nodes[i].on_active_path {
+            break; // cycle guard
+        }
+        nodes[i].on_active_path = true;
+        active_path.push(uuid);
+        cursor = nodes[i].parent_uuid.clone();
+    }
+    active_path.reverse();
+
+    Ok(ConversationTree {
+        roots,
+        branch_points: nodes
+            .iter()
+            .filter(|n| n.children.len() > 1)
+            .map(|n| n.uuid.clone())
+            .collect(),
+        leaves: nodes
+            .iter()
+            .filter(|n| n.children.is_empty())
+            .map(|n| n.uuid.clone())
+            .collect(),
+        active_path,
+        nodes,
+    })
+}
@@ -0,0 +1,121 @@
+//! Live updates for commands, agents and skills
+//!
+//! Watches ~/.claude/commands (and the archived `.commands`), `agents` and
+//! `skills`, so files edited in an external editor show up without a manual
+//! refresh. Changes are debounced and announced with "customizations-changed",
+//! naming the affected kinds and paths. `~/.claude` itself is watched too, so a
+//! folder created after startup is picked up.
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Top-level folders of ~/.claude and the kind of customization they hold
+const WATCHED: [(&str, &str); 4] = [
+    ("commands", "commands"),
+    (".commands", "commands"),
+    ("agents", "agents"),
+    ("skills", "skills"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomizationsChanged {
+    /// "commands", "agents" and/or "skills"
+    pub kinds: Vec<String>,
+    pub paths: Vec<String>,
+}
+
+/// Kind of customization a path under `claude_dir` belongs to
+fn kind_of(claude_dir: &Path, path: &Path) -> Option<&'static str> {
+    let top = path.strip_prefix(claude_dir).ok()?.components().next()?;
+    let top = top.as_os_str().to_str()?;
+    WATCHED
+        .iter()
+        .find(|(dir, _)| *dir == top)
+        .map(|(_, kind)| *kind)
+}
+
+/// Recursively watch the customization folders not watched yet
+fn watch_folders(
+    watcher: &mut RecommendedWatcher,
+    claude_dir: &Path,
+    watched: &mut BTreeSet<PathBuf>,
+) {
+    for (dir, _) in WATCHED {
+        let path = claude_dir.join(dir);
+        if path.is_dir()
+            && !watched.contains(&path)
+            && watcher.watch(&path, RecursiveMode::Recursive).is_ok()
+        {
+            watched.insert(path);
+        }
+    }
+}
+
+/// Watch the customization folders and emit "customizations-changed" (debounced)
+pub fn start(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        let _watcher_guard = crate::resource_limits::track_watcher("customizations");
+        let claude_dir = crate::get_claude_dir();
+        if !claude_dir.exists() {
+            return;
+        }
+
+        let (tx, rx) = channel::<Vec<PathBuf>>();
+        let mut watcher: RecommendedWatcher =
+            match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+                if let Ok(event) = res {
+                    if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() {
+                        let _ = tx.send(event.paths);
+                    }
+                }
+            }) {
+                Ok(w) => w,
+                Err(_) => return,
+            };
+
+        if watcher
+            .watch(&claude_dir, RecursiveMode::NonRecursive)
+            .is_err()
+        {
+            return;
+        }
+        let mut watched = BTreeSet::new();
+        watch_folders(&mut watcher, &claude_dir, &mut watched);
+
+        while let Ok(first) = rx.recv() {
+            let mut paths: BTreeSet<PathBuf> = first.into_iter().collect();
+            // Drain any additional events that came in quickly
+            while let Ok(more) = rx.recv_timeout(Duration::from_millis(200)) {
+                paths.extend(more);
+            }
+
+            let mut kinds = BTreeSet::new();
+            let mut changed = Vec::new();
+            for path in paths {
+                if let Some(kind) = kind_of(&claude_dir, &path) {
+                    kinds.insert(kind.to_string());
+                    changed.push(path.to_string_lossy().to_string());
+                }
+            }
+            if kinds.is_empty() {
+                continue;
+            }
+            // A folder may have been created (or recreated) since the last event
+            watched.retain(|path| path.is_dir());
+            watch_folders(&mut watcher, &claude_dir, &mut watched);
+
+            let _ = app_handle.emit(
+                "customizations-changed",
+                CustomizationsChanged {
+                    kinds: kinds.into_iter().collect(),
+                    paths: changed,
+                },
+            );
+        }
+    });
+}
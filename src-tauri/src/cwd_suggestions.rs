@@ -0,0 +1,277 @@
+//! Working directory suggestions for new panels
+//!
+//! Candidates come from recently used PTY cwds, workspace projects, the user's
+//! shell history (zsh/bash/fish `cd` commands) and the filesystem itself.
+//! Recent cwds are persisted to ~/.lovstudio/lovcode/recent-cwds.json
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of recent cwds kept on disk
+const MAX_RECENT: usize = 50;
+
+/// Only the tail of each history file is scanned
+const MAX_HISTORY_LINES: usize = 10_000;
+
+/// Default number of suggestions returned
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CwdSuggestion {
+    pub path: String,
+    /// Path with the home directory shortened to `~`
+    pub display: String,
+    /// "recent" | "workspace" | "history" | "filesystem"
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecentCwd {
+    path: String,
+    last_used: u64,
+}
+
+fn get_recent_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("recent-cwds.json")
+}
+
+fn load_recent() -> Vec<RecentCwd> {
+    fs::read_to_string(get_recent_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Remember a cwd used to spawn a PTY
+pub fn record_cwd(cwd: &str) -> Result<(), String> {
+    if cwd.is_empty() {
+        return Ok(());
+    }
+
+    let mut recent = load_recent();
+    recent.retain(|r| r.path != cwd);
+    recent.insert(
+        0,
+        RecentCwd {
+            path: cwd.to_string(),
+            last_used: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        },
+    );
+    recent.truncate(MAX_RECENT);
+
+    let path = get_recent_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&recent).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_home(path: &str, home: &Path) -> String {
+    if path == "~" {
+        home.to_string_lossy().to_string()
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        home.join(rest).to_string_lossy().to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+fn shorten_home(path: &str, home: &Path) -> String {
+    let home_str = home.to_string_lossy();
+    match path.strip_prefix(home_str.as_ref()) {
+        Some("") => "~".to_string(),
+        Some(rest) if rest.starts_with('/') => format!("~{}", rest),
+        _ => path.to_string(),
+    }
+}
+
+/// Read the last lines of a file, tolerating non-UTF8 bytes (zsh metafied history)
+fn read_tail_lines(path: &Path) -> Vec<String> {
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new();
+    };
+    let content = String::from_utf8_lossy(&bytes);
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(MAX_HISTORY_LINES);
+    lines[start..].iter().map(|l| l.to_string()).collect()
+}
+
+/// Extract the directory argument of `cd`/`pushd` from a shell command line
+fn parse_cd_target(command: &str) -> Option<String> {
+    for part in command.split(['&', ';', '|']) {
+        let mut words = part.split_whitespace();
+        match words.next() {
+            Some("cd") | Some("pushd") => {}
+            _ => continue,
+        }
+        // Skip options (`-P`, `--`); `cd -` and `cd -2` go back through the
+        // directory stack, which history can't resolve
+        let arg = words.find(|w| !matches!(*w, "-P" | "-L" | "-e" | "-q" | "-s" | "--"))?;
+        if arg.starts_with(['-', '+', '$']) {
+            return None;
+        }
+        return Some(arg.trim_matches(|c| c == '"' || c == '\'').to_string());
+    }
+    None
+}
+
+/// Collect absolute `cd` targets from zsh, bash and fish history, most used first
+fn history_dirs(home: &Path) -> Vec<String> {
+    let mut commands: Vec<String> = Vec::new();
+
+    // The default locations, plus `HISTFILE` when the app was started from a
+    // shell that exports it; each file is read once however it is reached
+    let mut history_files = vec![home.join(".zsh_history"), home.join(".bash_history")];
+    if let Some(histfile) = std::env::var_os("HISTFILE").map(PathBuf::from) {
+        history_files.push(histfile);
+    }
+    let mut read = HashSet::new();
+    for path in history_files {
+        if !read.insert(fs::canonicalize(&path).unwrap_or(path.clone())) {
+            continue;
+        }
+        for line in read_tail_lines(&path) {
+            // zsh extended format: `: <timestamp>:<duration>;<command>`
+            let command = match line.strip_prefix(": ") {
+                Some(rest) => rest.split_once(';').map(|(_, c)| c).unwrap_or(rest),
+                None => line.as_str(),
+            };
+            commands.push(command.to_string());
+        }
+    }
+
+    let fish_history = home
+        .join(".local")
+        .join("share")
+        .join("fish")
+        .join("fish_history");
+    for line in read_tail_lines(&fish_history) {
+        if let Some(command) = line.trim_start().strip_prefix("- cmd: ") {
+            commands.push(command.to_string());
+        }
+    }
+
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for (order, command) in commands.iter().enumerate() {
+        if let Some(target) = parse_cd_target(command) {
+            let expanded = expand_home(&target, home);
+            // Relative cd targets can't be resolved without the shell's cwd
+            if !expanded.starts_with('/') {
+                continue;
+            }
+            let expanded = expanded.trim_end_matches('/').to_string();
+            let entry = counts.entry(expanded).or_insert((0, 0));
+            entry.0 += 1;
+            entry.1 = order;
+        }
+    }
+
+    let mut dirs: Vec<(String, (usize, usize))> = counts.into_iter().collect();
+    dirs.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(b.1 .1.cmp(&a.1 .1)));
+    dirs.into_iter().map(|(path, _)| path).collect()
+}
+
+/// Subdirectories matching a partially typed path (e.g. `~/code/lo` -> `~/code/lovcode`)
+fn filesystem_dirs(expanded_prefix: &str) -> Vec<String> {
+    let (parent, partial) = if expanded_prefix.ends_with('/') {
+        (PathBuf::from(expanded_prefix), String::new())
+    } else {
+        let path = Path::new(expanded_prefix);
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => {
+                (parent.to_path_buf(), name.to_string_lossy().to_lowercase())
+            }
+            _ => return Vec::new(),
+        }
+    };
+
+    let mut dirs: Vec<String> = fs::read_dir(&parent)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            !name.starts_with('.') || partial.starts_with('.')
+        })
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .to_lowercase()
+                .starts_with(&partial)
+        })
+        .map(|e| e.path().to_string_lossy().to_string())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Suggest cwd candidates matching `prefix` (path prefix or name fragment)
+pub fn get_suggestions(
+    prefix: &str,
+    workspace_paths: &[String],
+    limit: Option<usize>,
+) -> Vec<CwdSuggestion> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let expanded_prefix = expand_home(prefix.trim(), &home);
+    let needle = expanded_prefix.to_lowercase();
+    let is_path = expanded_prefix.starts_with('/');
+
+    let matches = |path: &str| -> bool {
+        if needle.is_empty() {
+            return true;
+        }
+        let lower = path.to_lowercase();
+        if is_path {
+            lower.starts_with(&needle)
+        } else {
+            // Fragment search on the last path component
+            Path::new(&lower)
+                .file_name()
+                .map(|n| n.to_string_lossy().contains(&needle))
+                .unwrap_or(false)
+        }
+    };
+
+    let mut candidates: Vec<(String, &str)> = Vec::new();
+    candidates.extend(load_recent().into_iter().map(|r| (r.path, "recent")));
+    candidates.extend(workspace_paths.iter().map(|p| (p.clone(), "workspace")));
+    candidates.extend(history_dirs(&home).into_iter().map(|p| (p, "history")));
+    if is_path {
+        candidates.extend(
+            filesystem_dirs(&expanded_prefix)
+                .into_iter()
+                .map(|p| (p, "filesystem")),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    let mut suggestions = Vec::new();
+    for (path, source) in candidates {
+        if suggestions.len() >= limit {
+            break;
+        }
+        if !matches(&path) || !seen.insert(path.clone()) || !Path::new(&path).is_dir() {
+            continue;
+        }
+        suggestions.push(CwdSuggestion {
+            display: shorten_home(&path, &home),
+            path,
+            source: source.to_string(),
+        });
+    }
+
+    suggestions
+}
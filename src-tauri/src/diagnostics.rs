@@ -4,6 +4,7 @@ use std::collections::HashSet;
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TechStack {
@@ -243,13 +244,19 @@ fn parse_env_keys(path: &Path) -> Vec<String> {
     keys
 }
 
+/// 敏感信息正则 - 匹配硬编码的 API keys, tokens, passwords (group 1 = key name, group 2 = value)
+pub fn secret_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r#"(?i)(api[_-]?key|secret|password|token|credential|private[_-]?key)\s*[=:]\s*['"]([\w\-_./+=]{8,})['""]"#
+        ).unwrap()
+    })
+}
+
 fn scan_for_leaked_secrets(project_path: &Path) -> Vec<LeakedSecret> {
     let mut secrets = Vec::new();
-
-    // 敏感信息正则 - 匹配硬编码的 API keys, tokens, passwords
-    let secret_pattern = Regex::new(
-        r#"(?i)(api[_-]?key|secret|password|token|credential|private[_-]?key)\s*[=:]\s*['"]([\w\-_./+=]{8,})['""]"#
-    ).unwrap();
+    let secret_pattern = secret_pattern();
 
     // 要扫描的文件扩展名
     let scan_extensions = ["ts", "tsx", "js", "jsx", "py", "rs", "go", "java", "rb"];
@@ -262,7 +269,7 @@ fn scan_for_leaked_secrets(project_path: &Path) -> Vec<LeakedSecret> {
         "chunks", "ssr", "static",  // Next.js 内部目录
     ];
 
-    scan_directory(project_path, &secret_pattern, &scan_extensions, &exclude_dirs, &mut secrets);
+    scan_directory(project_path, secret_pattern, &scan_extensions, &exclude_dirs, &mut secrets);
 
     secrets
 }
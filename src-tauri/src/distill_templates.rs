@@ -0,0 +1,217 @@
+//! Knowledge base document templates
+//!
+//! Built-in scaffolds (decision record, bug postmortem, how-to) plus user templates
+//! stored as markdown in ~/.lovstudio/lovcode/distill-templates/. A user template with
+//! the same id as a built-in one replaces it. Bodies use `{{var}}` placeholders.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistillTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub body: String,
+    pub builtin: bool,
+}
+
+const BUILTIN_TEMPLATES: &[(&str, &str, &str, &str, &str)] = &[
+    (
+        "decision-record",
+        "Decision Record",
+        "Capture an architectural or product decision with its alternatives",
+        "decision, adr",
+        "# {{title}}
+
+## Context
+
+{{context}}
+
+## Decision
+
+{{decision}}
+
+## Alternatives Considered
+
+-
+
+## Consequences
+
+-
+",
+    ),
+    (
+        "bug-postmortem",
+        "Bug Postmortem",
+        "Root cause, fix and prevention for a bug",
+        "bug, postmortem",
+        "# {{title}}
+
+## Symptoms
+
+{{symptoms}}
+
+## Root Cause
+
+{{cause}}
+
+## Fix
+
+{{fix}}
+
+## Prevention
+
+-
+",
+    ),
+    (
+        "how-to",
+        "How-To",
+        "Step-by-step guide for a recurring task",
+        "how-to",
+        "# {{title}}
+
+## Goal
+
+{{goal}}
+
+## Steps
+
+1.
+
+## Gotchas
+
+-
+",
+    ),
+];
+
+fn get_templates_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("distill-templates")
+}
+
+fn parse_tags(value: &str) -> Vec<String> {
+    value
+        .trim_matches(|c| c == '[' || c == ']')
+        .split(',')
+        .map(|t| t.trim().trim_matches('"').trim_matches('\'').to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn builtin_templates() -> Vec<DistillTemplate> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .map(|(id, name, description, tags, body)| DistillTemplate {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            tags: parse_tags(tags),
+            body: body.to_string(),
+            builtin: true,
+        })
+        .collect()
+}
+
+/// Built-in templates merged with the user's template files
+pub fn list_templates() -> Vec<DistillTemplate> {
+    let mut templates = builtin_templates();
+
+    let Ok(entries) = fs::read_dir(get_templates_dir()) else {
+        return templates;
+    };
+    let mut user_templates: Vec<DistillTemplate> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                return None;
+            }
+            let id = path.file_stem()?.to_string_lossy().to_string();
+            let content = fs::read_to_string(&path).ok()?;
+            let (frontmatter, _, body) = crate::parse_frontmatter(&content);
+            Some(DistillTemplate {
+                name: frontmatter
+                    .get("name")
+                    .cloned()
+                    .unwrap_or_else(|| id.clone()),
+                description: frontmatter.get("description").cloned(),
+                tags: frontmatter
+                    .get("tags")
+                    .map(|t| parse_tags(t))
+                    .unwrap_or_default(),
+                body,
+                builtin: false,
+                id,
+            })
+        })
+        .collect();
+    user_templates.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for template in user_templates {
+        match templates.iter_mut().find(|t| t.id == template.id) {
+            Some(existing) => *existing = template,
+            None => templates.push(template),
+        }
+    }
+    templates
+}
+
+pub fn get_template(id: &str) -> Option<DistillTemplate> {
+    list_templates().into_iter().find(|t| t.id == id)
+}
+
+/// Substitute `{{var}}` placeholders; unknown placeholders are left empty
+pub fn render(body: &str, vars: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let key = after[..end].trim();
+                if let Some(value) = vars.get(key) {
+                    output.push_str(value);
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Write a user template, overriding a built-in one with the same id
+pub fn save_template(template: &DistillTemplate) -> Result<(), String> {
+    if template.id.is_empty() || template.id.contains(['/', '\\', '.']) {
+        return Err(format!("Invalid template id: {}", template.id));
+    }
+
+    let dir = get_templates_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let mut content = format!("---\nname: {}\n", template.name);
+    if let Some(description) = &template.description {
+        content.push_str(&format!("description: {}\n", description));
+    }
+    if !template.tags.is_empty() {
+        content.push_str(&format!("tags: [{}]\n", template.tags.join(", ")));
+    }
+    content.push_str("---\n\n");
+    content.push_str(&template.body);
+
+    fs::write(dir.join(format!("{}.md", template.id)), content).map_err(|e| e.to_string())
+}
@@ -0,0 +1,370 @@
+//! Section-aware diff of two markdown documents
+//!
+//! Documents are split at headings; sections are matched by their heading path
+//! (e.g. "Setup > Install") and only matched sections with different bodies get a
+//! line diff. Used to compare two versions of a reference doc or overlapping notes.
+//! Whole-file line diffs can also be grouped into unified-diff hunks.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Line diffs above this many cells fall back to delete-all/insert-all
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffLine {
+    /// "equal" | "insert" | "delete"
+    pub op: &'static str,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionDiff {
+    /// Heading path, "" for text before the first heading
+    pub heading: String,
+    pub level: usize,
+    /// "added" | "removed" | "changed" | "unchanged"
+    pub status: &'static str,
+    /// Line diff for changed sections; full body for added/removed ones
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiffStats {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+}
+
+/// A run of changes with surrounding context; line numbers are 1-based
+#[derive(Debug, Clone, Serialize)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnifiedDiff {
+    /// Text in `diff -u` format, empty when the files are equal
+    pub unified: String,
+    pub hunks: Vec<Hunk>,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentDiff {
+    pub sections: Vec<SectionDiff>,
+    pub stats: DiffStats,
+}
+
+struct Section<'a> {
+    key: String,
+    heading: String,
+    level: usize,
+    body: Vec<&'a str>,
+}
+
+fn heading_level(line: &str) -> Option<(usize, &str)> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) {
+        let rest = &line[hashes..];
+        if rest.is_empty() || rest.starts_with(' ') {
+            return Some((hashes, rest.trim()));
+        }
+    }
+    None
+}
+
+/// Split a markdown document into sections keyed by heading path
+fn split_sections(text: &str) -> Vec<Section<'_>> {
+    let mut sections = vec![Section {
+        key: String::new(),
+        heading: String::new(),
+        level: 0,
+        body: Vec::new(),
+    }];
+    let mut path: Vec<(usize, String)> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut in_fence = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+
+        let heading = if in_fence { None } else { heading_level(line) };
+        let Some((level, title)) = heading else {
+            if let Some(section) = sections.last_mut() {
+                section.body.push(line);
+            }
+            continue;
+        };
+
+        path.retain(|(l, _)| *l < level);
+        path.push((level, title.to_string()));
+        let heading = path
+            .iter()
+            .map(|(_, t)| t.as_str())
+            .collect::<Vec<_>>()
+            .join(" > ");
+
+        // Repeated headings at the same path are matched by occurrence
+        let count = seen.entry(heading.clone()).or_default();
+        *count += 1;
+        let key = if *count > 1 {
+            format!("{}#{}", heading, count)
+        } else {
+            heading.clone()
+        };
+
+        sections.push(Section {
+            key,
+            heading,
+            level,
+            body: Vec::new(),
+        });
+    }
+
+    // Drop an empty preamble
+    if sections[0].body.iter().all(|l| l.trim().is_empty()) {
+        sections.remove(0);
+    }
+    sections
+}
+
+/// Body without leading/trailing blank lines
+fn trimmed_body<'a, 'b>(body: &'b [&'a str]) -> &'b [&'a str] {
+    let start = body
+        .iter()
+        .position(|l| !l.trim().is_empty())
+        .unwrap_or(body.len());
+    let end = body
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .map(|i| i + 1)
+        .unwrap_or(start);
+    &body[start..end]
+}
+
+/// LCS-based line diff
+pub fn diff_lines(a: &[&str], b: &[&str]) -> Vec<DiffLine> {
+    let line = |op, text: &str| DiffLine {
+        op,
+        text: text.to_string(),
+    };
+
+    if a.len().saturating_mul(b.len()) > MAX_LCS_CELLS {
+        return a
+            .iter()
+            .map(|t| line("delete", t))
+            .chain(b.iter().map(|t| line("insert", t)))
+            .collect();
+    }
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut result = Vec::with_capacity(a.len().max(b.len()));
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            result.push(line("equal", a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            result.push(line("delete", a[i]));
+            i += 1;
+        } else {
+            result.push(line("insert", b[j]));
+            j += 1;
+        }
+    }
+    result.extend(a[i..].iter().map(|t| line("delete", t)));
+    result.extend(b[j..].iter().map(|t| line("insert", t)));
+    result
+}
+
+fn whole_section(section: &Section, status: &'static str, op: &'static str) -> SectionDiff {
+    SectionDiff {
+        heading: section.heading.clone(),
+        level: section.level,
+        status,
+        lines: trimmed_body(&section.body)
+            .iter()
+            .map(|t| DiffLine {
+                op,
+                text: t.to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Diff two markdown documents section by section, in the order of `b`
+pub fn diff(a: &str, b: &str) -> DocumentDiff {
+    let sections_a = split_sections(a);
+    let sections_b = split_sections(b);
+    let index_a: HashMap<&str, usize> = sections_a
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.key.as_str(), i))
+        .collect();
+
+    let mut emitted_a = vec![false; sections_a.len()];
+    let mut sections = Vec::new();
+    let mut stats = DiffStats::default();
+
+    let emit_removed_before = |limit: usize,
+                               emitted_a: &mut Vec<bool>,
+                               sections: &mut Vec<SectionDiff>,
+                               stats: &mut DiffStats| {
+        for (i, section) in sections_a.iter().enumerate().take(limit) {
+            if !emitted_a[i] && !sections_b.iter().any(|s| s.key == section.key) {
+                emitted_a[i] = true;
+                stats.removed += 1;
+                sections.push(whole_section(section, "removed", "delete"));
+            }
+        }
+    };
+
+    for section_b in &sections_b {
+        match index_a.get(section_b.key.as_str()) {
+            Some(&i) => {
+                emit_removed_before(i, &mut emitted_a, &mut sections, &mut stats);
+                emitted_a[i] = true;
+
+                let body_a = trimmed_body(&sections_a[i].body);
+                let body_b = trimmed_body(&section_b.body);
+                if body_a == body_b {
+                    stats.unchanged += 1;
+                    sections.push(SectionDiff {
+                        heading: section_b.heading.clone(),
+                        level: section_b.level,
+                        status: "unchanged",
+                        lines: Vec::new(),
+                    });
+                } else {
+                    stats.changed += 1;
+                    sections.push(SectionDiff {
+                        heading: section_b.heading.clone(),
+                        level: section_b.level,
+                        status: "changed",
+                        lines: diff_lines(body_a, body_b),
+                    });
+                }
+            }
+            None => {
+                stats.added += 1;
+                sections.push(whole_section(section_b, "added", "insert"));
+            }
+        }
+    }
+    emit_removed_before(sections_a.len(), &mut emitted_a, &mut sections, &mut stats);
+
+    DocumentDiff { sections, stats }
+}
+
+/// Group a line diff into hunks with `context` equal lines around each change
+pub fn hunks(lines: &[DiffLine], context: usize) -> Vec<Hunk> {
+    // Line numbers in a and b before each diff line
+    let mut positions = Vec::with_capacity(lines.len());
+    let (mut old_no, mut new_no) = (1, 1);
+    for line in lines {
+        positions.push((old_no, new_no));
+        if line.op != "insert" {
+            old_no += 1;
+        }
+        if line.op != "delete" {
+            new_no += 1;
+        }
+    }
+
+    let changes: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].op != "equal")
+        .collect();
+    let mut hunks = Vec::new();
+    let mut group_start = 0;
+    for k in 0..changes.len() {
+        let last_in_group = changes
+            .get(k + 1)
+            .is_none_or(|&next| next - changes[k] > 2 * context + 1);
+        if !last_in_group {
+            continue;
+        }
+        let start = changes[group_start].saturating_sub(context);
+        let end = (changes[k] + context + 1).min(lines.len());
+        let hunk_lines = &lines[start..end];
+        let (old_start, new_start) = positions[start];
+        hunks.push(Hunk {
+            old_start,
+            old_lines: hunk_lines.iter().filter(|l| l.op != "insert").count(),
+            new_start,
+            new_lines: hunk_lines.iter().filter(|l| l.op != "delete").count(),
+            lines: hunk_lines.to_vec(),
+        });
+        group_start = k + 1;
+    }
+    hunks
+}
+
+/// Line diff of two whole texts as unified-diff hunks, labelled `label_a` and `label_b`
+pub fn unified(a: &str, b: &str, label_a: &str, label_b: &str) -> UnifiedDiff {
+    let lines_a: Vec<&str> = a.lines().collect();
+    let lines_b: Vec<&str> = b.lines().collect();
+    let lines = diff_lines(&lines_a, &lines_b);
+    let hunks = hunks(&lines, 3);
+
+    let mut unified = String::new();
+    if !hunks.is_empty() {
+        unified.push_str(&format!("--- {}\n+++ {}\n", label_a, label_b));
+    }
+    for hunk in &hunks {
+        // An empty side is numbered from the line before it, as diff -u does
+        let old_start = if hunk.old_lines == 0 {
+            hunk.old_start - 1
+        } else {
+            hunk.old_start
+        };
+        let new_start = if hunk.new_lines == 0 {
+            hunk.new_start - 1
+        } else {
+            hunk.new_start
+        };
+        unified.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start, hunk.old_lines, new_start, hunk.new_lines
+        ));
+        for line in &hunk.lines {
+            let prefix = match line.op {
+                "insert" => '+',
+                "delete" => '-',
+                _ => ' ',
+            };
+            unified.push(prefix);
+            unified.push_str(&line.text);
+            unified.push('\n');
+        }
+    }
+
+    UnifiedDiff {
+        unified,
+        insertions: lines.iter().filter(|l| l.op == "insert").count(),
+        deletions: lines.iter().filter(|l| l.op == "delete").count(),
+        hunks,
+    }
+}
@@ -0,0 +1,381 @@
+//! Export format registry
+//!
+//! Every export format implements [`Exporter`] and is listed in [`REGISTRY`]; the
+//! `export` command dispatches by format id and `list_export_formats` lets the UI
+//! discover formats and which targets they accept. Adding a format means one
+//! `Exporter` impl plus one registry entry.
+
+use crate::session_export::{self, HtmlExportOptions};
+use crate::{kb_export, Message};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+use tauri::AppHandle;
+
+/// What to export
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportTarget {
+    Session {
+        project_id: String,
+        session_id: String,
+    },
+    /// A project, selected sessions, or everything when both are unset
+    Sessions {
+        project_id: Option<String>,
+        session_ids: Option<Vec<String>>,
+    },
+    /// Sessions of a curated collection
+    Collection {
+        collection_id: String,
+    },
+    KnowledgeBase,
+}
+
+impl ExportTarget {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Session { .. } => "session",
+            Self::Sessions { .. } => "sessions",
+            Self::Collection { .. } => "collection",
+            Self::KnowledgeBase => "knowledge_base",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportOptions {
+    /// File to write, or directory for formats that produce several files
+    pub output_path: String,
+    /// Format-specific settings, e.g. `include_tools` for transcripts
+    #[serde(default)]
+    pub settings: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportFormatInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// File extension, None when the output is a directory
+    pub extension: Option<&'static str>,
+    /// Target types this format accepts
+    pub targets: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportResult {
+    pub format: String,
+    pub path: String,
+    /// Messages, sessions or documents written
+    pub items: usize,
+}
+
+pub struct ExportContext {
+    pub app_handle: AppHandle,
+}
+
+pub trait Exporter: Send + Sync {
+    fn info(&self) -> ExportFormatInfo;
+    fn export(
+        &self,
+        ctx: &ExportContext,
+        target: &ExportTarget,
+        options: &ExportOptions,
+    ) -> Result<ExportResult, String>;
+}
+
+/// Parse format-specific settings, treating a missing value as defaults
+fn settings<T: Default + serde::de::DeserializeOwned>(
+    options: &ExportOptions,
+) -> Result<T, String> {
+    if options.settings.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(options.settings.clone())
+        .map_err(|e| format!("Invalid export settings: {}", e))
+}
+
+fn session_target(target: &ExportTarget) -> Result<(String, Vec<Message>), String> {
+    match target {
+        ExportTarget::Session {
+            project_id,
+            session_id,
+        } => crate::load_session_for_export(project_id, session_id),
+        other => Err(format!("Expected a session, got {}", other.kind())),
+    }
+}
+
+fn result(id: &str, options: &ExportOptions, items: usize) -> ExportResult {
+    ExportResult {
+        format: id.to_string(),
+        path: options.output_path.clone(),
+        items,
+    }
+}
+
+/// Single-session transcript rendered to one text file
+struct Transcript {
+    id: &'static str,
+    label: &'static str,
+    extension: &'static str,
+    render: fn(&str, &[Message], &HtmlExportOptions) -> String,
+}
+
+impl Exporter for Transcript {
+    fn info(&self) -> ExportFormatInfo {
+        ExportFormatInfo {
+            id: self.id,
+            label: self.label,
+            extension: Some(self.extension),
+            targets: vec!["session"],
+        }
+    }
+
+    fn export(
+        &self,
+        _ctx: &ExportContext,
+        target: &ExportTarget,
+        options: &ExportOptions,
+    ) -> Result<ExportResult, String> {
+        let (title, messages) = session_target(target)?;
+        let transcript_options: HtmlExportOptions = settings(options)?;
+        let content = (self.render)(&title, &messages, &transcript_options);
+        fs::write(&options.output_path, content).map_err(|e| e.to_string())?;
+        let written = messages
+            .iter()
+            .filter(|m| transcript_options.includes(m))
+            .count();
+        Ok(result(self.id, options, written))
+    }
+}
+
+struct Pdf;
+
+impl Exporter for Pdf {
+    fn info(&self) -> ExportFormatInfo {
+        ExportFormatInfo {
+            id: "pdf",
+            label: "PDF",
+            extension: Some("pdf"),
+            targets: vec!["session"],
+        }
+    }
+
+    fn export(
+        &self,
+        _ctx: &ExportContext,
+        target: &ExportTarget,
+        options: &ExportOptions,
+    ) -> Result<ExportResult, String> {
+        let (title, messages) = session_target(target)?;
+        let mut html_options: HtmlExportOptions = settings(options)?;
+        html_options.expand.get_or_insert(true);
+        let html = session_export::render_html(&title, &messages, &html_options);
+
+        let html_path =
+            std::env::temp_dir().join(format!("lovcode-export-{}.html", uuid::Uuid::new_v4()));
+        fs::write(&html_path, html).map_err(|e| e.to_string())?;
+        let converted = session_export::html_to_pdf(&html_path, Path::new(&options.output_path));
+        let _ = fs::remove_file(&html_path);
+        let written = messages.iter().filter(|m| html_options.includes(m)).count();
+        converted.map(|_| result("pdf", options, written))
+    }
+}
+
+/// Self-contained page with inline images, redacted by default
+struct SharePage;
+
+impl Exporter for SharePage {
+    fn info(&self) -> ExportFormatInfo {
+        ExportFormatInfo {
+            id: "share_page",
+            label: "Shareable page",
+            extension: Some("html"),
+            targets: vec!["session"],
+        }
+    }
+
+    fn export(
+        &self,
+        _ctx: &ExportContext,
+        target: &ExportTarget,
+        options: &ExportOptions,
+    ) -> Result<ExportResult, String> {
+        let ExportTarget::Session {
+            project_id,
+            session_id,
+        } = target
+        else {
+            return Err(format!("Expected a session, got {}", target.kind()));
+        };
+        let (html, messages, _) =
+            crate::build_share_page(project_id, session_id, &settings(options)?)?;
+        fs::write(&options.output_path, html).map_err(|e| e.to_string())?;
+        Ok(result("share_page", options, messages))
+    }
+}
+
+/// Raw session entries bundled into one archive
+struct Archive {
+    id: &'static str,
+    label: &'static str,
+    extension: &'static str,
+    ndjson: bool,
+}
+
+impl Exporter for Archive {
+    fn info(&self) -> ExportFormatInfo {
+        ExportFormatInfo {
+            id: self.id,
+            label: self.label,
+            extension: Some(self.extension),
+            targets: vec!["session", "sessions", "collection"],
+        }
+    }
+
+    fn export(
+        &self,
+        _ctx: &ExportContext,
+        target: &ExportTarget,
+        options: &ExportOptions,
+    ) -> Result<ExportResult, String> {
+        let sources = match target {
+            ExportTarget::Session {
+                project_id,
+                session_id,
+            } => crate::collect_archive_sources(Some(project_id), Some(vec![session_id.clone()]))?,
+            ExportTarget::Sessions {
+                project_id,
+                session_ids,
+            } => crate::collect_archive_sources(project_id.as_deref(), session_ids.clone())?,
+            ExportTarget::Collection { collection_id } => {
+                let collection = crate::collections::get(collection_id)?;
+                let sources: Vec<_> = collection
+                    .entries
+                    .iter()
+                    .filter_map(|e| {
+                        crate::collect_archive_sources(
+                            Some(&e.project_id),
+                            Some(vec![e.session_id.clone()]),
+                        )
+                        .ok()
+                    })
+                    .flatten()
+                    .collect();
+                if sources.is_empty() {
+                    return Err(format!("Collection '{}' has no sessions", collection.name));
+                }
+                sources
+            }
+            other => return Err(format!("Expected sessions, got {}", other.kind())),
+        };
+        let summary =
+            session_export::write_archive(&sources, Path::new(&options.output_path), self.ndjson)?;
+        Ok(result(self.id, options, summary.sessions))
+    }
+}
+
+struct KnowledgeBase(kb_export::ExportFormat);
+
+impl Exporter for KnowledgeBase {
+    fn info(&self) -> ExportFormatInfo {
+        let (id, label) = match self.0 {
+            kb_export::ExportFormat::Obsidian => ("obsidian", "Obsidian vault"),
+            kb_export::ExportFormat::MdBook => ("mdbook", "mdBook project"),
+        };
+        ExportFormatInfo {
+            id,
+            label,
+            extension: None,
+            targets: vec!["knowledge_base"],
+        }
+    }
+
+    fn export(
+        &self,
+        ctx: &ExportContext,
+        target: &ExportTarget,
+        options: &ExportOptions,
+    ) -> Result<ExportResult, String> {
+        if !matches!(target, ExportTarget::KnowledgeBase) {
+            return Err(format!(
+                "Expected the knowledge base, got {}",
+                target.kind()
+            ));
+        }
+        let sections = crate::collect_kb_sections(&ctx.app_handle)?;
+        let summary = kb_export::export(&sections, Path::new(&options.output_path), self.0)?;
+        Ok(result(self.info().id, options, summary.documents))
+    }
+}
+
+pub static REGISTRY: LazyLock<Vec<Box<dyn Exporter>>> = LazyLock::new(|| {
+    vec![
+        Box::new(Transcript {
+            id: "markdown",
+            label: "Markdown",
+            extension: "md",
+            render: session_export::render_markdown,
+        }),
+        Box::new(Transcript {
+            id: "html",
+            label: "HTML",
+            extension: "html",
+            render: session_export::render_html,
+        }),
+        Box::new(Pdf),
+        Box::new(SharePage),
+        Box::new(Transcript {
+            id: "csv",
+            label: "CSV",
+            extension: "csv",
+            render: |_, messages, options| session_export::render_csv(messages, options),
+        }),
+        Box::new(Archive {
+            id: "json",
+            label: "JSON archive",
+            extension: "json",
+            ndjson: false,
+        }),
+        Box::new(Archive {
+            id: "jsonl",
+            label: "JSONL archive",
+            extension: "jsonl",
+            ndjson: true,
+        }),
+        Box::new(KnowledgeBase(kb_export::ExportFormat::Obsidian)),
+        Box::new(KnowledgeBase(kb_export::ExportFormat::MdBook)),
+    ]
+});
+
+pub fn list() -> Vec<ExportFormatInfo> {
+    REGISTRY.iter().map(|e| e.info()).collect()
+}
+
+/// Export `target` in format `kind`
+pub fn export(
+    ctx: &ExportContext,
+    kind: &str,
+    target: &ExportTarget,
+    options: &ExportOptions,
+) -> Result<ExportResult, String> {
+    let exporter = REGISTRY
+        .iter()
+        .find(|e| e.info().id == kind)
+        .ok_or_else(|| format!("Unknown export format: {}", kind))?;
+    let info = exporter.info();
+    if !info.targets.contains(&target.kind()) {
+        return Err(format!(
+            "{} export does not support {}",
+            info.label,
+            target.kind()
+        ));
+    }
+    if let Some(parent) = Path::new(&options.output_path).parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            return Err(format!("Folder '{}' does not exist", parent.display()));
+        }
+    }
+    exporter.export(ctx, target, options)
+}
@@ -0,0 +1,379 @@
+//! Stopwatch and pomodoro timers bound to features
+//!
+//! At most one timer runs per feature. Time is wall-clock: a timer left running
+//! keeps counting while the app is closed, so restarts don't lose tracked time.
+//! Every run interval (start or resume until pause or stop) is logged and feeds
+//! the time report. A ticker emits "timer-tick" every second while timers run and
+//! "timer-finished" when a pomodoro reaches its duration.
+//! Persisted to ~/.lovstudio/lovcode/timers.json
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+static STORE: Mutex<Option<TimerStore>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerMode {
+    Stopwatch,
+    Pomodoro,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureTimer {
+    pub project_id: String,
+    pub feature_id: String,
+    pub mode: TimerMode,
+    /// Pomodoro length
+    pub duration_secs: Option<u64>,
+    pub started_at: u64,
+    /// Set while running
+    pub running_since: Option<u64>,
+    /// Time of earlier intervals of this run
+    pub accumulated_secs: u64,
+}
+
+impl FeatureTimer {
+    fn elapsed(&self, now: u64) -> u64 {
+        self.accumulated_secs
+            + self
+                .running_since
+                .map(|since| now.saturating_sub(since))
+                .unwrap_or(0)
+    }
+}
+
+/// A logged stretch of tracked time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub project_id: String,
+    pub feature_id: String,
+    pub mode: TimerMode,
+    pub started_at: u64,
+    pub ended_at: u64,
+    /// Last interval of a pomodoro that ran its full length
+    #[serde(default)]
+    pub completed_pomodoro: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TimerStore {
+    timers: Vec<FeatureTimer>,
+    entries: Vec<TimeEntry>,
+}
+
+/// Timer as shown in the UI and sent with "timer-tick"
+#[derive(Debug, Clone, Serialize)]
+pub struct TimerStatus {
+    pub project_id: String,
+    pub feature_id: String,
+    pub mode: TimerMode,
+    pub running: bool,
+    pub elapsed_secs: u64,
+    /// Pomodoro time left
+    pub remaining_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureTime {
+    pub project_id: String,
+    pub feature_id: String,
+    /// None when the feature no longer exists
+    pub feature_name: Option<String>,
+    pub seconds: u64,
+    pub intervals: usize,
+    pub pomodoros_completed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeReport {
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub total_secs: u64,
+    /// Most tracked time first
+    pub features: Vec<FeatureTime>,
+}
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("timers.json")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load() -> Result<TimerStore, String> {
+    let path = get_store_path();
+    if !path.exists() {
+        return Ok(TimerStore::default());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read timers: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse timers: {}", e))
+}
+
+/// The cached store, read from disk on first use; a failed read is retried next time
+fn cached(guard: &mut Option<TimerStore>) -> Result<&mut TimerStore, String> {
+    if guard.is_none() {
+        *guard = Some(load()?);
+    }
+    Ok(guard.get_or_insert_default())
+}
+
+fn save(store: &TimerStore) -> Result<(), String> {
+    let path = get_store_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Run `f` on the store and persist it when it succeeds
+fn with_store<T>(f: impl FnOnce(&mut TimerStore) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = STORE.lock().map_err(|e| e.to_string())?;
+    let store = cached(&mut guard)?;
+    let result = f(store)?;
+    save(store)?;
+    Ok(result)
+}
+
+fn status(timer: &FeatureTimer, now: u64) -> TimerStatus {
+    let elapsed_secs = timer.elapsed(now);
+    TimerStatus {
+        project_id: timer.project_id.clone(),
+        feature_id: timer.feature_id.clone(),
+        mode: timer.mode,
+        running: timer.running_since.is_some(),
+        elapsed_secs,
+        remaining_secs: timer.duration_secs.map(|d| d.saturating_sub(elapsed_secs)),
+    }
+}
+
+/// Close the running interval of a timer, logging it
+fn close_interval(store: &mut TimerStore, index: usize, now: u64, completed_pomodoro: bool) {
+    let timer = &mut store.timers[index];
+    let Some(since) = timer.running_since.take() else {
+        return;
+    };
+    timer.accumulated_secs += now.saturating_sub(since);
+    store.entries.push(TimeEntry {
+        project_id: timer.project_id.clone(),
+        feature_id: timer.feature_id.clone(),
+        mode: timer.mode,
+        started_at: since,
+        ended_at: now,
+        completed_pomodoro,
+    });
+}
+
+fn find(store: &TimerStore, feature_id: &str) -> Result<usize, String> {
+    store
+        .timers
+        .iter()
+        .position(|t| t.feature_id == feature_id)
+        .ok_or_else(|| format!("No timer for feature '{}'", feature_id))
+}
+
+pub fn start(
+    project_id: &str,
+    feature_id: &str,
+    mode: TimerMode,
+    duration_secs: Option<u64>,
+) -> Result<TimerStatus, String> {
+    let duration_secs = match mode {
+        TimerMode::Pomodoro => Some(duration_secs.filter(|d| *d > 0).unwrap_or(25 * 60)),
+        TimerMode::Stopwatch => None,
+    };
+    with_store(|store| {
+        if store.timers.iter().any(|t| t.feature_id == feature_id) {
+            return Err("Feature already has a timer".to_string());
+        }
+        let now = now_secs();
+        let timer = FeatureTimer {
+            project_id: project_id.to_string(),
+            feature_id: feature_id.to_string(),
+            mode,
+            duration_secs,
+            started_at: now,
+            running_since: Some(now),
+            accumulated_secs: 0,
+        };
+        let started = status(&timer, now);
+        store.timers.push(timer);
+        Ok(started)
+    })
+}
+
+pub fn pause(feature_id: &str) -> Result<TimerStatus, String> {
+    with_store(|store| {
+        let index = find(store, feature_id)?;
+        let now = now_secs();
+        close_interval(store, index, now, false);
+        Ok(status(&store.timers[index], now))
+    })
+}
+
+pub fn resume(feature_id: &str) -> Result<TimerStatus, String> {
+    with_store(|store| {
+        let index = find(store, feature_id)?;
+        let now = now_secs();
+        let timer = &mut store.timers[index];
+        if timer.running_since.is_none() {
+            timer.running_since = Some(now);
+        }
+        Ok(status(timer, now))
+    })
+}
+
+/// Stop and discard a timer; its time stays in the log
+pub fn stop(feature_id: &str) -> Result<TimerStatus, String> {
+    with_store(|store| {
+        let index = find(store, feature_id)?;
+        let now = now_secs();
+        close_interval(store, index, now, false);
+        Ok(status(&store.timers.remove(index), now))
+    })
+}
+
+pub fn list() -> Vec<TimerStatus> {
+    let now = now_secs();
+    let Ok(mut guard) = STORE.lock() else {
+        return Vec::new();
+    };
+    let Ok(store) = cached(&mut guard) else {
+        return Vec::new();
+    };
+    store.timers.iter().map(|t| status(t, now)).collect()
+}
+
+/// Drop timers of a deleted feature (logged time is kept)
+pub fn forget_feature(feature_id: &str) {
+    let _ = with_store(|store| {
+        store.timers.retain(|t| t.feature_id != feature_id);
+        Ok(())
+    });
+}
+
+/// Finish pomodoros that reached their length, returning them
+fn finish_due(now: u64) -> Vec<TimerStatus> {
+    let Ok(mut guard) = STORE.lock() else {
+        return Vec::new();
+    };
+    let Ok(store) = cached(&mut guard) else {
+        return Vec::new();
+    };
+    let mut finished = Vec::new();
+    while let Some(index) = store.timers.iter().position(|t| {
+        t.running_since.is_some() && t.duration_secs.is_some_and(|d| t.elapsed(now) >= d)
+    }) {
+        // Close the interval where the pomodoro ran out, not at this tick
+        let timer = &store.timers[index];
+        let overshoot = timer.elapsed(now) - timer.duration_secs.unwrap_or(0);
+        let end = now
+            .saturating_sub(overshoot)
+            .max(timer.running_since.unwrap_or(0));
+        close_interval(store, index, end, true);
+        finished.push(status(&store.timers.remove(index), end));
+    }
+    if !finished.is_empty() {
+        if let Err(e) = save(store) {
+            eprintln!("[Lovcode] Failed to save timers: {}", e);
+        }
+    }
+    finished
+}
+
+/// Tracked time per feature, optionally limited to a project and a time range
+/// (intervals are clipped to the range). Running intervals count up to now.
+pub fn report(project_id: Option<&str>, since: Option<u64>, until: Option<u64>) -> TimeReport {
+    let now = now_secs();
+    let (mut entries, timers) = match STORE.lock() {
+        Ok(mut guard) => match cached(&mut guard) {
+            Ok(store) => (store.entries.clone(), store.timers.clone()),
+            Err(_) => (Vec::new(), Vec::new()),
+        },
+        Err(_) => (Vec::new(), Vec::new()),
+    };
+    entries.extend(timers.iter().filter_map(|t| {
+        Some(TimeEntry {
+            project_id: t.project_id.clone(),
+            feature_id: t.feature_id.clone(),
+            mode: t.mode,
+            started_at: t.running_since?,
+            ended_at: now,
+            completed_pomodoro: false,
+        })
+    }));
+
+    let names: HashMap<String, String> = crate::workspace_store::load_workspace()
+        .map(|data| {
+            data.projects
+                .into_iter()
+                .flat_map(|p| p.features)
+                .map(|f| (f.id, f.name))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut by_feature: HashMap<String, FeatureTime> = HashMap::new();
+    for entry in entries {
+        if project_id.is_some_and(|p| p != entry.project_id) {
+            continue;
+        }
+        let start = since.map_or(entry.started_at, |s| entry.started_at.max(s));
+        let end = until.map_or(entry.ended_at, |u| entry.ended_at.min(u));
+        if end <= start {
+            continue;
+        }
+        let feature = by_feature
+            .entry(entry.feature_id.clone())
+            .or_insert_with(|| FeatureTime {
+                project_id: entry.project_id.clone(),
+                feature_id: entry.feature_id.clone(),
+                feature_name: names.get(&entry.feature_id).cloned(),
+                seconds: 0,
+                intervals: 0,
+                pomodoros_completed: 0,
+            });
+        feature.seconds += end - start;
+        feature.intervals += 1;
+        feature.pomodoros_completed += usize::from(entry.completed_pomodoro);
+    }
+
+    let mut features: Vec<FeatureTime> = by_feature.into_values().collect();
+    features.sort_by_key(|f| std::cmp::Reverse(f.seconds));
+    TimeReport {
+        since,
+        until,
+        total_secs: features.iter().map(|f| f.seconds).sum(),
+        features,
+    }
+}
+
+/// Emit "timer-tick" every second while timers run, and "timer-finished" for
+/// pomodoros that ran out (also those that ran out while the app was closed)
+pub fn start_ticker(app_handle: AppHandle) {
+    std::thread::spawn(move || loop {
+        let now = now_secs();
+        for finished in finish_due(now) {
+            let _ = app_handle.emit("timer-finished", finished);
+        }
+        let running: Vec<TimerStatus> = list().into_iter().filter(|t| t.running).collect();
+        if !running.is_empty() {
+            let _ = app_handle.emit("timer-tick", running);
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    });
+}
@@ -0,0 +1,177 @@
+//! Reading and line-level editing of markdown frontmatter
+//!
+//! Only the lines of the field being changed are touched: other keys, their
+//! order, comments, blank lines and line endings stay as they were, so a file
+//! edited by hand keeps its formatting. A field spans its `key:` line plus the
+//! indented lines right after it (lists, block scalars).
+
+/// Byte range of the frontmatter lines (between the fences) and start of the body
+struct Split {
+    fields_start: usize,
+    fields_end: usize,
+    body_start: usize,
+}
+
+fn split(content: &str) -> Option<Split> {
+    let mut lines = content.split_inclusive('\n');
+    let first = lines.next()?;
+    if first.trim_end() != "---" {
+        return None;
+    }
+    let mut offset = first.len();
+    for line in lines {
+        if line.trim_end() == "---" {
+            return Some(Split {
+                fields_start: first.len(),
+                fields_end: offset,
+                body_start: offset + line.len(),
+            });
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn line_ending(content: &str) -> &'static str {
+    if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Quote a value when YAML would read it as something other than a plain string
+fn yaml_value(value: &str) -> String {
+    let needs_quotes = value.trim() != value
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.ends_with(':')
+        || value.starts_with(|c: char| "-?[]{},&*!|>'\"%@`#".contains(c));
+    quote_if(needs_quotes, value)
+}
+
+/// `yaml_value` for an item of a `[a, b]` list, where commas and brackets end the item
+fn yaml_list_item(value: &str) -> String {
+    if value.contains([',', '[', ']', '{', '}']) {
+        quote_if(true, value)
+    } else {
+        yaml_value(value)
+    }
+}
+
+fn quote_if(needs_quotes: bool, value: &str) -> String {
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Set `key` to `value`, or remove it when `value` is None or empty
+pub fn set_field(content: &str, key: &str, value: Option<&str>) -> Result<String, String> {
+    let value = value.filter(|v| !v.is_empty());
+    if value.is_some_and(|v| v.contains(['\n', '\r'])) {
+        return Err(format!("'{}' must be a single line", key));
+    }
+    Ok(set_line(content, key, value.map(yaml_value)))
+}
+
+/// Set `key` to a one-line `[a, b]` list of `items`
+pub fn set_list(content: &str, key: &str, items: &[String]) -> Result<String, String> {
+    if items.iter().any(|item| item.contains(['\n', '\r'])) {
+        return Err(format!("'{}' items must be single lines", key));
+    }
+    let items: Vec<String> = items.iter().map(|item| yaml_list_item(item)).collect();
+    let list = format!("[{}]", items.join(", "));
+    Ok(set_line(content, key, Some(list)))
+}
+
+/// Replace the lines of `key` with `key: value`, or drop them when `value` is None
+fn set_line(content: &str, key: &str, value: Option<String>) -> String {
+    let eol = line_ending(content);
+    let new_line = value.map(|v| format!("{}: {}{}", key, v, eol));
+
+    let Some(parts) = split(content) else {
+        return match new_line {
+            Some(line) => format!("---{eol}{line}---{eol}{eol}{content}"),
+            None => content.to_string(),
+        };
+    };
+
+    let fields = &content[parts.fields_start..parts.fields_end];
+    let mut out = String::with_capacity(content.len());
+    out.push_str(&content[..parts.fields_start]);
+
+    let mut replaced = false;
+    let mut in_field = false;
+    for line in fields.split_inclusive('\n') {
+        let indented = line.starts_with([' ', '\t']);
+        if in_field && indented {
+            // Continuation of the field being replaced
+            continue;
+        }
+        in_field = false;
+        let is_key = !indented
+            && line
+                .split_once(':')
+                .is_some_and(|(k, _)| k.trim_end() == key);
+        if is_key && !replaced {
+            replaced = true;
+            in_field = true;
+            if let Some(new_line) = &new_line {
+                out.push_str(new_line);
+            }
+            continue;
+        }
+        out.push_str(line);
+    }
+    if !replaced {
+        if let Some(new_line) = &new_line {
+            if !out.ends_with('\n') {
+                out.push_str(eol);
+            }
+            out.push_str(new_line);
+        }
+    }
+
+    out.push_str(&content[parts.fields_end..]);
+    out
+}
+
+/// Frontmatter lines between the fences, if the content has frontmatter
+pub fn fields(content: &str) -> Option<&str> {
+    split(content).map(|parts| &content[parts.fields_start..parts.fields_end])
+}
+
+/// Frontmatter including its fences, if the content has frontmatter
+pub fn block(content: &str) -> Option<&str> {
+    split(content).map(|parts| &content[..parts.body_start])
+}
+
+/// Everything after the frontmatter, without the blank lines in between
+pub fn body(content: &str) -> &str {
+    match split(content) {
+        Some(parts) => content[parts.body_start..].trim_start_matches(['\n', '\r']),
+        None => content,
+    }
+}
+
+/// Replace everything after the frontmatter, keeping the gap between the two
+pub fn set_body(content: &str, body: &str) -> String {
+    let Some(parts) = split(content) else {
+        return body.to_string();
+    };
+    let rest = &content[parts.body_start..];
+    let gap = &rest[..rest.len() - rest.trim_start_matches(['\n', '\r']).len()];
+    let gap = if gap.is_empty() {
+        line_ending(content)
+    } else {
+        gap
+    };
+    format!(
+        "{}{}{}",
+        &content[..parts.body_start],
+        gap,
+        body.trim_start_matches(['\n', '\r'])
+    )
+}
@@ -0,0 +1,224 @@
+//! Runtime health summary
+//!
+//! One snapshot of the background machinery: live watchers, how far the search
+//! index lags behind the session files, cache sizes, open terminals and the last
+//! maintenance run. Anything not working as expected is listed under `degraded`
+//! so the UI can show a single ok/degraded indicator with details on demand.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Watchers started by deferred init that should stay alive
+const EXPECTED_WATCHERS: &[&str] = &["customizations", "distill", "projects"];
+/// Descriptor usage share reported as degraded
+const FD_WARN_RATIO: f64 = 0.8;
+/// Maintenance should run daily; flag it once it is this late
+const MAINTENANCE_OVERDUE_SECS: u64 = 3 * 24 * 60 * 60;
+/// How long a count of stale sessions is reused before walking the projects again
+const STALE_SCAN_TTL: Duration = Duration::from_secs(5 * 60);
+
+static STALE_SCAN: Mutex<Option<(Instant, usize)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexHealth {
+    pub built: bool,
+    /// Last commit to the global index
+    pub updated_at: Option<u64>,
+    pub partitions: usize,
+    /// Session files modified since they were last indexed
+    pub stale_sessions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DegradedSubsystem {
+    /// e.g. "watcher", "index", "memory", "file_descriptors", "maintenance"
+    pub subsystem: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeHealth {
+    /// "ok" or "degraded"
+    pub status: String,
+    pub uptime_ms: u64,
+    /// Live file watchers by kind
+    pub watchers: HashMap<String, usize>,
+    pub index: IndexHealth,
+    pub caches: Vec<crate::memory::CacheUsage>,
+    pub rss_bytes: Option<u64>,
+    pub ptys: usize,
+    pub open_fds: Option<u64>,
+    pub fd_limit: Option<u64>,
+    pub last_maintenance: Option<crate::maintenance::MaintenanceRun>,
+    pub degraded: Vec<DegradedSubsystem>,
+}
+
+fn mtime_secs(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Session files changed since their watermark in the index. Sessions indexed
+/// before watermarks were kept fall back to the index's own update time.
+fn count_stale_sessions(updated_at: u64) -> usize {
+    let mut stale = 0;
+    for project in fs::read_dir(crate::get_claude_dir().join("projects"))
+        .into_iter()
+        .flatten()
+        .flatten()
+    {
+        let project_id = project.file_name().to_string_lossy().to_string();
+        for entry in fs::read_dir(project.path()).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(session_id) = name.strip_suffix(".jsonl") else {
+                continue;
+            };
+            let indexed_at =
+                crate::index_watermarks::get(&project_id, session_id).unwrap_or(updated_at);
+            if mtime_secs(&entry.path()).is_some_and(|m| m > indexed_at) {
+                stale += 1;
+            }
+        }
+    }
+    stale
+}
+
+/// `count_stale_sessions`, reused for `STALE_SCAN_TTL`
+fn stale_sessions(updated_at: u64) -> usize {
+    let Ok(mut cache) = STALE_SCAN.lock() else {
+        return count_stale_sessions(updated_at);
+    };
+    match *cache {
+        Some((at, count)) if at.elapsed() < STALE_SCAN_TTL => count,
+        _ => {
+            let count = count_stale_sessions(updated_at);
+            *cache = Some((Instant::now(), count));
+            count
+        }
+    }
+}
+
+fn index_health() -> IndexHealth {
+    let index_dir = crate::get_index_dir();
+    let updated_at = mtime_secs(&index_dir.join("meta.json"));
+    let partitions = fs::read_dir(crate::get_partitions_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().join("meta.json").exists())
+        .count();
+
+    let stale_sessions = match updated_at {
+        Some(updated_at) => stale_sessions(updated_at),
+        None => 0,
+    };
+
+    IndexHealth {
+        built: updated_at.is_some() || partitions > 0,
+        updated_at,
+        partitions,
+        stale_sessions,
+    }
+}
+
+pub fn report() -> RuntimeHealth {
+    let resources = crate::resource_limits::usage();
+    let memory = crate::memory::report();
+    let index = index_health();
+    let last_maintenance = crate::maintenance::history(Some(1)).into_iter().next();
+    let mut degraded = Vec::new();
+    let mut flag = |subsystem: &str, message: String| {
+        degraded.push(DegradedSubsystem {
+            subsystem: subsystem.to_string(),
+            message,
+        })
+    };
+
+    if crate::startup::deferred_started() {
+        for kind in EXPECTED_WATCHERS {
+            if resources.watchers.get(*kind).copied().unwrap_or(0) == 0 {
+                flag("watcher", format!("The {} watcher is not running", kind));
+            }
+        }
+    }
+    if index.stale_sessions > 0 {
+        flag(
+            "index",
+            format!(
+                "{} sessions changed since the search index was updated",
+                index.stale_sessions
+            ),
+        );
+    }
+    if memory.over_budget {
+        flag("memory", "Memory use is over budget".to_string());
+    }
+    for cache in memory.caches.iter().filter(|c| c.bytes > c.limit_bytes) {
+        flag(
+            "memory",
+            format!("The {} cache is over its limit", cache.name),
+        );
+    }
+    if let (Some(open), Some(limit)) = (resources.open_fds, resources.soft_limit) {
+        if open as f64 >= limit.max(1) as f64 * FD_WARN_RATIO {
+            flag(
+                "file_descriptors",
+                format!("{} of {} file descriptors in use", open, limit),
+            );
+        }
+    }
+    match &last_maintenance {
+        Some(run) if !run.ok() => {
+            let failed: Vec<&str> = run
+                .tasks
+                .iter()
+                .filter(|t| !t.ok)
+                .map(|t| t.name.as_str())
+                .collect();
+            flag(
+                "maintenance",
+                format!("Last maintenance failed: {}", failed.join(", ")),
+            );
+        }
+        Some(run) if now_secs().saturating_sub(run.started_at) > MAINTENANCE_OVERDUE_SECS => {
+            flag(
+                "maintenance",
+                "Maintenance has not run for over 3 days".to_string(),
+            );
+        }
+        _ => {}
+    }
+
+    RuntimeHealth {
+        status: if degraded.is_empty() {
+            "ok"
+        } else {
+            "degraded"
+        }
+        .to_string(),
+        uptime_ms: crate::startup::profile().uptime_ms,
+        watchers: resources.watchers,
+        index,
+        caches: memory.caches,
+        rss_bytes: memory.rss_bytes,
+        ptys: resources.ptys,
+        open_fds: resources.open_fds,
+        fd_limit: resources.soft_limit,
+        last_maintenance,
+        degraded,
+    }
+}
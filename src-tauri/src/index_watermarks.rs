@@ -0,0 +1,84 @@
+//! Per-session watermarks of the search index
+//!
+//! The indexer notes each session file's mtime as it reads the file, so the
+//! health report can tell which sessions changed since they were indexed.
+//! Notes are kept in memory and written by `save` after the index commits.
+//! Stored next to the index in watermarks.json; a rebuild starts afresh.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// "<project_id>/<session_id>" -> mtime (unix secs) when last indexed
+static MARKS: Mutex<Option<HashMap<String, u64>>> = Mutex::new(None);
+
+fn get_marks_path() -> PathBuf {
+    crate::get_index_dir().join("watermarks.json")
+}
+
+fn key(project_id: &str, session_id: &str) -> String {
+    format!("{}/{}", project_id, session_id)
+}
+
+fn load_from_disk() -> Result<HashMap<String, u64>, String> {
+    let path = get_marks_path();
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read index watermarks: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse index watermarks: {}", e))
+}
+
+fn cached(guard: &mut Option<HashMap<String, u64>>) -> Result<&mut HashMap<String, u64>, String> {
+    if guard.is_none() {
+        *guard = Some(load_from_disk()?);
+    }
+    Ok(guard.get_or_insert_default())
+}
+
+/// Note that a session was indexed as of `mtime`
+pub fn note(project_id: &str, session_id: &str, mtime: u64) {
+    if let Ok(mut guard) = MARKS.lock() {
+        if let Ok(marks) = cached(&mut guard) {
+            marks.insert(key(project_id, session_id), mtime);
+        }
+    }
+}
+
+/// Drop the watermark of a session removed from the index
+pub fn forget(project_id: &str, session_id: &str) {
+    if let Ok(mut guard) = MARKS.lock() {
+        if let Ok(marks) = cached(&mut guard) {
+            marks.remove(&key(project_id, session_id));
+        }
+    }
+}
+
+/// Start over for a rebuild; the old index and its watermarks are gone
+pub fn reset() {
+    if let Ok(mut guard) = MARKS.lock() {
+        *guard = Some(HashMap::new());
+    }
+}
+
+/// Write the noted watermarks; nothing to do while there is no index
+pub fn save() -> Result<(), String> {
+    if !crate::get_index_dir().exists() {
+        return Ok(());
+    }
+    let mut guard = MARKS.lock().map_err(|e| e.to_string())?;
+    let marks = cached(&mut guard)?;
+    let content = serde_json::to_string(marks).map_err(|e| e.to_string())?;
+    fs::write(get_marks_path(), content).map_err(|e| e.to_string())
+}
+
+/// Watermark of a session, if it has been indexed
+pub fn get(project_id: &str, session_id: &str) -> Option<u64> {
+    let mut guard = MARKS.lock().ok()?;
+    cached(&mut guard)
+        .ok()?
+        .get(&key(project_id, session_id))
+        .copied()
+}
@@ -0,0 +1,174 @@
+//! Knowledge base export
+//!
+//! Writes distill notes and reference docs either as an Obsidian vault (frontmatter
+//! kept, relative `.md` links turned into wikilinks) or as an mdBook project
+//! (frontmatter stripped, SUMMARY.md generated).
+
+use regex::Regex;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// `[text](target.md#anchor)` with a relative target
+static MD_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[([^\]]*)\]\(([^)\s:]+?)\.md(#[^)\s]*)?\)").expect("valid link regex")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Obsidian,
+    MdBook,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "obsidian" => Ok(Self::Obsidian),
+            "mdbook" => Ok(Self::MdBook),
+            other => Err(format!("Unknown export format: {}", other)),
+        }
+    }
+}
+
+/// A document to export
+pub struct KbDoc {
+    pub title: String,
+    pub source: PathBuf,
+    /// Frontmatter to add when the file has none (Obsidian only)
+    pub frontmatter: Option<String>,
+}
+
+/// A group of documents written to one folder
+pub struct KbSection {
+    /// Folder path inside the export, e.g. "Distill" or "Reference/claude-code"
+    pub folder: String,
+    pub title: String,
+    pub docs: Vec<KbDoc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KbExportSummary {
+    pub dest: String,
+    pub format: String,
+    pub documents: usize,
+    pub sections: usize,
+    /// Source files listed for export that no longer exist
+    pub missing: Vec<String>,
+}
+
+fn to_wikilinks(body: &str) -> String {
+    MD_LINK_RE
+        .replace_all(body, |caps: &regex::Captures| {
+            let target = caps[2].rsplit('/').next().unwrap_or(&caps[2]);
+            let anchor = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+            let text = &caps[1];
+            if text.is_empty() || text == target {
+                format!("[[{}{}]]", target, anchor)
+            } else {
+                format!("[[{}{}|{}]]", target, anchor, text)
+            }
+        })
+        .to_string()
+}
+
+fn write_doc(doc: &KbDoc, dest: &Path, format: ExportFormat) -> Result<(), String> {
+    let content = fs::read_to_string(&doc.source).map_err(|e| e.to_string())?;
+    let frontmatter = crate::frontmatter::block(&content);
+    let body = crate::frontmatter::body(&content);
+
+    let output = match format {
+        ExportFormat::Obsidian => {
+            let frontmatter = frontmatter
+                .map(|f| f.to_string())
+                .or_else(|| doc.frontmatter.clone());
+            match frontmatter {
+                Some(f) => format!("{}\n\n{}", f.trim_end(), to_wikilinks(body)),
+                None => to_wikilinks(body),
+            }
+        }
+        // mdBook renders frontmatter as text, and its relative links already resolve
+        ExportFormat::MdBook => body.to_string(),
+    };
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(dest, output).map_err(|e| e.to_string())
+}
+
+fn file_name(doc: &KbDoc) -> String {
+    doc.source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| format!("{}.md", doc.title))
+}
+
+/// Export sections into `dest` in the given format
+pub fn export(
+    sections: &[KbSection],
+    dest: &Path,
+    format: ExportFormat,
+) -> Result<KbExportSummary, String> {
+    fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+
+    let root = match format {
+        ExportFormat::Obsidian => dest.to_path_buf(),
+        ExportFormat::MdBook => dest.join("src"),
+    };
+
+    let mut documents = 0;
+    let mut written_sections = 0;
+    let mut missing = Vec::new();
+    let mut summary = String::from("# Summary\n\n[Introduction](README.md)\n");
+    for section in sections {
+        let (docs, gone): (Vec<&KbDoc>, Vec<&KbDoc>) =
+            section.docs.iter().partition(|doc| doc.source.exists());
+        missing.extend(
+            gone.iter()
+                .map(|doc| doc.source.to_string_lossy().to_string()),
+        );
+        if docs.is_empty() {
+            continue;
+        }
+        written_sections += 1;
+        summary.push_str(&format!("\n# {}\n\n", section.title));
+
+        for doc in docs {
+            let name = file_name(doc);
+            let rel = format!("{}/{}", section.folder, name);
+            write_doc(doc, &root.join(&rel), format)?;
+            summary.push_str(&format!("- [{}]({})\n", doc.title.replace(']', ""), rel));
+            documents += 1;
+        }
+    }
+
+    if format == ExportFormat::MdBook {
+        fs::write(root.join("SUMMARY.md"), summary).map_err(|e| e.to_string())?;
+        fs::write(
+            root.join("README.md"),
+            "# Knowledge Base\n\nExported from Lovcode.\n",
+        )
+        .map_err(|e| e.to_string())?;
+        let book_toml = dest.join("book.toml");
+        if !book_toml.exists() {
+            fs::write(
+                book_toml,
+                "[book]\ntitle = \"Knowledge Base\"\nsrc = \"src\"\n",
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(KbExportSummary {
+        dest: dest.to_string_lossy().to_string(),
+        format: match format {
+            ExportFormat::Obsidian => "obsidian",
+            ExportFormat::MdBook => "mdbook",
+        }
+        .to_string(),
+        documents,
+        sections: written_sections,
+        missing,
+    })
+}
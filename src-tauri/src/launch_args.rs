@@ -0,0 +1,196 @@
+//! Targets passed on the command line or opened from the OS
+//!
+//! `lovcode /path/to/project` adds the folder to the workspace (a file opens its
+//! folder), `lovcode --session <id>` opens a session's messages. `file://` and
+//! `lovcode://open?path=…` / `lovcode://session/<id>` URLs are accepted too, for
+//! "Open With" and URL handlers. Arguments are resolved in `run()` before the
+//! window exists and held until the frontend takes them; targets opened while
+//! running are held the same way and announced with "launch-target".
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+#[cfg(target_os = "macos")]
+use tauri::{AppHandle, Emitter};
+
+static PENDING: Mutex<Option<LaunchTarget>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchArg {
+    Path(PathBuf),
+    Session(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LaunchTarget {
+    Project {
+        workspace_project_id: String,
+        path: String,
+    },
+    Session {
+        project_id: String,
+        session_id: String,
+        summary: Option<String>,
+    },
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                out.push(b' ');
+                i += 1;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// A single path or URL argument
+pub fn parse_target(arg: &str) -> Option<LaunchArg> {
+    if let Some(path) = arg.strip_prefix("file://") {
+        return Some(LaunchArg::Path(PathBuf::from(percent_decode(path))));
+    }
+    if let Some(rest) = arg.strip_prefix("lovcode://") {
+        if let Some(id) = rest.strip_prefix("session/") {
+            let id = id.trim_end_matches('/');
+            return (!id.is_empty()).then(|| LaunchArg::Session(percent_decode(id)));
+        }
+        let query = rest.strip_prefix("open")?.trim_start_matches('/');
+        return query
+            .strip_prefix('?')?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("path="))
+            .map(|path| LaunchArg::Path(PathBuf::from(percent_decode(path))));
+    }
+    if arg.contains("://") {
+        return None;
+    }
+    Some(LaunchArg::Path(PathBuf::from(arg)))
+}
+
+/// The first usable target in the process arguments (without the program name)
+pub fn parse(args: impl IntoIterator<Item = String>) -> Option<LaunchArg> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--session" {
+            return args.next().map(LaunchArg::Session);
+        }
+        if let Some(id) = arg.strip_prefix("--session=") {
+            return Some(LaunchArg::Session(id.to_string()));
+        }
+        // Flags of our own or of the platform (e.g. macOS `-psn_…`) are skipped
+        if arg.starts_with('-') {
+            continue;
+        }
+        if let Some(target) = parse_target(&arg) {
+            return Some(target);
+        }
+    }
+    None
+}
+
+fn resolve_project(path: &Path) -> Result<LaunchTarget, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("{} has no parent folder", path.display()))?
+    };
+    let dir = dir.to_string_lossy().to_string();
+
+    let existing = crate::workspace_store::load_workspace()?
+        .projects
+        .into_iter()
+        .find(|p| p.path == dir);
+    let project = match existing {
+        Some(project) => project,
+        None => crate::workspace_store::add_project(dir.clone())?,
+    };
+    crate::workspace_store::set_active_project(&project.id)?;
+
+    Ok(LaunchTarget::Project {
+        workspace_project_id: project.id,
+        path: dir,
+    })
+}
+
+fn resolve_session(session_id: &str) -> Result<LaunchTarget, String> {
+    let file_name = format!("{}.jsonl", session_id);
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let entries = std::fs::read_dir(&projects_dir).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        let path = entry.path().join(&file_name);
+        if path.is_file() {
+            let (summary, _) = crate::read_session_head(&path, 20);
+            return Ok(LaunchTarget::Session {
+                project_id: entry.file_name().to_string_lossy().to_string(),
+                session_id: session_id.to_string(),
+                summary,
+            });
+        }
+    }
+    Err(format!("Session '{}' not found", session_id))
+}
+
+pub fn resolve(arg: &LaunchArg) -> Result<LaunchTarget, String> {
+    match arg {
+        LaunchArg::Path(path) => resolve_project(path),
+        LaunchArg::Session(id) => resolve_session(id),
+    }
+}
+
+/// Resolve the process arguments and keep the target for the frontend
+pub fn capture_from_env() {
+    let Some(arg) = parse(std::env::args().skip(1)) else {
+        return;
+    };
+    match resolve(&arg) {
+        Ok(target) => {
+            if let Ok(mut pending) = PENDING.lock() {
+                *pending = Some(target);
+            }
+        }
+        Err(e) => eprintln!("[Lovcode] Ignoring launch argument: {}", e),
+    }
+}
+
+/// The launch target, once
+pub fn take() -> Option<LaunchTarget> {
+    PENDING.lock().ok().and_then(|mut pending| pending.take())
+}
+
+/// Resolve a target opened while the app runs and notify the frontend, which
+/// takes it like a launch target (it may not be listening yet)
+#[cfg(target_os = "macos")]
+pub fn open(app_handle: &AppHandle, arg: &LaunchArg) {
+    match resolve(arg) {
+        Ok(target) => {
+            if let Ok(mut pending) = PENDING.lock() {
+                *pending = Some(target.clone());
+            }
+            let _ = app_handle.emit("launch-target", target);
+        }
+        Err(e) => eprintln!("[Lovcode] Ignoring opened target: {}", e),
+    }
+}
@@ -1,6 +1,68 @@
+mod accessibility;
+mod agent_files;
+mod agent_lint;
+mod agent_templates;
+mod annotations;
+mod attachments;
+mod chat_feed;
+mod collections;
+mod command_conflicts;
+mod command_files;
+mod command_lint;
+mod command_preview;
+mod command_search;
+mod command_usage;
+mod command_versions;
+mod compaction;
+mod context_files;
+mod conversation_tree;
+mod customizations_watcher;
+mod cwd_suggestions;
 mod diagnostics;
+mod distill_templates;
+mod doc_diff;
+mod exporters;
+mod feature_timers;
+mod frontmatter;
+mod health;
 mod hook_watcher;
+mod index_watermarks;
+mod kb_export;
+mod launch_args;
+mod listing_cache;
+mod maintenance;
+mod markdown;
+mod memory;
+mod message_counts;
+mod notes_import;
+mod orphaned_projects;
+mod path_mappings;
+mod port_scanner;
+mod project_assets;
+mod project_groups;
+mod project_meta;
+mod project_overview;
+mod project_relocation;
+mod project_usage;
 mod pty_manager;
+mod redaction;
+mod reference_freshness;
+mod resource_limits;
+mod session_archive;
+mod session_cache;
+mod session_cleanup;
+mod session_diff;
+mod session_export;
+mod session_links;
+mod session_meta;
+mod sessions_watcher;
+mod skill_files;
+mod skill_templates;
+mod startup;
+mod tool_calls;
+mod trash;
+mod usage;
+mod workspace_snapshots;
 mod workspace_store;
 
 use jieba_rs::Jieba;
@@ -15,7 +77,7 @@ use std::sync::LazyLock;
 use std::sync::Mutex;
 use std::time::Duration;
 use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
+use tantivy::query::{AllQuery, BooleanQuery, Occur, PhraseQuery, Query, QueryParser, TermQuery};
 use tantivy::schema::{self, Value as TantivyValue, *};
 use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, TokenStream, Tokenizer};
 use tantivy::{doc, Index, IndexWriter, ReloadPolicy};
@@ -26,19 +88,8 @@ use objc::runtime::YES;
 #[cfg(target_os = "macos")]
 use objc::*;
 
-// Global jieba instance for Chinese tokenization
-static JIEBA: LazyLock<Jieba> = LazyLock::new(|| Jieba::new());
-
-// Cache for command stats with incremental update support
-// (stats, scanned_files with their mtime)
-static COMMAND_STATS_CACHE: LazyLock<Mutex<CommandStatsCache>> =
-    LazyLock::new(|| Mutex::new(CommandStatsCache::default()));
-
-#[derive(Default)]
-struct CommandStatsCache {
-    stats: HashMap<String, usize>,
-    scanned: HashMap<String, u64>, // path -> file_size (for incremental read)
-}
+// Global jieba instance for Chinese tokenization, loaded on first search
+static JIEBA: LazyLock<Jieba> = LazyLock::new(|| startup::measure("jieba_dictionary", Jieba::new));
 
 // Custom tokenizer for Chinese + English mixed content
 #[derive(Clone)]
@@ -102,6 +153,18 @@ impl TokenStream for JiebaTokenStream {
 // Global search index state
 static SEARCH_INDEX: Mutex<Option<SearchIndex>> = Mutex::new(None);
 
+// Per-project index partitions opened so far (project_id -> index)
+static SEARCH_PARTITIONS: LazyLock<Mutex<HashMap<String, SearchIndex>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Cancellation flags for in-flight session message streams (stream_id -> cancelled)
+static SESSION_STREAMS: LazyLock<Mutex<HashMap<String, std::sync::Arc<std::sync::atomic::AtomicBool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Live-tailed sessions ("<project_id>/<session_id>" -> stop flag)
+static SESSION_WATCHES: LazyLock<Mutex<HashMap<String, std::sync::Arc<std::sync::atomic::AtomicBool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 // Distill watch state
 static DISTILL_WATCH_ENABLED: std::sync::atomic::AtomicBool =
     std::sync::atomic::AtomicBool::new(true);
@@ -140,6 +203,8 @@ fn create_schema() -> Schema {
     schema_builder.add_text_field("session_id", STRING | STORED);
     schema_builder.add_text_field("session_summary", text_options);
     schema_builder.add_text_field("timestamp", STRING | STORED);
+    // Set for agent sidechain documents; links back to the main session
+    schema_builder.add_text_field("parent_session_id", STRING | STORED);
     schema_builder.build()
 }
 
@@ -156,9 +221,36 @@ pub struct Project {
     pub path: String,
     pub session_count: usize,
     pub last_active: u64,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub archived: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Which projects `list_projects` returns; by default neither hidden nor archived ones
+#[derive(Debug, Default, Deserialize)]
+pub struct ProjectFilters {
+    #[serde(default)]
+    pub include_hidden: bool,
+    #[serde(default)]
+    pub include_archived: bool,
+    /// Only archived projects (implies include_archived)
+    #[serde(default)]
+    pub archived_only: bool,
+}
+
+impl ProjectFilters {
+    fn matches(&self, project: &Project) -> bool {
+        if self.archived_only {
+            return project.archived && (self.include_hidden || !project.hidden);
+        }
+        (self.include_hidden || !project.hidden) && (self.include_archived || !project.archived)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Session {
     pub id: String,
     pub project_id: String,
@@ -166,9 +258,168 @@ pub struct Session {
     pub summary: Option<String>,
     pub message_count: usize,
     pub last_modified: u64,
+    /// Size of the session file on disk
+    #[serde(default)]
+    pub size_bytes: u64,
+    /// Rough token count of the message content (about 4 characters per token)
+    #[serde(default)]
+    pub estimated_tokens: u64,
+    /// Auto-detected topics (frontend, infra, debugging, writing)
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// Detected conversation language ("zh" / "en")
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub starred: bool,
+    /// User-assigned tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// User-provided title; display it in place of `summary` when set
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Review comments left on messages of this session
+    #[serde(default)]
+    pub annotation_count: usize,
+    /// Token totals and estimated cost, once the session has been analyzed
+    #[serde(default)]
+    pub usage: Option<usage::SessionUsage>,
+    /// Sessions this one continues, is continued in, or forks from
+    #[serde(default)]
+    pub related_sessions: Vec<session_links::RelatedSession>,
+}
+
+/// Fill in fields derived from the session metadata cache, returning the cache entry
+fn apply_cached_meta(
+    session: &mut Session,
+    prices: &usage::PriceTable,
+) -> Option<session_cache::SessionMeta> {
+    let path = get_session_path(&session.project_id, &session.id);
+    let meta = session_cache::get(&path)?;
+    session.topics = meta.topics.clone();
+    session.language = meta.language.clone();
+    session.usage = Some(usage::summarize(&meta.usage, prices));
+    Some(meta)
+}
+
+/// Fill in favorites/tags/notes from the user metadata store
+fn apply_user_meta<'a>(
+    session: &mut Session,
+    store: &'a session_meta::SessionMetaStore,
+) -> Option<&'a session_meta::SessionUserMeta> {
+    let meta = store.get(&session_meta::key(&session.project_id, &session.id))?;
+    session.starred = meta.starred;
+    session.tags = meta.tags.clone();
+    session.note = meta.note.clone();
+    session.title = meta.title.clone();
+    Some(meta)
+}
+
+fn apply_annotation_count(session: &mut Session, counts: &HashMap<String, usize>) {
+    session.annotation_count = counts
+        .get(&session_meta::key(&session.project_id, &session.id))
+        .copied()
+        .unwrap_or(0);
+}
+
+/// Server-side filters for `list_all_sessions`, evaluated against the metadata cache
+#[derive(Debug, Default, Deserialize)]
+pub struct SessionFilters {
+    pub topic: Option<String>,
+    pub has_errors: Option<bool>,
+    /// Slash command name, with or without leading slash
+    pub used_command: Option<String>,
+    /// Case-insensitive substring of the model id (e.g. "opus")
+    pub model: Option<String>,
+    /// Only sessions with more than N user turns
+    pub longer_than: Option<usize>,
+    /// Substring of a file path touched by tools
+    pub touched_file: Option<String>,
+    /// Only sessions without detected topics
+    pub untagged: Option<bool>,
+    pub starred: Option<bool>,
+    /// User-assigned tag
+    pub tag: Option<String>,
+    /// Only sessions with (or without) message annotations
+    pub annotated: Option<bool>,
+}
+
+impl SessionFilters {
+    /// True when no cache-based filter is set
+    fn is_empty(&self) -> bool {
+        self.topic.is_none()
+            && self.has_errors.is_none()
+            && self.used_command.is_none()
+            && self.model.is_none()
+            && self.longer_than.is_none()
+            && self.touched_file.is_none()
+            && self.untagged.is_none()
+    }
+
+    fn matches(&self, meta: Option<&session_cache::SessionMeta>) -> bool {
+        // Sessions not analyzed yet can't satisfy any filter
+        let Some(meta) = meta else {
+            return self.is_empty();
+        };
+
+        if let Some(topic) = &self.topic {
+            if !meta.topics.contains(topic) {
+                return false;
+            }
+        }
+        if let Some(has_errors) = self.has_errors {
+            if meta.has_errors != has_errors {
+                return false;
+            }
+        }
+        if let Some(command) = &self.used_command {
+            let command = command.trim_start_matches('/');
+            if !meta.commands.iter().any(|c| c == command) {
+                return false;
+            }
+        }
+        if let Some(model) = &self.model {
+            let model = model.to_lowercase();
+            if !meta.models.iter().any(|m| m.to_lowercase().contains(&model)) {
+                return false;
+            }
+        }
+        if let Some(turns) = self.longer_than {
+            if meta.turns <= turns {
+                return false;
+            }
+        }
+        if let Some(file) = &self.touched_file {
+            if !meta.touched_files.iter().any(|f| f.contains(file.as_str())) {
+                return false;
+            }
+        }
+        if let Some(untagged) = self.untagged {
+            if meta.topics.is_empty() != untagged {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn matches_user(&self, meta: Option<&session_meta::SessionUserMeta>) -> bool {
+        if let Some(starred) = self.starred {
+            if meta.map(|m| m.starred).unwrap_or(false) != starred {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if !meta.map(|m| m.tags.contains(tag)).unwrap_or(false) {
+                return false;
+            }
+        }
+        true
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub uuid: String,
     pub role: String,
@@ -177,6 +428,18 @@ pub struct Message {
     pub is_meta: bool,  // slash command 展开的内容
     pub is_tool: bool,  // tool_use 或 tool_result
     pub line_number: usize,
+    /// Tool calls issued by this message
+    #[serde(default)]
+    pub tools: Vec<tool_calls::ToolCall>,
+    /// Extended-thinking text, only when requested
+    #[serde(default)]
+    pub thinking: Option<String>,
+    /// Summary a compacted conversation continues from
+    #[serde(default)]
+    pub is_compact_summary: bool,
+    /// Images, documents and `@file` references (filled for session pages)
+    #[serde(default)]
+    pub attachments: Vec<attachments::Attachment>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -194,7 +457,10 @@ pub struct ChatMessage {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChatsResponse {
     pub items: Vec<ChatMessage>,
+    /// Approximate number of messages across all sessions
     pub total: usize,
+    /// Pass back as `cursor` for the next page; None after the last one
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -207,12 +473,25 @@ struct RawLine {
     timestamp: Option<String>,
     #[serde(rename = "isMeta")]
     is_meta: Option<bool>,
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+    /// e.g. "compact_boundary" on `system` lines
+    subtype: Option<String>,
+    #[serde(rename = "compactMetadata")]
+    compact_metadata: Option<compaction::CompactMetadata>,
+    #[serde(rename = "isCompactSummary")]
+    is_compact_summary: Option<bool>,
+    /// Working directory when the line was written
+    cwd: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct RawMessage {
+    id: Option<String>,
     role: Option<String>,
+    model: Option<String>,
     content: Option<serde_json::Value>,
+    usage: Option<usage::TokenUsage>,
 }
 
 /// Entry from history.jsonl - used as fast session index
@@ -242,9 +521,45 @@ pub struct LocalCommand {
     pub deprecated_by: Option<String>, // replacement command name
     pub changelog: Option<String>,     // changelog content if .changelog file exists
     pub aliases: Vec<String>,          // previous names for stats aggregation
+    pub tags: Vec<String>,             // topics from the `tags:` frontmatter field
     pub frontmatter: Option<String>,   // raw frontmatter text (if any)
 }
 
+/// Which commands `list_local_commands` returns; by default all of them
+#[derive(Debug, Default, Deserialize)]
+pub struct CommandFilters {
+    /// Commands with any of these tags (case-insensitive)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// "active" | "deprecated" | "archived"
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl CommandFilters {
+    fn matches(&self, command: &LocalCommand) -> bool {
+        let tag_match = self.tags.is_empty()
+            || command
+                .tags
+                .iter()
+                .any(|t| self.tags.iter().any(|f| f.eq_ignore_ascii_case(t)));
+        tag_match && self.status.as_ref().is_none_or(|s| *s == command.status)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandTagGroup {
+    pub tag: String,
+    pub commands: Vec<LocalCommand>,
+}
+
+/// Commands grouped by tag; a command with several tags is in each of their groups
+#[derive(Debug, Serialize)]
+pub struct CommandsByTag {
+    pub groups: Vec<CommandTagGroup>,
+    pub untagged: Vec<LocalCommand>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct McpServer {
     pub name: String,
@@ -312,7 +627,12 @@ fn encode_project_path(path: &str) -> String {
 /// Decode project ID to actual filesystem path.
 /// Claude Code encodes: `/` -> `-`, and `.` -> `-`
 /// So `/.` becomes `--`, but `-` in directory names is NOT escaped
+/// A user-set mapping takes precedence over the guess.
 fn decode_project_path(id: &str) -> String {
+    if let Some(path) = path_mappings::get(id) {
+        return path;
+    }
+
     // First, handle `--` which means `/.` (hidden directories like .claude)
     // Replace `--` with a placeholder, then `-` with `/`, then restore `/.`
     let base = id
@@ -374,57 +694,159 @@ fn try_merge_segments(prefix: &str, rest: &str) -> Option<String> {
 }
 
 #[tauri::command]
-async fn list_projects() -> Result<Vec<Project>, String> {
+async fn list_projects(filters: Option<ProjectFilters>) -> Result<Vec<Project>, String> {
     // Run blocking IO on a separate thread to avoid blocking the main thread
-    tauri::async_runtime::spawn_blocking(|| {
-        let projects_dir = get_claude_dir().join("projects");
+    tauri::async_runtime::spawn_blocking(move || Ok(collect_projects(&filters.unwrap_or_default())))
+        .await
+        .map_err(|e| e.to_string())?
+}
 
-        if !projects_dir.exists() {
-            return Ok(vec![]);
-        }
+/// Matching projects, pinned first, then most recent
+fn collect_projects(filters: &ProjectFilters) -> Vec<Project> {
+    let user_meta = project_meta::load().unwrap_or_default();
+    let mut projects: Vec<Project> = listing_cache::all_sessions()
+        .into_iter()
+        .map(|(id, sessions)| {
+            let meta = user_meta.get(&id).cloned().unwrap_or_default();
+            Project {
+                path: decode_project_path(&id),
+                id,
+                session_count: sessions.len(),
+                last_active: sessions.iter().map(|s| s.mtime).max().unwrap_or(0),
+                pinned: meta.pinned,
+                hidden: meta.hidden,
+                archived: meta.archived,
+            }
+        })
+        .filter(|project| filters.matches(project))
+        .collect();
 
-        let mut projects = Vec::new();
+    projects.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then(b.last_active.cmp(&a.last_active))
+    });
+    projects
+}
 
-        for entry in fs::read_dir(&projects_dir).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
-            let path = entry.path();
+#[derive(Debug, Serialize)]
+pub struct ProjectGroupListing {
+    pub group: project_groups::ProjectGroup,
+    pub projects: Vec<Project>,
+}
 
-            if path.is_dir() {
-                let id = path.file_name().unwrap().to_string_lossy().to_string();
-                let display_path = decode_project_path(&id);
-
-                let mut session_count = 0;
-                let mut last_active: u64 = 0;
-
-                if let Ok(entries) = fs::read_dir(&path) {
-                    for entry in entries.filter_map(|e| e.ok()) {
-                        let name = entry.file_name().to_string_lossy().to_string();
-                        if name.ends_with(".jsonl") && !name.starts_with("agent-") {
-                            session_count += 1;
-                            if let Ok(meta) = entry.metadata() {
-                                if let Ok(modified) = meta.modified() {
-                                    if let Ok(duration) =
-                                        modified.duration_since(std::time::UNIX_EPOCH)
-                                    {
-                                        last_active = last_active.max(duration.as_secs());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+#[derive(Debug, Serialize)]
+pub struct GroupedProjects {
+    pub groups: Vec<ProjectGroupListing>,
+    /// Projects in no group
+    pub ungrouped: Vec<Project>,
+}
 
-                projects.push(Project {
-                    id: id.clone(),
-                    path: display_path,
-                    session_count,
-                    last_active,
-                });
-            }
-        }
+/// Projects by group for the sidebar; each group keeps the `list_projects` order
+#[tauri::command]
+async fn list_projects_grouped(filters: Option<ProjectFilters>) -> Result<GroupedProjects, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut ungrouped = collect_projects(&filters.unwrap_or_default());
+        let groups = project_groups::load()?
+            .into_iter()
+            .map(|group| {
+                let (projects, rest) = ungrouped
+                    .drain(..)
+                    .partition(|p: &Project| group.project_ids.contains(&p.id));
+                ungrouped = rest;
+                ProjectGroupListing { group, projects }
+            })
+            .collect();
+        Ok(GroupedProjects { groups, ungrouped })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn create_project_group(
+    name: String,
+    color: Option<String>,
+) -> Result<project_groups::ProjectGroup, String> {
+    project_groups::create(&name, color)
+}
+
+/// Rename, recolor or fold a group; omitted fields stay unchanged
+#[tauri::command]
+fn update_project_group(
+    id: String,
+    name: Option<String>,
+    color: Option<String>,
+    collapsed: Option<bool>,
+) -> Result<project_groups::ProjectGroup, String> {
+    project_groups::update(&id, name, color, collapsed)
+}
+
+/// Delete a group; its projects become ungrouped
+#[tauri::command]
+fn delete_project_group(id: String) -> Result<(), String> {
+    project_groups::delete(&id)
+}
+
+/// Move a project into a group, or out of all groups when `group_id` is omitted
+#[tauri::command]
+fn set_project_group(project_id: String, group_id: Option<String>) -> Result<(), String> {
+    project_groups::assign(&project_id, group_id.as_deref())
+}
 
-        projects.sort_by(|a, b| b.last_active.cmp(&a.last_active));
-        Ok(projects)
+#[tauri::command]
+fn reorder_project_groups(ids: Vec<String>) -> Result<Vec<project_groups::ProjectGroup>, String> {
+    project_groups::reorder(&ids)
+}
+
+/// User-set project paths, by project id
+#[tauri::command]
+fn get_project_path_mappings() -> Result<std::collections::BTreeMap<String, String>, String> {
+    path_mappings::load()
+}
+
+/// Fix the path of a misdecoded project; `path: None` goes back to decoding
+#[tauri::command]
+fn set_project_path_mapping(project_id: String, path: Option<String>) -> Result<String, String> {
+    path_mappings::set(&project_id, path.as_deref())?;
+    Ok(decode_project_path(&project_id))
+}
+
+/// Move a project's history to the folder it now lives in
+#[tauri::command]
+async fn relocate_project(
+    project_id: String,
+    new_path: String,
+    merge: Option<bool>,
+) -> Result<project_relocation::RelocationResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        project_relocation::relocate(&project_id, &new_path, merge.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Session, usage, command and tool stats of one project
+#[tauri::command]
+async fn get_project_overview(
+    project_id: String,
+) -> Result<project_overview::ProjectOverview, String> {
+    tauri::async_runtime::spawn_blocking(move || project_overview::overview(&project_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Tokens and estimated cost of a project by day and model; `period` defaults to all time
+#[tauri::command]
+async fn get_project_usage(
+    project_id: String,
+    period: Option<project_usage::UsagePeriod>,
+) -> Result<project_usage::ProjectUsage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        project_usage::project_usage(
+            &project_id,
+            period.unwrap_or(project_usage::UsagePeriod::All),
+        )
     })
     .await
     .map_err(|e| e.to_string())?
@@ -439,37 +861,20 @@ async fn list_sessions(project_id: String) -> Result<Vec<Session>, String> {
             return Err("Project not found".to_string());
         }
 
-        let mut sessions = Vec::new();
+        let mut sessions: Vec<Session> = listing_cache::project_sessions(&project_id)
+            .iter()
+            .map(|cached| session_from_cached(cached, None))
+            .collect();
 
-        for entry in fs::read_dir(&project_dir).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
-            let path = entry.path();
-            let name = path.file_name().unwrap().to_string_lossy().to_string();
-
-            if name.ends_with(".jsonl") && !name.starts_with("agent-") {
-                let session_id = name.trim_end_matches(".jsonl").to_string();
-
-                // Only read head for summary (much faster)
-                let (summary, message_count) = read_session_head(&path, 20);
-
-                let metadata = fs::metadata(&path).ok();
-                let last_modified = metadata
-                    .and_then(|m| m.modified().ok())
-                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                    .map(|d| d.as_secs())
-                    .unwrap_or(0);
-
-                sessions.push(Session {
-                    id: session_id,
-                    project_id: project_id.clone(),
-                    project_path: None,
-                    summary,
-                    message_count,
-                    last_modified,
-                });
-            }
+        let user_meta = session_meta::load().unwrap_or_default();
+        let annotation_counts = annotations::counts();
+        let prices = usage::load_prices();
+        for session in sessions.iter_mut() {
+            apply_cached_meta(session, &prices);
+            apply_user_meta(session, &user_meta);
+            apply_annotation_count(session, &annotation_counts);
         }
-
+        session_links::apply(&mut sessions);
         sessions.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
         Ok(sessions)
     })
@@ -477,6 +882,24 @@ async fn list_sessions(project_id: String) -> Result<Vec<Session>, String> {
     .map_err(|e| e.to_string())?
 }
 
+/// Session entry from its cached listing data; user and derived metadata are applied later
+fn session_from_cached(
+    cached: &listing_cache::CachedSession,
+    project_path: Option<String>,
+) -> Session {
+    Session {
+        id: cached.session_id.clone(),
+        project_id: cached.project_id.clone(),
+        project_path,
+        summary: cached.summary.clone(),
+        message_count: cached.message_count,
+        last_modified: cached.mtime,
+        size_bytes: cached.size,
+        estimated_tokens: cached.estimated_tokens,
+        ..Default::default()
+    }
+}
+
 /// Read only the first N lines of a session file to get summary (much faster than reading entire file)
 fn read_session_head(path: &Path, max_lines: usize) -> (Option<String>, usize) {
     use std::io::{BufRead, BufReader};
@@ -551,109 +974,61 @@ fn build_session_index_from_history() -> HashMap<(String, String), (u64, Option<
 }
 
 #[tauri::command]
-async fn list_all_sessions() -> Result<Vec<Session>, String> {
-    tauri::async_runtime::spawn_blocking(|| {
+async fn list_all_sessions(
+    filters: Option<SessionFilters>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<Vec<Session>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
         let projects_dir = get_claude_dir().join("projects");
 
         if !projects_dir.exists() {
             return Ok(vec![]);
         }
 
-        // Build index from history.jsonl first (fast)
+        // history.jsonl has a prompt to fall back on when a session has no summary
         let history_index = build_session_index_from_history();
 
         let mut all_sessions = Vec::new();
-        let mut seen_sessions: std::collections::HashSet<(String, String)> =
-            std::collections::HashSet::new();
-
-        // First pass: use history index for sessions with sessionId
-        for ((project_id, session_id), (timestamp, display)) in &history_index {
-            let session_path = projects_dir
-                .join(project_id)
-                .join(format!("{}.jsonl", session_id));
-
-            if !session_path.exists() {
-                continue;
-            }
-
-            seen_sessions.insert((project_id.clone(), session_id.clone()));
-
-            // Only read head for summary (first 20 lines should be enough)
-            let (summary, head_msg_count) = read_session_head(&session_path, 20);
-
-            // Use display as fallback summary
-            let final_summary = summary.or_else(|| display.clone());
-
-            // Use file mtime for accurate last_modified
-            let metadata = fs::metadata(&session_path).ok();
-            let last_modified = metadata
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_secs())
-                .unwrap_or(*timestamp / 1000); // fallback to history timestamp
-
-            let display_path = decode_project_path(project_id);
-
-            all_sessions.push(Session {
-                id: session_id.clone(),
-                project_id: project_id.clone(),
-                project_path: Some(display_path),
-                summary: final_summary,
-                message_count: head_msg_count, // approximate from head
-                last_modified,
-            });
-        }
-
-        // Second pass: scan for sessions not in history (older sessions without sessionId)
-        for project_entry in fs::read_dir(&projects_dir).into_iter().flatten().flatten() {
-            let project_path = project_entry.path();
-            if !project_path.is_dir() {
-                continue;
-            }
-
-            let project_id = project_path
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .to_string();
+        for (project_id, cached_sessions) in listing_cache::all_sessions() {
             let display_path = decode_project_path(&project_id);
-
-            for entry in fs::read_dir(&project_path).into_iter().flatten().flatten() {
-                let path = entry.path();
-                let name = path.file_name().unwrap().to_string_lossy().to_string();
-
-                if name.ends_with(".jsonl") && !name.starts_with("agent-") {
-                    let session_id = name.trim_end_matches(".jsonl").to_string();
-
-                    // Skip if already processed from history
-                    if seen_sessions.contains(&(project_id.clone(), session_id.clone())) {
-                        continue;
-                    }
-
-                    // Read only head for summary
-                    let (summary, head_msg_count) = read_session_head(&path, 20);
-
-                    let metadata = fs::metadata(&path).ok();
-                    let last_modified = metadata
-                        .and_then(|m| m.modified().ok())
-                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                        .map(|d| d.as_secs())
-                        .unwrap_or(0);
-
-                    all_sessions.push(Session {
-                        id: session_id,
-                        project_id: project_id.clone(),
-                        project_path: Some(display_path.clone()),
-                        summary,
-                        message_count: head_msg_count,
-                        last_modified,
-                    });
+            for cached in &cached_sessions {
+                let mut session = session_from_cached(cached, Some(display_path.clone()));
+                if session.summary.is_none() {
+                    session.summary = history_index
+                        .get(&(project_id.clone(), cached.session_id.clone()))
+                        .and_then(|(_, display)| display.clone());
                 }
+                all_sessions.push(session);
             }
         }
 
+        let filters = filters.unwrap_or_default();
+        let user_meta = session_meta::load().unwrap_or_default();
+        let annotation_counts = annotations::counts();
+        let prices = usage::load_prices();
+        all_sessions.retain_mut(|session| {
+            let meta = apply_cached_meta(session, &prices);
+            let user = apply_user_meta(session, &user_meta);
+            apply_annotation_count(session, &annotation_counts);
+            let annotated = filters
+                .annotated
+                .map(|a| a == (session.annotation_count > 0))
+                .unwrap_or(true);
+            filters.matches(meta.as_ref()) && filters.matches_user(user) && annotated
+        });
+
+        message_counts::persist();
         all_sessions.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
-        Ok(all_sessions)
+
+        // Page after filtering so the UI gets full pages of matches
+        let mut page: Vec<Session> = all_sessions
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+        session_links::apply(&mut page);
+        Ok(page)
     })
     .await
     .map_err(|e| e.to_string())?
@@ -662,178 +1037,1532 @@ async fn list_all_sessions() -> Result<Vec<Session>, String> {
 #[tauri::command]
 async fn list_all_chats(
     limit: Option<usize>,
-    offset: Option<usize>,
+    cursor: Option<String>,
 ) -> Result<ChatsResponse, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        let projects_dir = get_claude_dir().join("projects");
-        let max_messages = limit.unwrap_or(50);
-        let skip = offset.unwrap_or(0);
-
-        if !projects_dir.exists() {
-            return Ok(ChatsResponse {
-                items: vec![],
-                total: 0,
-            });
-        }
-
-        // Collect all session files with metadata
-        let mut session_files: Vec<(PathBuf, String, String, u64)> = Vec::new();
+        chat_feed::page(limit.unwrap_or(50), cursor.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-        for project_entry in fs::read_dir(&projects_dir).map_err(|e| e.to_string())? {
-            let project_entry = project_entry.map_err(|e| e.to_string())?;
-            let project_path = project_entry.path();
+/// Parse one jsonl line into a displayable message (None for non-message or empty lines)
+fn parse_message_line(line: &str, line_number: usize) -> Option<Message> {
+    let parsed = serde_json::from_str::<RawLine>(line).ok()?;
+    message_from_raw(parsed, line_number, false, false)
+}
 
-            if !project_path.is_dir() {
-                continue;
-            }
+/// `include_attachments` decodes inline images into the attachment cache
+fn message_from_raw(
+    parsed: RawLine,
+    line_number: usize,
+    include_thinking: bool,
+    include_attachments: bool,
+) -> Option<Message> {
+    let line_type = parsed.line_type.as_deref();
+    if line_type != Some("user") && line_type != Some("assistant") {
+        return None;
+    }
 
-            let project_id = project_path
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .to_string();
-            let display_path = decode_project_path(&project_id);
+    let msg = parsed.message.as_ref()?;
+    let role = msg.role.clone().unwrap_or_default();
+    let (content, is_tool) = extract_content_with_meta(&msg.content);
+    let tools = tool_calls::tool_uses(&msg.content);
+    let thinking = if include_thinking {
+        extract_thinking(&msg.content)
+    } else {
+        None
+    };
+    let is_meta = parsed.is_meta.unwrap_or(false);
+    // Judged without decoding, so a message counts the same with or without attachments
+    let has_inline = attachments::has_inline(&msg.content);
+    if content.is_empty() && tools.is_empty() && thinking.is_none() && !has_inline {
+        return None;
+    }
+    let attachments = if include_attachments {
+        let prompt = (role == "user" && !is_meta).then_some(content.as_str());
+        attachments::extract(&msg.content, prompt, parsed.cwd.as_deref())
+    } else {
+        Vec::new()
+    };
 
-            for entry in fs::read_dir(&project_path).map_err(|e| e.to_string())? {
-                let entry = entry.map_err(|e| e.to_string())?;
-                let path = entry.path();
-                let name = path.file_name().unwrap().to_string_lossy().to_string();
-
-                if name.ends_with(".jsonl") && !name.starts_with("agent-") {
-                    let last_modified = entry
-                        .metadata()
-                        .ok()
-                        .and_then(|m| m.modified().ok())
-                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                        .map(|d| d.as_secs())
-                        .unwrap_or(0);
+    Some(Message {
+        uuid: parsed.uuid.unwrap_or_default(),
+        role,
+        content,
+        timestamp: parsed.timestamp.unwrap_or_default(),
+        is_meta,
+        is_tool,
+        line_number,
+        tools,
+        thinking,
+        is_compact_summary: parsed.is_compact_summary.unwrap_or(false),
+        attachments,
+    })
+}
 
-                    session_files.push((
-                        path,
-                        project_id.clone(),
-                        display_path.clone(),
-                        last_modified,
-                    ));
-                }
+/// A window of messages from a session plus the total message count
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MessagesPage {
+    pub items: Vec<Message>,
+    pub total: usize,
+    /// Index of the first item within the whole session
+    pub offset: usize,
+    /// Token usage and estimated cost of the whole session
+    pub usage: usage::SessionUsage,
+    /// Items as labelled plain text, when requested for screen readers
+    #[serde(default)]
+    pub transcript: Option<String>,
+    /// Stretches of the whole session between compactions, in order
+    #[serde(default)]
+    pub segments: Vec<compaction::SessionSegment>,
+}
+
+/// Which messages to return, applied while parsing so filtered-out messages never
+/// cross IPC. `total` and `offset` of the page count filtered messages only.
+#[derive(Debug, Default, Deserialize)]
+pub struct MessageFilter {
+    /// Only messages with one of these roles ("user", "assistant")
+    pub roles: Option<Vec<String>>,
+    /// Slash command expansions; default on
+    pub include_meta: Option<bool>,
+    /// Tool-only messages and tool calls of other messages; default on
+    pub include_tools: Option<bool>,
+    /// Only messages with text content
+    pub text_only: Option<bool>,
+}
+
+impl MessageFilter {
+    /// The message as it should be returned, or None when it is filtered out
+    fn apply(&self, mut message: Message) -> Option<Message> {
+        if let Some(roles) = &self.roles {
+            if !roles.iter().any(|r| r == &message.role) {
+                return None;
             }
         }
+        if message.is_meta && !self.include_meta.unwrap_or(true) {
+            return None;
+        }
+        if !self.include_tools.unwrap_or(true) {
+            if message.is_tool && message.content.is_empty() {
+                return None;
+            }
+            message.tools.clear();
+        }
+        if self.text_only.unwrap_or(false) && message.content.trim().is_empty() {
+            return None;
+        }
+        Some(message)
+    }
+}
 
-        // Sort by last modified (newest first)
-        session_files.sort_by(|a, b| b.3.cmp(&a.3));
-
-        let mut all_chats: Vec<ChatMessage> = Vec::new();
-
-        // Process all sessions to get total count
-        for (path, project_id, project_path, _) in session_files {
-            let session_id = path.file_stem().unwrap().to_string_lossy().to_string();
-            let content = fs::read_to_string(&path).unwrap_or_default();
-
-            let mut session_summary: Option<String> = None;
-            let mut session_messages: Vec<ChatMessage> = Vec::new();
-
-            for line in content.lines() {
-                if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
-                    let line_type = parsed.line_type.as_deref();
+/// Load a page of messages. `tail` returns the last N messages and takes precedence
+/// over `offset`; without `limit`/`tail` the whole session is returned.
+/// `include_thinking` adds extended-thinking blocks to assistant messages;
+/// `accessible` adds a plain-text transcript of the items; `filter` drops
+/// messages by role and kind before paging.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn get_session_messages(
+    project_id: String,
+    session_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    tail: Option<usize>,
+    include_thinking: Option<bool>,
+    accessible: Option<bool>,
+    filter: Option<MessageFilter>,
+) -> Result<MessagesPage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_path = get_session_path(&project_id, &session_id);
+        if !session_path.exists() {
+            return Err("Session not found".to_string());
+        }
+        let mut page = load_messages_page(
+            &session_path,
+            offset,
+            limit,
+            tail,
+            include_thinking.unwrap_or(false),
+            &filter.unwrap_or_default(),
+        )?;
+        if accessible.unwrap_or(false) {
+            page.transcript = Some(accessibility::transcript(&page.items));
+        }
+        Ok(page)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-                    if line_type == Some("summary") {
-                        session_summary = parsed.summary;
+/// Read a window of messages from a session file, pairing tool calls and summing usage
+fn load_messages_page(
+    session_path: &Path,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    tail: Option<usize>,
+    include_thinking: bool,
+    filter: &MessageFilter,
+) -> Result<MessagesPage, String> {
+    let file = fs::File::open(session_path).map_err(|e| e.to_string())?;
+    Ok(read_messages_page(
+        std::io::BufReader::new(file),
+        offset,
+        limit,
+        tail,
+        include_thinking,
+        filter,
+    ))
+}
+
+/// `load_messages_page` over any line source, e.g. a decompressed archive
+fn read_messages_page(
+    reader: impl std::io::BufRead,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    tail: Option<usize>,
+    include_thinking: bool,
+    filter: &MessageFilter,
+) -> MessagesPage {
+    let start = offset.unwrap_or(0);
+    let end = limit.map(|l| start.saturating_add(l)).unwrap_or(usize::MAX);
+
+    // Stream lines so large sessions never have to be held in memory as a whole.
+    // Items keep their raw line so attachments are extracted for the page only.
+    let mut items: std::collections::VecDeque<(Message, String)> =
+        std::collections::VecDeque::new();
+    let mut total = 0;
+    let mut session_usage = usage::UsageAccumulator::default();
+    let mut pairing = tool_calls::ToolPairing::default();
+    let mut segments = compaction::SegmentTracker::default();
+
+    for (idx, line) in reader.lines().enumerate() {
+        let Ok(line) = line else { continue };
+        let Ok(parsed) = serde_json::from_str::<RawLine>(&line) else {
+            continue;
+        };
+        segments.observe(&parsed, idx + 1, total);
+        if let Some(msg) = parsed.message.as_ref() {
+            match parsed.line_type.as_deref() {
+                Some("assistant") => {
+                    if let Some(u) = &msg.usage {
+                        session_usage.add(msg.id.as_deref(), msg.model.as_deref(), u);
                     }
+                }
+                Some("user") => pairing.add_results(
+                    &msg.content,
+                    parsed.timestamp.as_deref().unwrap_or_default(),
+                ),
+                _ => {}
+            }
+        }
+        let Some(message) = message_from_raw(parsed, idx + 1, include_thinking, false)
+            .and_then(|m| filter.apply(m))
+        else {
+            continue;
+        };
 
-                    if line_type == Some("user") || line_type == Some("assistant") {
-                        if let Some(msg) = &parsed.message {
-                            let role = msg.role.clone().unwrap_or_default();
-                            let (text_content, _is_tool) = extract_content_with_meta(&msg.content);
-                            let is_meta = parsed.is_meta.unwrap_or(false);
-
-                            // Skip meta messages and empty content
-                            if !is_meta && !text_content.is_empty() {
-                                session_messages.push(ChatMessage {
-                                    uuid: parsed.uuid.unwrap_or_default(),
-                                    role,
-                                    content: text_content,
-                                    timestamp: parsed.timestamp.unwrap_or_default(),
-                                    project_id: project_id.clone(),
-                                    project_path: project_path.clone(),
-                                    session_id: session_id.clone(),
-                                    session_summary: None, // Will be filled later
-                                });
-                            }
+        match tail {
+            Some(n) => {
+                if n > 0 {
+                    if items.len() == n {
+                        if let Some((dropped, _)) = items.pop_front() {
+                            pairing.forget(&dropped.tools);
                         }
                     }
+                    pairing.want(&message.tools, &message.timestamp);
+                    items.push_back((message, line));
                 }
             }
-
-            // Update session_summary for all messages
-            for msg in &mut session_messages {
-                msg.session_summary = session_summary.clone();
+            None => {
+                if total >= start && total < end {
+                    pairing.want(&message.tools, &message.timestamp);
+                    items.push_back((message, line));
+                }
             }
+        }
+        total += 1;
+    }
 
-            all_chats.extend(session_messages);
+    for (message, line) in items.iter_mut() {
+        pairing.attach(&mut message.tools);
+        let Ok(parsed) = serde_json::from_str::<RawLine>(line) else {
+            continue;
+        };
+        if let Some(msg) = parsed.message.as_ref() {
+            let is_prompt = message.role == "user" && !message.is_meta;
+            let prompt = is_prompt.then_some(message.content.as_str());
+            message.attachments =
+                attachments::extract(&msg.content, prompt, parsed.cwd.as_deref());
         }
+    }
 
-        // Sort all by timestamp (newest first)
-        all_chats.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    let offset = match tail {
+        Some(_) => total - items.len(),
+        None => start.min(total),
+    };
 
-        let total = all_chats.len();
-        let items: Vec<ChatMessage> = all_chats
-            .into_iter()
-            .skip(skip)
-            .take(max_messages)
-            .collect();
+    MessagesPage {
+        items: items.into_iter().map(|(message, _)| message).collect(),
+        total,
+        offset,
+        usage: usage::summarize(&session_usage.by_model(), &usage::load_prices()),
+        transcript: None,
+        segments: segments.finish(),
+    }
+}
 
-        Ok(ChatsResponse { items, total })
+/// Message DAG of a session built from `parentUuid`, with branches and the active path
+#[tauri::command]
+async fn get_session_tree(
+    project_id: String,
+    session_id: String,
+) -> Result<conversation_tree::ConversationTree, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_path = get_session_path(&project_id, &session_id);
+        if !session_path.exists() {
+            return Err("Session not found".to_string());
+        }
+        conversation_tree::build(&session_path)
     })
     .await
     .map_err(|e| e.to_string())?
 }
 
+// ============================================================================
+// Agent Sidechains
+// ============================================================================
+
+/// A subagent transcript spawned from a main session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSidechain {
+    /// File stem, e.g. "agent-a1b2c3"
+    pub id: String,
+    pub parent_session_id: String,
+    /// First prompt given to the agent
+    pub summary: Option<String>,
+    pub message_count: usize,
+    pub first_timestamp: Option<String>,
+    pub last_modified: u64,
+}
+
+/// Sidechain files of a session: `agent-*.jsonl` next to it whose `sessionId`
+/// points at it, and those under `<session_id>/subagents/`
+fn find_sidechain_files(project_dir: &Path, session_id: &str) -> Vec<PathBuf> {
+    let is_agent_file = |name: &str| name.starts_with("agent-") && name.ends_with(".jsonl");
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(project_dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_agent_file(&name) && read_sidechain_parent(&path).as_deref() == Some(session_id) {
+            files.push(path);
+        }
+    }
+
+    let subagents_dir = project_dir.join(session_id).join("subagents");
+    for entry in fs::read_dir(&subagents_dir).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_agent_file(&name) {
+            files.push(entry.path());
+        }
+    }
+
+    files
+}
+
+fn summarize_sidechain(path: &Path, parent_session_id: &str) -> Option<SessionSidechain> {
+    use std::io::{BufRead, BufReader};
+
+    let file = fs::File::open(path).ok()?;
+    let mut summary = None;
+    let mut first_timestamp = None;
+    let mut message_count = 0;
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let Ok(line) = line else { continue };
+        let Some(message) = parse_message_line(&line, idx + 1) else {
+            continue;
+        };
+        if first_timestamp.is_none() {
+            first_timestamp = Some(message.timestamp.clone());
+        }
+        if summary.is_none() && message.role == "user" && !message.content.is_empty() {
+            summary = Some(truncate_chars(&message.content, 200));
+        }
+        message_count += 1;
+    }
+
+    let last_modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    Some(SessionSidechain {
+        id: path.file_stem()?.to_string_lossy().to_string(),
+        parent_session_id: parent_session_id.to_string(),
+        summary,
+        message_count,
+        first_timestamp,
+        last_modified,
+    })
+}
+
+/// Subagent sessions spawned from a session, oldest first
 #[tauri::command]
-async fn get_session_messages(
+async fn list_session_sidechains(
     project_id: String,
     session_id: String,
-) -> Result<Vec<Message>, String> {
+) -> Result<Vec<SessionSidechain>, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        let session_path = get_claude_dir()
-            .join("projects")
-            .join(&project_id)
-            .join(format!("{}.jsonl", session_id));
+        let project_dir = get_claude_dir().join("projects").join(&project_id);
+        let mut sidechains: Vec<SessionSidechain> = find_sidechain_files(&project_dir, &session_id)
+            .iter()
+            .filter_map(|path| summarize_sidechain(path, &session_id))
+            .collect();
+        sidechains.sort_by(|a, b| a.first_timestamp.cmp(&b.first_timestamp));
+        Ok(sidechains)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-        if !session_path.exists() {
-            return Err("Session not found".to_string());
-        }
+/// Load messages of a sidechain; paging works like `get_session_messages`
+#[tauri::command]
+async fn get_sidechain_messages(
+    project_id: String,
+    session_id: String,
+    sidechain_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    tail: Option<usize>,
+    include_thinking: Option<bool>,
+) -> Result<MessagesPage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let project_dir = get_claude_dir().join("projects").join(&project_id);
+        let path = find_sidechain_files(&project_dir, &session_id)
+            .into_iter()
+            .find(|p| p.file_stem().map(|s| s.to_string_lossy() == sidechain_id).unwrap_or(false))
+            .ok_or_else(|| "Sidechain not found".to_string())?;
+        load_messages_page(
+            &path,
+            offset,
+            limit,
+            tail,
+            include_thinking.unwrap_or(false),
+            &MessageFilter::default(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-        let content = fs::read_to_string(&session_path).map_err(|e| e.to_string())?;
-        let mut messages = Vec::new();
+/// Compare two sessions (or ranges of one) by aligning their user prompts
+#[tauri::command]
+async fn diff_sessions(
+    a: session_diff::SessionRange,
+    b: session_diff::SessionRange,
+) -> Result<session_diff::SessionDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let load = |range: &session_diff::SessionRange| {
+            let path = get_claude_dir()
+                .join("projects")
+                .join(&range.project_id)
+                .join(format!("{}.jsonl", range.session_id));
+            let limit = range
+                .end
+                .map(|end| end.saturating_sub(range.start.unwrap_or(0)));
+            load_messages_page(&path, range.start, limit, None, false, &MessageFilter::default())
+                .map_err(|e| format!("{}: {}", range.session_id, e))
+        };
+        let page_a = load(&a)?;
+        let page_b = load(&b)?;
+        Ok(session_diff::diff(
+            (&a, &page_a.items, page_a.usage),
+            (&b, &page_b.items, page_b.usage),
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-        for (idx, line) in content.lines().enumerate() {
-            if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
-                let line_type = parsed.line_type.as_deref();
-                if line_type == Some("user") || line_type == Some("assistant") {
-                    if let Some(msg) = &parsed.message {
-                        let role = msg.role.clone().unwrap_or_default();
-                        let (content, is_tool) = extract_content_with_meta(&msg.content);
-                        let is_meta = parsed.is_meta.unwrap_or(false);
+/// Default number of messages per streamed batch
+const STREAM_BATCH_SIZE: usize = 200;
 
-                        if !content.is_empty() {
-                            messages.push(Message {
-                                uuid: parsed.uuid.unwrap_or_default(),
-                                role,
-                                content,
-                                timestamp: parsed.timestamp.unwrap_or_default(),
-                                is_meta,
-                                is_tool,
-                                line_number: idx + 1,
-                            });
-                        }
-                    }
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionMessagesBatch {
+    pub stream_id: String,
+    pub items: Vec<Message>,
+    /// Messages emitted so far, including this batch
+    pub loaded: usize,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+/// Parse a session in the background, emitting "session-messages-batch" events
+/// tagged with the caller-chosen `stream_id`, which also cancels the stream.
+/// The caller picks the id so it can listen before the first batch goes out.
+#[tauri::command]
+fn stream_session_messages(
+    app_handle: tauri::AppHandle,
+    stream_id: String,
+    project_id: String,
+    session_id: String,
+    batch_size: Option<usize>,
+) -> Result<String, String> {
+    use std::io::{BufRead, BufReader};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let session_path = get_session_path(&project_id, &session_id);
+    if !session_path.exists() {
+        return Err("Session not found".to_string());
+    }
+    let file = fs::File::open(&session_path).map_err(|e| e.to_string())?;
+
+    let cancelled = std::sync::Arc::new(AtomicBool::new(false));
+    {
+        let mut streams = SESSION_STREAMS.lock().map_err(|e| e.to_string())?;
+        if streams.contains_key(&stream_id) {
+            return Err(format!("Stream '{}' is already running", stream_id));
+        }
+        streams.insert(stream_id.clone(), cancelled.clone());
+    }
+
+    let batch_size = batch_size.unwrap_or(STREAM_BATCH_SIZE).max(1);
+    let id = stream_id.clone();
+    std::thread::spawn(move || {
+        let emit = |items: Vec<Message>, loaded: usize, done: bool, error: Option<String>| {
+            let _ = app_handle.emit(
+                "session-messages-batch",
+                SessionMessagesBatch {
+                    stream_id: id.clone(),
+                    items,
+                    loaded,
+                    done,
+                    error,
+                },
+            );
+        };
+
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut loaded = 0;
+        let mut error = None;
+
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    error = Some(e.to_string());
+                    break;
+                }
+            };
+            if let Some(message) = parse_message_line(&line, idx + 1) {
+                batch.push(message);
+                if batch.len() >= batch_size {
+                    loaded += batch.len();
+                    emit(std::mem::take(&mut batch), loaded, false, None);
                 }
             }
         }
 
-        Ok(messages)
+        loaded += batch.len();
+        emit(batch, loaded, true, error);
+
+        if let Ok(mut streams) = SESSION_STREAMS.lock() {
+            streams.remove(&id);
+        }
+    });
+
+    Ok(stream_id)
+}
+
+/// Stop a running session message stream; a final batch with `done` is still emitted
+#[tauri::command]
+fn cancel_session_stream(stream_id: String) -> Result<(), String> {
+    if let Some(flag) = SESSION_STREAMS.lock().map_err(|e| e.to_string())?.get(&stream_id) {
+        flag.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Load every message of a session plus its summary, for exports
+fn load_session_for_export(
+    project_id: &str,
+    session_id: &str,
+) -> Result<(String, Vec<Message>), String> {
+    load_session_with_attachments(project_id, session_id, false)
+}
+
+/// `load_session_for_export`, optionally with message attachments (inline images are cached)
+fn load_session_with_attachments(
+    project_id: &str,
+    session_id: &str,
+    include_attachments: bool,
+) -> Result<(String, Vec<Message>), String> {
+    use std::io::{BufRead, BufReader};
+
+    let session_path = get_session_path(project_id, session_id);
+    if !session_path.exists() {
+        return Err("Session not found".to_string());
+    }
+
+    let (summary, _) = read_session_head(&session_path, 20);
+    let file = fs::File::open(&session_path).map_err(|e| e.to_string())?;
+    let messages = BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let parsed = serde_json::from_str::<RawLine>(&line.ok()?).ok()?;
+            message_from_raw(parsed, idx + 1, false, include_attachments)
+        })
+        .collect();
+
+    Ok((summary.unwrap_or_else(|| session_id.to_string()), messages))
+}
+
+/// Render a session as a shareable page, redacted unless turned off.
+/// Returns the page, the number of messages and what was masked.
+fn build_share_page(
+    project_id: &str,
+    session_id: &str,
+    options: &session_export::SharePageOptions,
+) -> Result<(String, usize, Option<redaction::RedactionReport>), String> {
+    let include_images = options.include_images.unwrap_or(true);
+    let (mut title, mut messages) = load_session_with_attachments(project_id, session_id, include_images)?;
+    let report = if options.redact.unwrap_or(true) {
+        let redactor = redaction::Redactor::new(&options.patterns)?;
+        let mut report = redaction::RedactionReport::default();
+        title = redactor.redact(&title, &mut report);
+        redactor.redact_messages(&mut messages, &mut report);
+        Some(report)
+    } else {
+        None
+    };
+    let context = format!("{} · {}", decode_project_path(project_id), session_id);
+    let html = session_export::render_share_page(&title, &context, &messages, options);
+    Ok((html, messages.len(), report))
+}
+
+#[derive(Debug, Serialize)]
+pub struct SharePageResult {
+    pub output_path: String,
+    pub bytes: u64,
+    pub messages: usize,
+    /// None when redaction was turned off
+    pub redaction: Option<redaction::RedactionReport>,
+}
+
+/// Package a session into one self-contained HTML page (no external requests)
+/// for bug reports or a wiki; secrets are masked by default
+#[tauri::command]
+async fn export_session_share_page(
+    project_id: String,
+    session_id: String,
+    output_path: String,
+    options: Option<session_export::SharePageOptions>,
+) -> Result<SharePageResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (html, messages, mut redaction) =
+            build_share_page(&project_id, &session_id, &options.unwrap_or_default())?;
+        fs::write(&output_path, &html).map_err(|e| e.to_string())?;
+        if let Some(report) = redaction.as_mut() {
+            report.output_path = output_path.clone();
+        }
+        Ok(SharePageResult {
+            output_path,
+            bytes: html.len() as u64,
+            messages,
+            redaction,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Export a session as a self-contained HTML file
+#[tauri::command]
+async fn export_session_html(
+    project_id: String,
+    session_id: String,
+    output_path: String,
+    options: Option<session_export::HtmlExportOptions>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (title, messages) = load_session_for_export(&project_id, &session_id)?;
+        let html = session_export::render_html(&title, &messages, &options.unwrap_or_default());
+        fs::write(&output_path, html).map_err(|e| e.to_string())?;
+        Ok(output_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Export a session as PDF (HTML export printed by a headless browser)
+#[tauri::command]
+async fn export_session_pdf(
+    project_id: String,
+    session_id: String,
+    output_path: String,
+    options: Option<session_export::HtmlExportOptions>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (title, messages) = load_session_for_export(&project_id, &session_id)?;
+        let mut options = options.unwrap_or_default();
+        options.expand.get_or_insert(true);
+        let html = session_export::render_html(&title, &messages, &options);
+
+        let html_path = std::env::temp_dir().join(format!("lovcode-export-{}.html", session_id));
+        fs::write(&html_path, html).map_err(|e| e.to_string())?;
+        let result = session_export::html_to_pdf(&html_path, Path::new(&output_path));
+        let _ = fs::remove_file(&html_path);
+
+        result.map(|_| output_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Export a session with API keys, tokens and passwords masked so it can be shared.
+/// `format` is "markdown" (default), "html" or "json"; `patterns` are extra regexes
+/// on top of the built-in and saved ones.
+#[tauri::command]
+async fn export_session_redacted(
+    project_id: String,
+    session_id: String,
+    output_path: String,
+    format: Option<String>,
+    patterns: Option<Vec<String>>,
+    options: Option<session_export::HtmlExportOptions>,
+) -> Result<redaction::RedactionReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let redactor = redaction::Redactor::new(&patterns.unwrap_or_default())?;
+        let (title, mut messages) = load_session_for_export(&project_id, &session_id)?;
+        let mut report = redaction::RedactionReport::default();
+        let title = redactor.redact(&title, &mut report);
+        redactor.redact_messages(&mut messages, &mut report);
+
+        let options = options.unwrap_or_default();
+        let output = match format.as_deref().unwrap_or("markdown") {
+            "markdown" => session_export::render_markdown(&title, &messages, &options),
+            "html" => session_export::render_html(&title, &messages, &options),
+            "json" => serde_json::to_string_pretty(&messages).map_err(|e| e.to_string())?,
+            other => return Err(format!("Unsupported format: {}", other)),
+        };
+        fs::write(&output_path, output).map_err(|e| e.to_string())?;
+        report.output_path = output_path;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Regexes masked in every redacted export, in addition to the built-in rules
+#[tauri::command]
+fn get_redaction_patterns() -> Result<Vec<String>, String> {
+    redaction::load_patterns()
+}
+
+#[tauri::command]
+fn set_redaction_patterns(patterns: Vec<String>) -> Result<(), String> {
+    redaction::save_patterns(&patterns)
+}
+
+/// Model prices (USD per million tokens) used for cost estimates
+#[tauri::command]
+fn get_price_table() -> usage::PriceTable {
+    usage::load_prices()
+}
+
+/// Save price overrides; keys are model id substrings (e.g. "sonnet", "opus-4-5")
+#[tauri::command]
+fn set_price_table(prices: usage::PriceTable) -> Result<(), String> {
+    usage::save_prices(&prices)
+}
+
+// ============================================================================
+// Project Pin, Hide & Archive
+// ============================================================================
+
+#[tauri::command]
+fn set_project_pinned(
+    project_id: String,
+    pinned: bool,
+) -> Result<project_meta::ProjectUserMeta, String> {
+    project_meta::set_pinned(&project_id, pinned)
+}
+
+#[tauri::command]
+fn set_project_hidden(
+    project_id: String,
+    hidden: bool,
+) -> Result<project_meta::ProjectUserMeta, String> {
+    project_meta::set_hidden(&project_id, hidden)
+}
+
+#[tauri::command]
+fn set_project_archived(
+    project_id: String,
+    archived: bool,
+) -> Result<project_meta::ProjectUserMeta, String> {
+    project_meta::set_archived(&project_id, archived)
+}
+
+// ============================================================================
+// Session Favorites, Tags & Notes
+// ============================================================================
+
+#[tauri::command]
+fn set_session_starred(
+    project_id: String,
+    session_id: String,
+    starred: bool,
+) -> Result<session_meta::SessionUserMeta, String> {
+    session_meta::set_starred(&project_id, &session_id, starred)
+}
+
+#[tauri::command]
+fn set_session_tags(
+    project_id: String,
+    session_id: String,
+    tags: Vec<String>,
+) -> Result<session_meta::SessionUserMeta, String> {
+    session_meta::set_tags(&project_id, &session_id, tags)
+}
+
+#[tauri::command]
+fn set_session_note(
+    project_id: String,
+    session_id: String,
+    note: Option<String>,
+) -> Result<session_meta::SessionUserMeta, String> {
+    session_meta::set_note(&project_id, &session_id, note)
+}
+
+/// Give a session a custom title (None or blank restores the generated summary)
+#[tauri::command]
+async fn rename_session(
+    project_id: String,
+    session_id: String,
+    title: Option<String>,
+) -> Result<session_meta::SessionUserMeta, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let meta = session_meta::set_title(&project_id, &session_id, title)?;
+        // Keep the title searchable; a stale index only affects search ranking
+        let session_path = get_session_path(&project_id, &session_id);
+        if let Err(e) = update_session_in_indexes(&project_id, &session_id, Some(&session_path)) {
+            eprintln!("[Lovcode] Failed to reindex renamed session: {}", e);
+        }
+        Ok(meta)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// All user tags with usage counts
+#[tauri::command]
+fn list_session_tags() -> Result<Vec<session_meta::TagCount>, String> {
+    session_meta::all_tags()
+}
+
+// ============================================================================
+// Message Annotations
+// ============================================================================
+
+/// Comments on a session's messages, optionally only those on one message
+#[tauri::command]
+fn list_annotations(
+    project_id: String,
+    session_id: String,
+    message_uuid: Option<String>,
+) -> Result<Vec<annotations::Annotation>, String> {
+    annotations::list(&project_id, &session_id, message_uuid.as_deref())
+}
+
+#[tauri::command]
+fn add_annotation(
+    project_id: String,
+    session_id: String,
+    message_uuid: String,
+    text: String,
+) -> Result<annotations::Annotation, String> {
+    annotations::add(&project_id, &session_id, &message_uuid, &text)
+}
+
+#[tauri::command]
+fn update_annotation(
+    project_id: String,
+    session_id: String,
+    id: String,
+    text: String,
+) -> Result<annotations::Annotation, String> {
+    annotations::update(&project_id, &session_id, &id, &text)
+}
+
+#[tauri::command]
+fn delete_annotation(project_id: String, session_id: String, id: String) -> Result<(), String> {
+    annotations::delete(&project_id, &session_id, &id)
+}
+
+// ============================================================================
+// Session Collections
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct CollectionDetail {
+    #[serde(flatten)]
+    pub collection: collections::Collection,
+    /// Sessions of the collection in the order they were added
+    pub sessions: Vec<Session>,
+    /// Entries whose session file no longer exists
+    pub missing: Vec<collections::CollectionEntry>,
+}
+
+/// Session listing entry for a single known session
+fn load_session_info(project_id: &str, session_id: &str) -> Option<Session> {
+    let path = get_session_path(project_id, session_id);
+    let metadata = fs::metadata(&path).ok()?;
+    let (summary, head_count) = read_session_head(&path, 20);
+    let file_stats = message_counts::stats(&path);
+    let message_count = file_stats.map(|s| s.messages).unwrap_or(head_count);
+    let mut session = Session {
+        id: session_id.to_string(),
+        project_id: project_id.to_string(),
+        project_path: Some(decode_project_path(project_id)),
+        summary,
+        message_count,
+        last_modified: metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        size_bytes: metadata.len(),
+        estimated_tokens: file_stats.map(|s| s.estimated_tokens).unwrap_or(0),
+        ..Default::default()
+    };
+    apply_cached_meta(&mut session, &usage::load_prices());
+    apply_user_meta(&mut session, &session_meta::load().unwrap_or_default());
+    apply_annotation_count(&mut session, &annotations::counts());
+    session.related_sessions = session_links::related(project_id, session_id);
+    message_counts::persist();
+    Some(session)
+}
+
+#[tauri::command]
+fn list_collections() -> Result<Vec<collections::CollectionSummary>, String> {
+    collections::list()
+}
+
+/// A collection with its sessions resolved for display
+#[tauri::command]
+async fn get_collection(id: String) -> Result<CollectionDetail, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let collection = collections::get(&id)?;
+        let mut sessions = Vec::new();
+        let mut missing = Vec::new();
+        for entry in &collection.entries {
+            match load_session_info(&entry.project_id, &entry.session_id) {
+                Some(session) => sessions.push(session),
+                None => missing.push(entry.clone()),
+            }
+        }
+        Ok(CollectionDetail {
+            collection,
+            sessions,
+            missing,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn create_collection(
+    name: String,
+    description: Option<String>,
+) -> Result<collections::Collection, String> {
+    collections::create(&name, description)
+}
+
+/// Rename or re-describe a collection; omitted fields stay unchanged
+#[tauri::command]
+fn update_collection(
+    id: String,
+    name: Option<String>,
+    description: Option<String>,
+) -> Result<collections::Collection, String> {
+    collections::update(&id, name, description)
+}
+
+#[tauri::command]
+fn delete_collection(id: String) -> Result<(), String> {
+    collections::delete(&id)
+}
+
+/// Add a session to a collection (or update its note)
+#[tauri::command]
+fn add_session_to_collection(
+    id: String,
+    project_id: String,
+    session_id: String,
+    note: Option<String>,
+) -> Result<collections::Collection, String> {
+    if !get_session_path(&project_id, &session_id).exists() {
+        return Err("Session not found".to_string());
+    }
+    collections::add_session(&id, &project_id, &session_id, note)
+}
+
+#[tauri::command]
+fn remove_session_from_collection(
+    id: String,
+    project_id: String,
+    session_id: String,
+) -> Result<collections::Collection, String> {
+    collections::remove_session(&id, &project_id, &session_id)
+}
+
+// ============================================================================
+// Session Trash
+// ============================================================================
+
+/// Files belonging to a session: its jsonl, its subagent dir, and agent sidechains
+fn collect_session_files(project_dir: &Path, session_id: &str) -> Vec<PathBuf> {
+    let mut files = vec![project_dir.join(format!("{}.jsonl", session_id))];
+
+    let session_dir = project_dir.join(session_id);
+    if session_dir.is_dir() {
+        files.push(session_dir);
+    }
+
+    if let Ok(entries) = fs::read_dir(project_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with("agent-")
+                && name.ends_with(".jsonl")
+                && read_sidechain_parent(&path).as_deref() == Some(session_id)
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    files
+}
+
+/// Move a session and its sidechains to the trash and drop it from the search index
+fn trash_session_files(project_id: &str, session_id: &str) -> Result<trash::TrashEntry, String> {
+    let project_dir = get_claude_dir().join("projects").join(project_id);
+    let session_path = project_dir.join(format!("{}.jsonl", session_id));
+    if !session_path.exists() {
+        return Err("Session not found".to_string());
+    }
+
+    let (summary, _) = read_session_head(&session_path, 20);
+    let files = collect_session_files(&project_dir, session_id);
+    let entry = trash::move_to_trash(project_id, session_id, summary, &files)?;
+
+    if let Err(e) = update_session_in_indexes(project_id, session_id, None) {
+        eprintln!("Failed to remove session {} from search index: {}", session_id, e);
+    }
+    Ok(entry)
+}
+
+#[tauri::command]
+async fn delete_session(project_id: String, session_id: String) -> Result<trash::TrashEntry, String> {
+    tauri::async_runtime::spawn_blocking(move || trash_session_files(&project_id, &session_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn list_trash() -> Vec<trash::TrashEntry> {
+    trash::list()
+}
+
+/// Move a trashed session back into its project and re-index it
+#[tauri::command]
+async fn restore_session(trash_id: String) -> Result<trash::TrashEntry, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let entry = trash::get(&trash_id)?;
+        let project_dir = get_claude_dir().join("projects").join(&entry.project_id);
+        let entry = trash::restore(&trash_id, &project_dir)?;
+
+        let session_path = project_dir.join(format!("{}.jsonl", entry.session_id));
+        if let Err(e) = update_session_in_indexes(&entry.project_id, &entry.session_id, Some(&session_path)) {
+            eprintln!("Failed to re-index restored session {}: {}", entry.session_id, e);
+        }
+        Ok(entry)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Permanently delete a trashed session
+#[tauri::command]
+fn purge_trash(trash_id: String) -> Result<(), String> {
+    trash::purge(&trash_id)
+}
+
+// ============================================================================
+// Session Archive
+// ============================================================================
+
+/// Gzip a session and its sidechains into the archive and drop it from the search index
+fn archive_session_files(project_id: &str, session_id: &str) -> Result<session_archive::ArchivedSession, String> {
+    let project_dir = get_claude_dir().join("projects").join(project_id);
+    let session_path = project_dir.join(format!("{}.jsonl", session_id));
+    if !session_path.exists() {
+        return Err("Session not found".to_string());
+    }
+
+    let (summary, head_count) = read_session_head(&session_path, 20);
+    let message_count = message_counts::message_count(&session_path).unwrap_or(head_count);
+    let files = collect_session_files(&project_dir, session_id);
+    let archived = session_archive::archive(project_id, session_id, summary, message_count, &project_dir, &files)?;
+
+    if let Err(e) = update_session_in_indexes(project_id, session_id, None) {
+        eprintln!("Failed to remove session {} from search index: {}", session_id, e);
+    }
+    Ok(archived)
+}
+
+/// Archive every session not modified in the last `older_than_days` days; sessions
+/// still being written are skipped whatever the retention
+#[tauri::command]
+async fn archive_old_sessions(older_than_days: u64) -> Result<session_archive::ArchiveReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let cutoff = std::time::SystemTime::now()
+            .checked_sub(Duration::from_secs(older_than_days.saturating_mul(24 * 60 * 60)))
+            .ok_or("Retention period is too long")?;
+        let projects_dir = get_claude_dir().join("projects");
+        let mut report = session_archive::ArchiveReport::default();
+
+        for project in fs::read_dir(&projects_dir).map_err(|e| e.to_string())?.flatten() {
+            let project_id = project.file_name().to_string_lossy().to_string();
+            for entry in fs::read_dir(project.path()).into_iter().flatten().flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let Some(session_id) = name.strip_suffix(".jsonl") else {
+                    continue;
+                };
+                if session_id.starts_with("agent-") {
+                    continue;
+                }
+                let modified = entry.metadata().and_then(|m| m.modified());
+                if !modified.is_ok_and(|m| m < cutoff)
+                    || session_cleanup::recently_modified(&entry.path())
+                {
+                    continue;
+                }
+                match archive_session_files(&project_id, session_id) {
+                    Ok(archived) => report.add(archived),
+                    Err(e) => report.failed.push(format!("{}/{}: {}", project_id, session_id, e)),
+                }
+            }
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn archive_session(project_id: String, session_id: String) -> Result<session_archive::ArchivedSession, String> {
+    tauri::async_runtime::spawn_blocking(move || archive_session_files(&project_id, &session_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn list_archived_sessions() -> Result<Vec<session_archive::ArchivedSession>, String> {
+    tauri::async_runtime::spawn_blocking(session_archive::list)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Archive size before and after compression
+#[tauri::command]
+async fn get_archive_stats() -> Result<session_archive::ArchiveStats, String> {
+    tauri::async_runtime::spawn_blocking(session_archive::stats)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Logged maintenance runs, newest first
+#[tauri::command]
+fn get_maintenance_history(limit: Option<usize>) -> Vec<maintenance::MaintenanceRun> {
+    maintenance::history(limit)
+}
+
+/// Run the nightly maintenance tasks now
+#[tauri::command]
+async fn run_maintenance() -> Result<maintenance::MaintenanceRun, String> {
+    tauri::async_runtime::spawn_blocking(|| maintenance::run(maintenance::MaintenanceTrigger::Manual))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Images decoded from sessions into the attachment cache
+#[tauri::command]
+fn get_attachment_cache_stats() -> attachments::CacheStats {
+    attachments::cache_stats()
+}
+
+/// Empty the attachment cache, returning what it held
+#[tauri::command]
+fn clear_attachment_cache() -> Result<attachments::CacheStats, String> {
+    attachments::clear_cache()
+}
+
+/// Messages of an archived session, decompressed on demand (paged like `get_session_messages`)
+#[tauri::command]
+async fn get_archived_session_messages(
+    project_id: String,
+    session_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    filter: Option<MessageFilter>,
+) -> Result<MessagesPage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let reader = session_archive::open_session(&project_id, &session_id)?;
+        Ok(read_messages_page(reader, offset, limit, None, false, &filter.unwrap_or_default()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn search_archived_sessions(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<session_archive::ArchiveHit>, String> {
+    tauri::async_runtime::spawn_blocking(move || session_archive::search(&query, limit.unwrap_or(50)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Unpack an archived session back into its project and re-index it
+#[tauri::command]
+async fn restore_archived_session(
+    project_id: String,
+    session_id: String,
+) -> Result<session_archive::ArchivedSession, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let project_dir = get_claude_dir().join("projects").join(&project_id);
+        let restored = session_archive::restore(&project_id, &session_id, &project_dir)?;
+
+        let session_path = project_dir.join(format!("{}.jsonl", session_id));
+        if let Err(e) = update_session_in_indexes(&project_id, &session_id, Some(&session_path)) {
+            eprintln!("Failed to re-index restored session {}: {}", session_id, e);
+        }
+        Ok(restored)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Preview (default) or apply a bulk cleanup: sessions matching every criterion of
+/// the policy are trashed or archived
+#[tauri::command]
+async fn cleanup_sessions(
+    policy: session_cleanup::CleanupPolicy,
+) -> Result<session_cleanup::CleanupReport, String> {
+    tauri::async_runtime::spawn_blocking(move || session_cleanup::run(&policy))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Projects whose decoded path no longer exists, largest first
+#[tauri::command]
+async fn find_orphaned_projects() -> Result<Vec<orphaned_projects::OrphanedProject>, String> {
+    tauri::async_runtime::spawn_blocking(orphaned_projects::scan)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Trash or archive the sessions of orphaned projects; only a preview unless `dry_run` is false
+#[tauri::command]
+async fn cleanup_orphaned_projects(
+    project_ids: Vec<String>,
+    action: Option<session_cleanup::CleanupAction>,
+    dry_run: Option<bool>,
+) -> Result<session_cleanup::CleanupReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        orphaned_projects::cleanup(
+            &project_ids,
+            action.unwrap_or_default(),
+            dry_run.unwrap_or(true),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Session files for an archive: a whole project, selected session ids (within the
+/// project or across all projects), or everything. Agent sidechains follow their
+/// parent session.
+fn collect_archive_sources(
+    project_id: Option<&str>,
+    session_ids: Option<Vec<String>>,
+) -> Result<Vec<session_export::ArchiveSource>, String> {
+    let projects_dir = get_claude_dir().join("projects");
+    let project_dirs: Vec<PathBuf> = match project_id {
+        Some(id) => vec![projects_dir.join(id)],
+        None => fs::read_dir(&projects_dir)
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect(),
+    };
+    let selected: Option<std::collections::HashSet<String>> =
+        session_ids.map(|ids| ids.into_iter().collect());
+
+    let mut sources = Vec::new();
+    for dir in project_dirs {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let dir_project_id = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(session_id) = name.strip_suffix(".jsonl") else {
+                continue;
+            };
+
+            let parent_session_id = if session_id.starts_with("agent-") {
+                read_sidechain_parent(&path)
+            } else {
+                None
+            };
+            if let Some(selected) = &selected {
+                let owner = parent_session_id.as_deref().unwrap_or(session_id);
+                if !selected.contains(owner) {
+                    continue;
+                }
+            }
+
+            sources.push(session_export::ArchiveSource {
+                project_id: dir_project_id.clone(),
+                session_id: session_id.to_string(),
+                parent_session_id,
+                path,
+            });
+        }
+    }
+
+    if sources.is_empty() {
+        return Err("No sessions to export".to_string());
+    }
+    sources.sort_by(|a, b| (&a.project_id, &a.session_id).cmp(&(&b.project_id, &b.session_id)));
+    Ok(sources)
+}
+
+/// Export formats the `export` command accepts, with the targets each supports
+#[tauri::command]
+fn list_export_formats() -> Vec<exporters::ExportFormatInfo> {
+    exporters::list()
+}
+
+/// Export a session, a set of sessions or the knowledge base in format `kind`
+#[tauri::command]
+async fn export(
+    app_handle: tauri::AppHandle,
+    kind: String,
+    target: exporters::ExportTarget,
+    options: exporters::ExportOptions,
+) -> Result<exporters::ExportResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let ctx = exporters::ExportContext { app_handle };
+        exporters::export(&ctx, &kind, &target, &options)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Export sessions into one JSON/NDJSON archive for backup or migration.
+/// See `collect_archive_sources` for the scope.
+#[tauri::command]
+async fn export_sessions_archive(
+    output_path: String,
+    project_id: Option<String>,
+    session_ids: Option<Vec<String>>,
+    format: Option<String>,
+) -> Result<session_export::ArchiveSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let sources = collect_archive_sources(project_id.as_deref(), session_ids)?;
+        let ndjson = format.as_deref() == Some("ndjson");
+        session_export::write_archive(&sources, Path::new(&output_path), ndjson)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionAppendedEvent {
+    pub project_id: String,
+    pub session_id: String,
+    pub messages: Vec<Message>,
+}
+
+/// Follow a session file and emit "session-message-appended" with messages written after
+/// line `from_line` (defaults to the current end of file). Watching twice is a no-op.
+#[tauri::command]
+fn watch_session(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+    session_id: String,
+    from_line: Option<usize>,
+) -> Result<(), String> {
+    use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let session_path = get_session_path(&project_id, &session_id);
+    if !session_path.exists() {
+        return Err("Session not found".to_string());
+    }
+
+    let key = format!("{}/{}", project_id, session_id);
+    let stopped = std::sync::Arc::new(AtomicBool::new(false));
+    {
+        let mut watches = SESSION_WATCHES.lock().map_err(|e| e.to_string())?;
+        if watches.contains_key(&key) {
+            return Ok(());
+        }
+        watches.insert(key.clone(), stopped.clone());
+    }
+
+    // Byte offset and line count of the part already delivered
+    let (mut offset, mut line_count) = {
+        let file = fs::File::open(&session_path).map_err(|e| e.to_string())?;
+        let mut reader = BufReader::new(file);
+        let skip = from_line.unwrap_or(usize::MAX);
+        let (mut offset, mut lines) = (0u64, 0usize);
+        let mut buf = Vec::new();
+        while lines < skip {
+            buf.clear();
+            let n = reader.read_until(b'\n', &mut buf).map_err(|e| e.to_string())?;
+            // Stop before a trailing partial line; it's picked up once completed
+            if n == 0 || buf.last() != Some(&b'\n') {
+                break;
+            }
+            offset += n as u64;
+            lines += 1;
+        }
+        (offset, lines)
+    };
+
+    std::thread::spawn(move || {
+        let _watcher_guard = resource_limits::track_watcher("session");
+        let (tx, rx) = channel();
+        let mut watcher: RecommendedWatcher =
+            match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+                if let Ok(event) = res {
+                    if event.kind.is_modify() || event.kind.is_create() {
+                        let _ = tx.send(());
+                    }
+                }
+            }) {
+                Ok(w) => w,
+                Err(_) => {
+                    let _ = SESSION_WATCHES.lock().map(|mut w| w.remove(&key));
+                    return;
+                }
+            };
+        if watcher.watch(&session_path, RecursiveMode::NonRecursive).is_err() {
+            let _ = SESSION_WATCHES.lock().map(|mut w| w.remove(&key));
+            return;
+        }
+
+        // Catch up on anything written between the caller's load and now
+        let mut pending = true;
+        while !stopped.load(Ordering::Relaxed) {
+            if !pending {
+                match rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(()) => {}
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(_) => break,
+                }
+            }
+            // Coalesce bursts of writes
+            while rx.recv_timeout(Duration::from_millis(100)).is_ok() {}
+            pending = false;
+
+            let Ok(mut file) = fs::File::open(&session_path) else {
+                continue;
+            };
+            let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+            if len < offset {
+                // Truncated or rewritten: start over
+                offset = 0;
+                line_count = 0;
+            }
+            if file.seek(SeekFrom::Start(offset)).is_err() {
+                continue;
+            }
+            let mut appended = Vec::new();
+            if file.read_to_end(&mut appended).is_err() {
+                continue;
+            }
+            let Some(last_newline) = appended.iter().rposition(|&b| b == b'\n') else {
+                continue;
+            };
+            offset += last_newline as u64 + 1;
+
+            let mut messages = Vec::new();
+            for line in String::from_utf8_lossy(&appended[..=last_newline]).lines() {
+                line_count += 1;
+                if let Some(message) = parse_message_line(line, line_count) {
+                    messages.push(message);
+                }
+            }
+            if !messages.is_empty() {
+                let _ = app_handle.emit(
+                    "session-message-appended",
+                    SessionAppendedEvent {
+                        project_id: project_id.clone(),
+                        session_id: session_id.clone(),
+                        messages,
+                    },
+                );
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+fn unwatch_session(project_id: String, session_id: String) -> Result<(), String> {
+    let key = format!("{}/{}", project_id, session_id);
+    if let Some(stopped) = SESSION_WATCHES.lock().map_err(|e| e.to_string())?.remove(&key) {
+        stopped.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Markdown Rendering
+// ============================================================================
+
+/// Render markdown to HTML off the UI thread
+#[tauri::command]
+async fn render_markdown(
+    text: String,
+    options: Option<markdown::RenderOptions>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        markdown::render(&text, &options.unwrap_or_default())
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
 }
 
 // ============================================================================
@@ -851,120 +2580,550 @@ pub struct SearchResult {
     pub session_summary: Option<String>,
     pub timestamp: String,
     pub score: f32,
+    /// Main session an agent sidechain belongs to (None for main sessions)
+    pub parent_session_id: Option<String>,
+}
+
+/// Resolved schema fields used when writing documents
+struct SearchFields {
+    uuid: Field,
+    content: Field,
+    role: Field,
+    project_id: Field,
+    project_path: Field,
+    session_id: Field,
+    session_summary: Field,
+    timestamp: Field,
+    parent_session_id: Field,
+}
+
+impl SearchFields {
+    fn from_schema(schema: &Schema) -> Self {
+        Self {
+            uuid: schema.get_field("uuid").unwrap(),
+            content: schema.get_field("content").unwrap(),
+            role: schema.get_field("role").unwrap(),
+            project_id: schema.get_field("project_id").unwrap(),
+            project_path: schema.get_field("project_path").unwrap(),
+            session_id: schema.get_field("session_id").unwrap(),
+            session_summary: schema.get_field("session_summary").unwrap(),
+            timestamp: schema.get_field("timestamp").unwrap(),
+            parent_session_id: schema.get_field("parent_session_id").unwrap(),
+        }
+    }
+}
+
+/// Read the parent session id recorded in an agent sidechain file
+fn read_sidechain_parent(path: &Path) -> Option<String> {
+    use std::io::{BufRead, BufReader};
+
+    let file = fs::File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .take(20)
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<RawLine>(&line).ok())
+        .find_map(|parsed| parsed.session_id)
+}
+
+/// Identifies the session a document belongs to
+struct SessionDocContext<'a> {
+    project_id: &'a str,
+    project_path: &'a str,
+    session_id: &'a str,
+    parent_session_id: Option<&'a str>,
+    /// Custom title of the (parent) session, searched along with the summary
+    title: Option<&'a str>,
+}
+
+/// Index all user/assistant messages of one session file, returns the number of new messages.
+/// `seen_uuids` tracks uuids already written in this run so replaced documents aren't counted twice.
+fn index_session_file(
+    index_writer: &IndexWriter,
+    fields: &SearchFields,
+    seen_uuids: &mut std::collections::HashSet<String>,
+    path: &Path,
+    ctx: &SessionDocContext,
+) -> Result<usize, String> {
+    // Taken before reading, so lines appended meanwhile still show up as stale
+    let mtime = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+    let file_content = fs::read_to_string(path).unwrap_or_default();
+    if let Some(mtime) = mtime {
+        index_watermarks::note(ctx.project_id, ctx.session_id, mtime);
+    }
+    let mut indexed_count = 0;
+
+    // First pass: get summary
+    let mut session_summary: Option<String> = None;
+    for line in file_content.lines() {
+        if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
+            if parsed.line_type.as_deref() == Some("summary") {
+                session_summary = parsed.summary;
+                break;
+            }
+        }
+    }
+
+    let searchable_summary = match (ctx.title, &session_summary) {
+        (Some(title), Some(summary)) => format!("{}\n{}", title, summary),
+        (Some(title), None) => title.to_string(),
+        (None, summary) => summary.clone().unwrap_or_default(),
+    };
+
+    // Second pass: index messages
+    for line in file_content.lines() {
+        if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
+            let line_type = parsed.line_type.as_deref();
+
+            if line_type == Some("user") || line_type == Some("assistant") {
+                if let Some(msg) = &parsed.message {
+                    let role = msg.role.clone().unwrap_or_default();
+                    let (text_content, _) = extract_content_with_meta(&msg.content);
+                    let is_meta = parsed.is_meta.unwrap_or(false);
+
+                    if !is_meta && !text_content.is_empty() {
+                        let uuid = parsed.uuid.clone().unwrap_or_default();
+                        // Upsert by uuid: resumed sessions replay earlier messages
+                        if !uuid.is_empty() {
+                            index_writer.delete_term(Term::from_field_text(fields.uuid, &uuid));
+                        }
+                        index_writer.add_document(doc!(
+                            fields.uuid => uuid.clone(),
+                            fields.content => text_content,
+                            fields.role => role,
+                            fields.project_id => ctx.project_id.to_string(),
+                            fields.project_path => ctx.project_path.to_string(),
+                            fields.session_id => ctx.session_id.to_string(),
+                            fields.session_summary => searchable_summary.clone(),
+                            fields.timestamp => parsed.timestamp.clone().unwrap_or_default(),
+                            fields.parent_session_id => ctx.parent_session_id.unwrap_or_default().to_string(),
+                        )).map_err(|e| e.to_string())?;
+
+                        if uuid.is_empty() || seen_uuids.insert(uuid) {
+                            indexed_count += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(indexed_count)
+}
+
+/// Per-project partitions live under the index dir when partitioned indexing is enabled
+fn get_partitions_dir() -> PathBuf {
+    get_index_dir().join("partitions")
+}
+
+/// Index every session file of one project directory
+fn index_project_dir(
+    index_writer: &IndexWriter,
+    fields: &SearchFields,
+    seen_uuids: &mut std::collections::HashSet<String>,
+    project_dir: &Path,
+    include_sidechains: bool,
+) -> Result<usize, String> {
+    let project_id = project_dir.file_name().unwrap().to_string_lossy().to_string();
+    let display_path = decode_project_path(&project_id);
+    let user_meta = session_meta::load().unwrap_or_default();
+    let mut indexed_count = 0;
+
+    for entry in fs::read_dir(project_dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+        if !name.ends_with(".jsonl") {
+            continue;
+        }
+
+        let session_id = name.trim_end_matches(".jsonl").to_string();
+
+        // Subagent sidechains carry the originating session in `sessionId`
+        let parent_session_id = if name.starts_with("agent-") {
+            if !include_sidechains {
+                continue;
+            }
+            read_sidechain_parent(&path)
+        } else {
+            None
+        };
+
+        let title = session_meta::title(
+            &user_meta,
+            &project_id,
+            parent_session_id.as_deref().unwrap_or(&session_id),
+        );
+        let ctx = SessionDocContext {
+            project_id: &project_id,
+            project_path: &display_path,
+            session_id: &session_id,
+            parent_session_id: parent_session_id.as_deref(),
+            title: title.as_deref(),
+        };
+        indexed_count += index_session_file(index_writer, fields, seen_uuids, &path, &ctx)?;
+    }
+
+    Ok(indexed_count)
+}
+
+/// Create a fresh index in `dir` containing the given project directories
+fn build_index_at(
+    dir: &Path,
+    project_dirs: &[PathBuf],
+    include_sidechains: bool,
+    heap_size: usize,
+) -> Result<(SearchIndex, usize), String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let schema = create_schema();
+    let index = Index::create_in_dir(dir, schema.clone()).map_err(|e| e.to_string())?;
+
+    // Register jieba tokenizer for Chinese support
+    register_jieba_tokenizer(&index);
+
+    let mut index_writer: IndexWriter = index.writer(heap_size).map_err(|e| e.to_string())?;
+    let fields = SearchFields::from_schema(&schema);
+    let mut seen_uuids = std::collections::HashSet::new();
+    let mut indexed_count = 0;
+
+    for project_dir in project_dirs {
+        indexed_count += index_project_dir(
+            &index_writer,
+            &fields,
+            &mut seen_uuids,
+            project_dir,
+            include_sidechains,
+        )?;
+    }
+
+    index_writer.commit().map_err(|e| e.to_string())?;
+
+    Ok((SearchIndex { index, schema }, indexed_count))
 }
 
 #[tauri::command]
-async fn build_search_index() -> Result<usize, String> {
-    tauri::async_runtime::spawn_blocking(|| {
+async fn build_search_index(
+    include_sidechains: Option<bool>,
+    partition_by_project: Option<bool>,
+) -> Result<usize, String> {
+    let include_sidechains = include_sidechains.unwrap_or(false);
+    let partition_by_project = partition_by_project.unwrap_or(false);
+
+    tauri::async_runtime::spawn_blocking(move || {
         let index_dir = get_index_dir();
 
+        // Drop loaded handles before removing their files
+        *SEARCH_INDEX.lock().map_err(|e| e.to_string())? = None;
+        SEARCH_PARTITIONS.lock().map_err(|e| e.to_string())?.clear();
+
         // Remove old index if exists
         if index_dir.exists() {
             fs::remove_dir_all(&index_dir).map_err(|e| e.to_string())?;
         }
         fs::create_dir_all(&index_dir).map_err(|e| e.to_string())?;
+        index_watermarks::reset();
+
+        let projects_dir = get_claude_dir().join("projects");
+        if !projects_dir.exists() {
+            return Ok(0);
+        }
 
-        let schema = create_schema();
-        let index = Index::create_in_dir(&index_dir, schema.clone()).map_err(|e| e.to_string())?;
+        let project_dirs: Vec<PathBuf> = fs::read_dir(&projects_dir)
+            .map_err(|e| e.to_string())?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
 
-        // Register jieba tokenizer for Chinese support
-        register_jieba_tokenizer(&index);
+        if !partition_by_project {
+            let (search_index, indexed_count) =
+                build_index_at(&index_dir, &project_dirs, include_sidechains, 50_000_000)?; // 50MB heap
 
-        let mut index_writer: IndexWriter = index
-            .writer(50_000_000) // 50MB heap
-            .map_err(|e| e.to_string())?;
+            // Store index in global state
+            let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
+            *guard = Some(search_index);
+            index_watermarks::save()?;
 
-        let uuid_field = schema.get_field("uuid").unwrap();
-        let content_field = schema.get_field("content").unwrap();
-        let role_field = schema.get_field("role").unwrap();
-        let project_id_field = schema.get_field("project_id").unwrap();
-        let project_path_field = schema.get_field("project_path").unwrap();
-        let session_id_field = schema.get_field("session_id").unwrap();
-        let session_summary_field = schema.get_field("session_summary").unwrap();
-        let timestamp_field = schema.get_field("timestamp").unwrap();
+            return Ok(indexed_count);
+        }
 
-        let projects_dir = get_claude_dir().join("projects");
+        // One small index per project; searches are routed to the relevant partitions
+        let partitions_dir = get_partitions_dir();
         let mut indexed_count = 0;
+        for project_dir in &project_dirs {
+            let project_id = project_dir.file_name().unwrap().to_string_lossy().to_string();
+            let (_, count) = build_index_at(
+                &partitions_dir.join(&project_id),
+                std::slice::from_ref(project_dir),
+                include_sidechains,
+                20_000_000,
+            )?;
+            indexed_count += count;
+        }
+        index_watermarks::save()?;
 
-        if !projects_dir.exists() {
-            return Ok(0);
+        Ok(indexed_count)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Open an index directory with the jieba tokenizer registered
+fn open_search_index(dir: &Path) -> Result<SearchIndex, String> {
+    let index = Index::open_in_dir(dir).map_err(|e| e.to_string())?;
+    // Use the on-disk schema so indexes built before newer fields still load
+    let schema = index.schema();
+    // Register jieba tokenizer for Chinese support
+    register_jieba_tokenizer(&index);
+    Ok(SearchIndex { index, schema })
+}
+
+/// Drop a session (and its sidechains) from the global index and the project's partition,
+/// optionally re-indexing the session file afterwards. Indexes that don't exist are skipped.
+fn update_session_in_indexes(
+    project_id: &str,
+    session_id: &str,
+    reindex: Option<&Path>,
+) -> Result<(), String> {
+    index_watermarks::forget(project_id, session_id);
+    for dir in [get_index_dir(), get_partitions_dir().join(project_id)] {
+        if !dir.join("meta.json").exists() {
+            continue;
+        }
+        let search_index = open_search_index(&dir)?;
+        let mut writer: IndexWriter = search_index
+            .index
+            .writer(15_000_000)
+            .map_err(|e| e.to_string())?;
+
+        for name in ["session_id", "parent_session_id"] {
+            if let Ok(field) = search_index.schema.get_field(name) {
+                writer.delete_term(Term::from_field_text(field, session_id));
+            }
         }
 
-        for project_entry in fs::read_dir(&projects_dir).map_err(|e| e.to_string())? {
-            let project_entry = project_entry.map_err(|e| e.to_string())?;
-            let project_path_buf = project_entry.path();
+        // Indexes built before parent_session_id existed are left for the next rebuild
+        if let (Some(path), Ok(_)) = (reindex, search_index.schema.get_field("parent_session_id")) {
+            let fields = SearchFields::from_schema(&search_index.schema);
+            let project_path = decode_project_path(project_id);
+            let user_meta = session_meta::load().unwrap_or_default();
+            let title = session_meta::title(&user_meta, project_id, session_id);
+            let ctx = SessionDocContext {
+                project_id,
+                project_path: &project_path,
+                session_id,
+                parent_session_id: None,
+                title: title.as_deref(),
+            };
+            let mut seen_uuids = std::collections::HashSet::new();
+            index_session_file(&writer, &fields, &mut seen_uuids, path, &ctx)?;
 
-            if !project_path_buf.is_dir() {
-                continue;
+            // Sidechain documents were dropped above along with the session's
+            if let Some(project_dir) = path.parent() {
+                for sidechain in find_sidechain_files(project_dir, session_id) {
+                    let sidechain_id = sidechain
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let ctx = SessionDocContext {
+                        session_id: &sidechain_id,
+                        parent_session_id: Some(session_id),
+                        ..ctx
+                    };
+                    index_session_file(&writer, &fields, &mut seen_uuids, &sidechain, &ctx)?;
+                }
             }
+        }
 
-            let project_id = project_path_buf.file_name().unwrap().to_string_lossy().to_string();
-            let display_path = decode_project_path(&project_id);
+        writer.commit().map_err(|e| e.to_string())?;
+    }
+    index_watermarks::save()
+}
 
-            for entry in fs::read_dir(&project_path_buf).map_err(|e| e.to_string())? {
-                let entry = entry.map_err(|e| e.to_string())?;
-                let path = entry.path();
-                let name = path.file_name().unwrap().to_string_lossy().to_string();
+/// Load the search index from disk into global state if it isn't loaded yet
+fn ensure_search_index(guard: &mut Option<SearchIndex>) -> Result<(), String> {
+    if guard.is_some() {
+        return Ok(());
+    }
 
-                if name.ends_with(".jsonl") && !name.starts_with("agent-") {
-                    let session_id = name.trim_end_matches(".jsonl").to_string();
-                    let file_content = fs::read_to_string(&path).unwrap_or_default();
+    let index_dir = get_index_dir();
+    if !index_dir.join("meta.json").exists() {
+        return Err("Search index not built. Please build index first.".to_string());
+    }
 
-                    let mut session_summary: Option<String> = None;
+    *guard = Some(open_search_index(&index_dir)?);
+    Ok(())
+}
 
-                    // First pass: get summary
-                    for line in file_content.lines() {
-                        if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
-                            if parsed.line_type.as_deref() == Some("summary") {
-                                session_summary = parsed.summary;
-                                break;
-                            }
-                        }
-                    }
+/// Execute a query and convert hits into results, applying the topic post-filter
+fn run_search(
+    search_index: &SearchIndex,
+    query: &dyn Query,
+    limit: usize,
+    topic: Option<&str>,
+) -> Result<Vec<SearchResult>, String> {
+    // Over-fetch when post-filtering so filtered searches still fill a page
+    let fetch_limit = if topic.is_some() { limit * 10 } else { limit };
 
-                    // Second pass: index messages
-                    for line in file_content.lines() {
-                        if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
-                            let line_type = parsed.line_type.as_deref();
-
-                            if line_type == Some("user") || line_type == Some("assistant") {
-                                if let Some(msg) = &parsed.message {
-                                    let role = msg.role.clone().unwrap_or_default();
-                                    let (text_content, _) = extract_content_with_meta(&msg.content);
-                                    let is_meta = parsed.is_meta.unwrap_or(false);
-
-                                    if !is_meta && !text_content.is_empty() {
-                                        index_writer.add_document(doc!(
-                                            uuid_field => parsed.uuid.clone().unwrap_or_default(),
-                                            content_field => text_content,
-                                            role_field => role,
-                                            project_id_field => project_id.clone(),
-                                            project_path_field => display_path.clone(),
-                                            session_id_field => session_id.clone(),
-                                            session_summary_field => session_summary.clone().unwrap_or_default(),
-                                            timestamp_field => parsed.timestamp.clone().unwrap_or_default(),
-                                        )).map_err(|e| e.to_string())?;
-
-                                        indexed_count += 1;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+    let reader = search_index
+        .index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::OnCommitWithDelay)
+        .try_into()
+        .map_err(|e: tantivy::TantivyError| e.to_string())?;
+
+    let searcher = reader.searcher();
+
+    let top_docs = searcher
+        .search(query, &TopDocs::with_limit(fetch_limit))
+        .map_err(|e| e.to_string())?;
+
+    let mut results = Vec::new();
+
+    for (score, doc_address) in top_docs {
+        if results.len() >= limit {
+            break;
+        }
+
+        let retrieved_doc: tantivy::TantivyDocument =
+            searcher.doc(doc_address).map_err(|e| e.to_string())?;
+
+        let get_text = |field_name: &str| -> String {
+            search_index
+                .schema
+                .get_field(field_name)
+                .ok()
+                .and_then(|field| retrieved_doc.get_first(field))
+                .and_then(|v| TantivyValue::as_str(&v))
+                .unwrap_or("")
+                .to_string()
+        };
+
+        let doc_project_id = get_text("project_id");
+        let doc_session_id = get_text("session_id");
+
+        // Filter by auto-detected topic from the session metadata cache
+        if let Some(topic) = topic {
+            let has_topic = session_cache::get(&get_session_path(&doc_project_id, &doc_session_id))
+                .map(|meta| meta.topics.iter().any(|t| t == topic))
+                .unwrap_or(false);
+            if !has_topic {
+                continue;
             }
         }
 
-        index_writer.commit().map_err(|e| e.to_string())?;
+        let summary = get_text("session_summary");
+        let parent_session_id = get_text("parent_session_id");
+
+        results.push(SearchResult {
+            uuid: get_text("uuid"),
+            content: get_text("content"),
+            role: get_text("role"),
+            project_id: doc_project_id,
+            project_path: get_text("project_path"),
+            session_id: doc_session_id,
+            session_summary: if summary.is_empty() {
+                None
+            } else {
+                Some(summary)
+            },
+            timestamp: get_text("timestamp"),
+            score,
+            parent_session_id: if parent_session_id.is_empty() {
+                None
+            } else {
+                Some(parent_session_id)
+            },
+        });
+    }
+
+    Ok(results)
+}
+
+/// Restrict a query to documents of the given projects
+fn scope_query_to_projects(
+    search_index: &SearchIndex,
+    query: Box<dyn Query>,
+    project_ids: &[String],
+) -> Box<dyn Query> {
+    let project_field = search_index.schema.get_field("project_id").unwrap();
+    let scope: Vec<(Occur, Box<dyn Query>)> = project_ids
+        .iter()
+        .map(|id| {
+            let q: Box<dyn Query> = Box::new(TermQuery::new(
+                Term::from_field_text(project_field, id),
+                IndexRecordOption::Basic,
+            ));
+            (Occur::Should, q)
+        })
+        .collect();
+    Box::new(BooleanQuery::new(vec![
+        (Occur::Must, query),
+        (Occur::Must, Box::new(BooleanQuery::new(scope))),
+    ]))
+}
+
+/// Builds the query for a given index (tokenizers are per index)
+type QueryBuilder<'a> = dyn Fn(&SearchIndex) -> Result<Box<dyn Query>, String> + 'a;
+
+/// Route a search to the monolithic index or to the relevant per-project partitions
+/// and merge the hits by score.
+fn federated_search(
+    make_query: &QueryBuilder,
+    limit: Option<usize>,
+    project_ids: Option<Vec<String>>,
+    topic: Option<&str>,
+) -> Result<Vec<SearchResult>, String> {
+    let max_results = limit.unwrap_or(50);
+    let partitions_dir = get_partitions_dir();
 
-        // Store index in global state
+    if !partitions_dir.exists() {
         let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
-        *guard = Some(SearchIndex { index, schema });
+        ensure_search_index(&mut guard)?;
+        let search_index = guard.as_ref().unwrap();
 
-        Ok(indexed_count)
-    })
-    .await
-    .map_err(|e| e.to_string())?
+        let mut query = make_query(search_index)?;
+        if let Some(ids) = &project_ids {
+            query = scope_query_to_projects(search_index, query, ids);
+        }
+        return run_search(search_index, query.as_ref(), max_results, topic);
+    }
+
+    // Only the requested partitions, or all of them for a global search
+    let partition_ids: Vec<String> = match project_ids {
+        Some(ids) => ids,
+        None => fs::read_dir(&partitions_dir)
+            .map_err(|e| e.to_string())?
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect(),
+    };
+
+    let mut partitions = SEARCH_PARTITIONS.lock().map_err(|e| e.to_string())?;
+    let mut results = Vec::new();
+
+    for id in partition_ids {
+        if !partitions.contains_key(&id) {
+            let dir = partitions_dir.join(&id);
+            if !dir.join("meta.json").exists() {
+                continue;
+            }
+            partitions.insert(id.clone(), open_search_index(&dir)?);
+        }
+        let search_index = &partitions[&id];
+        let query = make_query(search_index)?;
+        results.extend(run_search(search_index, query.as_ref(), max_results, topic)?);
+    }
+
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    results.truncate(max_results);
+    Ok(results)
 }
 
 #[tauri::command]
@@ -972,94 +3131,317 @@ fn search_chats(
     query: String,
     limit: Option<usize>,
     project_id: Option<String>,
+    topic: Option<String>,
+    project_ids: Option<Vec<String>>,
 ) -> Result<Vec<SearchResult>, String> {
-    let max_results = limit.unwrap_or(50);
+    let make_query = |search_index: &SearchIndex| -> Result<Box<dyn Query>, String> {
+        let content_field = search_index.schema.get_field("content").unwrap();
+        let session_summary_field = search_index.schema.get_field("session_summary").unwrap();
 
-    // Try to get index from global state or load from disk
-    let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
+        let query_parser = QueryParser::for_index(
+            &search_index.index,
+            vec![content_field, session_summary_field],
+        );
+        query_parser.parse_query(&query).map_err(|e| e.to_string())
+    };
 
-    if guard.is_none() {
-        let index_dir = get_index_dir();
-        if !index_dir.exists() {
-            return Err("Search index not built. Please build index first.".to_string());
+    let scope = project_ids.or_else(|| project_id.map(|id| vec![id]));
+    federated_search(&make_query, limit, scope, topic.as_deref())
+}
+
+/// One clause of a structured query; text is matched literally (no query syntax)
+#[derive(Debug, Deserialize)]
+pub struct QueryClause {
+    /// Target field (content, session_summary, role, project_id, session_id, ...).
+    /// Defaults to content + session_summary.
+    pub field: Option<String>,
+    pub text: String,
+    /// Require the words to appear as an exact phrase
+    #[serde(default)]
+    pub phrase: bool,
+}
+
+/// Boolean query built from clauses, so the frontend never has to escape query-parser syntax
+#[derive(Debug, Default, Deserialize)]
+pub struct StructuredQuery {
+    #[serde(default)]
+    pub must: Vec<QueryClause>,
+    #[serde(default)]
+    pub should: Vec<QueryClause>,
+    #[serde(default)]
+    pub must_not: Vec<QueryClause>,
+}
+
+/// Build a tantivy query for one clause. Returns None if the text yields no terms.
+fn build_clause_query(
+    search_index: &SearchIndex,
+    clause: &QueryClause,
+) -> Result<Option<Box<dyn Query>>, String> {
+    let schema = &search_index.schema;
+    let fields = match clause.field.as_deref() {
+        None => vec![
+            schema.get_field("content").unwrap(),
+            schema.get_field("session_summary").unwrap(),
+        ],
+        Some(name) => vec![schema
+            .get_field(name)
+            .map_err(|_| format!("Unknown search field: {}", name))?],
+    };
+
+    let mut field_queries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+    for field in fields {
+        let tokenized = match schema.get_field_entry(field).field_type() {
+            FieldType::Str(options) => options
+                .get_indexing_options()
+                .map(|o| o.tokenizer() == JIEBA_TOKENIZER_NAME)
+                .unwrap_or(false),
+            _ => false,
+        };
+
+        // Raw (STRING) fields are matched as a single exact term
+        if !tokenized {
+            let term = Term::from_field_text(field, &clause.text);
+            field_queries.push((
+                Occur::Should,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+            ));
+            continue;
+        }
+
+        let mut analyzer = search_index
+            .index
+            .tokenizer_for_field(field)
+            .map_err(|e| e.to_string())?;
+        let mut stream = analyzer.token_stream(&clause.text);
+        let mut terms = Vec::new();
+        while stream.advance() {
+            terms.push(Term::from_field_text(field, &stream.token().text));
         }
 
-        let schema = create_schema();
-        let index = Index::open_in_dir(&index_dir).map_err(|e| e.to_string())?;
-        // Register jieba tokenizer for Chinese support
-        register_jieba_tokenizer(&index);
-        *guard = Some(SearchIndex { index, schema });
+        let query: Box<dyn Query> = match terms.len() {
+            0 => continue,
+            1 => Box::new(TermQuery::new(
+                terms.remove(0),
+                IndexRecordOption::WithFreqs,
+            )),
+            _ if clause.phrase => Box::new(PhraseQuery::new(terms)),
+            _ => Box::new(BooleanQuery::new(
+                terms
+                    .into_iter()
+                    .map(|term| {
+                        let q: Box<dyn Query> =
+                            Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs));
+                        (Occur::Must, q)
+                    })
+                    .collect(),
+            )),
+        };
+        field_queries.push((Occur::Should, query));
     }
 
-    let search_index = guard.as_ref().unwrap();
-    let reader = search_index
-        .index
-        .reader_builder()
-        .reload_policy(ReloadPolicy::OnCommitWithDelay)
-        .try_into()
-        .map_err(|e: tantivy::TantivyError| e.to_string())?;
+    if field_queries.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Box::new(BooleanQuery::new(field_queries))))
+}
+
+#[tauri::command]
+fn search_chats_structured(
+    query: StructuredQuery,
+    limit: Option<usize>,
+    project_id: Option<String>,
+    topic: Option<String>,
+    project_ids: Option<Vec<String>>,
+) -> Result<Vec<SearchResult>, String> {
+    let make_query = |search_index: &SearchIndex| -> Result<Box<dyn Query>, String> {
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for (occur, group) in [
+            (Occur::Must, &query.must),
+            (Occur::Should, &query.should),
+            (Occur::MustNot, &query.must_not),
+        ] {
+            for clause in group {
+                match build_clause_query(search_index, clause)? {
+                    Some(q) => clauses.push((occur, q)),
+                    // Dropping a required clause would broaden the search
+                    None if occur == Occur::Must => {
+                        return Err(format!(
+                            "Required term \"{}\" has no searchable terms",
+                            clause.text
+                        ));
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        if clauses.iter().all(|(occur, _)| *occur == Occur::MustNot) {
+            if clauses.is_empty() {
+                return Err("Query has no searchable terms".to_string());
+            }
+            // A purely negative query needs something to subtract from
+            clauses.push((Occur::Must, Box::new(AllQuery)));
+        }
 
-    let searcher = reader.searcher();
+        Ok(Box::new(BooleanQuery::new(clauses)))
+    };
 
-    let content_field = search_index.schema.get_field("content").unwrap();
-    let session_summary_field = search_index.schema.get_field("session_summary").unwrap();
+    let scope = project_ids.or_else(|| project_id.map(|id| vec![id]));
+    federated_search(&make_query, limit, scope, topic.as_deref())
+}
 
-    let query_parser = QueryParser::for_index(
-        &search_index.index,
-        vec![content_field, session_summary_field],
-    );
-    let parsed_query = query_parser
-        .parse_query(&query)
-        .map_err(|e| e.to_string())?;
+// ============================================================================
+// Message Permalinks
+// ============================================================================
 
-    let top_docs = searcher
-        .search(&parsed_query, &TopDocs::with_limit(max_results))
-        .map_err(|e| e.to_string())?;
+/// Where a message lives, for deep links from search results and distill docs
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageLocation {
+    pub uuid: String,
+    pub project_id: String,
+    /// Main session (the parent for messages inside an agent sidechain)
+    pub session_id: String,
+    pub sidechain_id: Option<String>,
+    /// Position among the messages `get_session_messages` returns (usable as
+    /// `offset`); None when the line isn't shown as a message (e.g. a tool result)
+    pub index: Option<usize>,
+    /// 1-based line and byte offset of the entry in the session file
+    pub line_number: usize,
+    pub byte_offset: u64,
+}
 
-    let mut results = Vec::new();
+/// Find the line of `uuid` in a session file: (index, line number, byte offset)
+fn locate_in_file(path: &Path, uuid: &str) -> Option<(Option<usize>, usize, u64)> {
+    use std::io::{BufRead, BufReader};
 
-    for (score, doc_address) in top_docs {
-        let retrieved_doc: tantivy::TantivyDocument =
-            searcher.doc(doc_address).map_err(|e| e.to_string())?;
+    let file = fs::File::open(path).ok()?;
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    let mut offset = 0u64;
+    let mut line_number = 0;
+    let mut shown = 0;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).ok()?;
+        if read == 0 {
+            return None;
+        }
+        line_number += 1;
+        // Each line is parsed once; the substring check spares the uuid comparison
+        let parsed = serde_json::from_str::<RawLine>(line.trim_end()).ok();
+        let is_target = line.contains(uuid)
+            && parsed.as_ref().is_some_and(|p| p.uuid.as_deref() == Some(uuid));
+        let is_message = parsed
+            .and_then(|p| message_from_raw(p, line_number, false, false))
+            .is_some();
+        if is_target {
+            return Some((is_message.then_some(shown), line_number, offset));
+        }
+        if is_message {
+            shown += 1;
+        }
+        offset += read as u64;
+    }
+}
 
-        let get_text = |field_name: &str| -> String {
-            let field = search_index.schema.get_field(field_name).unwrap();
-            retrieved_doc
-                .get_first(field)
-                .and_then(|v| TantivyValue::as_str(&v))
-                .unwrap_or("")
-                .to_string()
-        };
+/// Session files that may hold `uuid`, with (project_id, session_id, sidechain_id),
+/// from the search index when it has the message
+fn indexed_candidates(uuid: &str) -> Vec<(String, String, Option<String>)> {
+    let make_query = |search_index: &SearchIndex| -> Result<Box<dyn Query>, String> {
+        let field = search_index
+            .schema
+            .get_field("uuid")
+            .map_err(|e| e.to_string())?;
+        Ok(Box::new(TermQuery::new(
+            Term::from_field_text(field, uuid),
+            schema::IndexRecordOption::Basic,
+        )))
+    };
+    federated_search(&make_query, Some(1), None, None)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| match r.parent_session_id {
+            Some(parent) => (r.project_id, parent, Some(r.session_id)),
+            None => (r.project_id, r.session_id, None),
+        })
+        .collect()
+}
 
-        let doc_project_id = get_text("project_id");
+fn session_file(project_id: &str, session_id: &str, sidechain_id: Option<&str>) -> Option<PathBuf> {
+    match sidechain_id {
+        None => Some(get_session_path(project_id, session_id)),
+        Some(sidechain_id) => {
+            let project_dir = get_claude_dir().join("projects").join(project_id);
+            find_sidechain_files(&project_dir, session_id)
+                .into_iter()
+                .find(|p| p.file_stem().map(|s| s.to_string_lossy() == sidechain_id).unwrap_or(false))
+        }
+    }
+}
 
-        // Filter by project_id if specified
-        if let Some(ref filter_id) = project_id {
-            if &doc_project_id != filter_id {
-                continue;
+/// Every session and sidechain file, for when the index doesn't know the message
+fn all_session_files() -> Vec<(String, String, Option<String>, PathBuf)> {
+    let mut files = Vec::new();
+    let projects_dir = get_claude_dir().join("projects");
+    for project in fs::read_dir(&projects_dir).into_iter().flatten().flatten() {
+        let project_id = project.file_name().to_string_lossy().to_string();
+        for entry in fs::read_dir(project.path()).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(stem) = name.strip_suffix(".jsonl") {
+                match stem.starts_with("agent-").then(|| read_sidechain_parent(&path)).flatten() {
+                    Some(parent) => files.push((project_id.clone(), parent, Some(stem.to_string()), path)),
+                    None => files.push((project_id.clone(), stem.to_string(), None, path)),
+                }
+            } else if path.is_dir() {
+                for agent in fs::read_dir(path.join("subagents")).into_iter().flatten().flatten() {
+                    let agent_name = agent.file_name().to_string_lossy().to_string();
+                    if let Some(agent_id) = agent_name.strip_suffix(".jsonl") {
+                        files.push((project_id.clone(), name.clone(), Some(agent_id.to_string()), agent.path()));
+                    }
+                }
             }
         }
+    }
+    files
+}
 
-        let summary = get_text("session_summary");
+/// Resolve a message uuid to its session and position within it
+#[tauri::command]
+async fn locate_message(uuid: String) -> Result<MessageLocation, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let location = |project_id: String, session_id: String, sidechain_id: Option<String>, path: &Path| {
+            locate_in_file(path, &uuid).map(|(index, line_number, byte_offset)| MessageLocation {
+                uuid: uuid.clone(),
+                project_id,
+                session_id,
+                sidechain_id,
+                index,
+                line_number,
+                byte_offset,
+            })
+        };
 
-        results.push(SearchResult {
-            uuid: get_text("uuid"),
-            content: get_text("content"),
-            role: get_text("role"),
-            project_id: doc_project_id,
-            project_path: get_text("project_path"),
-            session_id: get_text("session_id"),
-            session_summary: if summary.is_empty() {
-                None
-            } else {
-                Some(summary)
-            },
-            timestamp: get_text("timestamp"),
-            score,
-        });
-    }
+        for (project_id, session_id, sidechain_id) in indexed_candidates(&uuid) {
+            let Some(path) = session_file(&project_id, &session_id, sidechain_id.as_deref()) else {
+                continue;
+            };
+            if let Some(found) = location(project_id, session_id, sidechain_id, &path) {
+                return Ok(found);
+            }
+        }
 
-    Ok(results)
+        // Not indexed (tool lines, index not built yet or stale): scan the files
+        all_session_files()
+            .into_iter()
+            .find_map(|(project_id, session_id, sidechain_id, path)| {
+                location(project_id, session_id, sidechain_id, &path)
+            })
+            .ok_or_else(|| format!("Message '{}' not found", uuid))
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 fn extract_content_with_meta(value: &Option<serde_json::Value>) -> (String, bool) {
@@ -1094,12 +3476,87 @@ fn extract_content_with_meta(value: &Option<serde_json::Value>) -> (String, bool
     }
 }
 
+/// Text of `thinking` blocks (redacted thinking has no readable text)
+fn extract_thinking(value: &Option<serde_json::Value>) -> Option<String> {
+    let arr = value.as_ref()?.as_array()?;
+    let thinking = arr
+        .iter()
+        .filter_map(|item| {
+            let obj = item.as_object()?;
+            if obj.get("type").and_then(|v| v.as_str()) != Some("thinking") {
+                return None;
+            }
+            obj.get("thinking").and_then(|v| v.as_str()).map(String::from)
+        })
+        .filter(|t| !t.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!thinking.is_empty()).then_some(thinking)
+}
+
 // ============================================================================
 // Commands Feature
 // ============================================================================
 
+/// Commands of ~/.claude (default) or of a project's .claude dir
+#[tauri::command]
+fn list_local_commands(
+    filters: Option<CommandFilters>,
+    scope: Option<command_files::CommandScope>,
+) -> Result<Vec<LocalCommand>, String> {
+    let filters = filters.unwrap_or_default();
+    let mut commands = command_files::list(&scope.unwrap_or(command_files::CommandScope::Global))?;
+    commands.retain(|c| filters.matches(c));
+    Ok(commands)
+}
+
+/// Matching commands grouped by tag, groups sorted by tag name
+#[tauri::command]
+fn list_commands_by_tag(
+    filters: Option<CommandFilters>,
+    scope: Option<command_files::CommandScope>,
+) -> Result<CommandsByTag, String> {
+    let mut groups: std::collections::BTreeMap<String, CommandTagGroup> =
+        std::collections::BTreeMap::new();
+    let mut untagged = Vec::new();
+    for command in list_local_commands(filters, scope)? {
+        if command.tags.is_empty() {
+            untagged.push(command);
+            continue;
+        }
+        for tag in &command.tags {
+            groups
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| CommandTagGroup {
+                    tag: tag.clone(),
+                    commands: Vec::new(),
+                })
+                .commands
+                .push(command.clone());
+        }
+    }
+    Ok(CommandsByTag {
+        groups: groups.into_values().collect(),
+        untagged,
+    })
+}
+
+/// Full-text search over command names, descriptions, tags and bodies
 #[tauri::command]
-fn list_local_commands() -> Result<Vec<LocalCommand>, String> {
+async fn search_commands(
+    query: String,
+    limit: Option<usize>,
+    project_path: Option<String>,
+) -> Result<Vec<command_search::CommandSearchResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        command_search::search(&query, limit.unwrap_or(20), project_path.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Global commands, active and deprecated, sorted by name
+fn collect_local_commands() -> Result<Vec<LocalCommand>, String> {
     let claude_dir = get_claude_dir();
     let commands_dir = claude_dir.join("commands");
     let dot_commands_dir = claude_dir.join(".commands");
@@ -1311,76 +3768,88 @@ fn collect_commands_from_dir(
         } else {
             let filename = path.file_name().unwrap_or_default().to_string_lossy();
 
-            // Determine file type
-            let (is_command, name_suffix) = if filename.ends_with(".md.archived") {
-                (true, ".md.archived")
-            } else if filename.ends_with(".md") {
-                (true, ".md")
-            } else {
-                (false, "")
-            };
+            if filename.ends_with(".md.archived") || filename.ends_with(".md") {
+                commands.push(read_command_file(base_dir, &path, status));
+            }
+        }
+    }
+    Ok(())
+}
 
-            if is_command {
-                let relative = path.strip_prefix(base_dir).unwrap_or(&path);
-                let name = relative
-                    .to_string_lossy()
-                    .trim_end_matches(name_suffix)
-                    .replace("\\", "/")
-                    .to_string();
+/// Parse one command file; `base_dir` is the dir its name is relative to
+fn read_command_file(base_dir: &Path, path: &Path, status: &str) -> LocalCommand {
+    let filename = path.file_name().unwrap_or_default().to_string_lossy();
+    let name_suffix = if filename.ends_with(".md.archived") {
+        ".md.archived"
+    } else {
+        ".md"
+    };
 
-                let content = fs::read_to_string(&path).unwrap_or_default();
-                let (frontmatter, raw_frontmatter, body) = parse_frontmatter(&content);
+    let relative = path.strip_prefix(base_dir).unwrap_or(path);
+    let name = relative
+        .to_string_lossy()
+        .trim_end_matches(name_suffix)
+        .replace("\\", "/")
+        .to_string();
 
-                // Use "archived" status for .md.archived files, otherwise use provided status
-                let actual_status = if filename.ends_with(".md.archived") {
-                    "archived"
-                } else {
-                    status
-                };
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let (frontmatter, raw_frontmatter, body) = parse_frontmatter(&content);
 
-                // Read changelog if exists (same directory, .changelog extension)
-                let changelog = path
-                    .parent()
-                    .map(|dir| {
-                        let base = path.file_stem().unwrap_or_default().to_string_lossy();
-                        dir.join(format!("{}.changelog", base))
-                    })
-                    .filter(|p| p.exists())
-                    .and_then(|p| fs::read_to_string(p).ok());
-
-                // Parse aliases: comma-separated list of previous command names
-                let aliases = frontmatter
-                    .get("aliases")
-                    .map(|s| {
-                        s.split(',')
-                            .map(|a| {
-                                a.trim()
-                                    .trim_matches(|c| c == '[' || c == ']' || c == '"' || c == '\'')
-                                    .to_string()
-                            })
-                            .filter(|a| !a.is_empty())
-                            .collect::<Vec<_>>()
-                    })
-                    .unwrap_or_default();
+    // Use "archived" status for .md.archived files, otherwise use provided status
+    let actual_status = if filename.ends_with(".md.archived") {
+        "archived"
+    } else {
+        status
+    };
 
-                commands.push(LocalCommand {
-                    name: format!("/{}", name),
-                    path: path.to_string_lossy().to_string(),
-                    description: frontmatter.get("description").cloned(),
-                    allowed_tools: frontmatter.get("allowed-tools").cloned(),
-                    argument_hint: frontmatter.get("argument-hint").cloned(),
-                    content: body,
-                    version: frontmatter.get("version").cloned(),
-                    status: actual_status.to_string(),
-                    deprecated_by: frontmatter.get("replaced-by").cloned(),
-                    changelog,
-                    aliases,
-                    frontmatter: raw_frontmatter,
-                });
-            }
-        }
-    }
-    Ok(())
+    // Read changelog if exists (same directory, .changelog extension)
+    let changelog = path
+        .parent()
+        .map(|dir| {
+            let base = path.file_stem().unwrap_or_default().to_string_lossy();
+            dir.join(format!("{}.changelog", base))
+        })
+        .filter(|p| p.exists())
+        .and_then(|p| fs::read_to_string(p).ok());
+
+    // Parse aliases: comma-separated list of previous command names
+    let aliases = frontmatter
+        .get("aliases")
+        .map(|s| parse_list_field(s))
+        .unwrap_or_default();
+    let tags = frontmatter
+        .get("tags")
+        .map(|s| parse_list_field(s))
+        .unwrap_or_default();
+
+    LocalCommand {
+        name: format!("/{}", name),
+        path: path.to_string_lossy().to_string(),
+        description: frontmatter.get("description").cloned(),
+        allowed_tools: frontmatter.get("allowed-tools").cloned(),
+        argument_hint: frontmatter.get("argument-hint").cloned(),
+        content: body,
+        version: frontmatter.get("version").cloned(),
+        status: actual_status.to_string(),
+        deprecated_by: frontmatter.get("replaced-by").cloned(),
+        changelog,
+        aliases,
+        tags,
+        frontmatter: raw_frontmatter,
+    }
+}
+
+/// Split a `a, b` or `[a, "b"]` frontmatter value into its items
+fn parse_list_field(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|a| {
+            a.trim()
+                .trim_matches(|c| c == '[' || c == ']' || c == '"' || c == '\'')
+                .to_string()
+        })
+        .filter(|a| !a.is_empty())
+        .collect()
 }
 
 fn parse_frontmatter(content: &str) -> (HashMap<String, String>, Option<String>, String) {
@@ -1431,8 +3900,10 @@ fn rename_command(
         return Err("New name cannot be empty".to_string());
     }
 
-    // Build destination path from command name
-    let commands_dir = get_claude_dir().join("commands");
+    // Build destination path from command name, in the commands dir (global or project) it is in
+    let commands_dir = command_files::scope_of(&src)
+        .ok_or("Command is not in a commands directory")?
+        .commands_dir()?;
     let new_filename = if name.ends_with(".md") {
         name.to_string()
     } else {
@@ -1603,7 +4074,7 @@ fn update_aliases_on_rename(content: &str, old_name: &str, new_name: &str) -> St
     }
 }
 
-/// Deprecate a command by moving it to ~/.claude/.commands/archived/
+/// Deprecate a command by moving it to .commands/archived/ (~/.claude or the project's .claude)
 /// This moves it outside the commands directory so Claude Code won't load it
 #[tauri::command]
 fn deprecate_command(
@@ -1616,8 +4087,9 @@ fn deprecate_command(
         return Err(format!("Command file not found: {}", path));
     }
 
-    let commands_dir = get_claude_dir().join("commands");
-    let archived_dir = get_claude_dir().join(".commands").join("archived");
+    let scope = command_files::scope_of(&src).ok_or("Command is not in commands directory")?;
+    let commands_dir = scope.commands_dir()?;
+    let archived_dir = scope.archived_dir()?;
 
     // Only allow deprecating active .md files from commands directory
     if !path.ends_with(".md") {
@@ -1701,8 +4173,9 @@ fn restore_command(path: String) -> Result<String, String> {
         return Err(format!("Command file not found: {}", path));
     }
 
-    let commands_dir = get_claude_dir().join("commands");
-    let archived_dir = get_claude_dir().join(".commands").join("archived");
+    let scope = command_files::scope_of(&src).unwrap_or(command_files::CommandScope::Global);
+    let commands_dir = scope.commands_dir()?;
+    let archived_dir = scope.archived_dir()?;
     let path_str = src.to_string_lossy();
 
     // Determine source type and calculate destination
@@ -1774,70 +4247,225 @@ fn add_frontmatter_field(content: &str, key: &str, value: &str) -> String {
     format!("---\n{}: {}\n---\n\n{}", key, value, content)
 }
 
-/// Helper to update or add a field in frontmatter
-fn update_frontmatter_field(content: &str, key: &str, value: &str) -> String {
-    if content.starts_with("---") {
-        if let Some(end_idx) = content[3..].find("---") {
-            let fm_content = &content[3..end_idx + 3];
-            let body = &content[end_idx + 6..];
+/// Helper to update or add a field in frontmatter
+fn update_frontmatter_field(content: &str, key: &str, value: &str) -> String {
+    if content.starts_with("---") {
+        if let Some(end_idx) = content[3..].find("---") {
+            let fm_content = &content[3..end_idx + 3];
+            let body = &content[end_idx + 6..];
+
+            // Check if key exists and update it
+            let mut found = false;
+            let mapped: Vec<String> = fm_content
+                .lines()
+                .map(|line| {
+                    if let Some(colon_idx) = line.find(':') {
+                        let k = line[..colon_idx].trim();
+                        if k == key {
+                            found = true;
+                            if value.is_empty() {
+                                return String::new(); // Remove the field
+                            }
+                            return format!("{}: {}", key, value);
+                        }
+                    }
+                    line.to_string()
+                })
+                .collect();
+            let updated_fm: Vec<String> = mapped
+                .into_iter()
+                .filter(|l| !l.is_empty() || !found)
+                .collect();
+
+            let fm_str = updated_fm.join("\n");
+            if found {
+                return format!("---\n{}\n---{}", fm_str, body);
+            } else if !value.is_empty() {
+                // Key not found, add it
+                return format!("---\n{}\n{}: {}\n---{}", fm_str, key, value, body);
+            }
+            return format!("---\n{}\n---{}", fm_str, body);
+        }
+    }
+    // No frontmatter, add one if value is not empty
+    if value.is_empty() {
+        content.to_string()
+    } else {
+        format!("---\n{}: {}\n---\n\n{}", key, value, content)
+    }
+}
+
+/// Update aliases for a command
+#[tauri::command]
+fn update_command_aliases(path: String, aliases: Vec<String>) -> Result<(), String> {
+    let file_path = PathBuf::from(&path);
+    if !file_path.exists() {
+        return Err(format!("Command file not found: {}", path));
+    }
+
+    let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+
+    // Format aliases as comma-separated string
+    let aliases_value = aliases.join(", ");
+    let updated_content = update_frontmatter_field(&content, "aliases", &aliases_value);
+
+    fs::write(&file_path, updated_content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Create a new command in ~/.claude/commands or a project's .claude/commands
+#[tauri::command]
+fn create_command(
+    name: String,
+    frontmatter: Option<String>,
+    body: String,
+    scope: Option<command_files::CommandScope>,
+) -> Result<LocalCommand, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    command_files::create(&name, frontmatter.as_deref(), &body, &scope)
+}
+
+/// Update a command's frontmatter fields and/or body without rewriting the rest
+#[tauri::command]
+fn update_command(
+    path: String,
+    update: command_files::CommandUpdate,
+) -> Result<LocalCommand, String> {
+    command_files::update(&path, &update)
+}
+
+/// Snapshots and archived versions of a command, newest first
+#[tauri::command]
+fn list_command_versions(path: String) -> Result<Vec<command_versions::CommandVersion>, String> {
+    command_versions::list(Path::new(&path))
+}
+
+/// Unified diff of two revisions of a command (active, archived or snapshot), frontmatter included
+#[tauri::command]
+async fn diff_command_versions(
+    path_a: String,
+    path_b: String,
+) -> Result<doc_diff::UnifiedDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        for path in [&path_a, &path_b] {
+            if ![".md", ".md.archived", ".md.snapshot"]
+                .iter()
+                .any(|s| path.ends_with(s))
+            {
+                return Err(format!("Not a command file: {}", path));
+            }
+        }
+        let a = fs::read_to_string(&path_a).map_err(|e| format!("{}: {}", path_a, e))?;
+        let b = fs::read_to_string(&path_b).map_err(|e| format!("{}: {}", path_b, e))?;
+        Ok(doc_diff::unified(&a, &b, &path_a, &path_b))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Lint a command's frontmatter and body
+#[tauri::command]
+fn validate_command(path: String) -> Result<command_lint::CommandValidation, String> {
+    command_lint::validate(Path::new(&path))
+}
+
+/// The prompt a command expands to with `arguments`. Shell snippets are only listed,
+/// not run, unless `dry_run` is false.
+#[tauri::command]
+async fn render_command_preview(
+    path: String,
+    arguments: Option<String>,
+    project_path: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<command_preview::CommandPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        command_preview::render(
+            Path::new(&path),
+            arguments.as_deref().unwrap_or(""),
+            project_path.as_deref(),
+            dry_run.unwrap_or(true),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Replace a command's content with one of its versions
+#[tauri::command]
+fn restore_command_version(path: String, version_path: String) -> Result<LocalCommand, String> {
+    let file = PathBuf::from(&path);
+    command_versions::restore(&file, &version_path)?;
+    command_files::read(&file)
+}
+
+/// Add a dated entry to a command's changelog; `bump_version` also sets its frontmatter version
+#[tauri::command]
+fn append_command_changelog(
+    path: String,
+    version: String,
+    note: String,
+    bump_version: Option<bool>,
+) -> Result<LocalCommand, String> {
+    command_files::append_changelog(&path, &version, &note, bump_version.unwrap_or(false))
+}
+
+/// Commands sharing an invocation name across the project, global commands and plugins
+#[tauri::command]
+fn find_command_conflicts(
+    project_path: Option<String>,
+) -> Result<Vec<command_conflicts::CommandConflict>, String> {
+    command_conflicts::find(project_path.as_deref())
+}
+
+/// Copy a command under a new name, e.g. to customize a shared one
+#[tauri::command]
+fn duplicate_command(path: String, new_name: String) -> Result<LocalCommand, String> {
+    command_files::duplicate(&path, &new_name)
+}
+
+/// Move a command (and its changelog) to the lovcode trash
+#[tauri::command]
+fn delete_command(path: String) -> Result<command_files::DeletedCommand, String> {
+    command_files::delete(&path)
+}
 
-            // Check if key exists and update it
-            let mut found = false;
-            let mapped: Vec<String> = fm_content
-                .lines()
-                .map(|line| {
-                    if let Some(colon_idx) = line.find(':') {
-                        let k = line[..colon_idx].trim();
-                        if k == key {
-                            found = true;
-                            if value.is_empty() {
-                                return String::new(); // Remove the field
-                            }
-                            return format!("{}: {}", key, value);
-                        }
-                    }
-                    line.to_string()
-                })
-                .collect();
-            let updated_fm: Vec<String> = mapped
-                .into_iter()
-                .filter(|l| !l.is_empty() || !found)
-                .collect();
+#[tauri::command]
+fn list_deleted_commands() -> Vec<command_files::DeletedCommand> {
+    command_files::list_deleted()
+}
 
-            let fm_str = updated_fm.join("\n");
-            if found {
-                return format!("---\n{}\n---{}", fm_str, body);
-            } else if !value.is_empty() {
-                // Key not found, add it
-                return format!("---\n{}\n{}: {}\n---{}", fm_str, key, value, body);
-            }
-            return format!("---\n{}\n---{}", fm_str, body);
-        }
-    }
-    // No frontmatter, add one if value is not empty
-    if value.is_empty() {
-        content.to_string()
-    } else {
-        format!("---\n{}: {}\n---\n\n{}", key, value, content)
-    }
+/// Put a deleted command back where it was
+#[tauri::command]
+fn restore_deleted_command(trash_id: String) -> Result<command_files::DeletedCommand, String> {
+    command_files::restore_deleted(&trash_id)
 }
 
-/// Update aliases for a command
+/// Permanently delete a trashed command
 #[tauri::command]
-fn update_command_aliases(path: String, aliases: Vec<String>) -> Result<(), String> {
-    let file_path = PathBuf::from(&path);
-    if !file_path.exists() {
-        return Err(format!("Command file not found: {}", path));
-    }
+fn purge_deleted_command(trash_id: String) -> Result<(), String> {
+    command_files::purge_deleted(&trash_id)
+}
 
-    let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+/// Create a command folder (namespace), returns its path
+#[tauri::command]
+fn create_command_folder(
+    name: String,
+    scope: Option<command_files::CommandScope>,
+) -> Result<String, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    command_files::create_folder(&name, &scope)
+}
 
-    // Format aliases as comma-separated string
-    let aliases_value = aliases.join(", ");
-    let updated_content = update_frontmatter_field(&content, "aliases", &aliases_value);
+/// Rename a command folder, returns the commands it contains under their new names
+#[tauri::command]
+fn rename_command_folder(path: String, new_name: String) -> Result<Vec<LocalCommand>, String> {
+    command_files::rename_folder(&path, &new_name)
+}
 
-    fs::write(&file_path, updated_content).map_err(|e| e.to_string())?;
-    Ok(())
+/// Move a command folder under another one ("" for the top level)
+#[tauri::command]
+fn move_command_folder(path: String, new_parent: String) -> Result<Vec<LocalCommand>, String> {
+    command_files::move_folder(&path, &new_parent)
 }
 
 // ============================================================================
@@ -1852,61 +4480,180 @@ pub struct LocalAgent {
     pub model: Option<String>,
     pub tools: Option<String>,
     pub content: String,
+    pub frontmatter: Option<String>,   // raw frontmatter text (if any)
+    pub status: String,                // "active" | "deprecated"
+    pub deprecated_by: Option<String>, // replacement agent name
+    pub changelog: Option<String>,     // changelog content if .changelog file exists
 }
 
+/// Agents of ~/.claude (default) or of a project's .claude dir; global ones
+/// include commands with a 'model' field
 #[tauri::command]
-fn list_local_agents() -> Result<Vec<LocalAgent>, String> {
-    let commands_dir = get_claude_dir().join("commands");
+fn list_local_agents(
+    scope: Option<command_files::CommandScope>,
+) -> Result<Vec<LocalAgent>, String> {
+    agent_files::list(&scope.unwrap_or(command_files::CommandScope::Global))
+}
 
-    if !commands_dir.exists() {
-        return Ok(vec![]);
-    }
+/// Global agents and those of each workspace project, labelled by scope
+#[tauri::command]
+fn list_agents_by_scope() -> Result<Vec<agent_files::ScopedAgents>, String> {
+    agent_files::list_by_scope()
+}
 
-    let mut agents = Vec::new();
-    collect_agents(&commands_dir, &commands_dir, &mut agents)?;
+/// Scaffold a new agent .md file
+#[tauri::command]
+fn create_agent(
+    draft: agent_files::AgentDraft,
+    scope: Option<command_files::CommandScope>,
+) -> Result<LocalAgent, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    agent_files::create(&draft, &scope)
+}
+
+/// Change an agent's frontmatter fields and/or body
+#[tauri::command]
+fn update_agent(path: String, update: agent_files::AgentUpdate) -> Result<LocalAgent, String> {
+    agent_files::update(&path, &update)
+}
+
+/// Lint an agent's frontmatter and prompt
+#[tauri::command]
+fn validate_agent(path: String) -> Result<command_lint::CommandValidation, String> {
+    agent_lint::validate(Path::new(&path))
+}
+
+#[tauri::command]
+fn rename_agent(path: String, new_name: String) -> Result<LocalAgent, String> {
+    agent_files::rename(&path, &new_name)
+}
+
+/// Move an agent to .agents/archived so Claude Code stops loading it
+#[tauri::command]
+fn deprecate_agent(
+    path: String,
+    replaced_by: Option<String>,
+    note: Option<String>,
+) -> Result<LocalAgent, String> {
+    agent_files::deprecate(&path, replaced_by.as_deref(), note.as_deref())
+}
+
+/// Move a deprecated agent back to the agents directory
+#[tauri::command]
+fn restore_agent(path: String) -> Result<LocalAgent, String> {
+    agent_files::restore(&path)
+}
+
+/// Add a dated entry to an agent's changelog; `bump_version` also sets its frontmatter version
+#[tauri::command]
+fn append_agent_changelog(
+    path: String,
+    version: String,
+    note: String,
+    bump_version: Option<bool>,
+) -> Result<LocalAgent, String> {
+    agent_files::append_changelog(&path, &version, &note, bump_version.unwrap_or(false))
+}
+
+/// Move an agent to the lovcode trash
+#[tauri::command]
+fn delete_agent(path: String) -> Result<agent_files::DeletedAgent, String> {
+    agent_files::delete(&path)
+}
+
+#[tauri::command]
+fn list_deleted_agents() -> Vec<agent_files::DeletedAgent> {
+    agent_files::list_deleted()
+}
+
+/// Put a deleted agent back where it was
+#[tauri::command]
+fn restore_deleted_agent(trash_id: String) -> Result<agent_files::DeletedAgent, String> {
+    agent_files::restore_deleted(&trash_id)
+}
 
-    agents.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(agents)
+/// Permanently delete a trashed agent
+#[tauri::command]
+fn purge_deleted_agent(trash_id: String) -> Result<(), String> {
+    agent_files::purge_deleted(&trash_id)
 }
 
+/// Collect agent files; with `require_model`, only files with a 'model' field count
+/// (agents mixed into commands/), otherwise every .md file does (.claude/agents/)
 fn collect_agents(
     base_dir: &PathBuf,
     current_dir: &PathBuf,
     agents: &mut Vec<LocalAgent>,
+    require_model: bool,
 ) -> Result<(), String> {
     for entry in fs::read_dir(current_dir).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
         let path = entry.path();
 
         if path.is_dir() {
-            collect_agents(base_dir, &path, agents)?;
+            collect_agents(base_dir, &path, agents, require_model)?;
         } else if path.extension().map_or(false, |e| e == "md") {
             let content = fs::read_to_string(&path).unwrap_or_default();
-            let (frontmatter, _, body) = parse_frontmatter(&content);
+            let (frontmatter, raw_frontmatter, body) = parse_frontmatter(&content);
 
             // Only include if it has a 'model' field (agents have model, commands don't)
-            if frontmatter.contains_key("model") {
-                let relative = path.strip_prefix(base_dir).unwrap_or(&path);
-                let name = relative
-                    .to_string_lossy()
-                    .trim_end_matches(".md")
-                    .replace("\\", "/")
-                    .to_string();
-
-                agents.push(LocalAgent {
-                    name,
-                    path: path.to_string_lossy().to_string(),
-                    description: frontmatter.get("description").cloned(),
-                    model: frontmatter.get("model").cloned(),
-                    tools: frontmatter.get("tools").cloned(),
-                    content: body,
-                });
+            if !require_model || frontmatter.contains_key("model") {
+                agents.push(agent_from_parts(
+                    base_dir,
+                    &path,
+                    frontmatter,
+                    raw_frontmatter,
+                    body,
+                ));
             }
         }
     }
     Ok(())
 }
 
+/// Parse one agent file; `base_dir` is the dir its name is relative to
+fn read_agent_file(base_dir: &Path, path: &Path) -> LocalAgent {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let (frontmatter, raw_frontmatter, body) = parse_frontmatter(&content);
+    agent_from_parts(base_dir, path, frontmatter, raw_frontmatter, body)
+}
+
+fn agent_from_parts(
+    base_dir: &Path,
+    path: &Path,
+    frontmatter: HashMap<String, String>,
+    raw_frontmatter: Option<String>,
+    body: String,
+) -> LocalAgent {
+    let relative = path.strip_prefix(base_dir).unwrap_or(path);
+    let name = relative
+        .to_string_lossy()
+        .trim_end_matches(".md")
+        .replace("\\", "/")
+        .to_string();
+
+    // Deprecated agents live in .agents/archived, outside the dir Claude Code loads
+    let status = if base_dir.ends_with(Path::new(".agents").join("archived")) {
+        "deprecated"
+    } else {
+        "active"
+    };
+    let changelog = fs::read_to_string(path.with_extension("changelog")).ok();
+
+    LocalAgent {
+        name,
+        path: path.to_string_lossy().to_string(),
+        description: frontmatter.get("description").cloned(),
+        model: frontmatter.get("model").cloned(),
+        tools: frontmatter.get("tools").cloned(),
+        content: body,
+        frontmatter: raw_frontmatter,
+        status: status.to_string(),
+        deprecated_by: frontmatter.get("replaced-by").cloned(),
+        changelog,
+    }
+}
+
 // ============================================================================
 // Skills Feature
 // ============================================================================
@@ -1917,44 +4664,153 @@ pub struct LocalSkill {
     pub path: String,
     pub description: Option<String>,
     pub content: String,
+    pub frontmatter: Option<String>, // raw frontmatter text (if any)
+    pub scope: command_files::CommandScope,
 }
 
+/// Skills of ~/.claude (default) or of a project's .claude dir
 #[tauri::command]
-fn list_local_skills() -> Result<Vec<LocalSkill>, String> {
-    let skills_dir = get_claude_dir().join("skills");
+fn list_local_skills(
+    scope: Option<command_files::CommandScope>,
+) -> Result<Vec<LocalSkill>, String> {
+    skill_files::list(&scope.unwrap_or(command_files::CommandScope::Global))
+}
 
-    if !skills_dir.exists() {
-        return Ok(vec![]);
-    }
+/// Global skills and those of each workspace project, labelled by scope
+#[tauri::command]
+fn list_skills_by_scope() -> Result<Vec<skill_files::ScopedSkills>, String> {
+    skill_files::list_by_scope()
+}
 
+/// Skills are dirs holding a SKILL.md
+fn collect_skills(skills_dir: &Path) -> Result<Vec<LocalSkill>, String> {
     let mut skills = Vec::new();
 
-    for entry in fs::read_dir(&skills_dir).map_err(|e| e.to_string())? {
+    for entry in fs::read_dir(skills_dir).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
         let path = entry.path();
 
         if path.is_dir() {
-            let skill_name = path.file_name().unwrap().to_string_lossy().to_string();
-            let skill_md = path.join("SKILL.md");
-
-            if skill_md.exists() {
-                let content = fs::read_to_string(&skill_md).unwrap_or_default();
-                let (frontmatter, _, body) = parse_frontmatter(&content);
-
-                skills.push(LocalSkill {
-                    name: skill_name,
-                    path: skill_md.to_string_lossy().to_string(),
-                    description: frontmatter.get("description").cloned(),
-                    content: body,
-                });
+            if let Some(skill) = read_skill_dir(&path) {
+                skills.push(skill);
             }
         }
     }
 
-    skills.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(skills)
 }
 
+/// The skill in `dir`, if it has a SKILL.md
+fn read_skill_dir(dir: &Path) -> Option<LocalSkill> {
+    let skill_md = dir.join("SKILL.md");
+    if !skill_md.exists() {
+        return None;
+    }
+    let content = fs::read_to_string(&skill_md).unwrap_or_default();
+    let (frontmatter, raw_frontmatter, body) = parse_frontmatter(&content);
+
+    Some(LocalSkill {
+        name: dir.file_name()?.to_string_lossy().to_string(),
+        path: skill_md.to_string_lossy().to_string(),
+        description: frontmatter.get("description").cloned(),
+        content: body,
+        frontmatter: raw_frontmatter,
+        scope: skill_files::scope_of(dir),
+    })
+}
+
+/// A skill with every file in its directory (scripts, references, assets)
+#[tauri::command]
+async fn get_skill_details(
+    name: String,
+    scope: Option<command_files::CommandScope>,
+) -> Result<skill_files::SkillDetails, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    tauri::async_runtime::spawn_blocking(move || skill_files::details(&name, &scope))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn read_skill_file(
+    name: String,
+    path: String,
+    scope: Option<command_files::CommandScope>,
+) -> Result<String, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    skill_files::read_file(&name, &scope, &path)
+}
+
+/// Create or overwrite a file in a skill directory, optionally marking it executable
+#[tauri::command]
+fn write_skill_file(
+    name: String,
+    path: String,
+    content: String,
+    executable: Option<bool>,
+    scope: Option<command_files::CommandScope>,
+) -> Result<skill_files::SkillFile, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    skill_files::write_file(&name, &scope, &path, &content, executable)
+}
+
+/// Move a file or folder of a skill to the lovcode trash
+#[tauri::command]
+fn delete_skill_file(
+    name: String,
+    path: String,
+    scope: Option<command_files::CommandScope>,
+) -> Result<skill_files::DeletedSkill, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    skill_files::delete_file(&name, &scope, &path)
+}
+
+/// Move a skill directory to the lovcode trash
+#[tauri::command]
+fn delete_skill(
+    name: String,
+    scope: Option<command_files::CommandScope>,
+) -> Result<skill_files::DeletedSkill, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    skill_files::delete(&name, &scope)
+}
+
+#[tauri::command]
+fn list_deleted_skills() -> Vec<skill_files::DeletedSkill> {
+    skill_files::list_deleted()
+}
+
+/// Put a deleted skill back where it was
+#[tauri::command]
+fn restore_deleted_skill(trash_id: String) -> Result<skill_files::DeletedSkill, String> {
+    skill_files::restore_deleted(&trash_id)
+}
+
+/// Permanently delete a trashed skill
+#[tauri::command]
+fn purge_deleted_skill(trash_id: String) -> Result<(), String> {
+    skill_files::purge_deleted(&trash_id)
+}
+
+/// Scaffold a skill in ~/.claude/skills (default) or a project's .claude/skills,
+/// optionally with scripts/ and references/ folders
+#[tauri::command]
+fn create_skill(
+    name: String,
+    description: String,
+    scripts: Option<bool>,
+    references: Option<bool>,
+    scope: Option<command_files::CommandScope>,
+) -> Result<LocalSkill, String> {
+    skill_files::create(
+        &name,
+        &description,
+        scripts.unwrap_or(false),
+        references.unwrap_or(false),
+        &scope.unwrap_or(command_files::CommandScope::Global),
+    )
+}
+
 // ============================================================================
 // Knowledge Base (Distill Documents)
 // ============================================================================
@@ -1968,6 +4824,9 @@ pub struct DistillDocument {
     pub tags: Vec<String>,
     #[serde(default)]
     pub session: Option<String>,
+    /// Project of the linked session, resolved when the session exists
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
 }
 
 fn get_distill_dir() -> PathBuf {
@@ -1994,6 +4853,9 @@ pub struct ReferenceDoc {
     pub name: String,
     pub path: String,
     pub group: Option<String>,
+    /// When the doc was fetched from upstream (unix seconds), if known
+    #[serde(default)]
+    pub fetched_at: Option<u64>,
 }
 
 /// Scan a directory for reference sources (subdirectories with markdown files)
@@ -2172,45 +5034,309 @@ fn list_reference_docs(app_handle: tauri::AppHandle, source: String) -> Result<V
         }
     }
 
-    let mut docs = Vec::new();
-    for entry in fs::read_dir(&source_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
+    let freshness = reference_freshness::source_freshness(&source_dir);
+    let mut docs = Vec::new();
+    for entry in fs::read_dir(&source_dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+
+        if path.extension().map(|e| e == "md").unwrap_or(false) {
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let group = order_map.get(&name).and_then(|(_, g)| g.clone());
+            let fetched_at = freshness.get(&name).map(|f| f.fetched_at);
+
+            docs.push(ReferenceDoc {
+                name,
+                path: path.to_string_lossy().to_string(),
+                group,
+                fetched_at,
+            });
+        }
+    }
+
+    // Sort by _order.txt if available, otherwise alphabetically
+    if !order_map.is_empty() {
+        docs.sort_by(|a, b| {
+            let a_idx = order_map
+                .get(&a.name)
+                .map(|(i, _)| *i)
+                .unwrap_or(usize::MAX);
+            let b_idx = order_map
+                .get(&b.name)
+                .map(|(i, _)| *i)
+                .unwrap_or(usize::MAX);
+            a_idx.cmp(&b_idx)
+        });
+    } else {
+        docs.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    Ok(docs)
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaleReferenceDoc {
+    pub source: String,
+    pub name: String,
+    pub path: String,
+    pub fetched_at: u64,
+    pub age_days: u64,
+    /// "url" | "git"
+    pub origin: String,
+    pub url: Option<String>,
+}
+
+/// Record that a doc in the user's reference directory was just fetched from upstream
+#[tauri::command]
+fn record_reference_fetch(
+    source: String,
+    doc: String,
+    url: Option<String>,
+    origin: Option<String>,
+) -> Result<(), String> {
+    let source_dir = get_reference_dir().join(&source);
+    if !source_dir.is_dir() {
+        return Err(format!("Reference source '{}' not found", source));
+    }
+    reference_freshness::record_fetch(&source_dir, &doc, url, origin)
+}
+
+/// Reference docs whose upstream copy was fetched more than `days` ago, oldest first
+#[tauri::command]
+fn get_stale_reference_docs(
+    app_handle: tauri::AppHandle,
+    days: u64,
+) -> Result<Vec<StaleReferenceDoc>, String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let mut stale = Vec::new();
+    for source in list_reference_sources(app_handle)? {
+        let source_dir = PathBuf::from(&source.path);
+        for (name, freshness) in reference_freshness::source_freshness(&source_dir) {
+            let path = source_dir.join(format!("{}.md", name));
+            let age_days = now.saturating_sub(freshness.fetched_at) / 86_400;
+            if age_days < days || !path.exists() {
+                continue;
+            }
+            stale.push(StaleReferenceDoc {
+                source: source.name.clone(),
+                name,
+                path: path.to_string_lossy().to_string(),
+                fetched_at: freshness.fetched_at,
+                age_days,
+                origin: freshness.origin,
+                url: freshness.url,
+            });
+        }
+    }
+
+    stale.sort_by_key(|d| d.fetched_at);
+    Ok(stale)
+}
+
+/// Section-by-section diff of two knowledge documents (reference docs or distill notes)
+#[tauri::command]
+async fn diff_documents(path_a: String, path_b: String) -> Result<doc_diff::DocumentDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let a = fs::read_to_string(&path_a).map_err(|e| format!("{}: {}", path_a, e))?;
+        let b = fs::read_to_string(&path_b).map_err(|e| format!("{}: {}", path_b, e))?;
+        let (_, _, body_a) = parse_frontmatter(&a);
+        let (_, _, body_b) = parse_frontmatter(&b);
+        Ok(doc_diff::diff(&body_a, &body_b))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Distill notes and every reference source, grouped for a knowledge base export
+fn collect_kb_sections(app_handle: &tauri::AppHandle) -> Result<Vec<kb_export::KbSection>, String> {
+    let distill_dir = get_distill_dir();
+    let distill_docs = list_distill_documents()?
+        .into_iter()
+        .map(|doc| {
+            let mut frontmatter = format!(
+                "---\ntitle: \"{}\"\ndate: {}\ntags: [{}]\n",
+                doc.title.replace('"', "\\\""),
+                doc.date,
+                doc.tags.join(", ")
+            );
+            if let Some(session) = &doc.session {
+                frontmatter.push_str(&format!("session: {}\n", session));
+            }
+            frontmatter.push_str("---");
+            kb_export::KbDoc {
+                title: doc.title,
+                source: distill_dir.join(&doc.file),
+                frontmatter: Some(frontmatter),
+            }
+        })
+        .collect();
+
+    let mut sections = vec![kb_export::KbSection {
+        folder: "Distill".to_string(),
+        title: "Distill".to_string(),
+        docs: distill_docs,
+    }];
+    for source in list_reference_sources(app_handle.clone())? {
+        let docs = list_reference_docs(app_handle.clone(), source.name.clone())?
+            .into_iter()
+            .map(|doc| kb_export::KbDoc {
+                title: doc.name,
+                source: PathBuf::from(doc.path),
+                frontmatter: None,
+            })
+            .collect();
+        sections.push(kb_export::KbSection {
+            folder: format!("Reference/{}", source.name),
+            title: format!("Reference: {}", source.name),
+            docs,
+        });
+    }
+    Ok(sections)
+}
+
+/// Export all distill and reference docs to `dest` as an Obsidian vault ("obsidian")
+/// or an mdBook project ("mdbook")
+#[tauri::command]
+async fn export_knowledge_base(
+    app_handle: tauri::AppHandle,
+    dest: String,
+    format: String,
+) -> Result<kb_export::KbExportSummary, String> {
+    let format = kb_export::ExportFormat::parse(&format)?;
 
-        if path.extension().map(|e| e == "md").unwrap_or(false) {
-            let name = path
-                .file_stem()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        let sections = collect_kb_sections(&app_handle)?;
+        kb_export::export(&sections, Path::new(&dest), format)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-            let group = order_map.get(&name).and_then(|(_, g)| g.clone());
+/// Unused `<yyyymmdd>-<slug>.md` file name in the distill dir
+fn new_distill_file_name(distill_dir: &Path, title: &str, fallback: &str) -> String {
+    let date = chrono::Local::now().format("%Y%m%d");
+    let slug = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .take(8)
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    let slug = if slug.is_empty() { fallback.to_string() } else { slug };
+
+    let mut file = format!("{}-{}.md", date, slug);
+    let mut n = 2;
+    while distill_dir.join(&file).exists() {
+        file = format!("{}-{}-{}.md", date, slug, n);
+        n += 1;
+    }
+    file
+}
+
+fn append_distill_index(distill_dir: &Path, doc: &DistillDocument) -> Result<(), String> {
+    use std::io::Write;
+
+    let line = serde_json::to_string(doc).map_err(|e| e.to_string())?;
+    let mut index = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(distill_dir.join("index.jsonl"))
+        .map_err(|e| e.to_string())?;
+    writeln!(index, "{}", line).map_err(|e| e.to_string())
+}
 
-            docs.push(ReferenceDoc {
-                name,
-                path: path.to_string_lossy().to_string(),
-                group,
-            });
+/// Resolved session link of a distill doc, cached by file mtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DistillLink {
+    session: String,
+    project_id: Option<String>,
+    mtime: u64,
+}
+
+fn get_distill_links_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("distill-links.json")
+}
+
+/// Link docs to their source sessions. A `session` in the doc's frontmatter takes
+/// precedence over index.jsonl; the session is validated with `find_session_project`
+/// and its project stored in distill-links.json so lookups aren't repeated. Sessions
+/// not found are looked up again next time, since they may show up later.
+fn resolve_distill_links(distill_dir: &Path, docs: &mut [DistillDocument]) {
+    let links_path = get_distill_links_path();
+    let mut links: HashMap<String, DistillLink> = fs::read_to_string(&links_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut changed = false;
+
+    for doc in docs.iter_mut() {
+        let file_path = distill_dir.join(&doc.file);
+        let mtime = fs::metadata(&file_path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let cached = links
+            .get(&doc.file)
+            .filter(|l| l.mtime == mtime && l.project_id.is_some());
+        if let Some(link) = cached {
+            doc.session = Some(link.session.clone());
+            doc.project_id = link.project_id.clone();
+            continue;
         }
-    }
 
-    // Sort by _order.txt if available, otherwise alphabetically
-    if !order_map.is_empty() {
-        docs.sort_by(|a, b| {
-            let a_idx = order_map
-                .get(&a.name)
-                .map(|(i, _)| *i)
-                .unwrap_or(usize::MAX);
-            let b_idx = order_map
-                .get(&b.name)
-                .map(|(i, _)| *i)
-                .unwrap_or(usize::MAX);
-            a_idx.cmp(&b_idx)
-        });
-    } else {
-        docs.sort_by(|a, b| a.name.cmp(&b.name));
+        let frontmatter_session = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|content| parse_frontmatter(&content).0.get("session").cloned())
+            .filter(|s| !s.is_empty());
+        if frontmatter_session.is_some() {
+            doc.session = frontmatter_session;
+        }
+
+        let Some(session) = doc.session.clone() else {
+            continue;
+        };
+        doc.project_id = find_session_project(session.clone())
+            .ok()
+            .flatten()
+            .map(|s| s.project_id);
+        if doc.project_id.is_none() {
+            changed |= links.remove(&doc.file).is_some();
+            continue;
+        }
+        links.insert(
+            doc.file.clone(),
+            DistillLink {
+                session,
+                project_id: doc.project_id.clone(),
+                mtime,
+            },
+        );
+        changed = true;
     }
 
-    Ok(docs)
+    if changed {
+        if let Ok(content) = serde_json::to_string_pretty(&links) {
+            let _ = fs::write(&links_path, content);
+        }
+    }
 }
 
 #[tauri::command]
@@ -2240,11 +5366,22 @@ fn list_distill_documents() -> Result<Vec<DistillDocument>, String> {
         })
         .collect();
 
+    resolve_distill_links(&distill_dir, &mut docs);
+
     // Sort by date descending (newest first)
     docs.sort_by(|a, b| b.date.cmp(&a.date));
     Ok(docs)
 }
 
+/// Distill docs that were written from the given session
+#[tauri::command]
+fn get_session_distills(session_id: String) -> Result<Vec<DistillDocument>, String> {
+    Ok(list_distill_documents()?
+        .into_iter()
+        .filter(|doc| doc.session.as_deref() == Some(session_id.as_str()))
+        .collect())
+}
+
 #[tauri::command]
 fn find_session_project(session_id: String) -> Result<Option<Session>, String> {
     let projects_dir = get_claude_dir().join("projects");
@@ -2280,19 +5417,395 @@ fn find_session_project(session_id: String) -> Result<Option<Session>, String> {
                 }
             }
 
-            return Ok(Some(Session {
+            let mut session = Session {
                 id: session_id,
                 project_id,
                 project_path: Some(display_path),
                 summary,
                 message_count: 0,
                 last_modified: 0,
-            }));
+                ..Default::default()
+            };
+            apply_user_meta(&mut session, &session_meta::load().unwrap_or_default());
+            return Ok(Some(session));
         }
     }
     Ok(None)
 }
 
+/// A diagram block found in an assistant message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDiagram {
+    /// `<message uuid>:<block index>`
+    pub id: String,
+    /// "mermaid" | "plantuml"
+    pub kind: String,
+    pub code: String,
+    /// Nearest heading or sentence before the block
+    pub title: Option<String>,
+    /// Assistant text leading up to the block
+    pub context: String,
+    /// The user prompt that produced the answer
+    pub prompt: Option<String>,
+    pub message_uuid: String,
+    pub line_number: usize,
+    pub timestamp: String,
+}
+
+fn diagram_kind(lang: &str) -> Option<&'static str> {
+    match lang.to_lowercase().as_str() {
+        "mermaid" | "mmd" => Some("mermaid"),
+        "plantuml" | "puml" | "uml" => Some("plantuml"),
+        _ => None,
+    }
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    let count = text.chars().count();
+    if count <= max {
+        text.to_string()
+    } else {
+        let tail: String = text.chars().skip(count - max).collect();
+        format!("…{}", tail)
+    }
+}
+
+/// Extract fenced mermaid/PlantUML blocks (and bare @startuml blocks) from markdown text.
+/// Returns (kind, code, text before the block).
+fn extract_diagram_blocks(content: &str) -> Vec<(&'static str, String, String)> {
+    let mut blocks = Vec::new();
+    let mut preceding: Vec<&str> = Vec::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        let fence = trimmed
+            .strip_prefix("```")
+            .or_else(|| trimmed.strip_prefix("~~~"));
+
+        if let Some(info) = fence {
+            let marker = &trimmed[..3];
+            let lang = info.split_whitespace().next().unwrap_or("");
+            let mut body = Vec::new();
+            for inner in lines.by_ref() {
+                if inner.trim_start().starts_with(marker) {
+                    break;
+                }
+                body.push(inner);
+            }
+            match diagram_kind(lang) {
+                Some(kind) => {
+                    blocks.push((kind, body.join("\n"), preceding.join("\n")));
+                    preceding.clear();
+                }
+                // Other code blocks are not useful as context
+                None => preceding.clear(),
+            }
+        } else if trimmed.starts_with("@startuml") {
+            let mut body = vec![line];
+            for inner in lines.by_ref() {
+                body.push(inner);
+                if inner.trim_start().starts_with("@enduml") {
+                    break;
+                }
+            }
+            blocks.push(("plantuml", body.join("\n"), preceding.join("\n")));
+            preceding.clear();
+        } else {
+            preceding.push(line);
+        }
+    }
+
+    blocks
+}
+
+/// Use the last heading, or the last non-empty line, before a diagram as its title
+fn diagram_title(context: &str) -> Option<String> {
+    let lines: Vec<&str> = context
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect();
+    lines
+        .iter()
+        .rev()
+        .find(|l| l.starts_with('#'))
+        .or(lines.last())
+        .map(|l| {
+            l.trim_start_matches('#')
+                .trim()
+                .trim_end_matches(':')
+                .trim_end_matches('：')
+                .to_string()
+        })
+        .filter(|l| !l.is_empty())
+}
+
+/// Locate a session file by id across all projects
+fn find_session_file(session_id: &str) -> Option<PathBuf> {
+    let projects_dir = get_claude_dir().join("projects");
+    fs::read_dir(&projects_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path().join(format!("{}.jsonl", session_id)))
+        .find(|path| path.exists())
+}
+
+/// List mermaid/PlantUML diagrams generated in a session
+#[tauri::command]
+async fn list_session_diagrams(session_id: String) -> Result<Vec<SessionDiagram>, String> {
+    use std::io::{BufRead, BufReader};
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = find_session_file(&session_id).ok_or("Session not found")?;
+        let file = fs::File::open(&path).map_err(|e| e.to_string())?;
+
+        let mut diagrams = Vec::new();
+        let mut last_prompt: Option<String> = None;
+
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            let Ok(line) = line else { continue };
+            let Some(message) = parse_message_line(&line, idx + 1) else {
+                continue;
+            };
+
+            if message.role == "user" {
+                if !message.is_tool && !message.is_meta {
+                    last_prompt = Some(message.content.chars().take(300).collect());
+                }
+                continue;
+            }
+
+            for (block_idx, (kind, code, context)) in
+                extract_diagram_blocks(&message.content).into_iter().enumerate()
+            {
+                if code.trim().is_empty() {
+                    continue;
+                }
+                diagrams.push(SessionDiagram {
+                    id: format!("{}:{}", message.uuid, block_idx),
+                    kind: kind.to_string(),
+                    code,
+                    title: diagram_title(&context),
+                    context: truncate_chars(context.trim(), 500),
+                    prompt: last_prompt.clone(),
+                    message_uuid: message.uuid.clone(),
+                    line_number: message.line_number,
+                    timestamp: message.timestamp.clone(),
+                });
+            }
+        }
+
+        Ok(diagrams)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Save a diagram as a knowledge base document and register it in index.jsonl
+#[tauri::command]
+fn save_diagram_to_distill(
+    session_id: String,
+    kind: String,
+    code: String,
+    title: String,
+    context: Option<String>,
+) -> Result<DistillDocument, String> {
+    let distill_dir = get_distill_dir();
+    fs::create_dir_all(&distill_dir).map_err(|e| e.to_string())?;
+
+    let now = chrono::Local::now();
+    let file = new_distill_file_name(&distill_dir, &title, "diagram");
+
+    let mut content = format!("# {}\n\n", title);
+    if let Some(context) = context.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        content.push_str(context);
+        content.push_str("\n\n");
+    }
+    content.push_str(&format!("```{}\n{}\n```\n", kind, code.trim_end()));
+    fs::write(distill_dir.join(&file), content).map_err(|e| e.to_string())?;
+
+    let doc = DistillDocument {
+        date: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        file,
+        title,
+        tags: vec!["diagram".to_string(), kind],
+        session: Some(session_id),
+        project_id: None,
+    };
+    append_distill_index(&distill_dir, &doc)?;
+
+    Ok(doc)
+}
+
+#[tauri::command]
+fn list_distill_templates() -> Vec<distill_templates::DistillTemplate> {
+    distill_templates::list_templates()
+}
+
+#[tauri::command]
+fn save_distill_template(template: distill_templates::DistillTemplate) -> Result<(), String> {
+    distill_templates::save_template(&template)
+}
+
+/// Create a knowledge base document from a template. Built-in vars: title, date,
+/// session, project (resolved from the linked session).
+#[tauri::command]
+fn create_distill_from_template(
+    template: String,
+    vars: Option<HashMap<String, String>>,
+    session_id: Option<String>,
+) -> Result<DistillDocument, String> {
+    let tpl = distill_templates::get_template(&template)
+        .ok_or_else(|| format!("Template '{}' not found", template))?;
+
+    let now = chrono::Local::now();
+    let mut vars = vars.unwrap_or_default();
+    let title = vars
+        .get("title")
+        .filter(|t| !t.trim().is_empty())
+        .cloned()
+        .unwrap_or_else(|| format!("{} {}", tpl.name, now.format("%Y-%m-%d")));
+    vars.insert("title".to_string(), title.clone());
+    vars.entry("date".to_string())
+        .or_insert_with(|| now.format("%Y-%m-%d").to_string());
+
+    let project_id = session_id
+        .as_deref()
+        .and_then(find_session_file)
+        .and_then(|p| p.parent().and_then(|d| d.file_name()).map(|n| n.to_string_lossy().to_string()));
+    let project = project_id.as_deref().map(decode_project_path);
+    if let Some(session_id) = &session_id {
+        vars.entry("session".to_string()).or_insert_with(|| session_id.clone());
+    }
+    if let Some(project) = &project {
+        vars.entry("project".to_string()).or_insert_with(|| project.clone());
+    }
+
+    let distill_dir = get_distill_dir();
+    fs::create_dir_all(&distill_dir).map_err(|e| e.to_string())?;
+    let file = new_distill_file_name(&distill_dir, &title, &tpl.id);
+
+    let mut content = format!("---\ntitle: \"{}\"\n", title.replace('"', "\\\""));
+    content.push_str(&format!("date: {}\n", now.format("%Y-%m-%d")));
+    content.push_str(&format!("tags: [{}]\n", tpl.tags.join(", ")));
+    content.push_str(&format!("template: {}\n", tpl.id));
+    if let Some(session_id) = &session_id {
+        content.push_str(&format!("session: {}\n", session_id));
+    }
+    if let Some(project) = &project {
+        content.push_str(&format!("project: {}\n", project));
+    }
+    content.push_str("---\n\n");
+    content.push_str(&distill_templates::render(&tpl.body, &vars));
+    fs::write(distill_dir.join(&file), content).map_err(|e| e.to_string())?;
+
+    let doc = DistillDocument {
+        date: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        file,
+        title,
+        tags: tpl.tags,
+        session: session_id,
+        project_id,
+    };
+    append_distill_index(&distill_dir, &doc)?;
+
+    Ok(doc)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotesImportSummary {
+    pub imported: Vec<DistillDocument>,
+    /// Source paths skipped because a note with the same title exists
+    pub duplicates: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Import an Obsidian vault folder or a Notion export zip into distill.
+/// `format` is "obsidian" or "notion"; detected from the path when omitted.
+#[tauri::command]
+async fn import_notes(
+    path: String,
+    format: Option<String>,
+) -> Result<NotesImportSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let source = PathBuf::from(&path);
+        let format = match format.as_deref() {
+            Some(f) => notes_import::ImportFormat::parse(f)?,
+            None => notes_import::ImportFormat::detect(&source),
+        };
+        let notes = notes_import::collect(&source, format)?;
+
+        let distill_dir = get_distill_dir();
+        fs::create_dir_all(&distill_dir).map_err(|e| e.to_string())?;
+        let mut seen: std::collections::HashSet<u64> = list_distill_documents()
+            .unwrap_or_default()
+            .iter()
+            .map(|doc| notes_import::title_hash(&doc.title))
+            .collect();
+
+        let now = chrono::Local::now();
+        let origin = match format {
+            notes_import::ImportFormat::Obsidian => "obsidian",
+            notes_import::ImportFormat::Notion => "notion",
+        };
+        let mut summary = NotesImportSummary {
+            imported: Vec::new(),
+            duplicates: Vec::new(),
+            failed: Vec::new(),
+        };
+
+        for note in notes {
+            if !seen.insert(notes_import::title_hash(&note.title)) {
+                summary.duplicates.push(note.source);
+                continue;
+            }
+
+            let file = new_distill_file_name(&distill_dir, &note.title, "imported");
+            let date = now.format("%Y-%m-%d").to_string();
+            // Start from empty frontmatter so a body opening with `---` stays body
+            let content = format!("---\n---\n\n{}", note.body);
+            let content = frontmatter::set_field(&content, "title", Some(&note.title))
+                .and_then(|c| frontmatter::set_field(&c, "date", Some(&date)))
+                .and_then(|c| frontmatter::set_list(&c, "tags", &note.tags))
+                .and_then(|c| frontmatter::set_field(&c, "imported_from", Some(origin)));
+            let content = match content {
+                Ok(content) => content,
+                Err(e) => {
+                    summary.failed.push(format!("{}: {}", note.source, e));
+                    continue;
+                }
+            };
+            let path = distill_dir.join(&file);
+            if let Err(e) = fs::write(&path, content) {
+                summary.failed.push(format!("{}: {}", note.source, e));
+                continue;
+            }
+
+            let doc = DistillDocument {
+                date: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                file,
+                title: note.title,
+                tags: note.tags,
+                session: None,
+                project_id: None,
+            };
+            // A doc missing from the index is invisible, so back out its file
+            // and go on with the rest instead of aborting half way
+            if let Err(e) = append_distill_index(&distill_dir, &doc) {
+                let _ = fs::remove_file(&path);
+                summary.failed.push(format!("{}: {}", note.source, e));
+                continue;
+            }
+            summary.imported.push(doc);
+        }
+
+        Ok(summary)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 fn get_distill_watch_enabled() -> bool {
     DISTILL_WATCH_ENABLED.load(std::sync::atomic::Ordering::Relaxed)
@@ -3015,6 +6528,51 @@ fn install_command_template(name: String, content: String) -> Result<String, Str
     Ok(file_path.to_string_lossy().to_string())
 }
 
+/// Install a marketplace agent into ~/.claude/agents (default) or a project's .claude/agents
+#[tauri::command]
+fn install_agent_template(
+    name: String,
+    content: String,
+    scope: Option<command_files::CommandScope>,
+) -> Result<agent_templates::AgentInstall, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    agent_templates::install(&name, &content, &scope)
+}
+
+/// Copy a marketplace skill directory (SKILL.md plus scripts and references) into ~/.claude/skills
+#[tauri::command]
+async fn install_skill_template(
+    app_handle: tauri::AppHandle,
+    source_id: String,
+    path: String,
+) -> Result<skill_templates::SkillInstall, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        skill_templates::install(Some(&app_handle), &source_id, &path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn list_skill_installs() -> Result<Vec<skill_templates::SkillInstall>, String> {
+    skill_templates::list()
+}
+
+#[tauri::command]
+fn list_agent_installs() -> Result<Vec<agent_templates::AgentInstall>, String> {
+    agent_templates::list()
+}
+
+/// Move an installed marketplace agent to the agent trash
+#[tauri::command]
+fn uninstall_agent_template(
+    name: String,
+    scope: Option<command_files::CommandScope>,
+) -> Result<Option<agent_files::DeletedAgent>, String> {
+    let scope = scope.unwrap_or(command_files::CommandScope::Global);
+    agent_templates::uninstall(&name, &scope)
+}
+
 #[tauri::command]
 fn install_mcp_template(name: String, config: String) -> Result<String, String> {
     // MCP servers are stored in ~/.claude.json (not ~/.claude/settings.json)
@@ -3436,6 +6994,12 @@ fn get_context_files() -> Result<Vec<ContextFile>, String> {
     Ok(files)
 }
 
+/// Commands, agents and skills defined in a project's own .claude dir
+#[tauri::command]
+fn get_project_assets(project_path: String) -> Result<project_assets::ProjectAssets, String> {
+    project_assets::list(&project_path)
+}
+
 #[tauri::command]
 fn get_project_context(project_path: String) -> Result<Vec<ContextFile>, String> {
     let mut files = Vec::new();
@@ -3516,6 +7080,35 @@ fn get_project_context(project_path: String) -> Result<Vec<ContextFile>, String>
     Ok(files)
 }
 
+/// Overwrite a CLAUDE.md or project command, keeping a backup of the old content
+#[tauri::command]
+fn save_context_file(
+    path: String,
+    content: String,
+) -> Result<context_files::SavedContextFile, String> {
+    context_files::save(&path, &content)
+}
+
+/// Backups of a context file, newest first
+#[tauri::command]
+fn get_context_file_history(path: String) -> Vec<context_files::ContextBackup> {
+    context_files::history(&path)
+}
+
+#[tauri::command]
+fn get_context_file_backup(path: String, backup_id: String) -> Result<String, String> {
+    context_files::read_backup(&path, &backup_id)
+}
+
+/// Restore a backup; the content it replaces is backed up too
+#[tauri::command]
+fn restore_context_file(
+    path: String,
+    backup_id: String,
+) -> Result<context_files::SavedContextFile, String> {
+    context_files::restore(&path, &backup_id)
+}
+
 // ============================================================================
 // Daily Message Stats for Activity Heatmap
 // ============================================================================
@@ -3528,18 +7121,22 @@ pub struct ActivityStats {
     pub hourly: HashMap<u32, usize>,
     /// Map of "date:hour" (YYYY-MM-DD:HH) to count for detailed heatmap
     pub detailed: HashMap<String, usize>,
+    /// Daily/hourly counts as tables, when requested for screen readers
+    #[serde(default)]
+    pub tables: Option<Vec<accessibility::DataTable>>,
 }
 
+/// `accessible` adds the counts as tables alongside the heatmap data
 #[tauri::command]
-async fn get_activity_stats() -> Result<ActivityStats, String> {
-    tauri::async_runtime::spawn_blocking(|| {
+async fn get_activity_stats(accessible: Option<bool>) -> Result<ActivityStats, String> {
+    tauri::async_runtime::spawn_blocking(move || {
         let history_path = get_claude_dir().join("history.jsonl");
         let mut daily: HashMap<String, usize> = HashMap::new();
         let mut hourly: HashMap<u32, usize> = HashMap::new();
         let mut detailed: HashMap<String, usize> = HashMap::new();
 
         if !history_path.exists() {
-            return Ok(ActivityStats { daily, hourly, detailed });
+            return Ok(ActivityStats { daily, hourly, detailed, tables: None });
         }
 
         if let Ok(content) = fs::read_to_string(&history_path) {
@@ -3565,7 +7162,10 @@ async fn get_activity_stats() -> Result<ActivityStats, String> {
             }
         }
 
-        Ok(ActivityStats { daily, hourly, detailed })
+        let tables = accessible
+            .unwrap_or(false)
+            .then(|| accessibility::activity_tables(&daily, &hourly));
+        Ok(ActivityStats { daily, hourly, detailed, tables })
     })
     .await
     .map_err(|e| e.to_string())?
@@ -3581,91 +7181,37 @@ pub struct CommandStats {
     pub count: usize,
 }
 
-#[tauri::command]
-async fn get_command_stats() -> Result<HashMap<String, usize>, String> {
-    // Get current cache state
-    let (cached_stats, cached_scanned) = {
-        let cache = COMMAND_STATS_CACHE.lock().unwrap();
-        (cache.stats.clone(), cache.scanned.clone())
-    };
-
-    // Incremental update in background
-    let (new_stats, new_scanned) = tauri::async_runtime::spawn_blocking(move || {
-        let projects_dir = get_claude_dir().join("projects");
-        let mut stats = cached_stats;
-        let mut scanned = cached_scanned;
-
-        if !projects_dir.exists() {
-            return Ok::<_, String>((stats, scanned));
-        }
-
-        let command_pattern = regex::Regex::new(r"<command-name>(/[^<]+)</command-name>")
-            .map_err(|e| e.to_string())?;
-
-        for project_entry in fs::read_dir(&projects_dir).map_err(|e| e.to_string())? {
-            let project_entry = project_entry.map_err(|e| e.to_string())?;
-            let project_path = project_entry.path();
-
-            if !project_path.is_dir() {
-                continue;
-            }
-
-            for session_entry in fs::read_dir(&project_path).map_err(|e| e.to_string())? {
-                let session_entry = session_entry.map_err(|e| e.to_string())?;
-                let session_path = session_entry.path();
-                let name = session_path
-                    .file_name()
-                    .unwrap()
-                    .to_string_lossy()
-                    .to_string();
-
-                if !name.ends_with(".jsonl") || name.starts_with("agent-") {
-                    continue;
-                }
-
-                let path_str = session_path.to_string_lossy().to_string();
-                let file_size = session_path.metadata().map(|m| m.len()).unwrap_or(0);
-                let prev_size = scanned.get(&path_str).copied().unwrap_or(0);
-
-                // Skip if no new content
-                if file_size <= prev_size {
-                    continue;
-                }
-
-                // Read only new content (from prev_size offset)
-                if let Ok(mut file) = std::fs::File::open(&session_path) {
-                    use std::io::{Read, Seek, SeekFrom};
-                    if file.seek(SeekFrom::Start(prev_size)).is_ok() {
-                        let mut new_content = String::new();
-                        if file.read_to_string(&mut new_content).is_ok() {
-                            for cap in command_pattern.captures_iter(&new_content) {
-                                if let Some(cmd_name) = cap.get(1) {
-                                    // Remove leading "/" to match cmd.name format
-                                    let name =
-                                        cmd_name.as_str().trim_start_matches('/').to_string();
-                                    *stats.entry(name).or_insert(0) += 1;
-                                }
-                            }
-                        }
-                    }
-                }
-                scanned.insert(path_str, file_size);
-            }
-        }
+#[tauri::command]
+async fn get_command_stats() -> Result<HashMap<String, usize>, String> {
+    tauri::async_runtime::spawn_blocking(command_usage::counts)
+        .await
+        .map_err(|e| e.to_string())?
+}
 
-        Ok((stats, scanned))
-    })
-    .await
-    .map_err(|e| e.to_string())??;
+/// Command usage broken down by project, week and month
+#[tauri::command]
+async fn get_command_usage() -> Result<Vec<command_usage::CommandUsage>, String> {
+    tauri::async_runtime::spawn_blocking(command_usage::report)
+        .await
+        .map_err(|e| e.to_string())?
+}
 
-    // Update cache
-    {
-        let mut cache = COMMAND_STATS_CACHE.lock().unwrap();
-        cache.stats = new_stats.clone();
-        cache.scanned = new_scanned;
-    }
+/// Subagent launches (Task tool calls) per agent type, by project, week and month
+#[tauri::command]
+async fn get_agent_stats() -> Result<Vec<command_usage::AgentUsage>, String> {
+    tauri::async_runtime::spawn_blocking(command_usage::agent_report)
+        .await
+        .map_err(|e| e.to_string())?
+}
 
-    Ok(new_stats)
+/// Active commands never used, or not used in the last `days` days
+#[tauri::command]
+async fn find_stale_commands(
+    days: Option<u64>,
+) -> Result<Vec<command_usage::StaleCommand>, String> {
+    tauri::async_runtime::spawn_blocking(move || command_usage::stale(days))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 // ============================================================================
@@ -4580,11 +8126,200 @@ fn pty_create(
     cwd: String,
     shell: Option<String>,
     command: Option<String>,
+    feature_id: Option<String>,
 ) -> Result<String, String> {
-    pty_manager::create_session(id.clone(), cwd, shell, command)?;
+    resource_limits::check_headroom(resource_limits::PTY_FDS)?;
+    let env = match &feature_id {
+        Some(fid) => workspace_store::feature_env_for_pty(fid)?,
+        None => HashMap::new(),
+    };
+    pty_manager::create_session(id.clone(), cwd.clone(), shell, command, env)?;
+    // Remember for cwd suggestions; failing to persist shouldn't fail the PTY
+    let _ = cwd_suggestions::record_cwd(&cwd);
     Ok(id)
 }
 
+#[derive(Debug, Serialize)]
+pub struct ResumedTerminal {
+    pub pty_id: String,
+    pub cwd: String,
+    /// Set when the terminal was attached to a workspace feature
+    pub panel: Option<workspace_store::PanelState>,
+}
+
+/// First working directory recorded in a session file
+fn recorded_cwd(session_path: &Path) -> Option<String> {
+    use std::io::{BufRead, BufReader};
+
+    #[derive(Deserialize)]
+    struct CwdLine {
+        cwd: Option<String>,
+    }
+
+    let file = fs::File::open(session_path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<CwdLine>(&line).ok())
+        .find_map(|l| l.cwd)
+}
+
+/// Working directory recorded in a session file, falling back to the decoded project id
+fn session_cwd(session_path: &Path, project_id: &str) -> String {
+    recorded_cwd(session_path).unwrap_or_else(|| decode_project_path(project_id))
+}
+
+/// Run `command` in a new PTY at `cwd`, as a panel of the feature when one is given
+fn launch_in_terminal(
+    cwd: String,
+    command: String,
+    title: String,
+    feature_id: Option<String>,
+) -> Result<ResumedTerminal, String> {
+    // Find the workspace project owning the feature before spawning anything
+    let owner = match &feature_id {
+        Some(fid) => Some(
+            workspace_store::load_workspace()?
+                .projects
+                .into_iter()
+                .find(|p| p.features.iter().any(|f| &f.id == fid))
+                .map(|p| p.id)
+                .ok_or_else(|| format!("Feature '{}' not found", fid))?,
+        ),
+        None => None,
+    };
+
+    let pty_id = uuid::Uuid::new_v4().to_string();
+    pty_create(
+        pty_id.clone(),
+        cwd.clone(),
+        None,
+        Some(command.clone()),
+        feature_id.clone(),
+    )?;
+
+    let panel = match (owner, feature_id) {
+        (Some(workspace_project_id), Some(fid)) => {
+            let tab_id = uuid::Uuid::new_v4().to_string();
+            let panel = workspace_store::PanelState {
+                id: uuid::Uuid::new_v4().to_string(),
+                sessions: vec![workspace_store::SessionState {
+                    id: tab_id.clone(),
+                    pty_id: pty_id.clone(),
+                    title,
+                    command: Some(command),
+                }],
+                active_session_id: tab_id,
+                is_shared: false,
+                cwd: cwd.clone(),
+            };
+            if let Err(e) =
+                workspace_store::add_panel_to_feature(&workspace_project_id, &fid, panel.clone())
+            {
+                let _ = pty_manager::kill_session(&pty_id);
+                return Err(e);
+            }
+            Some(panel)
+        }
+        _ => None,
+    };
+
+    Ok(ResumedTerminal { pty_id, cwd, panel })
+}
+
+/// Start `claude --resume <session_id>` in a new PTY, optionally as a panel of a feature
+#[tauri::command]
+async fn resume_session_in_terminal(
+    project_id: String,
+    session_id: String,
+    feature_id: Option<String>,
+) -> Result<ResumedTerminal, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let session_path = get_claude_dir()
+            .join("projects")
+            .join(&project_id)
+            .join(format!("{}.jsonl", session_id));
+        if !session_path.exists() {
+            return Err(format!("Session '{}' not found", session_id));
+        }
+        let cwd = session_cwd(&session_path, &project_id);
+        if !Path::new(&cwd).is_dir() {
+            return Err(format!("Project directory '{}' no longer exists", cwd));
+        }
+
+        let command = format!("claude --resume {}", shell_quote(&session_id));
+        let title = format!("Resume {}", session_id.chars().take(8).collect::<String>());
+        launch_in_terminal(cwd, command, title, feature_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Single-quote `s` for `sh -c`
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Start `claude` in a new PTY at `cwd`, asked to hand `prompt` to the named
+/// subagent, optionally as a panel of a feature
+#[tauri::command]
+async fn test_agent(
+    agent_name: String,
+    prompt: String,
+    cwd: String,
+    feature_id: Option<String>,
+) -> Result<ResumedTerminal, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if !Path::new(&cwd).is_dir() {
+            return Err(format!("Directory '{}' does not exist", cwd));
+        }
+        let prompt = prompt.trim();
+        if prompt.is_empty() {
+            return Err("Test prompt cannot be empty".to_string());
+        }
+
+        // The agent has to be one Claude Code will load from this cwd
+        let project = command_files::CommandScope::Project {
+            project_path: cwd.clone(),
+        };
+        let mut agents = agent_files::list(&project)?;
+        agents.extend(agent_files::list(&command_files::CommandScope::Global)?);
+        let agent = agents
+            .iter()
+            .find(|a| a.name == agent_name)
+            .ok_or_else(|| format!("Agent '{}' not found", agent_name))?;
+        if agent.status != "active" {
+            return Err(format!("Agent '{}' is deprecated", agent_name));
+        }
+
+        let instruction = format!(
+            "Use the {} subagent for this task:\n\n{}",
+            agent_name, prompt
+        );
+        let command = format!("claude {}", shell_quote(&instruction));
+        launch_in_terminal(cwd, command, format!("Test {}", agent_name), feature_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn get_cwd_suggestions(
+    prefix: String,
+    limit: Option<usize>,
+) -> Result<Vec<cwd_suggestions::CwdSuggestion>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let workspace_paths: Vec<String> = workspace_store::load_workspace()?
+            .projects
+            .into_iter()
+            .map(|p| p.path)
+            .collect();
+        Ok(cwd_suggestions::get_suggestions(&prefix, &workspace_paths, limit))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 fn pty_write(id: String, data: Vec<u8>) -> Result<(), String> {
     pty_manager::write_to_session(&id, &data)
@@ -4632,28 +8367,127 @@ fn pty_flush_scrollback() {
     pty_manager::flush_all_scrollback()
 }
 
+/// Listening ports opened by processes inside any PTY of a feature
+#[tauri::command]
+async fn get_feature_ports(feature_id: String) -> Result<Vec<port_scanner::ListeningPort>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let data = workspace_store::load_workspace()?;
+        let feature = data
+            .projects
+            .iter()
+            .flat_map(|p| p.features.iter())
+            .find(|f| f.id == feature_id)
+            .ok_or_else(|| format!("Feature '{}' not found", feature_id))?;
+
+        let ptys: Vec<(String, u32)> = feature
+            .panels
+            .iter()
+            .flat_map(|panel| panel.sessions.iter())
+            .filter_map(|session| {
+                pty_manager::session_pid(&session.pty_id).map(|pid| (session.pty_id.clone(), pid))
+            })
+            .collect();
+
+        Ok(port_scanner::ports_for_ptys(&ptys))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn open_port_in_browser(port: u16) -> Result<(), String> {
+    let url = format!("http://localhost:{}", port);
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg(&url)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("cmd")
+            .args(["/C", "start", "", &url])
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open")
+            .arg(&url)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 // ============================================================================
 // Workspace Commands
 // ============================================================================
 
 #[tauri::command]
 fn workspace_load() -> Result<workspace_store::WorkspaceData, String> {
-    workspace_store::load_workspace()
+    let mut data = workspace_store::load_workspace()?;
+    workspace_store::mask_workspace_env(&mut data);
+    Ok(data)
 }
 
 #[tauri::command]
-fn workspace_save(data: workspace_store::WorkspaceData) -> Result<(), String> {
+fn workspace_save(mut data: workspace_store::WorkspaceData) -> Result<(), String> {
+    workspace_store::restore_masked_env(&mut data)?;
     workspace_store::save_workspace(&data)
 }
 
+/// Workspace snapshots, newest first
+#[tauri::command]
+fn list_workspace_snapshots() -> Vec<workspace_snapshots::SnapshotInfo> {
+    workspace_snapshots::list()
+}
+
+/// What restoring a snapshot would change
+#[tauri::command]
+fn preview_workspace_snapshot(timestamp: u64) -> Result<workspace_snapshots::SnapshotDiff, String> {
+    workspace_snapshots::preview(timestamp)
+}
+
+/// Roll the workspace back to a snapshot, returning it masked like `workspace_load`
+#[tauri::command]
+fn restore_workspace_snapshot(timestamp: u64) -> Result<workspace_store::WorkspaceData, String> {
+    let mut data = workspace_snapshots::restore(timestamp)?;
+    workspace_store::mask_workspace_env(&mut data);
+    Ok(data)
+}
+
 #[tauri::command]
 fn workspace_add_project(path: String) -> Result<workspace_store::WorkspaceProject, String> {
     workspace_store::add_project(path)
 }
 
 #[tauri::command]
-fn workspace_list_projects() -> Result<Vec<workspace_store::WorkspaceProject>, String> {
-    workspace_store::load_workspace().map(|d| d.projects)
+fn workspace_list_projects(
+    query: Option<workspace_store::ProjectQuery>,
+) -> Result<Vec<workspace_store::WorkspaceProject>, String> {
+    let mut data = workspace_store::load_workspace()?;
+    workspace_store::mask_workspace_env(&mut data);
+    Ok(workspace_store::query_projects(data.projects, &query.unwrap_or_default()))
+}
+
+#[tauri::command]
+fn workspace_get_project_metadata(
+    project_id: String,
+) -> Result<std::collections::BTreeMap<String, workspace_store::MetadataValue>, String> {
+    workspace_store::get_project_metadata(&project_id)
+}
+
+/// Set a custom project field; a null value removes it
+#[tauri::command]
+fn workspace_set_project_metadata(
+    project_id: String,
+    key: String,
+    value: Option<workspace_store::MetadataValue>,
+) -> Result<std::collections::BTreeMap<String, workspace_store::MetadataValue>, String> {
+    workspace_store::set_project_metadata(&project_id, &key, value)
 }
 
 #[tauri::command]
@@ -4687,7 +8521,64 @@ fn workspace_update_feature_status(
 
 #[tauri::command]
 fn workspace_delete_feature(project_id: String, feature_id: String) -> Result<(), String> {
-    workspace_store::delete_feature(&project_id, &feature_id)
+    workspace_store::delete_feature(&project_id, &feature_id)?;
+    feature_timers::forget_feature(&feature_id);
+    Ok(())
+}
+
+/// Start a stopwatch, or a pomodoro of `duration_minutes` (default 25), on a feature
+#[tauri::command]
+fn timer_start(
+    project_id: String,
+    feature_id: String,
+    mode: feature_timers::TimerMode,
+    duration_minutes: Option<u64>,
+) -> Result<feature_timers::TimerStatus, String> {
+    let duration_secs = duration_minutes.map(|m| m.saturating_mul(60));
+    feature_timers::start(&project_id, &feature_id, mode, duration_secs)
+}
+
+#[tauri::command]
+fn timer_pause(feature_id: String) -> Result<feature_timers::TimerStatus, String> {
+    feature_timers::pause(&feature_id)
+}
+
+#[tauri::command]
+fn timer_resume(feature_id: String) -> Result<feature_timers::TimerStatus, String> {
+    feature_timers::resume(&feature_id)
+}
+
+#[tauri::command]
+fn timer_stop(feature_id: String) -> Result<feature_timers::TimerStatus, String> {
+    feature_timers::stop(&feature_id)
+}
+
+#[tauri::command]
+fn list_timers() -> Vec<feature_timers::TimerStatus> {
+    feature_timers::list()
+}
+
+/// Time tracked per feature, optionally for one project and a unix-seconds range
+#[tauri::command]
+fn get_time_report(project_id: Option<String>, since: Option<u64>, until: Option<u64>) -> feature_timers::TimeReport {
+    feature_timers::report(project_id.as_deref(), since, until)
+}
+
+/// Mark a feature as blocked by another feature of the same project
+#[tauri::command]
+fn workspace_add_feature_dependency(project_id: String, feature_id: String, blocked_by: String) -> Result<(), String> {
+    workspace_store::add_feature_dependency(&project_id, &feature_id, &blocked_by)
+}
+
+#[tauri::command]
+fn workspace_remove_feature_dependency(project_id: String, feature_id: String, blocked_by: String) -> Result<(), String> {
+    workspace_store::remove_feature_dependency(&project_id, &feature_id, &blocked_by)
+}
+
+/// Blocked-by/blocks links of a project's features and which ones can start now
+#[tauri::command]
+fn get_feature_graph(project_id: String) -> Result<workspace_store::FeatureGraph, String> {
+    workspace_store::get_feature_graph(&project_id)
 }
 
 #[tauri::command]
@@ -4714,6 +8605,54 @@ fn workspace_toggle_panel_shared(project_id: String, panel_id: String) -> Result
     workspace_store::toggle_panel_shared(&project_id, &panel_id)
 }
 
+#[tauri::command]
+fn workspace_get_feature_env(
+    project_id: String,
+    feature_id: String,
+    reveal: Option<bool>,
+) -> Result<Vec<workspace_store::EnvVarEntry>, String> {
+    workspace_store::get_feature_env(&project_id, &feature_id, reveal.unwrap_or(false))
+}
+
+#[tauri::command]
+fn workspace_set_feature_env(
+    project_id: String,
+    feature_id: String,
+    env: HashMap<String, String>,
+) -> Result<(), String> {
+    workspace_store::set_feature_env(&project_id, &feature_id, env)
+}
+
+#[tauri::command]
+fn workspace_create_group(name: String, color: Option<String>) -> Result<workspace_store::ProjectGroup, String> {
+    workspace_store::create_group(name, color)
+}
+
+#[tauri::command]
+fn workspace_update_group(
+    group_id: String,
+    name: Option<String>,
+    color: Option<String>,
+    collapsed: Option<bool>,
+) -> Result<workspace_store::ProjectGroup, String> {
+    workspace_store::update_group(&group_id, name, color, collapsed)
+}
+
+#[tauri::command]
+fn workspace_delete_group(group_id: String) -> Result<(), String> {
+    workspace_store::delete_group(&group_id)
+}
+
+#[tauri::command]
+fn workspace_set_project_group(project_id: String, group_id: Option<String>) -> Result<(), String> {
+    workspace_store::set_project_group(&project_id, group_id)
+}
+
+#[tauri::command]
+fn workspace_get_group_stats() -> Result<Vec<workspace_store::GroupStats>, String> {
+    workspace_store::get_group_stats()
+}
+
 #[tauri::command]
 fn workspace_get_pending_reviews() -> Result<Vec<(String, String, String)>, String> {
     workspace_store::get_pending_reviews()
@@ -5275,8 +9214,96 @@ fn activate_and_focus_window(window: &tauri::WebviewWindow) {
     }
 }
 
+/// Project or session passed at launch (or opened since), returned once
+#[tauri::command]
+fn take_launch_target() -> Option<launch_args::LaunchTarget> {
+    launch_args::take()
+}
+
+/// Startup phase timings, including deferred init and the jieba dictionary load
+#[tauri::command]
+fn get_startup_profile() -> startup::StartupProfile {
+    startup::profile()
+}
+
+/// Open file descriptors, limits, terminals and watchers
+#[tauri::command]
+fn get_resource_usage() -> resource_limits::ResourceUsage {
+    resource_limits::usage()
+}
+
+/// Process memory and cache sizes for the diagnostics panel
+#[tauri::command]
+fn get_memory_report() -> memory::MemoryReport {
+    memory::report()
+}
+
+/// Watchers, index freshness, caches, terminals, maintenance and degraded subsystems
+#[tauri::command]
+async fn get_runtime_health() -> Result<health::RuntimeHealth, String> {
+    tauri::async_runtime::spawn_blocking(health::report)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_memory_budget() -> memory::MemoryBudget {
+    memory::budget()
+}
+
+/// Take the memory budget from the app config and apply it right away
+#[tauri::command]
+fn set_memory_budget(budget: memory::MemoryBudget) -> Result<memory::MemoryReport, String> {
+    memory::set_budget(budget)?;
+    Ok(memory::enforce())
+}
+
+/// Watch the distill directory and emit "distill-changed" (debounced)
+fn start_distill_watcher(app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let _watcher_guard = resource_limits::track_watcher("distill");
+        let distill_dir = get_distill_dir();
+        if !distill_dir.exists() {
+            // Create directory if it doesn't exist so we can watch it
+            let _ = fs::create_dir_all(&distill_dir);
+        }
+
+        let (tx, rx) = channel();
+        let mut watcher: RecommendedWatcher = match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                // Only trigger on create/modify/remove events
+                if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() {
+                    let _ = tx.send(());
+                }
+            }
+        }) {
+            Ok(w) => w,
+            Err(_) => return,
+        };
+
+        if watcher.watch(&distill_dir, RecursiveMode::NonRecursive).is_err() {
+            return;
+        }
+
+        // Debounce: wait for events to settle before emitting
+        loop {
+            if rx.recv().is_ok() {
+                // Drain any additional events that came in quickly
+                while rx.recv_timeout(Duration::from_millis(200)).is_ok() {}
+                // Only emit if watch is enabled
+                if DISTILL_WATCH_ENABLED.load(std::sync::atomic::Ordering::Relaxed) {
+                    let _ = app_handle.emit("distill-changed", ());
+                }
+            }
+        }
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    startup::mark_process_start();
+    // `lovcode <path>` / `lovcode --session <id>`, resolved before any window exists
+    startup::measure("launch_args", launch_args::capture_from_env);
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -5284,45 +9311,42 @@ pub fn run() {
             use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder, PredefinedMenuItem};
 
             // Initialize PTY manager with app handle for event emission
-            pty_manager::init(app.handle().clone());
+            startup::measure("pty_manager", || pty_manager::init(app.handle().clone()));
 
-            // Start watching distill directory for changes
+            // Terminals and watchers need more descriptors than the default soft limit
+            startup::measure("fd_limit", resource_limits::raise_fd_limit);
+
+            // Background work waits for the first paint
             let app_handle = app.handle().clone();
-            std::thread::spawn(move || {
-                let distill_dir = get_distill_dir();
-                if !distill_dir.exists() {
-                    // Create directory if it doesn't exist so we can watch it
-                    let _ = fs::create_dir_all(&distill_dir);
-                }
+            let app_handle_for_limits = app.handle().clone();
+            startup::on_app_ready(app.handle(), move || {
+                // Keep session topics/language up to date in the background
+                session_cache::start_background_refresh(app_handle.clone());
+
+                // Drop trashed sessions, commands and agents past their retention period
+                std::thread::spawn(|| {
+                    trash::purge_expired();
+                    command_files::purge_expired();
+                    agent_files::purge_expired();
+                    skill_files::purge_expired();
+                });
 
-                let (tx, rx) = channel();
-                let mut watcher: RecommendedWatcher = match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
-                    if let Ok(event) = res {
-                        // Only trigger on create/modify/remove events
-                        if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() {
-                            let _ = tx.send(());
-                        }
-                    }
-                }) {
-                    Ok(w) => w,
-                    Err(_) => return,
-                };
+                start_distill_watcher(app_handle.clone());
 
-                if watcher.watch(&distill_dir, RecursiveMode::NonRecursive).is_err() {
-                    return;
-                }
+                // Resume feature timers persisted from the last run
+                feature_timers::start_ticker(app_handle.clone());
 
-                // Debounce: wait for events to settle before emitting
-                loop {
-                    if rx.recv().is_ok() {
-                        // Drain any additional events that came in quickly
-                        while rx.recv_timeout(Duration::from_millis(200)).is_ok() {}
-                        // Only emit if watch is enabled
-                        if DISTILL_WATCH_ENABLED.load(std::sync::atomic::Ordering::Relaxed) {
-                            let _ = app_handle.emit("distill-changed", ());
-                        }
-                    }
-                }
+                // Pick up commands/agents/skills edited outside the app
+                customizations_watcher::start(app_handle.clone());
+
+                // Refresh project/session lists when Claude Code writes sessions
+                sessions_watcher::start(app_handle);
+
+                // Index, cache, retention and backup upkeep while the machine is idle
+                maintenance::start_scheduler();
+
+                memory::start_monitor();
+                resource_limits::start_monitor(app_handle_for_limits);
             });
 
             let settings = MenuItemBuilder::with_id("settings", "Settings...")
@@ -5428,27 +9452,164 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             list_projects,
+            get_project_path_mappings,
+            set_project_path_mapping,
+            relocate_project,
+            get_project_overview,
+            get_project_usage,
+            set_project_pinned,
+            set_project_hidden,
+            set_project_archived,
+            list_projects_grouped,
+            create_project_group,
+            update_project_group,
+            delete_project_group,
+            set_project_group,
+            reorder_project_groups,
             list_sessions,
             list_all_sessions,
             list_all_chats,
             get_session_messages,
+            get_session_tree,
+            list_session_sidechains,
+            get_sidechain_messages,
+            diff_sessions,
+            stream_session_messages,
+            cancel_session_stream,
+            watch_session,
+            unwatch_session,
+            render_markdown,
+            export_session_html,
+            export_session_pdf,
+            export_session_redacted,
+            export_session_share_page,
+            get_redaction_patterns,
+            set_redaction_patterns,
+            export_sessions_archive,
+            list_export_formats,
+            export,
+            get_price_table,
+            set_price_table,
+            set_session_starred,
+            set_session_tags,
+            set_session_note,
+            rename_session,
+            list_annotations,
+            add_annotation,
+            update_annotation,
+            delete_annotation,
+            list_collections,
+            get_collection,
+            create_collection,
+            update_collection,
+            delete_collection,
+            add_session_to_collection,
+            remove_session_from_collection,
+            list_session_tags,
+            delete_session,
+            list_trash,
+            restore_session,
+            purge_trash,
+            archive_old_sessions,
+            archive_session,
+            list_archived_sessions,
+            get_archive_stats,
+            get_attachment_cache_stats,
+            get_maintenance_history,
+            run_maintenance,
+            clear_attachment_cache,
+            get_archived_session_messages,
+            search_archived_sessions,
+            restore_archived_session,
+            cleanup_sessions,
+            find_orphaned_projects,
+            cleanup_orphaned_projects,
+            list_session_diagrams,
+            save_diagram_to_distill,
+            list_distill_templates,
+            save_distill_template,
+            create_distill_from_template,
+            import_notes,
+            get_startup_profile,
+            take_launch_target,
+            get_memory_report,
+            get_resource_usage,
+            get_memory_budget,
+            get_runtime_health,
+            set_memory_budget,
             build_search_index,
             search_chats,
+            search_chats_structured,
+            locate_message,
             list_local_commands,
+            list_commands_by_tag,
+            search_commands,
             list_local_agents,
+            list_agents_by_scope,
+            create_agent,
+            update_agent,
+            rename_agent,
+            deprecate_agent,
+            restore_agent,
+            append_agent_changelog,
+            validate_agent,
+            delete_agent,
+            list_deleted_agents,
+            restore_deleted_agent,
+            purge_deleted_agent,
             list_local_skills,
+            list_skills_by_scope,
+            create_skill,
+            get_skill_details,
+            read_skill_file,
+            write_skill_file,
+            delete_skill_file,
+            delete_skill,
+            list_deleted_skills,
+            restore_deleted_skill,
+            purge_deleted_skill,
             get_context_files,
             get_project_context,
+            get_project_assets,
+            save_context_file,
+            get_context_file_history,
+            get_context_file_backup,
+            restore_context_file,
             get_settings,
             get_command_stats,
+            get_command_usage,
+            find_stale_commands,
+            get_agent_stats,
             get_activity_stats,
             get_templates_catalog,
             install_command_template,
+            install_agent_template,
+            list_agent_installs,
+            uninstall_agent_template,
+            install_skill_template,
+            list_skill_installs,
             rename_command,
             deprecate_command,
             archive_command,
             restore_command,
             update_command_aliases,
+            create_command,
+            update_command,
+            list_command_versions,
+            restore_command_version,
+            diff_command_versions,
+            validate_command,
+            render_command_preview,
+            duplicate_command,
+            delete_command,
+            list_deleted_commands,
+            restore_deleted_command,
+            purge_deleted_command,
+            append_command_changelog,
+            find_command_conflicts,
+            create_command_folder,
+            rename_command_folder,
+            move_command_folder,
             install_mcp_template,
             uninstall_mcp_template,
             check_mcp_installed,
@@ -5484,16 +9645,26 @@ pub fn run() {
             test_openai_connection,
             test_claude_cli,
             list_distill_documents,
+            get_session_distills,
             find_session_project,
             get_distill_watch_enabled,
             set_distill_watch_enabled,
             list_reference_sources,
             list_reference_docs,
+            record_reference_fetch,
+            get_stale_reference_docs,
+            diff_documents,
+            export_knowledge_base,
             get_claude_code_version_info,
             install_claude_code_version,
             set_claude_code_autoupdater,
             // PTY commands
             pty_create,
+            resume_session_in_terminal,
+            test_agent,
+            get_feature_ports,
+            open_port_in_browser,
+            get_cwd_suggestions,
             pty_write,
             pty_read,
             pty_resize,
@@ -5506,8 +9677,13 @@ pub fn run() {
             // Workspace commands
             workspace_load,
             workspace_save,
+            list_workspace_snapshots,
+            preview_workspace_snapshot,
+            restore_workspace_snapshot,
             workspace_add_project,
             workspace_list_projects,
+            workspace_get_project_metadata,
+            workspace_set_project_metadata,
             workspace_remove_project,
             workspace_set_active_project,
             workspace_create_feature,
@@ -5515,10 +9691,26 @@ pub fn run() {
             workspace_update_feature_status,
             workspace_delete_feature,
             workspace_set_active_feature,
+            workspace_add_feature_dependency,
+            workspace_remove_feature_dependency,
+            get_feature_graph,
+            timer_start,
+            timer_pause,
+            timer_resume,
+            timer_stop,
+            list_timers,
+            get_time_report,
             workspace_add_panel,
             workspace_remove_panel,
             workspace_toggle_panel_shared,
             workspace_get_pending_reviews,
+            workspace_get_feature_env,
+            workspace_set_feature_env,
+            workspace_create_group,
+            workspace_update_group,
+            workspace_delete_group,
+            workspace_set_project_group,
+            workspace_get_group_stats,
             // Hook watcher commands
             hook_start_monitoring,
             hook_stop_monitoring,
@@ -5552,6 +9744,15 @@ pub fn run() {
             {
                 use tauri::{Manager, RunEvent, WebviewWindowBuilder, WebviewUrl};
 
+                // Finder "Open With" and URL handlers deliver targets as events on macOS
+                if let RunEvent::Opened { urls } = &_event {
+                    for url in urls {
+                        if let Some(arg) = launch_args::parse_target(url.as_str()) {
+                            launch_args::open(_app, &arg);
+                        }
+                    }
+                }
+
                 if let RunEvent::Reopen { has_visible_windows, .. } = _event {
                     println!("[Lovcode] Dock clicked! has_visible_windows: {}", has_visible_windows);
 
@@ -0,0 +1,252 @@
+//! Persistent metadata cache for project and session listings
+//!
+//! Listing used to open every session file for its summary and message count
+//! on each call. Rows here hold that per-file data keyed by path, and a file is
+//! only read again when its size or mtime changed, so a listing costs one
+//! `stat` per file. Stored in SQLite at ~/.lovstudio/lovcode/listing-cache.db;
+//! if the database can't be opened, listings fall back to reading the files.
+
+use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Bump when the columns or how they are derived change
+const SCHEMA_VERSION: i64 = 1;
+/// Lines read from the top of a changed file for its summary
+const HEAD_LINES: usize = 20;
+
+static DB: LazyLock<Mutex<Option<Connection>>> = LazyLock::new(|| Mutex::new(open().ok()));
+
+/// Listing data of one session file
+#[derive(Debug, Clone)]
+pub struct CachedSession {
+    pub project_id: String,
+    pub session_id: String,
+    pub size: u64,
+    pub mtime: u64,
+    pub summary: Option<String>,
+    pub message_count: usize,
+    pub estimated_tokens: u64,
+}
+
+fn get_db_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("listing-cache.db")
+}
+
+fn open() -> Result<Connection, String> {
+    let path = get_db_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if version != SCHEMA_VERSION {
+        conn.execute_batch(&format!(
+            "DROP TABLE IF EXISTS sessions;
+             CREATE TABLE sessions (
+                 path TEXT PRIMARY KEY,
+                 project_id TEXT NOT NULL,
+                 session_id TEXT NOT NULL,
+                 size INTEGER NOT NULL,
+                 mtime INTEGER NOT NULL,
+                 summary TEXT,
+                 message_count INTEGER NOT NULL,
+                 estimated_tokens INTEGER NOT NULL
+             );
+             CREATE INDEX sessions_project ON sessions(project_id);
+             PRAGMA user_version = {};",
+            SCHEMA_VERSION
+        ))
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(conn)
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let mtime = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((metadata.len(), mtime))
+}
+
+/// Session files (not sidechains) of a project dir with their ids
+fn session_files(project_dir: &Path) -> Vec<(String, PathBuf)> {
+    fs::read_dir(project_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let session_id = name.strip_suffix(".jsonl")?;
+            (!session_id.starts_with("agent-")).then(|| (session_id.to_string(), entry.path()))
+        })
+        .collect()
+}
+
+/// Read a file's listing data
+fn read_session(
+    project_id: &str,
+    session_id: &str,
+    path: &Path,
+    stamp: (u64, u64),
+) -> CachedSession {
+    let (summary, head_count) = crate::read_session_head(path, HEAD_LINES);
+    let stats = crate::message_counts::stats(path);
+    CachedSession {
+        project_id: project_id.to_string(),
+        session_id: session_id.to_string(),
+        size: stamp.0,
+        mtime: stamp.1,
+        summary,
+        message_count: stats.map(|s| s.messages).unwrap_or(head_count),
+        estimated_tokens: stats.map(|s| s.estimated_tokens).unwrap_or(0),
+    }
+}
+
+fn cached_rows(
+    conn: &Connection,
+    project_id: &str,
+) -> rusqlite::Result<HashMap<String, CachedSession>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT path, session_id, size, mtime, summary, message_count, estimated_tokens
+         FROM sessions WHERE project_id = ?1",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            CachedSession {
+                project_id: project_id.to_string(),
+                session_id: row.get(1)?,
+                size: row.get::<_, i64>(2)? as u64,
+                mtime: row.get::<_, i64>(3)? as u64,
+                summary: row.get(4)?,
+                message_count: row.get::<_, i64>(5)? as usize,
+                estimated_tokens: row.get::<_, i64>(6)? as u64,
+            },
+        ))
+    })?;
+    rows.collect()
+}
+
+/// Bring one project's rows up to date with its dir and return them
+fn refresh_project(
+    conn: &mut Connection,
+    projects_dir: &Path,
+    project_id: &str,
+) -> rusqlite::Result<Vec<CachedSession>> {
+    let mut cached = cached_rows(conn, project_id)?;
+    let tx = conn.transaction()?;
+    let mut sessions = Vec::new();
+    for (session_id, path) in session_files(&projects_dir.join(project_id)) {
+        let Some(stamp) = file_stamp(&path) else {
+            continue;
+        };
+        let key = path.to_string_lossy().to_string();
+        match cached.remove(&key) {
+            Some(row) if (row.size, row.mtime) == stamp => sessions.push(row),
+            _ => {
+                let row = read_session(project_id, &session_id, &path, stamp);
+                tx.execute(
+                    "INSERT OR REPLACE INTO sessions
+                     (path, project_id, session_id, size, mtime, summary, message_count, estimated_tokens)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        key,
+                        project_id,
+                        row.session_id,
+                        row.size as i64,
+                        row.mtime as i64,
+                        row.summary,
+                        row.message_count as i64,
+                        row.estimated_tokens as i64,
+                    ],
+                )?;
+                sessions.push(row);
+            }
+        }
+    }
+    // Whatever is left was deleted or moved away
+    for path in cached.keys() {
+        tx.execute("DELETE FROM sessions WHERE path = ?1", params![path])?;
+    }
+    tx.commit()?;
+    Ok(sessions)
+}
+
+/// Without the database: read every file
+fn read_project(projects_dir: &Path, project_id: &str) -> Vec<CachedSession> {
+    session_files(&projects_dir.join(project_id))
+        .into_iter()
+        .filter_map(|(session_id, path)| {
+            let stamp = file_stamp(&path)?;
+            Some(read_session(project_id, &session_id, &path, stamp))
+        })
+        .collect()
+}
+
+fn project_sessions_in(projects_dir: &Path, project_id: &str) -> Vec<CachedSession> {
+    let mut guard = DB.lock().unwrap_or_else(|e| e.into_inner());
+    match guard.as_mut() {
+        Some(conn) => refresh_project(conn, projects_dir, project_id).unwrap_or_else(|e| {
+            eprintln!("[Lovcode] Listing cache error: {}", e);
+            read_project(projects_dir, project_id)
+        }),
+        None => read_project(projects_dir, project_id),
+    }
+}
+
+/// Sessions of one project
+pub fn project_sessions(project_id: &str) -> Vec<CachedSession> {
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let sessions = project_sessions_in(&projects_dir, project_id);
+    crate::message_counts::persist();
+    sessions
+}
+
+/// Sessions of every project, by project id (projects without sessions included)
+pub fn all_sessions() -> HashMap<String, Vec<CachedSession>> {
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let project_ids: Vec<String> = fs::read_dir(&projects_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+
+    let by_project: HashMap<String, Vec<CachedSession>> = project_ids
+        .iter()
+        .map(|id| (id.clone(), project_sessions_in(&projects_dir, id)))
+        .collect();
+
+    // Drop rows of projects whose dir is gone
+    let guard = DB.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(conn) = guard.as_ref() {
+        let known: HashSet<&String> = project_ids.iter().collect();
+        let stale: Vec<String> = conn
+            .prepare("SELECT DISTINCT project_id FROM sessions")
+            .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+            .unwrap_or_default();
+        for project_id in stale.iter().filter(|id| !known.contains(id)) {
+            let _ = conn.execute(
+                "DELETE FROM sessions WHERE project_id = ?1",
+                params![project_id],
+            );
+        }
+    }
+    drop(guard);
+    crate::message_counts::persist();
+    by_project
+}
@@ -0,0 +1,328 @@
+//! Nightly maintenance
+//!
+//! Once a day, while the machine is idle, brings the search index up to date
+//! with sessions changed since the last run, refreshes the session metadata
+//! cache and message stats, purges expired trash and rotates backups of the
+//! user's own data (workspace, titles, collections, timers, annotations).
+//! Preferred window is the small hours; a run that is overdue happens at the
+//! next idle moment. Every run is logged to ~/.lovstudio/lovcode/maintenance-log.json.
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// A day's run may happen this soon after the previous one
+const MIN_GAP_SECS: u64 = 20 * 60 * 60;
+/// After this long, run at the next idle moment even outside the night window
+const OVERDUE_SECS: u64 = 48 * 60 * 60;
+/// Local hours [start, end) preferred for a run
+const NIGHT_HOURS: (u32, u32) = (1, 6);
+/// 1-minute load per CPU below which the machine counts as idle
+const IDLE_LOAD_PER_CPU: f64 = 0.3;
+const MAX_LOG_RUNS: usize = 100;
+const MAX_BACKUPS: usize = 7;
+/// Files in ~/.lovstudio/lovcode that hold data the user can't regenerate
+const BACKUP_FILES: &[&str] = &[
+    "workspace.json",
+    "session-meta.json",
+    "collections.json",
+    "timers.json",
+    "disabled_env.json",
+];
+const BACKUP_DIRS: &[&str] = &["annotations"];
+
+/// Only one run at a time, from the scheduler or on demand
+static RUNNING: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceTrigger {
+    Scheduled,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceTask {
+    pub name: String,
+    pub ok: bool,
+    /// What was done, or the error
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRun {
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub trigger: MaintenanceTrigger,
+    pub tasks: Vec<MaintenanceTask>,
+}
+
+impl MaintenanceRun {
+    pub fn ok(&self) -> bool {
+        self.tasks.iter().all(|t| t.ok)
+    }
+}
+
+fn get_lovcode_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+}
+
+fn get_log_path() -> PathBuf {
+    get_lovcode_dir().join("maintenance-log.json")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load_log() -> Vec<MaintenanceRun> {
+    fs::read_to_string(get_log_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_log(runs: &[MaintenanceRun]) -> Result<(), String> {
+    let path = get_log_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(runs).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Past runs, newest first
+pub fn history(limit: Option<usize>) -> Vec<MaintenanceRun> {
+    let mut runs = load_log();
+    runs.reverse();
+    runs.truncate(limit.unwrap_or(usize::MAX));
+    runs
+}
+
+/// Start of the last run where every task succeeded
+fn last_successful_run(runs: &[MaintenanceRun]) -> Option<u64> {
+    runs.iter().rev().find(|r| r.ok()).map(|r| r.started_at)
+}
+
+#[cfg(unix)]
+fn machine_idle() -> bool {
+    let mut load = [0f64; 3];
+    if unsafe { libc::getloadavg(load.as_mut_ptr(), 3) } < 1 {
+        return true;
+    }
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    load[0] / (cpus as f64) < IDLE_LOAD_PER_CPU
+}
+
+#[cfg(not(unix))]
+fn machine_idle() -> bool {
+    true
+}
+
+fn due(runs: &[MaintenanceRun], now: u64) -> bool {
+    let since_last = runs
+        .last()
+        .map_or(u64::MAX, |r| now.saturating_sub(r.started_at));
+    if since_last < MIN_GAP_SECS {
+        return false;
+    }
+    let hour = chrono::Local::now().hour();
+    let in_window = (NIGHT_HOURS.0..NIGHT_HOURS.1).contains(&hour);
+    (in_window || since_last >= OVERDUE_SECS) && machine_idle()
+}
+
+fn task(name: &str, f: impl FnOnce() -> Result<String, String>) -> MaintenanceTask {
+    let started = Instant::now();
+    let result = f();
+    MaintenanceTask {
+        name: name.to_string(),
+        ok: result.is_ok(),
+        detail: result.unwrap_or_else(|e| e),
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Session files (not sidechains) with their project and session id
+fn session_files() -> Vec<(String, String, PathBuf)> {
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let mut sessions = Vec::new();
+    for project in fs::read_dir(&projects_dir).into_iter().flatten().flatten() {
+        let project_id = project.file_name().to_string_lossy().to_string();
+        for entry in fs::read_dir(project.path()).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(session_id) = name.strip_suffix(".jsonl") else {
+                continue;
+            };
+            if !session_id.starts_with("agent-") {
+                sessions.push((project_id.clone(), session_id.to_string(), entry.path()));
+            }
+        }
+    }
+    sessions
+}
+
+fn modified_since(path: &Path, since: u64) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .is_none_or(|d| d.as_secs() >= since)
+}
+
+/// Re-index sessions changed since the last good run (all of them on the first run)
+fn update_index(since: Option<u64>) -> Result<String, String> {
+    let changed: Vec<_> = session_files()
+        .into_iter()
+        .filter(|(_, _, path)| since.is_none_or(|s| modified_since(path, s)))
+        .collect();
+    let mut failed = 0;
+    for (project_id, session_id, path) in &changed {
+        if crate::update_session_in_indexes(project_id, session_id, Some(path)).is_err() {
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(format!(
+            "{} of {} sessions failed to index",
+            failed,
+            changed.len()
+        ));
+    }
+    Ok(format!("{} sessions re-indexed", changed.len()))
+}
+
+fn aggregate_stats() -> Result<String, String> {
+    let sessions = session_files();
+    let tokens: u64 = sessions
+        .iter()
+        .filter_map(|(_, _, path)| crate::message_counts::stats(path))
+        .map(|s| s.estimated_tokens)
+        .sum();
+    crate::message_counts::persist();
+    Ok(format!("{} sessions, ~{} tokens", sessions.len(), tokens))
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)?.flatten() {
+        let target = dst.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy user data into backups/<date>/ and keep the newest MAX_BACKUPS
+fn rotate_backups() -> Result<String, String> {
+    let source = get_lovcode_dir();
+    let backups = source.join("backups");
+    let dir = backups.join(chrono::Local::now().format("%Y-%m-%d").to_string());
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let mut copied = 0;
+    for name in BACKUP_FILES {
+        let path = source.join(name);
+        if path.is_file() {
+            fs::copy(&path, dir.join(name)).map_err(|e| format!("{}: {}", name, e))?;
+            copied += 1;
+        }
+    }
+    for name in BACKUP_DIRS {
+        let path = source.join(name);
+        if path.is_dir() {
+            copy_dir(&path, &dir.join(name)).map_err(|e| format!("{}: {}", name, e))?;
+            copied += 1;
+        }
+    }
+
+    // Dated names sort chronologically; other dirs (e.g. context/) are not rotated
+    let mut existing: Vec<PathBuf> = fs::read_dir(&backups)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| chrono::NaiveDate::parse_from_str(n, "%Y-%m-%d").is_ok())
+        })
+        .collect();
+    existing.sort();
+    let excess = existing.len().saturating_sub(MAX_BACKUPS);
+    for old in existing.drain(..excess) {
+        let _ = fs::remove_dir_all(old);
+    }
+    Ok(format!(
+        "{} items backed up, {} older backups removed",
+        copied, excess
+    ))
+}
+
+/// Run every maintenance task and log the run; tasks continue past failures
+pub fn run(trigger: MaintenanceTrigger) -> Result<MaintenanceRun, String> {
+    let _running = RUNNING
+        .try_lock()
+        .map_err(|_| "Maintenance is already running".to_string())?;
+    let mut runs = load_log();
+    let since = last_successful_run(&runs);
+    let started_at = now_secs();
+
+    let tasks = vec![
+        task("index", || update_index(since)),
+        task("session_cache", || {
+            crate::session_cache::refresh_all().map(|n| format!("{} sessions analyzed", n))
+        }),
+        task("stats", aggregate_stats),
+        task("retention", || {
+            Ok(format!(
+                "{} expired trash entries purged",
+                crate::trash::purge_expired()
+                    + crate::command_files::purge_expired()
+                    + crate::agent_files::purge_expired()
+                    + crate::skill_files::purge_expired()
+            ))
+        }),
+        task("backup", rotate_backups),
+    ];
+
+    let run = MaintenanceRun {
+        started_at,
+        finished_at: now_secs(),
+        trigger,
+        tasks,
+    };
+    runs.push(run.clone());
+    let excess = runs.len().saturating_sub(MAX_LOG_RUNS);
+    runs.drain(..excess);
+    save_log(&runs)?;
+    Ok(run)
+}
+
+/// Check every few minutes whether a scheduled run is due
+pub fn start_scheduler() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        if !due(&load_log(), now_secs()) {
+            continue;
+        }
+        match run(MaintenanceTrigger::Scheduled) {
+            Ok(run) if !run.ok() => eprintln!("[Lovcode] Maintenance finished with errors"),
+            Ok(_) => {}
+            Err(e) => eprintln!("[Lovcode] Maintenance failed: {}", e),
+        }
+    });
+}
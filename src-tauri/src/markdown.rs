@@ -0,0 +1,157 @@
+//! Markdown rendering service
+//!
+//! Renders markdown to HTML with comrak so distill docs, commands and CLAUDE.md
+//! previews look the same everywhere. Code fences are syntax highlighted with
+//! syntect; ```mermaid fences are passed through untouched as
+//! `<pre class="mermaid">` for the webview to render.
+
+use comrak::adapters::SyntaxHighlighterAdapter;
+use comrak::plugins::syntect::SyntectAdapter;
+use comrak::{markdown_to_html_with_plugins, Options, Plugins};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{LazyLock, Mutex};
+use syntect::highlighting::ThemeSet;
+
+const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+/// Names of syntect's bundled themes, the only ones `SyntectAdapter` can load
+static THEME_NAMES: LazyLock<Vec<String>> =
+    LazyLock::new(|| ThemeSet::load_defaults().themes.into_keys().collect());
+
+/// Loading syntax definitions is slow, so each theme's adapter is built once
+/// and kept for the life of the app (there are only a handful of themes)
+static HIGHLIGHTERS: LazyLock<Mutex<HashMap<String, &'static SyntectAdapter>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The adapter for `theme`, falling back to the default for unknown names
+fn highlighter(theme: Option<&str>) -> &'static SyntectAdapter {
+    let theme = theme
+        .filter(|t| THEME_NAMES.iter().any(|name| name == t))
+        .unwrap_or(DEFAULT_THEME);
+    let mut highlighters = HIGHLIGHTERS.lock().unwrap_or_else(|e| e.into_inner());
+    highlighters
+        .entry(theme.to_string())
+        .or_insert_with(|| Box::leak(Box::new(SyntectAdapter::new(Some(theme)))))
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RenderOptions {
+    /// GitHub flavored extensions (tables, task lists, strikethrough, autolinks); default on
+    pub gfm: Option<bool>,
+    /// Syntax highlight code fences; default on
+    pub highlight: Option<bool>,
+    /// syntect theme name; unknown names fall back to base16-ocean.dark
+    pub theme: Option<String>,
+    /// Render soft line breaks as <br>
+    pub hard_breaks: Option<bool>,
+    /// Allow raw HTML in the source; default off
+    pub allow_html: Option<bool>,
+}
+
+/// Wraps the syntect adapter, passing mermaid fences through unhighlighted
+struct FenceHighlighter<'a> {
+    inner: Option<&'a SyntectAdapter>,
+}
+
+fn is_diagram(lang: Option<&str>) -> bool {
+    matches!(lang, Some("mermaid"))
+}
+
+fn write_escaped(output: &mut dyn Write, code: &str) -> io::Result<()> {
+    for c in code.chars() {
+        match c {
+            '&' => output.write_all(b"&amp;")?,
+            '<' => output.write_all(b"&lt;")?,
+            '>' => output.write_all(b"&gt;")?,
+            '"' => output.write_all(b"&quot;")?,
+            _ => write!(output, "{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+fn write_tag(
+    output: &mut dyn Write,
+    tag: &str,
+    attributes: &HashMap<String, String>,
+) -> io::Result<()> {
+    write!(output, "<{}", tag)?;
+    let mut keys: Vec<&String> = attributes.keys().collect();
+    keys.sort();
+    for key in keys {
+        write!(output, " {}=\"", key)?;
+        write_escaped(output, &attributes[key])?;
+        output.write_all(b"\"")?;
+    }
+    output.write_all(b">")
+}
+
+impl SyntaxHighlighterAdapter for FenceHighlighter<'_> {
+    fn write_highlighted(
+        &self,
+        output: &mut dyn Write,
+        lang: Option<&str>,
+        code: &str,
+    ) -> io::Result<()> {
+        match self.inner {
+            Some(inner) if !is_diagram(lang) => inner.write_highlighted(output, lang, code),
+            _ => write_escaped(output, code),
+        }
+    }
+
+    fn write_pre_tag(
+        &self,
+        output: &mut dyn Write,
+        mut attributes: HashMap<String, String>,
+    ) -> io::Result<()> {
+        // `github_pre_lang` puts the fence language on <pre lang="...">
+        if is_diagram(attributes.get("lang").map(String::as_str)) {
+            attributes.insert("class".to_string(), "mermaid".to_string());
+            return write_tag(output, "pre", &attributes);
+        }
+        match self.inner {
+            Some(inner) => inner.write_pre_tag(output, attributes),
+            None => write_tag(output, "pre", &attributes),
+        }
+    }
+
+    fn write_code_tag(
+        &self,
+        output: &mut dyn Write,
+        attributes: HashMap<String, String>,
+    ) -> io::Result<()> {
+        match self.inner {
+            Some(inner) => inner.write_code_tag(output, attributes),
+            None => write_tag(output, "code", &attributes),
+        }
+    }
+}
+
+/// Render markdown to an HTML fragment
+pub fn render(text: &str, options: &RenderOptions) -> String {
+    let gfm = options.gfm.unwrap_or(true);
+
+    let mut comrak_options = Options::default();
+    comrak_options.extension.table = gfm;
+    comrak_options.extension.tasklist = gfm;
+    comrak_options.extension.strikethrough = gfm;
+    comrak_options.extension.autolink = gfm;
+    comrak_options.extension.footnotes = gfm;
+    comrak_options.extension.front_matter_delimiter = Some("---".to_string());
+    comrak_options.render.github_pre_lang = true;
+    comrak_options.render.hardbreaks = options.hard_breaks.unwrap_or(false);
+    comrak_options.render.unsafe_ = options.allow_html.unwrap_or(false);
+
+    let inner = options
+        .highlight
+        .unwrap_or(true)
+        .then(|| highlighter(options.theme.as_deref()));
+
+    let highlighter = FenceHighlighter { inner };
+    let mut plugins = Plugins::default();
+    plugins.render.codefence_syntax_highlighter = Some(&highlighter);
+
+    markdown_to_html_with_plugins(text, &comrak_options, &plugins)
+}
@@ -0,0 +1,199 @@
+//! Memory budget and cache eviction
+//!
+//! A background monitor periodically measures process RSS and the in-memory
+//! caches (PTY scrollback, session metadata). Caches over their share of the
+//! budget are evicted: scrollback buffers least recently used first (they are
+//! persisted and paged back in on demand), the metadata cache as a whole (it
+//! reloads from disk). The budget is part of the frontend's app config, which
+//! hands it over with `set_budget`; the defaults apply until then.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Scrollback used this recently is never evicted
+const SCROLLBACK_KEEP_RECENT: Duration = Duration::from_secs(120);
+/// Minimum time between two over-budget evictions. Freed memory rarely goes
+/// back to the OS, so RSS stays high and every check would evict again.
+const OVER_BUDGET_COOLDOWN: Duration = Duration::from_secs(30 * 60);
+const MB: u64 = 1024 * 1024;
+
+static SCROLLBACK_EVICTIONS: AtomicU64 = AtomicU64::new(0);
+static SESSION_CACHE_EVICTIONS: AtomicU64 = AtomicU64::new(0);
+static LAST_CHECK: Mutex<Option<u64>> = Mutex::new(None);
+static LAST_OVER_BUDGET_EVICTION: Mutex<Option<Instant>> = Mutex::new(None);
+static BUDGET: Mutex<Option<MemoryBudget>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryBudget {
+    /// Whole-process RSS above which idle caches are dropped
+    pub total_mb: u64,
+    pub scrollback_mb: u64,
+    pub session_cache_mb: u64,
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            total_mb: 1024,
+            scrollback_mb: 32,
+            session_cache_mb: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheUsage {
+    pub name: String,
+    pub bytes: u64,
+    pub entries: usize,
+    pub limit_bytes: u64,
+    /// Evictions since launch
+    pub evictions: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryReport {
+    /// Resident set size of the app process, when it can be measured
+    pub rss_bytes: Option<u64>,
+    pub budget: MemoryBudget,
+    pub caches: Vec<CacheUsage>,
+    pub over_budget: bool,
+    /// Unix time of the last background check
+    pub last_check: Option<u64>,
+}
+
+/// The budget in effect
+pub fn budget() -> MemoryBudget {
+    BUDGET
+        .lock()
+        .ok()
+        .and_then(|b| b.clone())
+        .unwrap_or_default()
+}
+
+/// Replace the budget, as set in the app config
+pub fn set_budget(budget: MemoryBudget) -> Result<(), String> {
+    *BUDGET.lock().map_err(|e| e.to_string())? = Some(budget);
+    Ok(())
+}
+
+/// RSS of this process, from /proc/self/statm
+#[cfg(target_os = "linux")]
+fn process_rss() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: sysconf has no preconditions
+    let page_size = u64::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).ok()?;
+    Some(pages * page_size)
+}
+
+/// RSS of this process, from the kernel's task info
+#[cfg(target_os = "macos")]
+fn process_rss() -> Option<u64> {
+    let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    // SAFETY: `info` is a writable buffer of exactly `size` bytes
+    let written = unsafe {
+        libc::proc_pidinfo(
+            std::process::id() as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            &mut info as *mut libc::proc_taskinfo as *mut libc::c_void,
+            size,
+        )
+    };
+    (written == size).then_some(info.pti_resident_size)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn process_rss() -> Option<u64> {
+    None
+}
+
+/// Measure current usage without evicting anything
+pub fn report() -> MemoryReport {
+    let budget = budget();
+    let rss_bytes = process_rss();
+    let (scrollback_bytes, scrollback_entries) = crate::pty_manager::scrollback_usage();
+    let (cache_bytes, cache_entries) = crate::session_cache::memory_usage();
+
+    MemoryReport {
+        rss_bytes,
+        over_budget: rss_bytes.map(|r| r > budget.total_mb * MB).unwrap_or(false),
+        caches: vec![
+            CacheUsage {
+                name: "scrollback".to_string(),
+                bytes: scrollback_bytes,
+                entries: scrollback_entries,
+                limit_bytes: budget.scrollback_mb * MB,
+                evictions: SCROLLBACK_EVICTIONS.load(Ordering::Relaxed),
+            },
+            CacheUsage {
+                name: "session_cache".to_string(),
+                bytes: cache_bytes,
+                entries: cache_entries,
+                limit_bytes: budget.session_cache_mb * MB,
+                evictions: SESSION_CACHE_EVICTIONS.load(Ordering::Relaxed),
+            },
+        ],
+        budget,
+        last_check: LAST_CHECK.lock().ok().and_then(|c| *c),
+    }
+}
+
+/// Measure and evict caches that exceed their limits. Over the total budget,
+/// every idle scrollback buffer and the metadata cache are dropped, at most once
+/// per `OVER_BUDGET_COOLDOWN`.
+pub fn enforce() -> MemoryReport {
+    let before = report();
+    let evict_all = before.over_budget
+        && LAST_OVER_BUDGET_EVICTION.lock().is_ok_and(|mut last| {
+            if last.is_some_and(|at| at.elapsed() < OVER_BUDGET_COOLDOWN) {
+                return false;
+            }
+            *last = Some(Instant::now());
+            true
+        });
+    let limit = |name: &str| {
+        if evict_all {
+            return 0;
+        }
+        before
+            .caches
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.limit_bytes)
+            .unwrap_or(u64::MAX)
+    };
+
+    let evicted = crate::pty_manager::evict_scrollback(limit("scrollback"), SCROLLBACK_KEEP_RECENT);
+    SCROLLBACK_EVICTIONS.fetch_add(evicted as u64, Ordering::Relaxed);
+
+    let cache_bytes = before
+        .caches
+        .iter()
+        .find(|c| c.name == "session_cache")
+        .map(|c| c.bytes)
+        .unwrap_or(0);
+    if cache_bytes > 0 && cache_bytes > limit("session_cache") {
+        crate::session_cache::unload();
+        SESSION_CACHE_EVICTIONS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    if let Ok(mut last) = LAST_CHECK.lock() {
+        *last = Some(chrono::Utc::now().timestamp() as u64);
+    }
+    report()
+}
+
+/// Check the budget periodically in the background
+pub fn start_monitor() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        enforce();
+    });
+}
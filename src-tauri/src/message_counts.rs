@@ -0,0 +1,189 @@
+//! Persistent per-file message counts
+//!
+//! Listing sessions used to count messages in the first 20 lines only. Counts are
+//! kept per session file with the byte offset scanned so far; session files are
+//! append-only, so a grown file is only scanned from that offset. A file that
+//! shrank or was rewritten in place is recounted from the start. The length of
+//! message content is summed alongside for a rough token estimate.
+//! Persisted to ~/.lovstudio/lovcode/message-counts.json
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+static COUNTS: LazyLock<Mutex<Option<HashMap<String, FileCount>>>> =
+    LazyLock::new(|| Mutex::new(None));
+static DIRTY: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct FileCount {
+    /// Bytes of complete lines counted so far
+    scanned: u64,
+    mtime: u64,
+    lines: usize,
+    /// User and assistant entries
+    messages: usize,
+    /// Characters of message content; None in entries saved before it was tracked
+    #[serde(default)]
+    content_chars: Option<u64>,
+}
+
+impl FileCount {
+    fn stats(&self) -> FileStats {
+        FileStats {
+            messages: self.messages,
+            estimated_tokens: self.content_chars.unwrap_or(0).div_ceil(CHARS_PER_TOKEN),
+        }
+    }
+}
+
+/// Counts of a session file
+#[derive(Debug, Clone, Copy)]
+pub struct FileStats {
+    pub messages: usize,
+    /// Roughly content characters / 4
+    pub estimated_tokens: u64,
+}
+
+const CHARS_PER_TOKEN: u64 = 4;
+
+#[derive(Deserialize)]
+struct LineHead {
+    #[serde(rename = "type")]
+    line_type: Option<String>,
+    message: Option<MessageBody>,
+}
+
+#[derive(Deserialize)]
+struct MessageBody {
+    #[serde(default)]
+    content: serde_json::Value,
+}
+
+/// Characters of text, thinking, tool input and tool result content
+fn content_chars(content: &serde_json::Value) -> u64 {
+    use serde_json::Value;
+    match content {
+        Value::String(text) => text.chars().count() as u64,
+        Value::Array(blocks) => blocks
+            .iter()
+            .map(|block| {
+                ["text", "thinking", "content"]
+                    .iter()
+                    .filter_map(|key| block.get(*key))
+                    .map(content_chars)
+                    .sum::<u64>()
+                    + block
+                        .get("input")
+                        .map(|input| input.to_string().chars().count() as u64)
+                        .unwrap_or(0)
+            })
+            .sum(),
+        _ => 0,
+    }
+}
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("message-counts.json")
+}
+
+fn load() -> HashMap<String, FileCount> {
+    fs::read_to_string(get_store_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Count complete lines from `entry.scanned` onwards
+fn scan(path: &Path, entry: &mut FileCount) -> Option<()> {
+    let mut file = fs::File::open(path).ok()?;
+    file.seek(SeekFrom::Start(entry.scanned)).ok()?;
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).ok()?;
+        // A trailing partial line is still being written; count it next time
+        if read == 0 || !line.ends_with('\n') {
+            return Some(());
+        }
+        entry.scanned += read as u64;
+        entry.lines += 1;
+        if let Ok(parsed) = serde_json::from_str::<LineHead>(line.trim_end()) {
+            if matches!(
+                parsed.line_type.as_deref(),
+                Some("user") | Some("assistant")
+            ) {
+                entry.messages += 1;
+                if let Some(message) = &parsed.message {
+                    *entry.content_chars.get_or_insert(0) += content_chars(&message.content);
+                }
+            }
+        }
+    }
+}
+
+/// Number of user/assistant entries in a session file
+pub fn message_count(path: &Path) -> Option<usize> {
+    stats(path).map(|s| s.messages)
+}
+
+/// Message count and token estimate of a session file
+pub fn stats(path: &Path) -> Option<FileStats> {
+    let metadata = fs::metadata(path).ok()?;
+    let size = metadata.len();
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let key = path.to_string_lossy().to_string();
+
+    let mut guard = COUNTS.lock().unwrap_or_else(|e| e.into_inner());
+    let counts = guard.get_or_insert_with(load);
+    let mut entry = counts.get(&key).cloned().unwrap_or_default();
+    if entry.scanned == size && entry.mtime == mtime && entry.content_chars.is_some() {
+        return Some(entry.stats());
+    }
+    // Shrunk, rewritten without growing, or counted before content was tracked
+    if size <= entry.scanned || entry.content_chars.is_none() {
+        entry = FileCount {
+            content_chars: Some(0),
+            ..Default::default()
+        };
+    }
+    scan(path, &mut entry)?;
+    entry.mtime = mtime;
+    let stats = entry.stats();
+    counts.insert(key, entry);
+    DIRTY.store(true, Ordering::Relaxed);
+    Some(stats)
+}
+
+
+/// Write counts gathered since the last save
+pub fn persist() {
+    if !DIRTY.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    let guard = COUNTS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(counts) = guard.as_ref() else {
+        return;
+    };
+    let path = get_store_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_string(counts) {
+        let _ = fs::write(&path, content);
+    }
+}
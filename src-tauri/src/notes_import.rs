@@ -0,0 +1,285 @@
+//! Import external note collections into distill
+//!
+//! Reads an Obsidian vault (a folder of markdown) or a Notion "Markdown & CSV"
+//! export zip, and turns each page into a note with a title, tags and body. Notion
+//! file names carry a 32-char page id suffix which is stripped from titles.
+
+use std::fs;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+/// Nested zips deeper than this are ignored (Notion splits big exports one level deep)
+const MAX_ZIP_DEPTH: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Obsidian,
+    Notion,
+}
+
+impl ImportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "obsidian" => Ok(Self::Obsidian),
+            "notion" => Ok(Self::Notion),
+            other => Err(format!("Unknown import format: {}", other)),
+        }
+    }
+
+    /// Zips are Notion exports, folders are treated as Obsidian vaults
+    pub fn detect(path: &Path) -> Self {
+        let is_zip = path
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("zip"))
+            .unwrap_or(false);
+        if is_zip {
+            Self::Notion
+        } else {
+            Self::Obsidian
+        }
+    }
+}
+
+/// A note read from the source, ready to be written into distill
+#[derive(Debug, Clone)]
+pub struct ImportedNote {
+    pub title: String,
+    pub tags: Vec<String>,
+    /// Markdown without frontmatter
+    pub body: String,
+    /// Path of the note inside the source folder or zip
+    pub source: String,
+}
+
+/// Stable FNV-1a hash of a normalized title, used to skip duplicates
+pub fn title_hash(title: &str) -> u64 {
+    let normalized = title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    normalized.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Read all markdown notes from `path`
+pub fn collect(path: &Path, format: ImportFormat) -> Result<Vec<ImportedNote>, String> {
+    if !path.exists() {
+        return Err(format!("Path not found: {}", path.display()));
+    }
+
+    let mut notes = Vec::new();
+    match format {
+        ImportFormat::Obsidian if path.is_dir() => collect_dir(path, path, format, &mut notes)?,
+        ImportFormat::Notion if path.is_dir() => collect_dir(path, path, format, &mut notes)?,
+        ImportFormat::Notion => {
+            let file = fs::File::open(path).map_err(|e| e.to_string())?;
+            collect_zip(file, format, 0, &mut notes)?;
+        }
+        ImportFormat::Obsidian => {
+            return Err(format!("Not a folder: {}", path.display()));
+        }
+    }
+    Ok(notes)
+}
+
+fn collect_dir(
+    root: &Path,
+    dir: &Path,
+    format: ImportFormat,
+    notes: &mut Vec<ImportedNote>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Skips .obsidian, .trash and other tool folders
+        if name.starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_dir(root, &path, format, notes)?;
+        } else if is_markdown(&name) {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let source = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            if let Some(note) = parse_note(&source, &content, format) {
+                notes.push(note);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn collect_zip<R: Read + Seek>(
+    reader: R,
+    format: ImportFormat,
+    depth: usize,
+    notes: &mut Vec<ImportedNote>,
+) -> Result<(), String> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+
+        if name.to_lowercase().ends_with(".zip") && depth + 1 < MAX_ZIP_DEPTH {
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf).map_err(|e| e.to_string())?;
+            collect_zip(Cursor::new(buf), format, depth + 1, notes)?;
+        } else if is_markdown(&name) {
+            let mut content = String::new();
+            if entry.read_to_string(&mut content).is_err() {
+                continue;
+            }
+            if let Some(note) = parse_note(&name, &content, format) {
+                notes.push(note);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_markdown(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".markdown")
+}
+
+/// "Meeting notes 1a2b...(32 hex)" -> "Meeting notes"
+fn strip_notion_id(stem: &str) -> &str {
+    match stem.rsplit_once(' ') {
+        Some((title, id)) if id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()) => title,
+        _ => stem,
+    }
+}
+
+/// Split "a, b", "[a, b]" or "#a #b" into tags
+fn split_tags(value: &str) -> Vec<String> {
+    value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split([',', ' '])
+        .map(|t| t.trim().trim_matches(['"', '\'']).trim_start_matches('#'))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect()
+}
+
+/// Title and tags from YAML frontmatter, including block-style tag lists
+fn frontmatter_fields(frontmatter: &str) -> (Option<String>, Vec<String>) {
+    let mut title = None;
+    let mut tags = Vec::new();
+    let mut in_tags = false;
+
+    for line in frontmatter.lines() {
+        if in_tags {
+            if let Some(item) = line.trim_start().strip_prefix("- ") {
+                tags.extend(split_tags(item));
+                continue;
+            }
+            in_tags = false;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "title" => {
+                let value = value.trim().trim_matches(['"', '\'']);
+                if !value.is_empty() {
+                    title = Some(value.to_string());
+                }
+            }
+            "tags" | "tag" => {
+                if value.trim().is_empty() {
+                    in_tags = true;
+                } else {
+                    tags.extend(split_tags(value));
+                }
+            }
+            _ => {}
+        }
+    }
+    (title, tags)
+}
+
+/// Notion puts page properties as "Key: value" lines right below the title
+fn notion_properties(body: &str) -> (Option<String>, Vec<String>) {
+    let mut lines = body.lines().skip_while(|l| l.trim().is_empty());
+    let title = lines
+        .next()
+        .and_then(|l| l.strip_prefix("# "))
+        .map(|t| t.trim().to_string());
+
+    let mut tags = Vec::new();
+    for line in lines.skip_while(|l| l.trim().is_empty()) {
+        let Some((key, value)) = line.split_once(": ") else {
+            break;
+        };
+        if key.len() > 40 {
+            break;
+        }
+        if matches!(
+            key.trim().to_lowercase().as_str(),
+            "tags" | "tag" | "labels"
+        ) {
+            tags.extend(
+                value
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty()),
+            );
+        }
+    }
+    (title, tags)
+}
+
+fn parse_note(source: &str, content: &str, format: ImportFormat) -> Option<ImportedNote> {
+    let frontmatter = crate::frontmatter::fields(content);
+    let body = crate::frontmatter::body(content);
+    if body.trim().is_empty() {
+        return None;
+    }
+    let (fm_title, mut tags) = frontmatter.map(frontmatter_fields).unwrap_or_default();
+
+    let (heading, property_tags) = match format {
+        ImportFormat::Notion => notion_properties(body),
+        ImportFormat::Obsidian => (
+            body.lines()
+                .find(|l| !l.trim().is_empty())
+                .and_then(|l| l.strip_prefix("# "))
+                .map(|t| t.trim().to_string()),
+            Vec::new(),
+        ),
+    };
+    for tag in property_tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    let stem = Path::new(source)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let title = fm_title
+        .or(heading)
+        .unwrap_or_else(|| strip_notion_id(&stem).to_string());
+
+    Some(ImportedNote {
+        title,
+        tags,
+        body: body.to_string(),
+        source: source.to_string(),
+    })
+}
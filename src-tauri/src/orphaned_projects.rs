@@ -0,0 +1,150 @@
+//! Project dirs whose code is gone
+//!
+//! A project is orphaned when its path no longer exists, typically because the
+//! repo was deleted or moved. The path is the user's path mapping if set, else
+//! the `cwd` recorded in its sessions; project ids decode too lossily to be
+//! trusted here. The scan reports size and session count; cleanup trashes or
+//! archives every idle session of the selected orphans and removes their dirs
+//! once empty. A moved repo is better relocated than cleaned up, so the scan
+//! is only a starting point.
+
+use crate::listing_cache::CachedSession;
+use crate::session_cleanup::{CleanupAction, CleanupCandidate, CleanupReport};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedProject {
+    pub project_id: String,
+    /// The path that no longer exists
+    pub decoded_path: String,
+    pub session_count: usize,
+    /// Everything in the project dir, sidechains included
+    pub size_bytes: u64,
+    pub last_active: u64,
+}
+
+/// Mapped or recorded path of a project, None when no session recorded one
+fn project_path(
+    project_dir: &Path,
+    project_id: &str,
+    sessions: &[CachedSession],
+) -> Option<String> {
+    crate::path_mappings::get(project_id).or_else(|| {
+        sessions
+            .iter()
+            .find_map(|s| crate::recorded_cwd(&project_dir.join(format!("{}.jsonl", s.session_id))))
+    })
+}
+
+/// The missing path of an orphaned project
+fn is_orphaned(project_dir: &Path, project_id: &str, sessions: &[CachedSession]) -> Option<String> {
+    let path = project_path(project_dir, project_id, sessions)?;
+    (!Path::new(&path).exists()).then_some(path)
+}
+
+/// Orphaned projects, largest first
+pub fn scan() -> Vec<OrphanedProject> {
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let mut orphans: Vec<OrphanedProject> = crate::listing_cache::all_sessions()
+        .into_iter()
+        .filter_map(|(project_id, sessions)| {
+            let project_dir = projects_dir.join(&project_id);
+            let decoded_path = is_orphaned(&project_dir, &project_id, &sessions)?;
+            let contents: Vec<_> = fs::read_dir(&project_dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|e| e.path())
+                .collect();
+            Some(OrphanedProject {
+                decoded_path,
+                session_count: sessions.len(),
+                size_bytes: crate::session_cleanup::paths_size(&contents),
+                last_active: sessions.iter().map(|s| s.mtime).max().unwrap_or(0),
+                project_id,
+            })
+        })
+        .collect();
+    orphans.sort_by_key(|o| std::cmp::Reverse(o.size_bytes));
+    orphans
+}
+
+/// Preview (default) or trash/archive every session of the given orphaned projects.
+/// Projects whose path exists again, and sessions still being written, are skipped.
+pub fn cleanup(
+    project_ids: &[String],
+    action: CleanupAction,
+    dry_run: bool,
+) -> Result<CleanupReport, String> {
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let mut report = CleanupReport {
+        dry_run,
+        action,
+        sessions: Vec::new(),
+        failed: Vec::new(),
+        total_bytes: 0,
+    };
+
+    for project_id in project_ids {
+        if project_id.contains(['/', '\\']) || project_id.contains("..") {
+            return Err(format!("Invalid project id: {}", project_id));
+        }
+        let project_dir = projects_dir.join(project_id);
+        let sessions = crate::listing_cache::project_sessions(project_id);
+        let Some(decoded_path) = is_orphaned(&project_dir, project_id, &sessions) else {
+            report.failed.push(format!(
+                "{}: path exists or is unknown, not orphaned",
+                project_id
+            ));
+            continue;
+        };
+
+        for session in sessions {
+            let session_path = project_dir.join(format!("{}.jsonl", session.session_id));
+            if crate::session_cleanup::recently_modified(&session_path) {
+                report.failed.push(format!(
+                    "{}/{}: modified recently, may still be in use",
+                    project_id, session.session_id
+                ));
+                continue;
+            }
+            let size_bytes = crate::session_cleanup::paths_size(&crate::collect_session_files(
+                &project_dir,
+                &session.session_id,
+            ));
+            if !dry_run {
+                let removed = match action {
+                    CleanupAction::Trash => {
+                        crate::trash_session_files(project_id, &session.session_id).map(|_| ())
+                    }
+                    CleanupAction::Archive => {
+                        crate::archive_session_files(project_id, &session.session_id).map(|_| ())
+                    }
+                };
+                if let Err(e) = removed {
+                    report
+                        .failed
+                        .push(format!("{}/{}: {}", project_id, session.session_id, e));
+                    continue;
+                }
+            }
+            report.total_bytes += size_bytes;
+            report.sessions.push(CleanupCandidate {
+                project_id: project_id.clone(),
+                session_id: session.session_id,
+                summary: session.summary,
+                size_bytes,
+                last_modified: session.mtime,
+                reasons: vec![format!("{} no longer exists", decoded_path)],
+            });
+        }
+
+        // Only succeeds once nothing is left in it
+        if !dry_run {
+            let _ = fs::remove_dir(&project_dir);
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,86 @@
+//! User overrides for project paths
+//!
+//! Project ids encode the project path lossily (`/`, `.` and `-` all become
+//! `-`), and `decode_project_path` can only guess where hyphens belong. A
+//! mapping set here is used instead of the guess. Stored in
+//! ~/.lovstudio/lovcode/path-mappings.json as project_id → path.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Loaded once; decode runs for every project on every listing
+static MAPPINGS: LazyLock<Mutex<Option<BTreeMap<String, String>>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("path-mappings.json")
+}
+
+fn load_from_disk() -> Result<BTreeMap<String, String>, String> {
+    let path = get_store_path();
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read path mappings: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse path mappings: {}", e))
+}
+
+/// The cached mappings, read from disk on first use; a failed read is retried next time
+fn cached(
+    guard: &mut Option<BTreeMap<String, String>>,
+) -> Result<&mut BTreeMap<String, String>, String> {
+    if guard.is_none() {
+        *guard = Some(load_from_disk()?);
+    }
+    Ok(guard.get_or_insert_default())
+}
+
+pub fn load() -> Result<BTreeMap<String, String>, String> {
+    let mut guard = MAPPINGS.lock().unwrap_or_else(|e| e.into_inner());
+    cached(&mut guard).map(|mappings| mappings.clone())
+}
+
+/// Mapped path of a project, if the user set one and the store is readable
+pub fn get(project_id: &str) -> Option<String> {
+    let mut guard = MAPPINGS.lock().unwrap_or_else(|e| e.into_inner());
+    cached(&mut guard).ok()?.get(project_id).cloned()
+}
+
+/// Map a project to `path`, or drop its mapping when None
+pub fn set(project_id: &str, path: Option<&str>) -> Result<(), String> {
+    if let Some(path) = path {
+        if !Path::new(path).is_absolute() {
+            return Err(format!("Not an absolute path: {}", path));
+        }
+        if !Path::new(path).is_dir() {
+            return Err(format!("Directory not found: {}", path));
+        }
+    }
+    let mut guard = MAPPINGS.lock().unwrap_or_else(|e| e.into_inner());
+    let mappings = cached(&mut guard)?;
+    match path {
+        Some(path) => {
+            mappings.insert(
+                project_id.to_string(),
+                path.trim_end_matches('/').to_string(),
+            );
+        }
+        None => {
+            mappings.remove(project_id);
+        }
+    }
+
+    let store = get_store_path();
+    if let Some(parent) = store.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(mappings).map_err(|e| e.to_string())?;
+    fs::write(&store, content).map_err(|e| e.to_string())
+}
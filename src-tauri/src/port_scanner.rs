@@ -0,0 +1,122 @@
+//! Listening port discovery for PTY process trees
+//!
+//! Maps TCP ports in LISTEN state back to the terminal that spawned them by walking
+//! the process tree (`ps`) and matching against `lsof` output. Unix only; on other
+//! platforms no ports are reported.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+/// A listening port owned by a process inside a PTY
+#[derive(Debug, Clone, Serialize)]
+pub struct ListeningPort {
+    pub port: u16,
+    pub pid: u32,
+    pub process_name: String,
+    /// PTY whose process tree contains the listener
+    pub pty_id: String,
+    pub url: String,
+}
+
+/// Parent pid -> child pids for all processes
+fn process_children() -> HashMap<u32, Vec<u32>> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+
+    let output = match Command::new("ps").args(["-A", "-o", "pid=,ppid="]).output() {
+        Ok(o) if o.status.success() => o,
+        _ => return children,
+    };
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut parts = line.split_whitespace();
+        if let (Some(pid), Some(ppid)) = (parts.next(), parts.next()) {
+            if let (Ok(pid), Ok(ppid)) = (pid.parse::<u32>(), ppid.parse::<u32>()) {
+                children.entry(ppid).or_default().push(pid);
+            }
+        }
+    }
+
+    children
+}
+
+/// Root pid plus all of its descendants
+fn descendants(root: u32, children: &HashMap<u32, Vec<u32>>) -> HashSet<u32> {
+    let mut result = HashSet::new();
+    let mut stack = vec![root];
+    while let Some(pid) = stack.pop() {
+        if result.insert(pid) {
+            if let Some(kids) = children.get(&pid) {
+                stack.extend(kids);
+            }
+        }
+    }
+    result
+}
+
+/// All TCP listeners on the machine: (pid, process name, port)
+fn listening_sockets() -> Vec<(u32, String, u16)> {
+    let output = match Command::new("lsof")
+        .args(["-nP", "-iTCP", "-sTCP:LISTEN", "-F", "pcn"])
+        .output()
+    {
+        Ok(o) => o,
+        Err(_) => return Vec::new(),
+    };
+
+    // Field output: `p<pid>`, `c<command>`, then one `n<addr>:<port>` per socket
+    let mut sockets = Vec::new();
+    let mut pid: Option<u32> = None;
+    let mut name = String::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let (tag, value) = line.split_at(line.len().min(1));
+        match tag {
+            "p" => {
+                pid = value.parse().ok();
+                name.clear();
+            }
+            "c" => name = value.to_string(),
+            "n" => {
+                let port = value.rsplit(':').next().and_then(|p| p.parse::<u16>().ok());
+                if let (Some(pid), Some(port)) = (pid, port) {
+                    if !sockets.iter().any(|(p, _, po)| *p == pid && *po == port) {
+                        sockets.push((pid, name.clone(), port));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    sockets
+}
+
+/// Find listening ports opened by processes under the given PTY shells
+pub fn ports_for_ptys(ptys: &[(String, u32)]) -> Vec<ListeningPort> {
+    if ptys.is_empty() {
+        return Vec::new();
+    }
+
+    let children = process_children();
+    let trees: Vec<(&String, HashSet<u32>)> = ptys
+        .iter()
+        .map(|(pty_id, pid)| (pty_id, descendants(*pid, &children)))
+        .collect();
+
+    let mut ports: Vec<ListeningPort> = listening_sockets()
+        .into_iter()
+        .filter_map(|(pid, process_name, port)| {
+            let (pty_id, _) = trees.iter().find(|(_, tree)| tree.contains(&pid))?;
+            Some(ListeningPort {
+                port,
+                pid,
+                process_name,
+                pty_id: pty_id.to_string(),
+                url: format!("http://localhost:{}", port),
+            })
+        })
+        .collect();
+
+    ports.sort_by_key(|p| p.port);
+    ports
+}
@@ -0,0 +1,47 @@
+//! Project-scoped commands, agents and skills
+//!
+//! Claude Code also loads customizations from a project's own .claude dir:
+//! .claude/commands (slash commands, namespaced by subdir), .claude/agents
+//! (every .md is a subagent) and .claude/skills (<name>/SKILL.md). These are
+//! parsed the same way as the global ones under ~/.claude.
+
+use crate::{LocalAgent, LocalCommand, LocalSkill};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Serialize)]
+pub struct ProjectAssets {
+    pub project_path: String,
+    pub commands: Vec<LocalCommand>,
+    pub agents: Vec<LocalAgent>,
+    pub skills: Vec<LocalSkill>,
+}
+
+/// Commands, agents and skills under `<project_path>/.claude`, each sorted by name
+pub fn list(project_path: &str) -> Result<ProjectAssets, String> {
+    let project_dir = Path::new(project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Directory not found: {}", project_path));
+    }
+    let dot_claude = project_dir.join(".claude");
+
+    let mut commands = Vec::new();
+    let commands_dir = dot_claude.join("commands");
+    if commands_dir.is_dir() {
+        crate::collect_commands_from_dir(&commands_dir, &commands_dir, &mut commands, "active")?;
+    }
+    commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let scope = crate::command_files::CommandScope::Project {
+        project_path: project_path.to_string(),
+    };
+    let agents = crate::agent_files::list(&scope)?;
+    let skills = crate::skill_files::list(&scope)?;
+
+    Ok(ProjectAssets {
+        project_path: project_path.to_string(),
+        commands,
+        agents,
+        skills,
+    })
+}
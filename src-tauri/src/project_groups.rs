@@ -0,0 +1,194 @@
+//! Project groups for the project browser
+//!
+//! User-named categories ("work", "oss", "experiments") that the sidebar shows
+//! as collapsible sections. A project is in at most one group; projects in no
+//! group are listed after the groups. Groups keep their user-given order and
+//! only reference projects by id. Separate from workspace groups, which group
+//! workspace projects. Persisted to ~/.lovstudio/lovcode/project-groups.json
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectGroup {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Shown folded in the sidebar
+    #[serde(default)]
+    pub collapsed: bool,
+    #[serde(default)]
+    pub project_ids: Vec<String>,
+    pub created_at: u64,
+}
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("project-groups.json")
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// All groups in display order; a missing file is empty, an unreadable one an error
+pub fn load() -> Result<Vec<ProjectGroup>, String> {
+    let path = get_store_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read project groups: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse project groups: {}", e))
+}
+
+fn save(groups: &[ProjectGroup]) -> Result<(), String> {
+    let path = get_store_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(groups).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn clean_name(groups: &[ProjectGroup], name: &str, id: Option<&str>) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Group name is empty".to_string());
+    }
+    if groups
+        .iter()
+        .any(|g| g.name == name && Some(g.id.as_str()) != id)
+    {
+        return Err(format!("Group '{}' already exists", name));
+    }
+    Ok(name.to_string())
+}
+
+pub fn create(name: &str, color: Option<String>) -> Result<ProjectGroup, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut groups = load()?;
+    let group = ProjectGroup {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: clean_name(&groups, name, None)?,
+        color,
+        collapsed: false,
+        project_ids: Vec::new(),
+        created_at: now(),
+    };
+    groups.push(group.clone());
+    save(&groups)?;
+    Ok(group)
+}
+
+/// Rename, recolor or fold a group; None leaves a field unchanged
+pub fn update(
+    id: &str,
+    name: Option<String>,
+    color: Option<String>,
+    collapsed: Option<bool>,
+) -> Result<ProjectGroup, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut groups = load()?;
+    let name = name
+        .map(|name| clean_name(&groups, &name, Some(id)))
+        .transpose()?;
+    let group = groups
+        .iter_mut()
+        .find(|g| g.id == id)
+        .ok_or_else(|| format!("Group '{}' not found", id))?;
+    if let Some(name) = name {
+        group.name = name;
+    }
+    if color.is_some() {
+        group.color = color;
+    }
+    if let Some(collapsed) = collapsed {
+        group.collapsed = collapsed;
+    }
+    let updated = group.clone();
+    save(&groups)?;
+    Ok(updated)
+}
+
+/// Delete a group; its projects become ungrouped
+pub fn delete(id: &str) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut groups = load()?;
+    let before = groups.len();
+    groups.retain(|g| g.id != id);
+    if groups.len() == before {
+        return Err(format!("Group '{}' not found", id));
+    }
+    save(&groups)
+}
+
+/// Move a project into a group, or out of any group when `group_id` is None
+pub fn assign(project_id: &str, group_id: Option<&str>) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut groups = load()?;
+    if let Some(group_id) = group_id {
+        if !groups.iter().any(|g| g.id == group_id) {
+            return Err(format!("Group '{}' not found", group_id));
+        }
+    }
+    for group in &mut groups {
+        group.project_ids.retain(|id| id != project_id);
+        if Some(group.id.as_str()) == group_id {
+            group.project_ids.push(project_id.to_string());
+        }
+    }
+    save(&groups)
+}
+
+/// Put groups in the given order; groups not listed keep their relative order at the end
+pub fn reorder(ids: &[String]) -> Result<Vec<ProjectGroup>, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut groups = load()?;
+    groups.sort_by_key(|g| ids.iter().position(|id| *id == g.id).unwrap_or(usize::MAX));
+    save(&groups)?;
+    Ok(groups)
+}
+
+/// Put the new project id in the old one's group unless it is grouped already;
+/// `keep_old` keeps the old id next to it instead of replacing it
+pub fn rekey_project(
+    old_project_id: &str,
+    new_project_id: &str,
+    keep_old: bool,
+) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut groups = load()?;
+    if groups
+        .iter()
+        .any(|g| g.project_ids.iter().any(|id| id == new_project_id))
+    {
+        return Ok(());
+    }
+    let Some((group, pos)) = groups.iter_mut().find_map(|g| {
+        let pos = g.project_ids.iter().position(|id| id == old_project_id)?;
+        Some((g, pos))
+    }) else {
+        return Ok(());
+    };
+    if keep_old {
+        group
+            .project_ids
+            .insert(pos + 1, new_project_id.to_string());
+    } else {
+        group.project_ids[pos] = new_project_id.to_string();
+    }
+    save(&groups)
+}
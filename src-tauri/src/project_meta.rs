@@ -0,0 +1,126 @@
+//! User-assigned project flags
+//!
+//! Pinned projects sort first; hidden and archived ones are left out of the
+//! project list unless asked for. Keyed by project id and persisted to
+//! ~/.lovstudio/lovcode/project-meta.json
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectUserMeta {
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+impl ProjectUserMeta {
+    fn is_empty(&self) -> bool {
+        !self.pinned && !self.hidden && !self.archived
+    }
+}
+
+pub type ProjectMetaStore = HashMap<String, ProjectUserMeta>;
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("project-meta.json")
+}
+
+/// The stored metadata; a missing file is an empty store, an unreadable one an error
+pub fn load() -> Result<ProjectMetaStore, String> {
+    let path = get_store_path();
+    if !path.exists() {
+        return Ok(ProjectMetaStore::default());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read project meta: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse project meta: {}", e))
+}
+
+fn save(store: &ProjectMetaStore) -> Result<(), String> {
+    let path = get_store_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Apply `change` to one project's entry, dropping entries that end up empty
+fn update(
+    project_id: &str,
+    change: impl FnOnce(&mut ProjectUserMeta),
+) -> Result<ProjectUserMeta, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load()?;
+
+    let mut meta = store.remove(project_id).unwrap_or_default();
+    change(&mut meta);
+    meta.updated_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if !meta.is_empty() {
+        store.insert(project_id.to_string(), meta.clone());
+    }
+
+    save(&store)?;
+    Ok(meta)
+}
+
+pub fn set_pinned(project_id: &str, pinned: bool) -> Result<ProjectUserMeta, String> {
+    update(project_id, |meta| meta.pinned = pinned)
+}
+
+pub fn set_hidden(project_id: &str, hidden: bool) -> Result<ProjectUserMeta, String> {
+    update(project_id, |meta| meta.hidden = hidden)
+}
+
+/// Archiving also unpins, so an archived project doesn't stay on top
+pub fn set_archived(project_id: &str, archived: bool) -> Result<ProjectUserMeta, String> {
+    update(project_id, |meta| {
+        meta.archived = archived;
+        if archived {
+            meta.pinned = false;
+        }
+    })
+}
+
+/// Carry a project's flags over to another project id unless it has its own;
+/// `keep_old` leaves them on the old id too
+pub fn rekey_project(
+    old_project_id: &str,
+    new_project_id: &str,
+    keep_old: bool,
+) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load()?;
+    if store.contains_key(new_project_id) {
+        return Ok(());
+    }
+    let meta = if keep_old {
+        store.get(old_project_id).cloned()
+    } else {
+        store.remove(old_project_id)
+    };
+    let Some(meta) = meta else {
+        return Ok(());
+    };
+    store.insert(new_project_id.to_string(), meta);
+    save(&store)
+}
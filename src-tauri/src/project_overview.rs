@@ -0,0 +1,103 @@
+//! Project landing page stats
+//!
+//! Everything the project page shows in one call: listing data from the
+//! listing cache, token usage, commands and tools from the session metadata
+//! cache, and whether the project has a CLAUDE.md. Sessions not yet analyzed
+//! by the session cache are counted but contribute no usage, commands or tools.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Entries in each top-N list
+const TOP_N: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NamedCount {
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectOverview {
+    pub project_id: String,
+    pub project_path: String,
+    pub session_count: usize,
+    /// Sessions covered by usage, commands and tools
+    pub analyzed_sessions: usize,
+    pub total_messages: usize,
+    pub estimated_tokens: u64,
+    pub usage: crate::usage::SessionUsage,
+    /// By number of sessions invoking the command
+    pub top_commands: Vec<NamedCount>,
+    /// By number of calls
+    pub top_tools: Vec<NamedCount>,
+    pub last_active: u64,
+    /// CLAUDE.md in the project root or .claude/, if any
+    pub claude_md: Option<String>,
+}
+
+fn top(counts: HashMap<String, usize>) -> Vec<NamedCount> {
+    let mut items: Vec<NamedCount> = counts
+        .into_iter()
+        .map(|(name, count)| NamedCount { name, count })
+        .collect();
+    items.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    items.truncate(TOP_N);
+    items
+}
+
+fn find_claude_md(project_path: &str) -> Option<String> {
+    let root = Path::new(project_path);
+    [
+        root.join("CLAUDE.md"),
+        root.join(".claude").join("CLAUDE.md"),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+    .map(|p| p.to_string_lossy().to_string())
+}
+
+pub fn overview(project_id: &str) -> Result<ProjectOverview, String> {
+    let project_dir = crate::get_claude_dir().join("projects").join(project_id);
+    if !project_dir.is_dir() {
+        return Err("Project not found".to_string());
+    }
+    let project_path = crate::decode_project_path(project_id);
+    let sessions = crate::listing_cache::project_sessions(project_id);
+
+    let mut analyzed_sessions = 0;
+    let mut by_model: HashMap<String, crate::usage::TokenUsage> = HashMap::new();
+    let mut commands: HashMap<String, usize> = HashMap::new();
+    let mut tools: HashMap<String, usize> = HashMap::new();
+    for session in &sessions {
+        let path = project_dir.join(format!("{}.jsonl", session.session_id));
+        let Some(meta) = crate::session_cache::get(&path) else {
+            continue;
+        };
+        analyzed_sessions += 1;
+        for (model, usage) in &meta.usage {
+            by_model.entry(model.clone()).or_default().add(usage);
+        }
+        for command in meta.commands {
+            *commands.entry(command).or_insert(0) += 1;
+        }
+        for (tool, calls) in meta.tools {
+            *tools.entry(tool).or_insert(0) += calls;
+        }
+    }
+
+    Ok(ProjectOverview {
+        project_id: project_id.to_string(),
+        claude_md: find_claude_md(&project_path),
+        project_path,
+        session_count: sessions.len(),
+        analyzed_sessions,
+        total_messages: sessions.iter().map(|s| s.message_count).sum(),
+        estimated_tokens: sessions.iter().map(|s| s.estimated_tokens).sum(),
+        usage: crate::usage::summarize(&by_model, &crate::usage::load_prices()),
+        top_commands: top(commands),
+        top_tools: top(tools),
+        last_active: sessions.iter().map(|s| s.mtime).max().unwrap_or(0),
+    })
+}
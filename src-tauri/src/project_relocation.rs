@@ -0,0 +1,134 @@
+//! Moving a project's history after its folder moved
+//!
+//! Claude Code keys history by the encoded project path, so a moved or renamed
+//! repo starts over under a new project id and the old dir is orphaned.
+//! Relocating moves the old dir's sessions into the new project's dir (merging
+//! with history already there when asked), carries over user data keyed by
+//! project id (titles, tags, flags, groups, collections, annotations), updates
+//! the search indexes and records the new path as a path mapping.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelocationResult {
+    pub old_project_id: String,
+    pub new_project_id: String,
+    pub new_path: String,
+    /// Files and dirs moved into the new project dir
+    pub moved: usize,
+    /// Names that already existed in the new project dir and were left in the old one
+    pub conflicts: Vec<String>,
+    /// The new project dir already had history
+    pub merged: bool,
+}
+
+/// Move already renamed entries back after `name` failed to move, describing both
+fn roll_back(renamed: &[(PathBuf, PathBuf)], name: &str, error: &str) -> String {
+    let stuck: Vec<String> = renamed
+        .iter()
+        .rev()
+        .filter(|(source, target)| fs::rename(target, source).is_err())
+        .map(|(_, target)| target.display().to_string())
+        .collect();
+    if stuck.is_empty() {
+        format!("Failed to move {}: {}; nothing was relocated", name, error)
+    } else {
+        format!(
+            "Failed to move {}: {}; could not move back {}",
+            name,
+            error,
+            stuck.join(", ")
+        )
+    }
+}
+
+/// Move `project_id`'s history to the project for `new_path`. Fails when that
+/// project already has history unless `merge` is set. A move that fails partway
+/// is rolled back; user data follows only the sessions that moved.
+pub fn relocate(project_id: &str, new_path: &str, merge: bool) -> Result<RelocationResult, String> {
+    let new_path = new_path.trim_end_matches('/');
+    if !Path::new(new_path).is_absolute() || !Path::new(new_path).is_dir() {
+        return Err(format!("Directory not found: {}", new_path));
+    }
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let old_dir = projects_dir.join(project_id);
+    if !old_dir.is_dir() {
+        return Err("Project not found".to_string());
+    }
+
+    let new_project_id = crate::encode_project_path(new_path);
+    let mut result = RelocationResult {
+        old_project_id: project_id.to_string(),
+        new_project_id: new_project_id.clone(),
+        new_path: new_path.to_string(),
+        moved: 0,
+        conflicts: Vec::new(),
+        merged: false,
+    };
+    // Same id: the folder didn't move, the path was only misdecoded
+    if new_project_id == project_id {
+        crate::path_mappings::set(project_id, Some(new_path))?;
+        return Ok(result);
+    }
+
+    let new_dir = projects_dir.join(&new_project_id);
+    result.merged = fs::read_dir(&new_dir).is_ok_and(|mut entries| entries.next().is_some());
+    if result.merged && !merge {
+        return Err(format!(
+            "{} already has history; relocate with merge to combine them",
+            new_path
+        ));
+    }
+    fs::create_dir_all(&new_dir).map_err(|e| e.to_string())?;
+
+    let mut renamed: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(&old_dir).map_err(|e| e.to_string())?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let target = new_dir.join(&name);
+        if target.exists() {
+            result.conflicts.push(name);
+            continue;
+        }
+        if let Err(e) = fs::rename(entry.path(), &target) {
+            return Err(roll_back(&renamed, &name, &e.to_string()));
+        }
+        renamed.push((entry.path(), target));
+    }
+    result.moved = renamed.len();
+    if result.conflicts.is_empty() {
+        let _ = fs::remove_dir(&old_dir);
+    }
+
+    let moved_sessions: Vec<(String, &PathBuf)> = renamed
+        .iter()
+        .filter_map(|(_, target)| {
+            let session_id = target.file_name()?.to_str()?.strip_suffix(".jsonl")?;
+            (!session_id.starts_with("agent-")).then(|| (session_id.to_string(), target))
+        })
+        .collect();
+    let session_ids: Vec<String> = moved_sessions.iter().map(|(id, _)| id.clone()).collect();
+    // The old project lives on when some of its history stayed behind
+    let keep_old = !result.conflicts.is_empty();
+
+    crate::session_meta::rekey_sessions(project_id, &new_project_id, &session_ids)?;
+    crate::collections::rekey_sessions(project_id, &new_project_id, &session_ids)?;
+    crate::annotations::rekey_sessions(project_id, &new_project_id, &session_ids)?;
+    crate::project_meta::rekey_project(project_id, &new_project_id, keep_old)?;
+    crate::project_groups::rekey_project(project_id, &new_project_id, keep_old)?;
+    if !keep_old {
+        crate::path_mappings::set(project_id, None)?;
+    }
+    if crate::decode_project_path(&new_project_id) != new_path {
+        crate::path_mappings::set(&new_project_id, Some(new_path))?;
+    }
+
+    // Index documents carry the project id; drop the old ones and index under the new id
+    for (session_id, path) in &moved_sessions {
+        crate::update_session_in_indexes(project_id, session_id, None)?;
+        crate::update_session_in_indexes(&new_project_id, session_id, Some(path))?;
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,262 @@
+//! Token usage and cost of a project over time
+//!
+//! Usage of every session file is split by local day and model, with messages
+//! deduplicated by id as in `usage`. Per-file results are cached by size and
+//! mtime in ~/.lovstudio/lovcode/daily-usage.json, so only sessions that changed
+//! since the last call are read again.
+
+use crate::usage::{TokenUsage, UsageAccumulator};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Bump when the per-file analysis changes
+const CACHE_VERSION: u32 = 1;
+
+static CACHE: LazyLock<Mutex<Option<CacheData>>> = LazyLock::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsagePeriod {
+    /// Today only
+    Day,
+    /// The last 7 days including today
+    Week,
+    /// The last 30 days including today
+    Month,
+    /// The last 365 days including today
+    Year,
+    All,
+}
+
+impl UsagePeriod {
+    /// First local date in the period (YYYY-MM-DD), None for all time
+    fn start_date(self) -> Option<String> {
+        let days = match self {
+            UsagePeriod::Day => 0,
+            UsagePeriod::Week => 6,
+            UsagePeriod::Month => 29,
+            UsagePeriod::Year => 364,
+            UsagePeriod::All => return None,
+        };
+        let start = chrono::Local::now().date_naive() - chrono::Duration::days(days);
+        Some(start.format("%Y-%m-%d").to_string())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct FileUsage {
+    size: u64,
+    mtime: u64,
+    /// Local date → model → usage
+    days: BTreeMap<String, HashMap<String, TokenUsage>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheData {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    files: HashMap<String, FileUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyUsage {
+    pub date: String,
+    pub usage: TokenUsage,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectUsage {
+    pub project_id: String,
+    pub period: UsagePeriod,
+    /// Totals and cost by model over the period
+    pub total: crate::usage::SessionUsage,
+    /// Days with usage, oldest first
+    pub by_day: Vec<DailyUsage>,
+    /// Sessions with usage in the period
+    pub sessions: usize,
+}
+
+fn get_cache_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("daily-usage.json")
+}
+
+fn load_cache() -> CacheData {
+    let data = fs::read_to_string(get_cache_path())
+        .ok()
+        .and_then(|content| serde_json::from_str::<CacheData>(&content).ok())
+        .unwrap_or_default();
+    if data.version != CACHE_VERSION {
+        return CacheData {
+            version: CACHE_VERSION,
+            files: HashMap::new(),
+        };
+    }
+    data
+}
+
+fn save_cache(data: &CacheData) -> Result<(), String> {
+    let path = get_cache_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string(data).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let mtime = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((metadata.len(), mtime))
+}
+
+fn local_date(timestamp: &str) -> Option<String> {
+    let time = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some(
+        time.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d")
+            .to_string(),
+    )
+}
+
+/// Usage of one session file by day; a message counts on the day of its last line
+fn analyze(path: &Path, size: u64, mtime: u64) -> FileUsage {
+    let mut by_day: BTreeMap<String, UsageAccumulator> = BTreeMap::new();
+    let mut message_day: HashMap<String, String> = HashMap::new();
+    if let Ok(file) = fs::File::open(path) {
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            if parsed.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+                continue;
+            }
+            let Some(day) = parsed
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .and_then(local_date)
+            else {
+                continue;
+            };
+            // Keep each message id on one day, so it is counted once
+            let message_id = parsed
+                .get("message")
+                .and_then(|m| m.get("id"))
+                .and_then(|v| v.as_str());
+            if let Some(id) = message_id {
+                let day_before = message_day.insert(id.to_string(), day.clone());
+                if let Some(before) = day_before.filter(|d| *d != day) {
+                    if let Some(acc) = by_day.get_mut(&before) {
+                        acc.remove(id);
+                    }
+                }
+            }
+            by_day.entry(day).or_default().add_line(&parsed);
+        }
+    }
+    FileUsage {
+        size,
+        mtime,
+        days: by_day
+            .into_iter()
+            .map(|(day, acc)| (day, acc.by_model()))
+            .filter(|(_, models)| !models.is_empty())
+            .collect(),
+    }
+}
+
+/// Usage of a project's sessions over `period`
+pub fn project_usage(project_id: &str, period: UsagePeriod) -> Result<ProjectUsage, String> {
+    let project_dir = crate::get_claude_dir().join("projects").join(project_id);
+    if !project_dir.is_dir() {
+        return Err("Project not found".to_string());
+    }
+    let start = period.start_date();
+
+    let mut guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = guard.get_or_insert_with(load_cache);
+    let mut changed = false;
+
+    let mut by_model: HashMap<String, TokenUsage> = HashMap::new();
+    let mut by_day: BTreeMap<String, HashMap<String, TokenUsage>> = BTreeMap::new();
+    let mut sessions = 0;
+    for entry in fs::read_dir(&project_dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+    {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.ends_with(".jsonl") || name.starts_with("agent-") {
+            continue;
+        }
+        let path = entry.path();
+        let Some((size, mtime)) = file_stamp(&path) else {
+            continue;
+        };
+        let key = path.to_string_lossy().to_string();
+        let fresh = cache
+            .files
+            .get(&key)
+            .is_some_and(|file| file.size == size && file.mtime == mtime);
+        if !fresh {
+            changed = true;
+            cache.files.insert(key.clone(), analyze(&path, size, mtime));
+        }
+        let file = &cache.files[&key];
+
+        let mut in_period = false;
+        for (day, models) in &file.days {
+            if start.as_ref().is_some_and(|start| day < start) {
+                continue;
+            }
+            in_period = true;
+            let day_models = by_day.entry(day.clone()).or_default();
+            for (model, usage) in models {
+                by_model.entry(model.clone()).or_default().add(usage);
+                day_models.entry(model.clone()).or_default().add(usage);
+            }
+        }
+        if in_period {
+            sessions += 1;
+        }
+    }
+
+    if changed {
+        // Drop entries of deleted files while saving
+        cache.files.retain(|key, _| Path::new(key).exists());
+        save_cache(cache)?;
+    }
+    drop(guard);
+
+    let prices = crate::usage::load_prices();
+    Ok(ProjectUsage {
+        project_id: project_id.to_string(),
+        period,
+        total: crate::usage::summarize(&by_model, &prices),
+        by_day: by_day
+            .into_iter()
+            .map(|(date, models)| {
+                let summary = crate::usage::summarize(&models, &prices);
+                DailyUsage {
+                    date,
+                    usage: summary.total,
+                    cost: summary.cost,
+                }
+            })
+            .collect(),
+        sessions,
+    })
+}
@@ -124,16 +124,31 @@ static PTY_SCROLLBACK: LazyLock<Mutex<HashMap<String, VecDeque<u8>>>> =
 static PTY_SCROLLBACK_LAST_SAVE: LazyLock<Mutex<HashMap<String, Instant>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
+/// Child process id per session (for port/process inspection)
+static PTY_PIDS: LazyLock<Mutex<HashMap<String, u32>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// Sessions with pending unsaved changes
 static PTY_SCROLLBACK_DIRTY: LazyLock<Mutex<HashSet<String>>> =
     LazyLock::new(|| Mutex::new(HashSet::new()));
 
+/// Last output or replay per session (for LRU eviction)
+static PTY_SCROLLBACK_LAST_USE: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn touch_scrollback(id: &str) {
+    if let Ok(mut last_use) = PTY_SCROLLBACK_LAST_USE.lock() {
+        last_use.insert(id.to_string(), Instant::now());
+    }
+}
+
 /// Create a new PTY session with background reader thread
 pub fn create_session(
     id: String,
     cwd: String,
     shell: Option<String>,
     command: Option<String>,
+    env: HashMap<String, String>,
 ) -> Result<(), String> {
     let app_handle = APP_HANDLE
         .get()
@@ -172,12 +187,22 @@ pub fn create_session(
     cmd.env("TERM", "xterm-256color");
     // Mark as lovcode terminal (similar to ITERM_SESSION_ID for iTerm)
     cmd.env("LOVCODE_TERMINAL", "1");
+    // Per-feature overrides (PORT, DATABASE_URL, ...)
+    for (key, value) in &env {
+        cmd.env(key, value);
+    }
 
-    let _child = pair
+    let child = pair
         .slave
         .spawn_command(cmd)
         .map_err(|e| format!("Failed to spawn shell: {}", e))?;
 
+    if let Some(pid) = child.process_id() {
+        if let Ok(mut pids) = PTY_PIDS.lock() {
+            pids.insert(id.clone(), pid);
+        }
+    }
+
     // Get reader and writer
     let reader = pair
         .master
@@ -256,45 +281,47 @@ fn read_loop(
 
                 // Save to scrollback buffer and persist to disk (debounced)
                 let should_save = if let Ok(mut scrollback) = PTY_SCROLLBACK.lock() {
-                    if let Some(buf) = scrollback.get_mut(&id) {
-                        // Remove old data if buffer would exceed max
-                        let overflow = (buf.len() + n).saturating_sub(SCROLLBACK_MAX_BYTES);
-                        if overflow > 0 {
-                            buf.drain(..overflow);
-                        }
-                        buf.extend(&data);
-
-                        // Check if we should persist to disk (debounced)
-                        let now = Instant::now();
-                        let should_save = if let Ok(mut last_save) = PTY_SCROLLBACK_LAST_SAVE.lock() {
-                            if let Some(last) = last_save.get(&id) {
-                                if now.duration_since(*last) >= Duration::from_millis(SCROLLBACK_SAVE_INTERVAL_MS) {
-                                    last_save.insert(id.clone(), now);
-                                    true
-                                } else {
-                                    // Mark as dirty for later save
-                                    if let Ok(mut dirty) = PTY_SCROLLBACK_DIRTY.lock() {
-                                        dirty.insert(id.clone());
-                                    }
-                                    false
-                                }
-                            } else {
+                    // Buffers evicted under memory pressure are paged back in on new output
+                    let buf = scrollback
+                        .entry(id.clone())
+                        .or_insert_with(|| load_scrollback_from_disk(&id).unwrap_or_default());
+                    touch_scrollback(&id);
+
+                    // Remove old data if buffer would exceed max
+                    let overflow = (buf.len() + n).saturating_sub(SCROLLBACK_MAX_BYTES);
+                    if overflow > 0 {
+                        buf.drain(..overflow);
+                    }
+                    buf.extend(&data);
+
+                    // Check if we should persist to disk (debounced)
+                    let now = Instant::now();
+                    let should_save = if let Ok(mut last_save) = PTY_SCROLLBACK_LAST_SAVE.lock() {
+                        if let Some(last) = last_save.get(&id) {
+                            if now.duration_since(*last) >= Duration::from_millis(SCROLLBACK_SAVE_INTERVAL_MS) {
                                 last_save.insert(id.clone(), now);
                                 true
+                            } else {
+                                // Mark as dirty for later save
+                                if let Ok(mut dirty) = PTY_SCROLLBACK_DIRTY.lock() {
+                                    dirty.insert(id.clone());
+                                }
+                                false
                             }
                         } else {
-                            false
-                        };
+                            last_save.insert(id.clone(), now);
+                            true
+                        }
+                    } else {
+                        false
+                    };
 
-                        if should_save {
-                            // Remove from dirty set since we're saving now
-                            if let Ok(mut dirty) = PTY_SCROLLBACK_DIRTY.lock() {
-                                dirty.remove(&id);
-                            }
-                            Some(buf.clone())
-                        } else {
-                            None
+                    if should_save {
+                        // Remove from dirty set since we're saving now
+                        if let Ok(mut dirty) = PTY_SCROLLBACK_DIRTY.lock() {
+                            dirty.remove(&id);
                         }
+                        Some(buf.clone())
                     } else {
                         None
                     }
@@ -358,6 +385,17 @@ fn cleanup_session(id: &str) {
     if let Ok(mut dirty) = PTY_SCROLLBACK_DIRTY.lock() {
         dirty.remove(id);
     }
+    if let Ok(mut last_use) = PTY_SCROLLBACK_LAST_USE.lock() {
+        last_use.remove(id);
+    }
+    if let Ok(mut pids) = PTY_PIDS.lock() {
+        pids.remove(id);
+    }
+}
+
+/// Get the shell process id of a running session
+pub fn session_pid(id: &str) -> Option<u32> {
+    PTY_PIDS.lock().ok()?.get(id).copied()
 }
 
 /// Write data to a PTY session
@@ -440,6 +478,7 @@ pub fn get_scrollback(id: &str) -> Vec<u8> {
     // Try memory first
     if let Ok(scrollback) = PTY_SCROLLBACK.lock() {
         if let Some(buf) = scrollback.get(id) {
+            touch_scrollback(id);
             return buf.iter().copied().collect();
         }
     }
@@ -449,6 +488,66 @@ pub fn get_scrollback(id: &str) -> Vec<u8> {
         .unwrap_or_default()
 }
 
+/// In-memory scrollback: (allocated bytes, buffers)
+pub fn scrollback_usage() -> (u64, usize) {
+    PTY_SCROLLBACK
+        .lock()
+        .map(|scrollback| {
+            let bytes = scrollback.values().map(|buf| buf.capacity() as u64).sum();
+            (bytes, scrollback.len())
+        })
+        .unwrap_or((0, 0))
+}
+
+/// Persist and drop least recently used buffers until at most `limit_bytes` remain
+/// in memory. Buffers used within `keep_recent` are never evicted. Returns the
+/// number of buffers evicted.
+pub fn evict_scrollback(limit_bytes: u64, keep_recent: Duration) -> usize {
+    let Ok(mut scrollback) = PTY_SCROLLBACK.lock() else {
+        return 0;
+    };
+    let mut total: u64 = scrollback.values().map(|buf| buf.capacity() as u64).sum();
+    if total <= limit_bytes {
+        return 0;
+    }
+
+    let last_use = PTY_SCROLLBACK_LAST_USE
+        .lock()
+        .map(|l| l.clone())
+        .unwrap_or_default();
+    let now = Instant::now();
+    let mut candidates: Vec<(String, Option<Instant>)> = scrollback
+        .keys()
+        .map(|id| (id.clone(), last_use.get(id).copied()))
+        .filter(|(_, used)| {
+            used.map(|u| now.duration_since(u) >= keep_recent)
+                .unwrap_or(true)
+        })
+        .collect();
+    // Never-used buffers first, then oldest
+    candidates.sort_by_key(|(_, used)| *used);
+
+    let mut evicted = 0;
+    for (id, _) in candidates {
+        if total <= limit_bytes {
+            break;
+        }
+        let Some(buf) = scrollback.get(&id) else {
+            continue;
+        };
+        if save_scrollback_to_disk(&id, buf).is_err() {
+            continue;
+        }
+        total = total.saturating_sub(buf.capacity() as u64);
+        scrollback.remove(&id);
+        if let Ok(mut dirty) = PTY_SCROLLBACK_DIRTY.lock() {
+            dirty.remove(&id);
+        }
+        evicted += 1;
+    }
+    evicted
+}
+
 /// Delete scrollback from disk (called when session is permanently removed)
 pub fn purge_scrollback(id: &str) {
     delete_scrollback_from_disk(id);
@@ -0,0 +1,189 @@
+//! Secret scrubbing for shared transcripts
+//!
+//! Masks API keys, tokens and passwords in a session before it is exported.
+//! The built-in rules are the diagnostics leaked-secret pattern plus well-known
+//! token formats; user patterns from ~/.lovstudio/lovcode/redaction-patterns.json
+//! and per-export patterns are added on top. A rule masks its first capture
+//! group when it has one (keeping e.g. the key name), else the whole match.
+
+use crate::Message;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const MASK: &str = "[REDACTED]";
+
+/// (name, pattern, capture group to mask; 0 = whole match)
+const BUILTIN_RULES: &[(&str, &str, usize)] = &[
+    (
+        "private_key",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+        0,
+    ),
+    ("anthropic_key", r"sk-ant-[A-Za-z0-9_\-]{20,}", 0),
+    ("openai_key", r"sk-(?:proj-)?[A-Za-z0-9_\-]{20,}", 0),
+    (
+        "github_token",
+        r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})",
+        0,
+    ),
+    ("aws_access_key", r"\bAKIA[0-9A-Z]{16}\b", 0),
+    ("slack_token", r"\bxox[abprs]-[A-Za-z0-9\-]{10,}", 0),
+    (
+        "bearer_token",
+        r"(?i)\bbearer\s+([A-Za-z0-9\-._~+/]{20,}=*)",
+        1,
+    ),
+    (
+        "env_assignment",
+        r"(?i)\b[A-Z0-9_]*(?:API_?KEY|SECRET|TOKEN|PASSWORD)[A-Z0-9_]*\s*=\s*([^\s'\x22]{8,})",
+        1,
+    ),
+];
+
+struct Rule {
+    name: String,
+    regex: Regex,
+    group: usize,
+}
+
+/// What was masked, by rule name
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RedactionReport {
+    pub output_path: String,
+    pub redactions: usize,
+    pub by_rule: BTreeMap<String, usize>,
+}
+
+pub struct Redactor {
+    rules: Vec<Rule>,
+}
+
+fn get_patterns_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("redaction-patterns.json")
+}
+
+/// User patterns applied to every redacted export; an unreadable file is an
+/// error so nothing is exported without them
+pub fn load_patterns() -> Result<Vec<String>, String> {
+    let path = get_patterns_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read redaction patterns: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse redaction patterns: {}", e))
+}
+
+pub fn save_patterns(patterns: &[String]) -> Result<(), String> {
+    for pattern in patterns {
+        Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+    }
+    let path = get_patterns_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(patterns).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+impl Redactor {
+    /// Built-in rules, saved user patterns, then `extra`
+    pub fn new(extra: &[String]) -> Result<Self, String> {
+        let mut rules: Vec<Rule> = BUILTIN_RULES
+            .iter()
+            .filter_map(|(name, pattern, group)| {
+                Some(Rule {
+                    name: name.to_string(),
+                    regex: Regex::new(pattern).ok()?,
+                    group: *group,
+                })
+            })
+            .collect();
+        rules.push(Rule {
+            name: "secret_assignment".to_string(),
+            regex: crate::diagnostics::secret_pattern().clone(),
+            group: 2,
+        });
+        for pattern in load_patterns()?.iter().chain(extra) {
+            let regex =
+                Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+            let group = usize::from(regex.captures_len() > 1);
+            rules.push(Rule {
+                name: format!("custom: {}", pattern),
+                regex,
+                group,
+            });
+        }
+        Ok(Redactor { rules })
+    }
+
+    pub fn redact(&self, text: &str, report: &mut RedactionReport) -> String {
+        let mut text = text.to_string();
+        for rule in &self.rules {
+            let mut hits = 0;
+            let replaced = rule.regex.replace_all(&text, |caps: &Captures| {
+                let whole = caps.get(0).map_or("", |m| m.as_str());
+                let Some(secret) = caps
+                    .get(rule.group)
+                    .filter(|m| !m.as_str().starts_with(MASK))
+                else {
+                    return whole.to_string();
+                };
+                hits += 1;
+                let start = caps.get(0).map_or(0, |m| m.start());
+                format!(
+                    "{}{}{}",
+                    &whole[..secret.start() - start],
+                    MASK,
+                    &whole[secret.end() - start..]
+                )
+            });
+            if hits > 0 {
+                text = replaced.into_owned();
+                report.redactions += hits;
+                *report.by_rule.entry(rule.name.clone()).or_insert(0) += hits;
+            }
+        }
+        text
+    }
+
+    fn redact_value(&self, value: &mut serde_json::Value, report: &mut RedactionReport) {
+        match value {
+            serde_json::Value::String(s) => *s = self.redact(s, report),
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact_value(item, report);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for item in map.values_mut() {
+                    self.redact_value(item, report);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Mask message text, thinking, tool inputs and tool results
+    pub fn redact_messages(&self, messages: &mut [Message], report: &mut RedactionReport) {
+        for message in messages {
+            message.content = self.redact(&message.content, report);
+            if let Some(thinking) = &message.thinking {
+                message.thinking = Some(self.redact(thinking, report));
+            }
+            for tool in &mut message.tools {
+                self.redact_value(&mut tool.input, report);
+                if let Some(result) = &tool.result {
+                    tool.result = Some(self.redact(result, report));
+                }
+            }
+        }
+    }
+}
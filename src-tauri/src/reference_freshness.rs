@@ -0,0 +1,174 @@
+//! Fetch dates for reference docs
+//!
+//! Docs downloaded from a URL are recorded in a `_source.json` manifest inside the
+//! reference source directory. Sources that live in a git checkout fall back to the
+//! last commit touching each file. Local-only docs have no fetch date.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{LazyLock, Mutex};
+
+const MANIFEST: &str = "_source.json";
+
+/// Commit times per source dir with the HEAD they were read at
+type CommitTimes = HashMap<String, u64>;
+static COMMIT_TIMES: LazyLock<Mutex<HashMap<PathBuf, (String, CommitTimes)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocFetch {
+    pub fetched_at: u64,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SourceManifest {
+    /// Upstream the docs were fetched from (site root or repository URL)
+    #[serde(default)]
+    origin: Option<String>,
+    /// Doc name (file stem) -> fetch record
+    #[serde(default)]
+    docs: HashMap<String, DocFetch>,
+}
+
+/// Where a doc's fetch date came from
+#[derive(Debug, Clone, Serialize)]
+pub struct DocFreshness {
+    pub fetched_at: u64,
+    /// "url" | "git"
+    pub origin: String,
+    pub url: Option<String>,
+}
+
+fn load_manifest(source_dir: &Path) -> SourceManifest {
+    fs::read_to_string(source_dir.join(MANIFEST))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Record that a doc was (re)fetched now
+pub fn record_fetch(
+    source_dir: &Path,
+    doc: &str,
+    url: Option<String>,
+    origin: Option<String>,
+) -> Result<(), String> {
+    let mut manifest = load_manifest(source_dir);
+    if origin.is_some() {
+        manifest.origin = origin;
+    }
+    manifest.docs.insert(
+        doc.to_string(),
+        DocFetch {
+            fetched_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            url,
+        },
+    );
+
+    let content = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(source_dir.join(MANIFEST), content).map_err(|e| e.to_string())
+}
+
+fn git_head(source_dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(source_dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `read_git_commit_times`, reused until the checkout's HEAD moves
+fn git_commit_times(source_dir: &Path) -> CommitTimes {
+    let Some(head) = git_head(source_dir) else {
+        return HashMap::new();
+    };
+    if let Ok(cache) = COMMIT_TIMES.lock() {
+        if let Some((cached_head, times)) = cache.get(source_dir) {
+            if *cached_head == head {
+                return times.clone();
+            }
+        }
+    }
+    let times = read_git_commit_times(source_dir);
+    if let Ok(mut cache) = COMMIT_TIMES.lock() {
+        cache.insert(source_dir.to_path_buf(), (head, times.clone()));
+    }
+    times
+}
+
+/// Last commit time of every markdown file under `source_dir`, keyed by file stem
+fn read_git_commit_times(source_dir: &Path) -> CommitTimes {
+    let mut times = HashMap::new();
+    let output = match Command::new("git")
+        .arg("-C")
+        .arg(source_dir)
+        .args([
+            "log",
+            "--format=%x00%ct",
+            "--name-only",
+            "--relative",
+            "--",
+            ".",
+        ])
+        .output()
+    {
+        Ok(o) if o.status.success() => o,
+        _ => return times,
+    };
+
+    // Newest commit comes first, so the first time seen for a file wins
+    let mut current: Option<u64> = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(ts) = line.strip_prefix('\0') {
+            current = ts.trim().parse().ok();
+        } else if let (Some(ts), Some(stem)) = (current, line.strip_suffix(".md")) {
+            // Only top-level docs are listed as reference docs
+            if !stem.contains('/') {
+                times.entry(stem.to_string()).or_insert(ts);
+            }
+        }
+    }
+    times
+}
+
+/// Fetch dates for all docs of a source that have one
+pub fn source_freshness(source_dir: &Path) -> HashMap<String, DocFreshness> {
+    let manifest = load_manifest(source_dir);
+    let mut result: HashMap<String, DocFreshness> = git_commit_times(source_dir)
+        .into_iter()
+        .map(|(name, fetched_at)| {
+            (
+                name,
+                DocFreshness {
+                    fetched_at,
+                    origin: "git".to_string(),
+                    url: None,
+                },
+            )
+        })
+        .collect();
+
+    // Explicit fetch records are more precise than commit dates
+    for (name, fetch) in manifest.docs {
+        result.insert(
+            name,
+            DocFreshness {
+                fetched_at: fetch.fetched_at,
+                origin: "url".to_string(),
+                url: fetch.url.or_else(|| manifest.origin.clone()),
+            },
+        );
+    }
+    result
+}
@@ -0,0 +1,175 @@
+//! File descriptor and watcher accounting
+//!
+//! PTYs and file watchers each hold descriptors; running out shows up as cryptic
+//! "Too many open files" errors deep inside other calls. At startup the soft
+//! `RLIMIT_NOFILE` is raised towards the hard limit, watchers register here while
+//! alive, and a monitor emits "resource-limit-warning" before the limit is hit.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Warn at this share of the soft limit, re-arm once usage drops below the second
+const WARN_RATIO: f64 = 0.8;
+const REARM_RATIO: f64 = 0.7;
+/// Descriptors a new PTY needs (master, slave, reader and writer handles)
+pub const PTY_FDS: u64 = 8;
+/// Soft limit we try to raise to (macOS rejects values above OPEN_MAX)
+const TARGET_SOFT_LIMIT: u64 = 10240;
+
+static WATCHERS: LazyLock<Mutex<HashMap<&'static str, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUsage {
+    /// None where descriptors can't be counted (Windows)
+    pub open_fds: Option<u64>,
+    pub soft_limit: Option<u64>,
+    pub hard_limit: Option<u64>,
+    pub ptys: usize,
+    /// Live file watchers by kind
+    pub watchers: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceWarning {
+    pub usage: ResourceUsage,
+    pub message: String,
+}
+
+/// Decrements the watcher count when dropped
+pub struct WatcherGuard(&'static str);
+
+impl Drop for WatcherGuard {
+    fn drop(&mut self) {
+        if let Ok(mut watchers) = WATCHERS.lock() {
+            if let Some(count) = watchers.get_mut(self.0) {
+                *count = count.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// Count a live watcher of `kind` until the guard is dropped
+pub fn track_watcher(kind: &'static str) -> WatcherGuard {
+    if let Ok(mut watchers) = WATCHERS.lock() {
+        *watchers.entry(kind).or_default() += 1;
+    }
+    WatcherGuard(kind)
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // rlim_t isn't u64 on every unix
+fn nofile_limits() -> Option<(u64, u64)> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: getrlimit only writes into the struct we pass
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return None;
+    }
+    Some((limit.rlim_cur as u64, limit.rlim_max as u64))
+}
+
+#[cfg(not(unix))]
+fn nofile_limits() -> Option<(u64, u64)> {
+    None
+}
+
+/// Raise the soft descriptor limit as far as allowed; returns the new soft limit
+#[cfg(unix)]
+pub fn raise_fd_limit() -> Option<u64> {
+    let (soft, hard) = nofile_limits()?;
+    let target = TARGET_SOFT_LIMIT.min(hard);
+    if soft >= target {
+        return Some(soft);
+    }
+    let limit = libc::rlimit {
+        rlim_cur: target as libc::rlim_t,
+        rlim_max: hard as libc::rlim_t,
+    };
+    // SAFETY: setrlimit only reads the struct we pass
+    if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) } != 0 {
+        return Some(soft);
+    }
+    Some(target)
+}
+
+#[cfg(not(unix))]
+pub fn raise_fd_limit() -> Option<u64> {
+    None
+}
+
+fn open_fds() -> Option<u64> {
+    let dir = if cfg!(target_os = "linux") {
+        "/proc/self/fd"
+    } else if cfg!(unix) {
+        "/dev/fd"
+    } else {
+        return None;
+    };
+    // The directory handle itself is one of the entries
+    std::fs::read_dir(dir)
+        .ok()
+        .map(|entries| (entries.count() as u64).saturating_sub(1))
+}
+
+pub fn usage() -> ResourceUsage {
+    let limits = nofile_limits();
+    ResourceUsage {
+        open_fds: open_fds(),
+        soft_limit: limits.map(|(soft, _)| soft),
+        hard_limit: limits.map(|(_, hard)| hard),
+        ptys: crate::pty_manager::list_sessions().len(),
+        watchers: WATCHERS
+            .lock()
+            .map(|w| w.iter().map(|(k, v)| (k.to_string(), *v)).collect())
+            .unwrap_or_default(),
+    }
+}
+
+fn guidance(usage: &ResourceUsage) -> String {
+    format!(
+        "{} of {} file descriptors in use ({} terminals, {} watchers). Close unused terminals or raise the limit (e.g. `ulimit -n {}` before launching).",
+        usage.open_fds.unwrap_or(0),
+        usage.soft_limit.unwrap_or(0),
+        usage.ptys,
+        usage.watchers.values().sum::<usize>(),
+        TARGET_SOFT_LIMIT
+    )
+}
+
+/// Fail early with guidance when `needed` more descriptors would exceed the limit
+pub fn check_headroom(needed: u64) -> Result<(), String> {
+    let usage = usage();
+    if let (Some(open), Some(limit)) = (usage.open_fds, usage.soft_limit) {
+        if open + needed > limit {
+            return Err(format!("Too many open files: {}", guidance(&usage)));
+        }
+    }
+    Ok(())
+}
+
+/// Watch descriptor usage in the background
+pub fn start_monitor(app_handle: AppHandle) {
+    std::thread::spawn(move || loop {
+        let usage = usage();
+        if let (Some(open), Some(limit)) = (usage.open_fds, usage.soft_limit) {
+            let ratio = open as f64 / limit.max(1) as f64;
+            if ratio >= WARN_RATIO && !WARNED.swap(true, Ordering::Relaxed) {
+                let message = guidance(&usage);
+                let _ =
+                    app_handle.emit("resource-limit-warning", ResourceWarning { usage, message });
+            } else if ratio < REARM_RATIO {
+                WARNED.store(false, Ordering::Relaxed);
+            }
+        }
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
@@ -0,0 +1,361 @@
+//! Compressed archive of old sessions
+//!
+//! Archiving moves a session (its jsonl, subagent dir and sidechains) out of
+//! ~/.claude/projects into ~/.lovstudio/lovcode/archive/<project_id>/<session_id>/,
+//! gzipping every file and recording them in a manifest.json. Archived sessions
+//! stay listable from their manifests; messages and search decompress on demand.
+//! Restoring unpacks the files back into the project dir.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "manifest.json";
+const FILES_DIR: &str = "files";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    /// Path relative to the project dir
+    pub path: String,
+    pub original_size: u64,
+    pub compressed_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    pub project_id: String,
+    pub session_id: String,
+    pub summary: Option<String>,
+    pub message_count: usize,
+    /// Modification time of the session file when it was archived
+    pub last_modified: u64,
+    pub archived_at: u64,
+    pub files: Vec<ArchivedFile>,
+}
+
+impl ArchivedSession {
+    pub fn original_size(&self) -> u64 {
+        self.files.iter().map(|f| f.original_size).sum()
+    }
+
+    pub fn compressed_size(&self) -> u64 {
+        self.files.iter().map(|f| f.compressed_size).sum()
+    }
+}
+
+/// Sessions archived in one run and the disk space this freed
+#[derive(Debug, Default, Serialize)]
+pub struct ArchiveReport {
+    pub archived: Vec<ArchivedSession>,
+    /// "<project_id>/<session_id>: <error>" for sessions left in place
+    pub failed: Vec<String>,
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+    pub reclaimed_bytes: u64,
+}
+
+impl ArchiveReport {
+    pub fn add(&mut self, session: ArchivedSession) {
+        self.original_bytes += session.original_size();
+        self.compressed_bytes += session.compressed_size();
+        self.reclaimed_bytes = self.original_bytes.saturating_sub(self.compressed_bytes);
+        self.archived.push(session);
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ArchiveStats {
+    pub sessions: usize,
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+    pub reclaimed_bytes: u64,
+}
+
+/// A message of an archived session matching a search
+#[derive(Debug, Serialize)]
+pub struct ArchiveHit {
+    pub project_id: String,
+    pub session_id: String,
+    pub summary: Option<String>,
+    pub line_number: usize,
+    pub role: String,
+    pub snippet: String,
+}
+
+fn get_archive_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("archive")
+}
+
+fn entry_dir(project_id: &str, session_id: &str) -> Result<PathBuf, String> {
+    for id in [project_id, session_id] {
+        if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+            return Err(format!("Invalid archive id: {}", id));
+        }
+    }
+    Ok(get_archive_dir().join(project_id).join(session_id))
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn mtime_secs(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Files under `path` (itself when it is a file)
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            collect_files(&entry.path(), files);
+        }
+    } else if path.is_file() {
+        files.push(path.to_path_buf());
+    }
+}
+
+fn compress(src: &Path, dst: &Path) -> std::io::Result<u64> {
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut input = fs::File::open(src)?;
+    let mut encoder = GzEncoder::new(fs::File::create(dst)?, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    Ok(fs::metadata(dst)?.len())
+}
+
+fn decompress(src: &Path, dst: &Path) -> std::io::Result<()> {
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut decoder = GzDecoder::new(fs::File::open(src)?);
+    let mut output = fs::File::create(dst)?;
+    std::io::copy(&mut decoder, &mut output)?;
+    Ok(())
+}
+
+fn compressed_path(dir: &Path, relative: &str) -> PathBuf {
+    dir.join(FILES_DIR).join(format!("{}.gz", relative))
+}
+
+/// Compress a session's files into the archive, then remove the originals.
+/// `paths` are the session's files and dirs inside `project_dir`.
+pub fn archive(
+    project_id: &str,
+    session_id: &str,
+    summary: Option<String>,
+    message_count: usize,
+    project_dir: &Path,
+    paths: &[PathBuf],
+) -> Result<ArchivedSession, String> {
+    let dir = entry_dir(project_id, session_id)?;
+    if dir.join(MANIFEST).exists() {
+        return Err(format!("Session '{}' is already archived", session_id));
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+        collect_files(path, &mut files);
+    }
+
+    let mut session = ArchivedSession {
+        project_id: project_id.to_string(),
+        session_id: session_id.to_string(),
+        summary,
+        message_count,
+        last_modified: mtime_secs(&project_dir.join(format!("{}.jsonl", session_id))),
+        archived_at: now_secs(),
+        files: Vec::new(),
+    };
+
+    // Originals are only removed once every file is safely compressed
+    let compressed: Result<(), String> = files.iter().try_for_each(|file| {
+        let relative = file
+            .strip_prefix(project_dir)
+            .map_err(|_| format!("{} is outside the project", file.display()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let original_size = fs::metadata(file).map_err(|e| e.to_string())?.len();
+        let compressed_size =
+            compress(file, &compressed_path(&dir, &relative)).map_err(|e| e.to_string())?;
+        session.files.push(ArchivedFile {
+            path: relative,
+            original_size,
+            compressed_size,
+        });
+        Ok(())
+    });
+    if let Err(e) = compressed {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(e);
+    }
+
+    let manifest = serde_json::to_string_pretty(&session).map_err(|e| e.to_string())?;
+    fs::write(dir.join(MANIFEST), manifest).map_err(|e| e.to_string())?;
+
+    for path in paths {
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+        removed.map_err(|e| e.to_string())?;
+    }
+
+    Ok(session)
+}
+
+/// Archived sessions, most recently modified first
+pub fn list() -> Vec<ArchivedSession> {
+    let mut sessions: Vec<ArchivedSession> = fs::read_dir(get_archive_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flat_map(|project| fs::read_dir(project.path()).into_iter().flatten().flatten())
+        .filter_map(|entry| fs::read_to_string(entry.path().join(MANIFEST)).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_modified));
+    sessions
+}
+
+pub fn get(project_id: &str, session_id: &str) -> Result<ArchivedSession, String> {
+    let content = fs::read_to_string(entry_dir(project_id, session_id)?.join(MANIFEST))
+        .map_err(|_| format!("Archived session '{}' not found", session_id))?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+/// Decompressing reader over the session's main jsonl
+pub fn open_session(project_id: &str, session_id: &str) -> Result<impl BufRead, String> {
+    let dir = entry_dir(project_id, session_id)?;
+    let file = fs::File::open(compressed_path(&dir, &format!("{}.jsonl", session_id)))
+        .map_err(|_| format!("Archived session '{}' not found", session_id))?;
+    Ok(BufReader::new(GzDecoder::new(file)))
+}
+
+/// Case-insensitive search over the messages of all archived sessions
+pub fn search(query: &str, limit: usize) -> Vec<ArchiveHit> {
+    let query = query.trim().to_lowercase();
+    let mut hits = Vec::new();
+    if query.is_empty() {
+        return hits;
+    }
+
+    for session in list() {
+        let Ok(reader) = open_session(&session.project_id, &session.session_id) else {
+            continue;
+        };
+        for (idx, line) in reader.lines().enumerate() {
+            if hits.len() >= limit {
+                return hits;
+            }
+            let Ok(line) = line else { break };
+            // Cheap reject before parsing the line
+            if !line.to_lowercase().contains(&query) {
+                continue;
+            }
+            let Some(message) = crate::parse_message_line(&line, idx + 1) else {
+                continue;
+            };
+            let lower = message.content.to_lowercase();
+            let Some(pos) = lower.find(&query) else {
+                continue;
+            };
+            hits.push(ArchiveHit {
+                project_id: session.project_id.clone(),
+                session_id: session.session_id.clone(),
+                summary: session.summary.clone(),
+                line_number: idx + 1,
+                role: message.role,
+                snippet: snippet(&message.content, &lower, pos, &query),
+            });
+        }
+    }
+    hits
+}
+
+/// The match with up to 60 characters on either side; `pos` is a byte offset
+/// into the lowercased text
+fn snippet(text: &str, lower: &str, pos: usize, query: &str) -> String {
+    let start = lower[..pos].chars().count();
+    let chars: Vec<char> = text.chars().collect();
+    let end = (start + query.chars().count() + 60).min(chars.len());
+    chars[start.saturating_sub(60).min(end)..end]
+        .iter()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Unpack an archived session into `project_dir` and drop it from the archive
+pub fn restore(
+    project_id: &str,
+    session_id: &str,
+    project_dir: &Path,
+) -> Result<ArchivedSession, String> {
+    let session = get(project_id, session_id)?;
+    let dir = entry_dir(project_id, session_id)?;
+
+    if let Some(existing) = session
+        .files
+        .iter()
+        .find(|f| project_dir.join(&f.path).exists())
+    {
+        return Err(format!("Cannot restore: {} already exists", existing.path));
+    }
+
+    for (index, file) in session.files.iter().enumerate() {
+        let dest = project_dir.join(&file.path);
+        if let Err(e) = decompress(&compressed_path(&dir, &file.path), &dest) {
+            // Leave no half-restored session behind; the archive is still intact
+            for restored in session.files[..=index].iter().rev() {
+                let path = project_dir.join(&restored.path);
+                let _ = fs::remove_file(&path);
+                // Dirs created for it, e.g. a subagent dir, go too once empty
+                for parent in path.ancestors().skip(1) {
+                    if parent == project_dir || fs::remove_dir(parent).is_err() {
+                        break;
+                    }
+                }
+            }
+            return Err(format!("{}: {}", file.path, e));
+        }
+    }
+    fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    if let Some(project) = dir.parent() {
+        // Only succeeds once the project has no archived sessions left
+        let _ = fs::remove_dir(project);
+    }
+
+    Ok(session)
+}
+
+/// Totals over the whole archive
+pub fn stats() -> ArchiveStats {
+    let mut stats = ArchiveStats::default();
+    for session in list() {
+        stats.sessions += 1;
+        stats.original_bytes += session.original_size();
+        stats.compressed_bytes += session.compressed_size();
+    }
+    stats.reclaimed_bytes = stats.original_bytes.saturating_sub(stats.compressed_bytes);
+    stats
+}
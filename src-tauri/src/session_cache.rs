@@ -0,0 +1,594 @@
+//! Session metadata cache
+//!
+//! Derived per-session data (detected topics, conversation language, errors, commands,
+//! models, turns, touched files, token usage, tool calls) that is too expensive to
+//! compute on every listing. Entries are keyed by session file path and
+//! invalidated by file size + mtime; a background thread keeps them up to date.
+//! Data is persisted to ~/.lovstudio/lovcode/session-cache.json
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Bump when the analysis changes so old entries get recomputed
+const CACHE_VERSION: u32 = 4;
+
+/// How often the background thread rescans session files
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Only the first N characters of user/assistant text are classified
+const MAX_CLASSIFY_CHARS: usize = 200_000;
+
+/// Cap on touched files kept per session
+const MAX_TOUCHED_FILES: usize = 500;
+
+static COMMAND_NAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<command-name>/?([^<]+)</command-name>").unwrap());
+
+static CACHE: LazyLock<Mutex<Option<CacheData>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Cached metadata for one session file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionMeta {
+    pub size: u64,
+    pub mtime: u64,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Any tool_result with is_error or API error message
+    #[serde(default)]
+    pub has_errors: bool,
+    /// Slash commands invoked (without leading slash)
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Assistant models used
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Number of real user prompts (excludes meta and tool results)
+    #[serde(default)]
+    pub turns: usize,
+    /// File paths passed to tools (Read/Edit/Write/...)
+    #[serde(default)]
+    pub touched_files: Vec<String>,
+    /// Token usage per model, deduplicated by message id
+    #[serde(default)]
+    pub usage: HashMap<String, crate::usage::TokenUsage>,
+    /// Number of calls per tool name
+    #[serde(default)]
+    pub tools: HashMap<String, usize>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheData {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    sessions: HashMap<String, SessionMeta>,
+}
+
+fn get_cache_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("session-cache.json")
+}
+
+fn load_cache() -> CacheData {
+    let data = fs::read_to_string(get_cache_path())
+        .ok()
+        .and_then(|content| serde_json::from_str::<CacheData>(&content).ok())
+        .unwrap_or_default();
+
+    if data.version != CACHE_VERSION {
+        return CacheData {
+            version: CACHE_VERSION,
+            sessions: HashMap::new(),
+        };
+    }
+    data
+}
+
+fn save_cache(data: &CacheData) -> Result<(), String> {
+    let path = get_cache_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string(data).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn with_cache<T>(f: impl FnOnce(&mut CacheData) -> T) -> T {
+    let mut guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let data = guard.get_or_insert_with(load_cache);
+    f(data)
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Some((meta.len(), mtime))
+}
+
+/// Get cached metadata for a session file (may be stale until the next refresh)
+pub fn get(path: &Path) -> Option<SessionMeta> {
+    let key = path.to_string_lossy().to_string();
+    with_cache(|data| data.sessions.get(&key).cloned())
+}
+
+impl SessionMeta {
+    /// Rough heap size of this entry
+    fn approx_bytes(&self) -> u64 {
+        let strings = |v: &[String]| v.iter().map(|s| s.len() + 24).sum::<usize>();
+        let size = std::mem::size_of::<Self>()
+            + strings(&self.topics)
+            + strings(&self.commands)
+            + strings(&self.models)
+            + strings(&self.touched_files)
+            + self.language.as_ref().map(|l| l.len()).unwrap_or(0)
+            + self.usage.keys().map(|k| k.len() + 64).sum::<usize>()
+            + self.tools.keys().map(|k| k.len() + 32).sum::<usize>();
+        size as u64
+    }
+}
+
+/// In-memory size of the cache: (approximate bytes, entries); zero when unloaded
+pub fn memory_usage() -> (u64, usize) {
+    let guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    match guard.as_ref() {
+        Some(data) => (
+            data.sessions
+                .iter()
+                .map(|(key, meta)| key.len() as u64 + meta.approx_bytes())
+                .sum(),
+            data.sessions.len(),
+        ),
+        None => (0, 0),
+    }
+}
+
+/// Drop the in-memory cache; it is saved on every change and reloads on next use
+pub fn unload() {
+    let mut guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    *guard = None;
+}
+
+/// Re-analyze every changed session file and drop entries for deleted files.
+/// Returns the number of sessions that were (re)analyzed.
+pub fn refresh_all() -> Result<usize, String> {
+    let projects_dir = crate::get_claude_dir().join("projects");
+
+    let mut files = Vec::new();
+    for project_entry in fs::read_dir(&projects_dir).into_iter().flatten().flatten() {
+        let project_path = project_entry.path();
+        if !project_path.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&project_path).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.ends_with(".jsonl") && !name.starts_with("agent-") {
+                files.push(path);
+            }
+        }
+    }
+
+    // Work out what needs analysis without holding the lock during IO
+    let stale: Vec<(PathBuf, u64, u64)> = with_cache(|data| {
+        files
+            .iter()
+            .filter_map(|path| {
+                let (size, mtime) = file_stamp(path)?;
+                let key = path.to_string_lossy().to_string();
+                match data.sessions.get(&key) {
+                    Some(meta) if meta.size == size && meta.mtime == mtime => None,
+                    _ => Some((path.clone(), size, mtime)),
+                }
+            })
+            .collect()
+    });
+
+    let analyzed: Vec<(String, SessionMeta)> = stale
+        .iter()
+        .map(|(path, size, mtime)| {
+            (
+                path.to_string_lossy().to_string(),
+                analyze_session(path, *size, *mtime),
+            )
+        })
+        .collect();
+    let count = analyzed.len();
+
+    with_cache(|data| {
+        let existing: std::collections::HashSet<String> = files
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        let before = data.sessions.len();
+        data.sessions.retain(|key, _| existing.contains(key));
+        let removed = before - data.sessions.len();
+
+        for (key, meta) in analyzed {
+            data.sessions.insert(key, meta);
+        }
+
+        if count > 0 || removed > 0 {
+            save_cache(data)
+        } else {
+            Ok(())
+        }
+    })?;
+
+    Ok(count)
+}
+
+/// Start the background classifier that keeps the cache fresh
+pub fn start_background_refresh(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        // The first pass is the startup warm-up
+        let mut result = crate::startup::measure("session_cache_warm", refresh_all);
+        loop {
+            match result {
+                Ok(count) if count > 0 => {
+                    let _ = app_handle.emit("session-cache-updated", count);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Session cache refresh failed: {}", e),
+            }
+            std::thread::sleep(REFRESH_INTERVAL);
+            result = refresh_all();
+        }
+    });
+}
+
+// ============================================================================
+// Analysis
+// ============================================================================
+
+/// Topic keywords: ASCII terms are matched on word boundaries, CJK terms as substrings
+const TOPIC_KEYWORDS: &[(&str, &[&str])] = &[
+    (
+        "frontend",
+        &[
+            "react",
+            "vue",
+            "svelte",
+            "tsx",
+            "jsx",
+            "css",
+            "tailwind",
+            "html",
+            "dom",
+            "component",
+            "components",
+            "vite",
+            "webpack",
+            "nextjs",
+            "next.js",
+            "frontend",
+            "layout",
+            "ui",
+            "ux",
+            "usestate",
+            "useeffect",
+            "前端",
+            "组件",
+            "样式",
+            "页面",
+        ],
+    ),
+    (
+        "infra",
+        &[
+            "docker",
+            "dockerfile",
+            "kubernetes",
+            "k8s",
+            "helm",
+            "terraform",
+            "nginx",
+            "ci",
+            "deploy",
+            "deployment",
+            "aws",
+            "gcp",
+            "azure",
+            "ansible",
+            "pipeline",
+            "github actions",
+            "workflow",
+            "systemd",
+            "cron",
+            "ssh",
+            "dns",
+            "部署",
+            "运维",
+            "服务器",
+        ],
+    ),
+    (
+        "debugging",
+        &[
+            "error",
+            "errors",
+            "bug",
+            "bugs",
+            "fix",
+            "traceback",
+            "stack trace",
+            "panic",
+            "exception",
+            "crash",
+            "failing",
+            "failed",
+            "undefined",
+            "null pointer",
+            "debug",
+            "报错",
+            "错误",
+            "修复",
+            "调试",
+            "崩溃",
+        ],
+    ),
+    (
+        "writing",
+        &[
+            "blog",
+            "article",
+            "essay",
+            "draft",
+            "readme",
+            "documentation",
+            "docs",
+            "copywriting",
+            "translate",
+            "translation",
+            "proofread",
+            "outline",
+            "文章",
+            "写作",
+            "文档",
+            "翻译",
+            "润色",
+        ],
+    ),
+];
+
+/// File extensions that hint at a topic when they show up in tool inputs
+const TOPIC_EXTENSIONS: &[(&str, &[&str])] = &[
+    (
+        "frontend",
+        &[".tsx", ".jsx", ".css", ".scss", ".vue", ".svelte", ".html"],
+    ),
+    (
+        "infra",
+        &["dockerfile", ".tf", ".yml", ".yaml", ".nginx", ".service"],
+    ),
+    ("writing", &[".md", ".mdx", ".txt"]),
+];
+
+/// Minimum score for a topic to be tagged
+const TOPIC_MIN_SCORE: usize = 3;
+
+/// (topic, word-boundary patterns for ASCII terms, CJK substrings)
+type TopicPattern = (&'static str, Vec<Regex>, Vec<&'static str>);
+
+static TOPIC_PATTERNS: LazyLock<Vec<TopicPattern>> = LazyLock::new(|| {
+    TOPIC_KEYWORDS
+        .iter()
+        .map(|(topic, words)| {
+            let (ascii, cjk): (Vec<&str>, Vec<&str>) = words.iter().partition(|w| w.is_ascii());
+            let patterns = ascii
+                .iter()
+                .filter_map(|w| Regex::new(&format!(r"\b{}\b", regex::escape(w))).ok())
+                .collect();
+            (*topic, patterns, cjk)
+        })
+        .collect()
+});
+
+/// Read a session file and derive its metadata
+fn analyze_session(path: &Path, size: u64, mtime: u64) -> SessionMeta {
+    let mut user_text = String::new();
+    let mut assistant_text = String::new();
+    let mut meta = SessionMeta {
+        size,
+        mtime,
+        ..Default::default()
+    };
+    let mut usage = crate::usage::UsageAccumulator::default();
+
+    if let Ok(file) = fs::File::open(path) {
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let parsed: Value = match serde_json::from_str(&line) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            let line_type = parsed.get("type").and_then(|v| v.as_str());
+            if line_type != Some("user") && line_type != Some("assistant") {
+                continue;
+            }
+            if parsed.get("isApiErrorMessage").and_then(|v| v.as_bool()) == Some(true) {
+                meta.has_errors = true;
+            }
+
+            let message = parsed.get("message");
+            let content = message.and_then(|m| m.get("content"));
+
+            // Slash command invocations are recorded as <command-name> tags
+            if let Some(Value::String(s)) = content {
+                for cap in COMMAND_NAME_RE.captures_iter(s) {
+                    let name = cap[1].trim().to_string();
+                    if !meta.commands.contains(&name) {
+                        meta.commands.push(name);
+                    }
+                }
+            }
+
+            if line_type == Some("assistant") {
+                usage.add_line(&parsed);
+                if let Some(model) = message
+                    .and_then(|m| m.get("model"))
+                    .and_then(|v| v.as_str())
+                {
+                    if !model.starts_with('<') && !meta.models.iter().any(|m| m == model) {
+                        meta.models.push(model.to_string());
+                    }
+                }
+            }
+
+            if parsed.get("isMeta").and_then(|v| v.as_bool()) == Some(true) {
+                continue;
+            }
+            let target = if line_type == Some("user") {
+                &mut user_text
+            } else {
+                &mut assistant_text
+            };
+
+            match content {
+                Some(Value::String(s)) => {
+                    push_limited(target, s);
+                    if line_type == Some("user") {
+                        meta.turns += 1;
+                    }
+                }
+                Some(Value::Array(items)) => {
+                    let mut has_text = false;
+                    for item in items {
+                        match item.get("type").and_then(|v| v.as_str()) {
+                            Some("text") => {
+                                if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                                    push_limited(target, text);
+                                    has_text = true;
+                                }
+                            }
+                            Some("tool_use") => {
+                                if let Some(name) = item.get("name").and_then(|v| v.as_str()) {
+                                    *meta.tools.entry(name.to_string()).or_insert(0) += 1;
+                                }
+                                let input = item.get("input");
+                                for key in ["file_path", "path", "notebook_path"] {
+                                    if let Some(p) =
+                                        input.and_then(|i| i.get(key)).and_then(|v| v.as_str())
+                                    {
+                                        if meta.touched_files.len() < MAX_TOUCHED_FILES
+                                            && !meta.touched_files.iter().any(|f| f == p)
+                                        {
+                                            meta.touched_files.push(p.to_string());
+                                        }
+                                    }
+                                }
+                            }
+                            Some("tool_result")
+                                if item.get("is_error").and_then(|v| v.as_bool()) == Some(true) =>
+                            {
+                                meta.has_errors = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                    if has_text && line_type == Some("user") {
+                        meta.turns += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let touched_lower: Vec<String> = meta
+        .touched_files
+        .iter()
+        .map(|f| f.to_lowercase())
+        .collect();
+    meta.topics = classify_topics(&user_text, &assistant_text, &touched_lower);
+    meta.language = detect_language(&user_text);
+    meta.usage = usage.by_model();
+    meta
+}
+
+fn push_limited(target: &mut String, text: &str) {
+    if target.len() >= MAX_CLASSIFY_CHARS {
+        return;
+    }
+    target.push_str(text);
+    target.push('\n');
+}
+
+/// Keyword/heuristic topic classifier; user text counts double
+fn classify_topics(user_text: &str, assistant_text: &str, touched_files: &[String]) -> Vec<String> {
+    let user_lower = user_text.to_lowercase();
+    let assistant_lower = assistant_text.to_lowercase();
+
+    let count_in = |text: &str, patterns: &[Regex], cjk: &[&str]| -> usize {
+        patterns
+            .iter()
+            .map(|re| re.find_iter(text).count())
+            .sum::<usize>()
+            + cjk.iter().map(|w| text.matches(w).count()).sum::<usize>()
+    };
+
+    let mut scores: Vec<(&str, usize)> = TOPIC_PATTERNS
+        .iter()
+        .map(|(topic, patterns, cjk)| {
+            let mut score = count_in(&user_lower, patterns, cjk) * 2
+                + count_in(&assistant_lower, patterns, cjk);
+            if let Some((_, exts)) = TOPIC_EXTENSIONS.iter().find(|(t, _)| t == topic) {
+                score += touched_files
+                    .iter()
+                    .filter(|f| exts.iter().any(|ext| f.ends_with(ext)))
+                    .count()
+                    * 2;
+            }
+            (*topic, score)
+        })
+        .collect();
+
+    scores.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    let top = scores.first().map(|(_, s)| *s).unwrap_or(0);
+
+    // Keep topics that are both significant and comparable to the strongest one
+    scores
+        .into_iter()
+        .filter(|(_, score)| *score >= TOPIC_MIN_SCORE && *score * 3 >= top)
+        .map(|(topic, _)| topic.to_string())
+        .collect()
+}
+
+/// Detect the conversation language from user text ("zh" / "en")
+fn detect_language(text: &str) -> Option<String> {
+    let mut cjk = 0usize;
+    let mut latin = 0usize;
+    for c in text.chars() {
+        if ('\u{4e00}'..='\u{9fff}').contains(&c) {
+            cjk += 1;
+        } else if c.is_ascii_alphabetic() {
+            latin += 1;
+        }
+    }
+
+    if cjk == 0 && latin == 0 {
+        return None;
+    }
+    // A CJK character carries roughly as much meaning as a short English word
+    if cjk * 4 >= latin {
+        Some("zh".to_string())
+    } else {
+        Some("en".to_string())
+    }
+}
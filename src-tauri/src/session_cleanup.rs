@@ -0,0 +1,247 @@
+//! Bulk cleanup of stale sessions
+//!
+//! A policy selects sessions by age, size and whether they hold any meaningful
+//! message; every criterion that is set must match. Cleanup runs as a preview
+//! unless `dry_run` is turned off, then trashes (restorable) or archives the
+//! matched sessions and reports what was removed.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Sessions written to this recently may still be in use and are never touched
+pub const MIN_IDLE_SECS: u64 = 10 * 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupAction {
+    /// Move to the trash
+    #[default]
+    Trash,
+    /// Gzip into the session archive
+    Archive,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CleanupPolicy {
+    /// Limit to one project
+    pub project_id: Option<String>,
+    /// Not modified for this many days
+    pub older_than_days: Option<u64>,
+    /// No user or assistant message with text (only meta, tool traffic or nothing)
+    #[serde(default)]
+    pub empty_only: bool,
+    /// Session files (with sidechains) larger than this
+    pub min_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub action: CleanupAction,
+    /// Only report what would be removed; default on
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupCandidate {
+    pub project_id: String,
+    pub session_id: String,
+    pub summary: Option<String>,
+    pub size_bytes: u64,
+    pub last_modified: u64,
+    /// Criteria the session matched, e.g. "older than 90 days"
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    pub action: CleanupAction,
+    /// Sessions matched (preview) or removed
+    pub sessions: Vec<CleanupCandidate>,
+    /// "<project_id>/<session_id>: <error>" for sessions left in place
+    pub failed: Vec<String>,
+    pub total_bytes: u64,
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn paths_size(paths: &[PathBuf]) -> u64 {
+    paths
+        .iter()
+        .map(|path| {
+            if path.is_dir() {
+                let children: Vec<PathBuf> = fs::read_dir(path)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|e| e.path())
+                    .collect();
+                paths_size(&children)
+            } else {
+                fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+/// Whether any non-meta message has text, stopping at the first one. Lines are
+/// decoded lossily; a file that can't be read counts as meaningful.
+fn has_meaningful_message(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return true;
+    };
+    for (idx, line) in BufReader::new(file).split(b'\n').enumerate() {
+        let Ok(line) = line else {
+            return true;
+        };
+        let line = String::from_utf8_lossy(&line);
+        if crate::parse_message_line(&line, idx + 1)
+            .is_some_and(|m| !m.is_meta && !m.content.trim().is_empty())
+        {
+            return true;
+        }
+    }
+    false
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    Some(
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs(),
+    )
+}
+
+/// Whether the file was written within `MIN_IDLE_SECS`, or can't be checked
+pub fn recently_modified(path: &Path) -> bool {
+    modified_secs(path).is_none_or(|m| now_secs().saturating_sub(m) < MIN_IDLE_SECS)
+}
+
+fn match_session(
+    policy: &CleanupPolicy,
+    project_dir: &Path,
+    project_id: &str,
+    session_id: &str,
+) -> Option<CleanupCandidate> {
+    let path = project_dir.join(format!("{}.jsonl", session_id));
+    let last_modified = modified_secs(&path)?;
+    let idle = now_secs().saturating_sub(last_modified);
+    if idle < MIN_IDLE_SECS {
+        return None;
+    }
+
+    let mut reasons = Vec::new();
+    if let Some(days) = policy.older_than_days {
+        if idle < days * 24 * 60 * 60 {
+            return None;
+        }
+        reasons.push(format!("older than {} days", days));
+    }
+    let size_bytes = paths_size(&crate::collect_session_files(project_dir, session_id));
+    if let Some(min) = policy.min_size_bytes {
+        if size_bytes <= min {
+            return None;
+        }
+        reasons.push(format!("larger than {} bytes", min));
+    }
+    // Last: the only criterion that reads the file
+    if policy.empty_only {
+        if has_meaningful_message(&path) {
+            return None;
+        }
+        reasons.push("no meaningful messages".to_string());
+    }
+
+    let (summary, _) = crate::read_session_head(&path, 20);
+    Some(CleanupCandidate {
+        project_id: project_id.to_string(),
+        session_id: session_id.to_string(),
+        summary,
+        size_bytes,
+        last_modified,
+        reasons,
+    })
+}
+
+/// Sessions matching every criterion set in the policy, largest first
+pub fn find_candidates(policy: &CleanupPolicy) -> Result<Vec<CleanupCandidate>, String> {
+    if policy.older_than_days.is_none() && !policy.empty_only && policy.min_size_bytes.is_none() {
+        return Err("Cleanup policy has no criteria".to_string());
+    }
+
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let projects: Vec<(String, PathBuf)> = match &policy.project_id {
+        Some(id) if id.contains(['/', '\\']) || id.contains("..") => {
+            return Err(format!("Invalid project id: {}", id));
+        }
+        Some(id) => vec![(id.clone(), projects_dir.join(id))],
+        None => fs::read_dir(&projects_dir)
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+            .collect(),
+    };
+
+    let mut candidates = Vec::new();
+    for (project_id, project_dir) in projects {
+        for entry in fs::read_dir(&project_dir).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(session_id) = name.strip_suffix(".jsonl") else {
+                continue;
+            };
+            if session_id.starts_with("agent-") {
+                continue;
+            }
+            if let Some(candidate) = match_session(policy, &project_dir, &project_id, session_id) {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.size_bytes));
+    Ok(candidates)
+}
+
+/// Preview or apply a cleanup policy
+pub fn run(policy: &CleanupPolicy) -> Result<CleanupReport, String> {
+    let dry_run = policy.dry_run.unwrap_or(true);
+    let mut report = CleanupReport {
+        dry_run,
+        action: policy.action,
+        sessions: Vec::new(),
+        failed: Vec::new(),
+        total_bytes: 0,
+    };
+
+    for candidate in find_candidates(policy)? {
+        if !dry_run {
+            let removed = match policy.action {
+                CleanupAction::Trash => {
+                    crate::trash_session_files(&candidate.project_id, &candidate.session_id)
+                        .map(|_| ())
+                }
+                CleanupAction::Archive => {
+                    crate::archive_session_files(&candidate.project_id, &candidate.session_id)
+                        .map(|_| ())
+                }
+            };
+            if let Err(e) = removed {
+                report.failed.push(format!(
+                    "{}/{}: {}",
+                    candidate.project_id, candidate.session_id, e
+                ));
+                continue;
+            }
+        }
+        report.total_bytes += candidate.size_bytes;
+        report.sessions.push(candidate);
+    }
+    Ok(report)
+}
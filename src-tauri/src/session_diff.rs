@@ -0,0 +1,208 @@
+//! Diff two sessions, or two ranges of one session, turn by turn
+//!
+//! A turn is a user prompt and the assistant output that follows it. Turns are
+//! aligned by their normalized prompt text, so a retry of the same task lines up
+//! even when some prompts were added or dropped; aligned turns get a line diff of
+//! the assistant text and the tools each side used.
+
+use crate::doc_diff::{self, DiffLine};
+use serde::{Deserialize, Serialize};
+
+/// Which part of which session to compare; `start`/`end` are message indices
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionRange {
+    pub project_id: String,
+    pub session_id: String,
+    pub start: Option<usize>,
+    /// Exclusive
+    pub end: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SideSummary {
+    pub session_id: String,
+    /// Models that answered anywhere in the session
+    pub models: Vec<String>,
+    pub turns: usize,
+    pub usage: crate::usage::SessionUsage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TurnDiff {
+    /// "identical" | "changed" | "only_a" | "only_b"
+    pub status: &'static str,
+    pub prompt: String,
+    /// Message index of the prompt on each side
+    pub index_a: Option<usize>,
+    pub index_b: Option<usize>,
+    /// Assistant output diff; the whole output for one-sided turns
+    pub lines: Vec<DiffLine>,
+    pub tools_a: Vec<String>,
+    pub tools_b: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TurnStats {
+    pub identical: usize,
+    pub changed: usize,
+    pub only_a: usize,
+    pub only_b: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDiff {
+    pub a: SideSummary,
+    pub b: SideSummary,
+    pub turns: Vec<TurnDiff>,
+    pub stats: TurnStats,
+}
+
+struct Turn {
+    index: usize,
+    prompt: String,
+    key: String,
+    output: String,
+    tools: Vec<String>,
+}
+
+fn normalize(prompt: &str) -> String {
+    prompt
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Group messages into turns; `offset` is the index of the first message
+fn turns(messages: &[crate::Message], offset: usize) -> Vec<Turn> {
+    let mut turns: Vec<Turn> = Vec::new();
+    for (i, message) in messages.iter().enumerate() {
+        if message.role == "user" && !message.is_meta && !message.is_tool {
+            turns.push(Turn {
+                index: offset + i,
+                prompt: message.content.trim().to_string(),
+                key: normalize(&message.content),
+                output: String::new(),
+                tools: Vec::new(),
+            });
+            continue;
+        }
+        // Output before the first prompt has nothing to align against
+        let Some(turn) = turns.last_mut() else {
+            continue;
+        };
+        if message.role != "assistant" {
+            continue;
+        }
+        if !message.content.trim().is_empty() {
+            if !turn.output.is_empty() {
+                turn.output.push_str("\n\n");
+            }
+            turn.output.push_str(message.content.trim());
+        }
+        turn.tools
+            .extend(message.tools.iter().map(|t| t.name.clone()));
+    }
+    turns
+}
+
+fn one_sided(turn: &Turn, status: &'static str, op: &'static str) -> TurnDiff {
+    let tools = turn.tools.clone();
+    let (index_a, index_b, tools_a, tools_b) = if status == "only_a" {
+        (Some(turn.index), None, tools, Vec::new())
+    } else {
+        (None, Some(turn.index), Vec::new(), tools)
+    };
+    TurnDiff {
+        status,
+        prompt: turn.prompt.clone(),
+        index_a,
+        index_b,
+        lines: turn
+            .output
+            .lines()
+            .map(|text| DiffLine {
+                op,
+                text: text.to_string(),
+            })
+            .collect(),
+        tools_a,
+        tools_b,
+    }
+}
+
+fn summary(session_id: &str, usage: crate::usage::SessionUsage, turns: usize) -> SideSummary {
+    SideSummary {
+        session_id: session_id.to_string(),
+        models: usage.by_model.iter().map(|m| m.model.clone()).collect(),
+        turns,
+        usage,
+    }
+}
+
+/// Align the turns of two message lists (already cut to their ranges)
+pub fn diff(
+    a: (&SessionRange, &[crate::Message], crate::usage::SessionUsage),
+    b: (&SessionRange, &[crate::Message], crate::usage::SessionUsage),
+) -> SessionDiff {
+    let turns_a = turns(a.1, a.0.start.unwrap_or(0));
+    let turns_b = turns(b.1, b.0.start.unwrap_or(0));
+
+    // The line diff over prompt keys doubles as the turn alignment
+    let keys_a: Vec<&str> = turns_a.iter().map(|t| t.key.as_str()).collect();
+    let keys_b: Vec<&str> = turns_b.iter().map(|t| t.key.as_str()).collect();
+    let alignment = doc_diff::diff_lines(&keys_a, &keys_b);
+
+    let mut result = Vec::with_capacity(alignment.len());
+    let mut stats = TurnStats::default();
+    let (mut i, mut j) = (0, 0);
+    for step in &alignment {
+        match step.op {
+            "equal" => {
+                let (ta, tb) = (&turns_a[i], &turns_b[j]);
+                let identical = ta.output == tb.output && ta.tools == tb.tools;
+                let status = if identical {
+                    stats.identical += 1;
+                    "identical"
+                } else {
+                    stats.changed += 1;
+                    "changed"
+                };
+                let lines_a: Vec<&str> = ta.output.lines().collect();
+                let lines_b: Vec<&str> = tb.output.lines().collect();
+                result.push(TurnDiff {
+                    status,
+                    prompt: tb.prompt.clone(),
+                    index_a: Some(ta.index),
+                    index_b: Some(tb.index),
+                    lines: if identical {
+                        Vec::new()
+                    } else {
+                        doc_diff::diff_lines(&lines_a, &lines_b)
+                    },
+                    tools_a: ta.tools.clone(),
+                    tools_b: tb.tools.clone(),
+                });
+                i += 1;
+                j += 1;
+            }
+            "delete" => {
+                stats.only_a += 1;
+                result.push(one_sided(&turns_a[i], "only_a", "delete"));
+                i += 1;
+            }
+            _ => {
+                stats.only_b += 1;
+                result.push(one_sided(&turns_b[j], "only_b", "insert"));
+                j += 1;
+            }
+        }
+    }
+
+    SessionDiff {
+        a: summary(&a.0.session_id, a.2, turns_a.len()),
+        b: summary(&b.0.session_id, b.2, turns_b.len()),
+        turns: result,
+        stats,
+    }
+}
@@ -0,0 +1,541 @@
+//! Standalone session exports
+//!
+//! Produces a single self-contained HTML file (inline CSS, inline-styled syntax
+//! highlighting, tool calls folded into <details>) that can be opened anywhere.
+//! PDF export prints that HTML through a headless Chromium-family browser.
+//! Markdown and CSV renderings of a transcript share the HTML options. The
+//! shareable page adds a filter box, inline images and optional redaction.
+//! Archives bundle raw session entries of many sessions into one JSON/NDJSON file
+//! for backup and migration.
+
+use crate::markdown::{self, RenderOptions};
+use crate::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HtmlExportOptions {
+    /// Include tool calls/results (collapsed); default on
+    pub include_tools: Option<bool>,
+    /// Include slash command expansions (collapsed); default off
+    pub include_meta: Option<bool>,
+    /// Render collapsed sections expanded (PDF export defaults this on)
+    pub expand: Option<bool>,
+    /// Document title, defaults to the session summary
+    pub title: Option<String>,
+}
+
+impl HtmlExportOptions {
+    /// Whether `message` is written with these options
+    pub fn includes(&self, message: &Message) -> bool {
+        (!message.is_tool || self.include_tools.unwrap_or(true))
+            && (!message.is_meta || self.include_meta.unwrap_or(false))
+    }
+}
+
+/// Options of the shareable page: transcript options plus redaction and images
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SharePageOptions {
+    #[serde(flatten)]
+    pub transcript: HtmlExportOptions,
+    /// Mask secrets before rendering; default on
+    pub redact: Option<bool>,
+    /// Extra redaction regexes for this export
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Inline images attached to messages; default on
+    pub include_images: Option<bool>,
+}
+
+const STYLE: &str = r#"
+:root { color-scheme: light; }
+* { box-sizing: border-box; }
+body { margin: 0; background: #f9f9f7; color: #181818; font: 15px/1.6 -apple-system, BlinkMacSystemFont, "Segoe UI", "PingFang SC", sans-serif; }
+main { max-width: 860px; margin: 0 auto; padding: 40px 24px 80px; }
+header { border-bottom: 1px solid #e5e5e0; margin-bottom: 32px; padding-bottom: 16px; }
+header h1 { font-size: 22px; margin: 0 0 4px; }
+header .meta { color: #8a8a85; font-size: 13px; }
+.message { margin: 0 0 20px; padding: 14px 18px; border-radius: 10px; background: #fff; border: 1px solid #e5e5e0; }
+.message.user { background: #f3efe9; border-color: #e8ddd0; }
+.role { font-size: 12px; font-weight: 600; text-transform: uppercase; letter-spacing: .04em; color: #cc785c; margin-bottom: 6px; }
+.message.assistant .role { color: #5c7ccc; }
+.time { float: right; font-weight: normal; color: #a5a5a0; text-transform: none; }
+.content > :first-child { margin-top: 0; }
+.content > :last-child { margin-bottom: 0; }
+pre { overflow-x: auto; padding: 12px 14px; border-radius: 8px; font-size: 13px; line-height: 1.5; }
+code { font-family: "SF Mono", Menlo, Consolas, monospace; }
+:not(pre) > code { background: #efede8; padding: 1px 5px; border-radius: 4px; font-size: 0.9em; }
+table { border-collapse: collapse; } th, td { border: 1px solid #e5e5e0; padding: 4px 10px; }
+details { margin: 0 0 12px; border: 1px dashed #d5d5d0; border-radius: 8px; padding: 6px 12px; background: #fcfcfa; }
+details summary { cursor: pointer; color: #8a8a85; font-size: 13px; }
+footer { margin-top: 48px; color: #a5a5a0; font-size: 12px; text-align: center; }
+.toolbar { position: sticky; top: 0; z-index: 1; display: flex; gap: 8px; padding: 10px 0; margin-bottom: 16px; background: #f9f9f7; }
+.toolbar input { flex: 1; padding: 6px 10px; border: 1px solid #e5e5e0; border-radius: 6px; font: inherit; }
+.toolbar button { padding: 6px 12px; border: 1px solid #e5e5e0; border-radius: 6px; background: #fff; font: inherit; cursor: pointer; }
+img.attachment { display: block; max-width: 100%; max-height: 480px; margin-top: 10px; border-radius: 6px; border: 1px solid #e5e5e0; }
+.attachment-file { margin-top: 8px; color: #8a8a85; font-size: 13px; }
+@media print { body { background: #fff; } details { border-style: solid; } .message { break-inside: avoid-page; } }
+"#;
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn format_time(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Message sections of a transcript page and how many messages were shown.
+/// With `embed_images`, attached images are inlined as data URIs.
+fn render_messages(
+    messages: &[Message],
+    options: &HtmlExportOptions,
+    embed_images: bool,
+) -> (String, usize) {
+    let open = if options.expand.unwrap_or(false) {
+        " open"
+    } else {
+        ""
+    };
+    let render_options = RenderOptions::default();
+
+    let mut body = String::new();
+    let mut shown = 0;
+    for message in messages {
+        if !options.includes(message) {
+            continue;
+        }
+        shown += 1;
+
+        let mut content = markdown::render(&message.content, &render_options);
+        if embed_images {
+            content.push_str(&render_attachments(message));
+        }
+        if message.is_tool || message.is_meta {
+            let label = tool_label(message);
+            body.push_str(&format!(
+                "<details class=\"{}\"{}><summary>{} · {}</summary><div class=\"content\">{}</div></details>\n",
+                if message.is_tool { "tool" } else { "meta" },
+                open,
+                escape_html(&label),
+                escape_html(&message.role),
+                content
+            ));
+            continue;
+        }
+
+        let role_class = if message.role == "user" {
+            "user"
+        } else {
+            "assistant"
+        };
+        body.push_str(&format!(
+            "<section class=\"message {}\"><div class=\"role\">{}<span class=\"time\">{}</span></div><div class=\"content\">{}</div></section>\n",
+            role_class,
+            escape_html(&message.role),
+            format_time(&message.timestamp),
+            content
+        ));
+    }
+    (body, shown)
+}
+
+/// Attached images as data URIs, other attachments by name
+fn render_attachments(message: &Message) -> String {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    let mut html = String::new();
+    for attachment in &message.attachments {
+        let image = match (attachment.kind, &attachment.path, &attachment.media_type) {
+            (crate::attachments::AttachmentKind::Image, Some(path), Some(media_type)) => {
+                fs::read(path)
+                    .ok()
+                    .map(|bytes| format!("data:{};base64,{}", media_type, STANDARD.encode(bytes)))
+            }
+            _ => None,
+        };
+        match image {
+            Some(src) => html.push_str(&format!(
+                "<img class=\"attachment\" src=\"{}\" alt=\"{}\">",
+                src,
+                escape_html(&attachment.name)
+            )),
+            None => html.push_str(&format!(
+                "<div class=\"attachment-file\">📎 {}</div>",
+                escape_html(&attachment.name)
+            )),
+        }
+    }
+    html
+}
+
+/// Render a session transcript as a standalone HTML document
+pub fn render_html(title: &str, messages: &[Message], options: &HtmlExportOptions) -> String {
+    let title = options.title.as_deref().unwrap_or(title);
+    let (body, shown) = render_messages(messages, options, false);
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<main>\n<header><h1>{title}</h1><div class=\"meta\">{shown} messages · exported {date}</div></header>\n{body}<footer>Exported with Lovcode</footer>\n</main>\n</body>\n</html>\n",
+        title = escape_html(title),
+        shown = shown,
+        date = chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        body = body,
+    )
+}
+
+/// Filter box and expand/collapse toggle of the shareable page
+const SHARE_SCRIPT: &str = r#"
+const filter = document.getElementById('filter');
+filter.addEventListener('input', () => {
+  const q = filter.value.toLowerCase();
+  document.querySelectorAll('main > section, main > details').forEach((el) => {
+    el.hidden = q !== '' && !el.textContent.toLowerCase().includes(q);
+  });
+});
+const toggle = document.getElementById('toggle');
+toggle.addEventListener('click', () => {
+  const open = toggle.dataset.open !== '1';
+  document.querySelectorAll('details').forEach((d) => { d.open = open; });
+  toggle.dataset.open = open ? '1' : '0';
+  toggle.textContent = open ? 'Collapse all' : 'Expand all';
+});
+"#;
+
+/// Render a session as one self-contained page for bug reports and wikis: inline
+/// CSS and JS, images as data URIs, and a CSP that blocks every external request.
+/// `context` is shown under the title, e.g. the project path and session id.
+pub fn render_share_page(
+    title: &str,
+    context: &str,
+    messages: &[Message],
+    options: &SharePageOptions,
+) -> String {
+    let title = options.transcript.title.as_deref().unwrap_or(title);
+    let (body, shown) = render_messages(
+        messages,
+        &options.transcript,
+        options.include_images.unwrap_or(true),
+    );
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta http-equiv=\"Content-Security-Policy\" content=\"default-src 'none'; style-src 'unsafe-inline'; script-src 'unsafe-inline'; img-src data:\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<main>\n<header><h1>{title}</h1><div class=\"meta\">{context} · {shown} messages · exported {date}{redacted}</div></header>\n<div class=\"toolbar\"><input id=\"filter\" type=\"search\" placeholder=\"Filter messages…\"><button id=\"toggle\" type=\"button\">Expand all</button></div>\n{body}<footer>Exported with Lovcode</footer>\n</main>\n<script>{SHARE_SCRIPT}</script>\n</body>\n</html>\n",
+        title = escape_html(title),
+        context = escape_html(context),
+        shown = shown,
+        date = chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        redacted = if options.redact.unwrap_or(true) { " · secrets redacted" } else { "" },
+        body = body,
+    )
+}
+
+fn tool_label(message: &Message) -> String {
+    if !message.tools.is_empty() {
+        let names: Vec<&str> = message.tools.iter().map(|t| t.name.as_str()).collect();
+        format!("Tool · {}", names.join(", "))
+    } else if message.is_tool {
+        "Tool".to_string()
+    } else {
+        "Command".to_string()
+    }
+}
+
+/// Render a session transcript as a markdown document; uses the same options as HTML
+pub fn render_markdown(title: &str, messages: &[Message], options: &HtmlExportOptions) -> String {
+    let title = options.title.as_deref().unwrap_or(title);
+
+    let mut out = format!(
+        "# {}\n\n_Exported {}_\n",
+        title,
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    );
+    for message in messages {
+        if !options.includes(message) {
+            continue;
+        }
+        let heading = if message.is_tool || message.is_meta {
+            tool_label(message)
+        } else if message.role == "user" {
+            "User".to_string()
+        } else {
+            "Assistant".to_string()
+        };
+        out.push_str(&format!("\n## {}", heading));
+        let time = format_time(&message.timestamp);
+        if !time.is_empty() {
+            out.push_str(&format!(" · {}", time));
+        }
+        out.push_str(&format!("\n\n{}\n", message.content.trim()));
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per message: timestamp, role, kind, tools, content
+pub fn render_csv(messages: &[Message], options: &HtmlExportOptions) -> String {
+    let mut out = String::from("timestamp,role,kind,tools,content\n");
+    for message in messages {
+        if !options.includes(message) {
+            continue;
+        }
+        let kind = if message.is_meta {
+            "command"
+        } else if message.is_tool {
+            "tool"
+        } else {
+            "message"
+        };
+        let tools: Vec<&str> = message.tools.iter().map(|t| t.name.as_str()).collect();
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&message.timestamp),
+            csv_field(&message.role),
+            kind,
+            csv_field(&tools.join(";")),
+            csv_field(&message.content)
+        ));
+    }
+    out
+}
+
+/// Locate a Chromium-family browser able to print headless
+fn find_print_browser() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let candidates = [
+        "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+        "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+        "/Applications/Chromium.app/Contents/MacOS/Chromium",
+        "/Applications/Brave Browser.app/Contents/MacOS/Brave Browser",
+    ];
+    #[cfg(target_os = "windows")]
+    let candidates = [
+        r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+        r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+        r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+        r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+    ];
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let candidates = [
+        "/usr/bin/google-chrome",
+        "/usr/bin/chromium",
+        "/usr/bin/chromium-browser",
+        "/usr/bin/microsoft-edge",
+    ];
+
+    candidates.iter().map(PathBuf::from).find(|p| p.exists())
+}
+
+/// Print an HTML file to PDF
+pub fn html_to_pdf(html_path: &Path, pdf_path: &Path) -> Result<(), String> {
+    let browser = find_print_browser()
+        .ok_or_else(|| "No Chrome, Edge or Chromium found for PDF export".to_string())?;
+
+    // Only a freshly written file counts as success
+    let _ = std::fs::remove_file(pdf_path);
+
+    let output = Command::new(browser)
+        .arg("--headless")
+        .arg("--disable-gpu")
+        .arg("--no-pdf-header-footer")
+        .arg(format!("--print-to-pdf={}", pdf_path.display()))
+        .arg(format!("file://{}", html_path.display()))
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !pdf_path.exists() {
+        return Err(format!(
+            "PDF export failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Marks the file as a lovcode session archive
+const ARCHIVE_FORMAT: &str = "lovcode-sessions";
+const ARCHIVE_VERSION: u32 = 1;
+
+/// A session file to include in an archive
+pub struct ArchiveSource {
+    pub project_id: String,
+    pub session_id: String,
+    /// Parent session for agent sidechains
+    pub parent_session_id: Option<String>,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveProject {
+    pub project_id: String,
+    pub project_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveSummary {
+    pub path: String,
+    pub projects: usize,
+    pub sessions: usize,
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+#[derive(Serialize)]
+struct ArchiveHeader<'a> {
+    format: &'a str,
+    version: u32,
+    exported_at: String,
+    projects: &'a [ArchiveProject],
+}
+
+#[derive(Serialize)]
+struct ArchiveSessionHeader<'a> {
+    project_id: &'a str,
+    session_id: &'a str,
+    parent_session_id: Option<&'a str>,
+    last_modified: u64,
+}
+
+/// Raw JSONL entries of a session; invalid lines (bad UTF-8 or JSON) are skipped,
+/// a read error fails the whole session
+fn session_entries(path: &Path) -> Result<Vec<String>, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).split(b'\n') {
+        let line = line.map_err(|e| format!("{}: {}", path.display(), e))?;
+        let Ok(line) = String::from_utf8(line) else {
+            continue;
+        };
+        let line = line.trim_end_matches('\r');
+        if serde_json::from_str::<serde::de::IgnoredAny>(line).is_ok() {
+            entries.push(line.to_string());
+        }
+    }
+    Ok(entries)
+}
+
+fn file_mtime(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Write sessions into a single archive. `ndjson` writes one record per line
+/// (`archive`, then `session` followed by its `entry` records); otherwise one JSON document.
+pub fn write_archive(
+    sources: &[ArchiveSource],
+    output: &Path,
+    ndjson: bool,
+) -> Result<ArchiveSummary, String> {
+    let projects: Vec<ArchiveProject> = sources
+        .iter()
+        .map(|s| {
+            (
+                s.project_id.clone(),
+                crate::decode_project_path(&s.project_id),
+            )
+        })
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(project_id, project_path)| ArchiveProject {
+            project_id,
+            project_path,
+        })
+        .collect();
+
+    let header = ArchiveHeader {
+        format: ARCHIVE_FORMAT,
+        version: ARCHIVE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        projects: &projects,
+    };
+
+    let file = fs::File::create(output).map_err(|e| e.to_string())?;
+    let mut out = BufWriter::new(file);
+    let mut entries = 0;
+
+    let header_json = serde_json::to_string(&header).map_err(|e| e.to_string())?;
+    if ndjson {
+        // {"type":"archive",...header}
+        writeln!(out, "{{\"type\":\"archive\",{}", &header_json[1..]).map_err(|e| e.to_string())?;
+    } else {
+        write!(
+            out,
+            "{},\"sessions\":[",
+            &header_json[..header_json.len() - 1]
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    for (i, source) in sources.iter().enumerate() {
+        let session_header = serde_json::to_string(&ArchiveSessionHeader {
+            project_id: &source.project_id,
+            session_id: &source.session_id,
+            parent_session_id: source.parent_session_id.as_deref(),
+            last_modified: file_mtime(&source.path),
+        })
+        .map_err(|e| e.to_string())?;
+        let lines = session_entries(&source.path)?;
+        entries += lines.len();
+
+        if ndjson {
+            writeln!(out, "{{\"type\":\"session\",{}", &session_header[1..])
+                .map_err(|e| e.to_string())?;
+            let session_id =
+                serde_json::to_string(&source.session_id).map_err(|e| e.to_string())?;
+            for line in &lines {
+                writeln!(
+                    out,
+                    "{{\"type\":\"entry\",\"session_id\":{},\"entry\":{}}}",
+                    session_id, line
+                )
+                .map_err(|e| e.to_string())?;
+            }
+        } else {
+            if i > 0 {
+                out.write_all(b",").map_err(|e| e.to_string())?;
+            }
+            write!(
+                out,
+                "{},\"entries\":[{}]}}",
+                &session_header[..session_header.len() - 1],
+                lines.join(",")
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    if !ndjson {
+        out.write_all(b"]}").map_err(|e| e.to_string())?;
+    }
+    out.flush().map_err(|e| e.to_string())?;
+
+    Ok(ArchiveSummary {
+        path: output.to_string_lossy().to_string(),
+        projects: projects.len(),
+        sessions: sources.len(),
+        entries,
+        bytes: fs::metadata(output).map(|m| m.len()).unwrap_or(0),
+    })
+}
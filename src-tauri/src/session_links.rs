@@ -0,0 +1,228 @@
+//! Continued and forked sessions
+//!
+//! `claude --continue` / `--resume` write a new session file that starts with
+//! lines copied from the session being continued, still carrying its
+//! `sessionId`, and often the same `summary` lines. Two sessions that open with
+//! the same message (same uuid, or the same substantial first prompt) are forks
+//! of one conversation. Only the first lines of each file are read, and heads
+//! are cached by file size and mtime.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+const HEAD_LINES: usize = 50;
+/// Shorter first prompts ("hi", "continue") are too common to mean a fork
+const MIN_PROMPT_CHARS: usize = 40;
+
+/// (size, mtime) of the file a head was read from
+type Stamp = (u64, u64);
+
+static HEADS: LazyLock<Mutex<HashMap<PathBuf, (Stamp, Head)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    /// This session picks up where the other one left off
+    ContinuedFrom,
+    /// The other session picks up where this one left off
+    ContinuedIn,
+    /// Both sessions start from the same conversation
+    Fork,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedSession {
+    pub session_id: String,
+    pub relation: Relation,
+    /// "resumed", "shared summary" or "same opening"
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Head {
+    /// `sessionId` values of copied lines
+    foreign_ids: BTreeSet<String>,
+    summaries: Vec<String>,
+    first_uuid: Option<String>,
+    first_prompt: Option<String>,
+    first_timestamp: Option<String>,
+}
+
+fn file_stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    let mtime = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((metadata.len(), mtime))
+}
+
+fn read_head(path: &Path, session_id: &str) -> Head {
+    let mut head = Head::default();
+    let Ok(file) = fs::File::open(path) else {
+        return head;
+    };
+    for line in BufReader::new(file).lines().take(HEAD_LINES) {
+        let Ok(line) = line else { continue };
+        let Ok(parsed) = serde_json::from_str::<crate::RawLine>(&line) else {
+            continue;
+        };
+        if let Some(id) = parsed.session_id.as_deref().filter(|id| *id != session_id) {
+            head.foreign_ids.insert(id.to_string());
+        }
+        match parsed.line_type.as_deref() {
+            Some("summary") => head.summaries.extend(parsed.summary.clone()),
+            Some("user") | Some("assistant") => {
+                if head.first_uuid.is_none() {
+                    head.first_uuid = parsed.uuid.clone();
+                    head.first_timestamp = parsed.timestamp.clone();
+                }
+                if head.first_prompt.is_none()
+                    && parsed.line_type.as_deref() == Some("user")
+                    && !parsed.is_meta.unwrap_or(false)
+                {
+                    let (text, is_tool) = parsed
+                        .message
+                        .as_ref()
+                        .map(|m| crate::extract_content_with_meta(&m.content))
+                        .unwrap_or_default();
+                    let text = text.trim();
+                    if !is_tool && !text.is_empty() && !text.starts_with('<') {
+                        head.first_prompt = Some(text.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    head
+}
+
+/// Heads of the sessions in a project dir, by session id
+fn project_heads(project_dir: &Path) -> HashMap<String, Head> {
+    let mut cache = HEADS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut heads = HashMap::new();
+    for entry in fs::read_dir(project_dir).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(session_id) = name.strip_suffix(".jsonl") else {
+            continue;
+        };
+        if session_id.starts_with("agent-") {
+            continue;
+        }
+        let path = entry.path();
+        let Some(stamp) = file_stamp(&path) else {
+            continue;
+        };
+        let head = match cache.get(&path) {
+            Some((cached, head)) if *cached == stamp => head.clone(),
+            _ => {
+                let head = read_head(&path, session_id);
+                cache.insert(path, (stamp, head.clone()));
+                head
+            }
+        };
+        heads.insert(session_id.to_string(), head);
+    }
+    heads
+}
+
+/// Related sessions of every session in a project dir that has any
+pub fn project_links(project_dir: &Path) -> HashMap<String, Vec<RelatedSession>> {
+    let heads = project_heads(project_dir);
+    let mut links: HashMap<String, Vec<RelatedSession>> = HashMap::new();
+    let mut linked: HashSet<(String, String)> = HashSet::new();
+    let mut link = |a: &str, b: &str, relation: Relation, reason: &str| {
+        let pair = if a < b {
+            (a.to_string(), b.to_string())
+        } else {
+            (b.to_string(), a.to_string())
+        };
+        if a == b || !linked.insert(pair) {
+            return;
+        }
+        let reverse = match relation {
+            Relation::ContinuedFrom => Relation::ContinuedIn,
+            Relation::ContinuedIn => Relation::ContinuedFrom,
+            Relation::Fork => Relation::Fork,
+        };
+        for (from, to, relation) in [(a, b, relation), (b, a, reverse)] {
+            links
+                .entry(from.to_string())
+                .or_default()
+                .push(RelatedSession {
+                    session_id: to.to_string(),
+                    relation,
+                    reason: reason.to_string(),
+                });
+        }
+    };
+
+    // Sorted so results don't depend on directory order
+    let mut ids: Vec<&String> = heads.keys().collect();
+    ids.sort_by_key(|id| (heads[*id].first_timestamp.clone(), (*id).clone()));
+
+    for id in &ids {
+        for foreign in &heads[*id].foreign_ids {
+            if heads.contains_key(foreign) {
+                link(id, foreign, Relation::ContinuedFrom, "resumed");
+            }
+        }
+    }
+
+    // Oldest first, so each session continues the previous one with the summary
+    let mut by_summary: HashMap<&str, Vec<&String>> = HashMap::new();
+    let mut by_opening: HashMap<&str, Vec<&String>> = HashMap::new();
+    for id in &ids {
+        let head = &heads[*id];
+        for summary in head.summaries.iter().filter(|s| !s.trim().is_empty()) {
+            by_summary.entry(summary.as_str()).or_default().push(id);
+        }
+        let prompt = head
+            .first_prompt
+            .as_deref()
+            .filter(|p| p.chars().count() >= MIN_PROMPT_CHARS);
+        for opening in [head.first_uuid.as_deref(), prompt].into_iter().flatten() {
+            by_opening.entry(opening).or_default().push(id);
+        }
+    }
+    for group in by_summary.values() {
+        for pair in group.windows(2) {
+            link(pair[1], pair[0], Relation::ContinuedFrom, "shared summary");
+        }
+    }
+    for group in by_opening.values() {
+        for pair in group.windows(2) {
+            link(pair[0], pair[1], Relation::Fork, "same opening");
+        }
+    }
+
+    links
+}
+
+/// Related sessions of one session
+pub fn related(project_id: &str, session_id: &str) -> Vec<RelatedSession> {
+    let project_dir = crate::get_claude_dir().join("projects").join(project_id);
+    project_links(&project_dir)
+        .remove(session_id)
+        .unwrap_or_default()
+}
+
+/// Fill `related_sessions` of listed sessions, reading each project once
+pub fn apply(sessions: &mut [crate::Session]) {
+    let projects_dir = crate::get_claude_dir().join("projects");
+    let mut by_project: HashMap<String, HashMap<String, Vec<RelatedSession>>> = HashMap::new();
+    for session in sessions {
+        let links = by_project
+            .entry(session.project_id.clone())
+            .or_insert_with(|| project_links(&projects_dir.join(&session.project_id)));
+        session.related_sessions = links.remove(&session.id).unwrap_or_default();
+    }
+}
@@ -0,0 +1,191 @@
+//! User-assigned session metadata
+//!
+//! Favorites, freeform tags, notes and custom titles, keyed by `<project_id>/<session_id>` and
+//! persisted to ~/.lovstudio/lovcode/session-meta.json
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionUserMeta {
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// User-provided title shown instead of the generated summary
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+impl SessionUserMeta {
+    fn is_empty(&self) -> bool {
+        !self.starred && self.tags.is_empty() && self.note.is_none() && self.title.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+pub type SessionMetaStore = HashMap<String, SessionUserMeta>;
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("session-meta.json")
+}
+
+pub fn key(project_id: &str, session_id: &str) -> String {
+    format!("{}/{}", project_id, session_id)
+}
+
+/// The stored metadata; a missing file is an empty store, an unreadable one an error
+pub fn load() -> Result<SessionMetaStore, String> {
+    let path = get_store_path();
+    if !path.exists() {
+        return Ok(SessionMetaStore::default());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read session meta: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse session meta: {}", e))
+}
+
+fn save(store: &SessionMetaStore) -> Result<(), String> {
+    let path = get_store_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Apply `change` to one session's entry, dropping entries that end up empty
+fn update(
+    project_id: &str,
+    session_id: &str,
+    change: impl FnOnce(&mut SessionUserMeta),
+) -> Result<SessionUserMeta, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load()?;
+    let key = key(project_id, session_id);
+
+    let mut meta = store.remove(&key).unwrap_or_default();
+    change(&mut meta);
+    meta.updated_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if !meta.is_empty() {
+        store.insert(key, meta.clone());
+    }
+
+    save(&store)?;
+    Ok(meta)
+}
+
+pub fn set_starred(
+    project_id: &str,
+    session_id: &str,
+    starred: bool,
+) -> Result<SessionUserMeta, String> {
+    update(project_id, session_id, |meta| meta.starred = starred)
+}
+
+/// Replace a session's tags (trimmed, deduplicated, empty tags dropped)
+pub fn set_tags(
+    project_id: &str,
+    session_id: &str,
+    tags: Vec<String>,
+) -> Result<SessionUserMeta, String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if !tag.is_empty() && !cleaned.contains(&tag) {
+            cleaned.push(tag);
+        }
+    }
+    update(project_id, session_id, |meta| meta.tags = cleaned)
+}
+
+pub fn set_note(
+    project_id: &str,
+    session_id: &str,
+    note: Option<String>,
+) -> Result<SessionUserMeta, String> {
+    let note = note.filter(|n| !n.trim().is_empty());
+    update(project_id, session_id, |meta| meta.note = note)
+}
+
+/// Set or clear (None/blank) a session's custom title
+pub fn set_title(
+    project_id: &str,
+    session_id: &str,
+    title: Option<String>,
+) -> Result<SessionUserMeta, String> {
+    let title = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    update(project_id, session_id, |meta| meta.title = title)
+}
+
+/// Custom title of a session, if any
+pub fn title(store: &SessionMetaStore, project_id: &str, session_id: &str) -> Option<String> {
+    store.get(&key(project_id, session_id))?.title.clone()
+}
+
+/// All tags in use, most used first
+pub fn all_tags() -> Result<Vec<TagCount>, String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for meta in load()?.values() {
+        for tag in &meta.tags {
+            *counts.entry(tag.clone()).or_default() += 1;
+        }
+    }
+
+    let mut tags: Vec<TagCount> = counts
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then(a.tag.cmp(&b.tag)));
+    Ok(tags)
+}
+
+/// Move the entries of `session_ids` to another project id; where the new id
+/// already has an entry, both are kept
+pub fn rekey_sessions(
+    old_project_id: &str,
+    new_project_id: &str,
+    session_ids: &[String],
+) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load()?;
+    let mut changed = false;
+    for session_id in session_ids {
+        let new_key = key(new_project_id, session_id);
+        if store.contains_key(&new_key) {
+            continue;
+        }
+        if let Some(meta) = store.remove(&key(old_project_id, session_id)) {
+            store.insert(new_key, meta);
+            changed = true;
+        }
+    }
+    if changed {
+        save(&store)?;
+    }
+    Ok(())
+}
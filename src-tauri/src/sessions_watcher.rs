@@ -0,0 +1,179 @@
+//! Live updates for the project and session lists
+//!
+//! Watches ~/.claude/projects recursively and announces changes once writes
+//! settle: "session-updated" for each session file created, appended to or
+//! removed, and "projects-changed" when a project dir or a session file comes or
+//! goes (which changes project counts and ordering). Sidechain files are
+//! ignored; an open session is followed by `watch_session` instead.
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Quiet period that ends a batch of changes
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// A session being written continuously still reports at least this often
+const MAX_BATCH: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionChange {
+    Created,
+    Modified,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionUpdated {
+    pub project_id: String,
+    pub session_id: String,
+    pub change: SessionChange,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectsChanged {
+    /// Projects whose dir or set of sessions changed
+    pub project_ids: Vec<String>,
+}
+
+enum Touched {
+    Project(String),
+    Session(String, String),
+}
+
+/// What a path under the projects dir refers to
+fn classify(projects_dir: &Path, path: &Path) -> Option<Touched> {
+    let parts: Vec<&str> = path
+        .strip_prefix(projects_dir)
+        .ok()?
+        .components()
+        .map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [project] => Some(Touched::Project(project.to_string())),
+        [project, file] => {
+            let session_id = file.strip_suffix(".jsonl")?;
+            (!session_id.starts_with("agent-"))
+                .then(|| Touched::Session(project.to_string(), session_id.to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn emit_batch(app_handle: &AppHandle, projects_dir: &Path, events: Vec<(EventKind, PathBuf)>) {
+    let mut projects = BTreeSet::new();
+    let mut sessions: BTreeMap<(String, String), SessionChange> = BTreeMap::new();
+    for (kind, path) in events {
+        match classify(projects_dir, &path) {
+            Some(Touched::Project(project_id)) => {
+                projects.insert(project_id);
+            }
+            Some(Touched::Session(project_id, session_id)) => {
+                let change = if !path.exists() {
+                    SessionChange::Removed
+                } else if kind.is_create() {
+                    SessionChange::Created
+                } else {
+                    SessionChange::Modified
+                };
+                let entry = sessions.entry((project_id, session_id)).or_insert(change);
+                // Created beats modified within a batch; existence decides removal
+                *entry = match (change, *entry) {
+                    (SessionChange::Removed, _) | (_, SessionChange::Removed) => change,
+                    (a, b) => a.min(b),
+                };
+            }
+            None => {}
+        }
+    }
+
+    for ((project_id, _), change) in &sessions {
+        if *change != SessionChange::Modified {
+            projects.insert(project_id.clone());
+        }
+    }
+    for ((project_id, session_id), change) in sessions {
+        let _ = app_handle.emit(
+            "session-updated",
+            SessionUpdated {
+                project_id,
+                session_id,
+                change,
+            },
+        );
+    }
+    if !projects.is_empty() {
+        let _ = app_handle.emit(
+            "projects-changed",
+            ProjectsChanged {
+                project_ids: projects.into_iter().collect(),
+            },
+        );
+    }
+}
+
+/// Watch ~/.claude/projects and emit "projects-changed" / "session-updated" (debounced)
+pub fn start(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        let _watcher_guard = crate::resource_limits::track_watcher("projects");
+        let claude_dir = crate::get_claude_dir();
+        let projects_dir = claude_dir.join("projects");
+        if !claude_dir.exists() {
+            return;
+        }
+
+        let (tx, rx) = channel::<(EventKind, Vec<PathBuf>)>();
+        let mut watcher: RecommendedWatcher =
+            match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+                if let Ok(event) = res {
+                    if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() {
+                        let _ = tx.send((event.kind, event.paths));
+                    }
+                }
+            }) {
+                Ok(w) => w,
+                Err(_) => return,
+            };
+
+        // ~/.claude itself, to notice the projects dir being created later
+        if watcher
+            .watch(&claude_dir, RecursiveMode::NonRecursive)
+            .is_err()
+        {
+            return;
+        }
+        let mut watching = watcher
+            .watch(&projects_dir, RecursiveMode::Recursive)
+            .is_ok();
+
+        while let Ok(first) = rx.recv() {
+            let started = Instant::now();
+            let mut events: Vec<(EventKind, PathBuf)> = Vec::new();
+            let mut push = |(kind, paths): (EventKind, Vec<PathBuf>)| {
+                events.extend(paths.into_iter().map(|p| (kind, p)));
+            };
+            push(first);
+            // Drain events until writes pause, but don't hold them back forever
+            while started.elapsed() < MAX_BATCH {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(more) => push(more),
+                    Err(_) => break,
+                }
+            }
+
+            if !watching && projects_dir.is_dir() {
+                watching = watcher
+                    .watch(&projects_dir, RecursiveMode::Recursive)
+                    .is_ok();
+            }
+            emit_batch(&app_handle, &projects_dir, events);
+        }
+    });
+}
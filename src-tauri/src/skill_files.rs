@@ -0,0 +1,458 @@
+//! Creating, browsing, editing and deleting skills
+//!
+//! A skill is a directory under ~/.claude/skills, or a project's .claude/skills,
+//! whose SKILL.md starts with `name` and `description` frontmatter; Claude Code
+//! reads the description to decide when to load the rest. Scripts and reference
+//! docs go in optional scripts/ and references/ subfolders, which SKILL.md
+//! points to so Claude only opens them when needed. `details` lists every file
+//! of a skill with a short preview of text files; files are edited by paths
+//! relative to the skill directory, which can't climb out of it.
+//!
+//! `list_by_scope` labels skills with where they come from: global, or one of
+//! the workspace projects.
+//!
+//! Deleting moves the whole skill directory to ~/.lovstudio/lovcode/skill-trash/,
+//! which works like the command trash and expires on the same schedule.
+
+use crate::command_files::{CommandScope, DeletedCommand};
+use crate::LocalSkill;
+use serde::Serialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const MAX_NAME_CHARS: usize = 64;
+const MAX_DESCRIPTION_CHARS: usize = 1024;
+/// Words Claude Code rejects in skill names
+const RESERVED_WORDS: &[&str] = &["anthropic", "claude"];
+/// Characters of a text file shown in `SkillFile::preview`
+const PREVIEW_CHARS: usize = 400;
+/// Bytes read to tell text from binary files and build the preview
+const SNIFF_BYTES: u64 = 8 * 1024;
+/// Larger files aren't opened by `read_file`
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillFile {
+    /// Path inside the skill directory, `/`-separated
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub executable: bool,
+    /// Start of the file, None for directories and binary files
+    pub preview: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkillDetails {
+    pub skill: LocalSkill,
+    pub dir: String,
+    /// Everything under the skill directory, SKILL.md included, sorted by path
+    pub files: Vec<SkillFile>,
+}
+
+/// Skills of one scope, labelled for display
+#[derive(Debug, Serialize)]
+pub struct ScopedSkills {
+    pub scope: CommandScope,
+    /// "Global", or the workspace project's name
+    pub label: String,
+    pub skills: Vec<LocalSkill>,
+}
+
+pub type DeletedSkill = DeletedCommand;
+
+pub fn skills_dir(scope: &CommandScope) -> Result<PathBuf, String> {
+    Ok(scope.claude_dir()?.join("skills"))
+}
+
+/// The scope whose skills dir holds the skill directory `dir`
+pub fn scope_of(dir: &Path) -> CommandScope {
+    let claude_dir = dir.parent().and_then(Path::parent);
+    match claude_dir.and_then(Path::parent) {
+        Some(project) if claude_dir != Some(crate::get_claude_dir().as_path()) => {
+            CommandScope::Project {
+                project_path: project.to_string_lossy().to_string(),
+            }
+        }
+        _ => CommandScope::Global,
+    }
+}
+
+/// Skills of a scope, sorted by name
+pub fn list(scope: &CommandScope) -> Result<Vec<LocalSkill>, String> {
+    let dir = skills_dir(scope)?;
+    let mut skills = if dir.is_dir() {
+        crate::collect_skills(&dir)?
+    } else {
+        Vec::new()
+    };
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(skills)
+}
+
+/// Global skills, then those of each non-archived workspace project that has any
+pub fn list_by_scope() -> Result<Vec<ScopedSkills>, String> {
+    let mut groups = vec![ScopedSkills {
+        label: "Global".to_string(),
+        skills: list(&CommandScope::Global)?,
+        scope: CommandScope::Global,
+    }];
+
+    let workspace = crate::workspace_store::load_workspace()?;
+    let mut seen = std::collections::HashSet::new();
+    for project in workspace.projects {
+        if project.archived == Some(true) || !seen.insert(project.path.clone()) {
+            continue;
+        }
+        let scope = CommandScope::Project {
+            project_path: project.path,
+        };
+        // Projects whose directory is gone have nothing to list
+        let Ok(skills) = list(&scope) else {
+            continue;
+        };
+        if !skills.is_empty() {
+            groups.push(ScopedSkills {
+                scope,
+                label: project.name,
+                skills,
+            });
+        }
+    }
+    Ok(groups)
+}
+
+fn get_trash_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("skill-trash")
+}
+
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_NAME_CHARS
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(format!(
+            "Invalid skill name '{}': use up to {} lowercase letters, digits and hyphens",
+            name, MAX_NAME_CHARS
+        ));
+    }
+    if let Some(word) = RESERVED_WORDS.iter().find(|w| name.contains(*w)) {
+        return Err(format!("Skill names cannot contain '{}'", word));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_description(description: &str) -> Result<String, String> {
+    let description = description.trim();
+    if description.is_empty() {
+        return Err("A skill needs a description".to_string());
+    }
+    if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(format!(
+            "Description must be at most {} characters",
+            MAX_DESCRIPTION_CHARS
+        ));
+    }
+    if description.contains(['<', '>']) {
+        return Err("Description cannot contain XML tags".to_string());
+    }
+    Ok(description.to_string())
+}
+
+/// SKILL.md body for a new skill, mentioning the subfolders it was created with
+fn skeleton(name: &str, scripts: bool, references: bool) -> String {
+    let mut body = format!(
+        "# {}\n\n## Instructions\n\nDescribe, step by step, what Claude should do when this skill applies.\n",
+        name
+    );
+    if scripts || references {
+        body.push_str("\n## Resources\n\n");
+        if scripts {
+            body.push_str("- `scripts/`: helper scripts to run rather than rewrite\n");
+        }
+        if references {
+            body.push_str("- `references/`: docs to read only when the task needs them\n");
+        }
+    }
+    body
+}
+
+/// Create <skills dir>/<name>/SKILL.md, plus scripts/ and references/ when asked
+pub fn create(
+    name: &str,
+    description: &str,
+    scripts: bool,
+    references: bool,
+    scope: &CommandScope,
+) -> Result<LocalSkill, String> {
+    let name = validate_name(name)?;
+    let description = validate_description(description)?;
+
+    let dir = skills_dir(scope)?.join(&name);
+    if dir.exists() {
+        return Err(format!("A skill named '{}' already exists", name));
+    }
+
+    let mut content = skeleton(&name, scripts, references);
+    for (key, value) in [("name", name.as_str()), ("description", &description)] {
+        content = crate::frontmatter::set_field(&content, key, Some(value))?;
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    for (wanted, sub) in [(scripts, "scripts"), (references, "references")] {
+        if wanted {
+            fs::create_dir_all(dir.join(sub)).map_err(|e| e.to_string())?;
+        }
+    }
+    fs::write(dir.join("SKILL.md"), content).map_err(|e| e.to_string())?;
+    crate::read_skill_dir(&dir).ok_or_else(|| format!("Failed to read skill '{}'", name))
+}
+
+/// Move a skill directory into a new trash entry
+pub fn move_to_trash(dir: &Path) -> Result<DeletedSkill, String> {
+    let name = dir
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    crate::command_files::move_to_trash(&get_trash_dir(), name, dir, &[dir])
+}
+
+/// Whether `path` only goes down, e.g. `scripts/run.sh`
+fn is_relative_down(path: &Path) -> bool {
+    path.components().count() > 0 && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Directory of the skill `name` in the skills dir of `scope`
+fn skill_dir(name: &str, scope: &CommandScope) -> Result<PathBuf, String> {
+    let dir = skills_dir(scope)?.join(name);
+    if Path::new(name).components().count() != 1
+        || !is_relative_down(Path::new(name))
+        || !dir.is_dir()
+    {
+        return Err(format!("Skill '{}' not found", name));
+    }
+    Ok(dir)
+}
+
+/// `relative` inside the skill `name`, refusing paths that leave the skill directory
+fn skill_file_path(
+    name: &str,
+    scope: &CommandScope,
+    relative: &str,
+) -> Result<(PathBuf, PathBuf), String> {
+    let dir = skill_dir(name, scope)?;
+    let relative = Path::new(relative.trim());
+    if !is_relative_down(relative) {
+        return Err(format!("Invalid path in skill: {}", relative.display()));
+    }
+    let path = dir.join(relative);
+    // Symlinks inside the skill could still point elsewhere
+    let real_dir = dir.canonicalize().map_err(|e| e.to_string())?;
+    let outside = path
+        .ancestors()
+        .filter(|a| a.starts_with(&dir) && a.exists())
+        .find_map(|a| a.canonicalize().ok())
+        .is_some_and(|real| !real.starts_with(&real_dir));
+    if outside {
+        return Err(format!("{} leads outside the skill", relative.display()));
+    }
+    Ok((dir, path))
+}
+
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        false
+    }
+}
+
+/// The first characters of a text file; None when it looks binary
+fn preview(path: &Path) -> Option<String> {
+    use std::io::Read;
+
+    let mut head = Vec::new();
+    fs::File::open(path)
+        .ok()?
+        .take(SNIFF_BYTES)
+        .read_to_end(&mut head)
+        .ok()?;
+    if head.contains(&0) {
+        return None;
+    }
+    // The read may have cut a multi-byte character at the end
+    let text = match std::str::from_utf8(&head) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&head[..e.valid_up_to()]).ok()?,
+        Err(_) => return None,
+    };
+    Some(text.chars().take(PREVIEW_CHARS).collect())
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<SkillFile>) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        // Don't follow linked directories, they may loop
+        let linked = entry.file_type().is_ok_and(|t| t.is_symlink());
+        files.push(SkillFile {
+            path: relative,
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            executable: metadata.is_file() && is_executable(&metadata),
+            preview: metadata.is_file().then(|| preview(&path)).flatten(),
+        });
+        if metadata.is_dir() && !linked {
+            collect_files(root, &path, files)?;
+        }
+    }
+    Ok(())
+}
+
+/// The skill `name` with every file in its directory
+pub fn details(name: &str, scope: &CommandScope) -> Result<SkillDetails, String> {
+    let dir = skill_dir(name, scope)?;
+    let skill =
+        crate::read_skill_dir(&dir).ok_or_else(|| format!("Skill '{}' has no SKILL.md", name))?;
+    let mut files = Vec::new();
+    collect_files(&dir, &dir, &mut files)?;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(SkillDetails {
+        skill,
+        dir: dir.to_string_lossy().to_string(),
+        files,
+    })
+}
+
+/// Full content of a text file in the skill `name`
+pub fn read_file(name: &str, scope: &CommandScope, relative: &str) -> Result<String, String> {
+    let (_, path) = skill_file_path(name, scope, relative)?;
+    let metadata = fs::metadata(&path).map_err(|e| format!("{}: {}", relative, e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", relative));
+    }
+    if metadata.len() > MAX_READ_BYTES {
+        return Err(format!("{} is too large to edit", relative));
+    }
+    let bytes = fs::read(&path).map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|_| format!("{} is not a text file", relative))
+}
+
+/// Create or overwrite a file in the skill `name`; `executable` sets or clears
+/// its execute bits, None keeps them
+pub fn write_file(
+    name: &str,
+    scope: &CommandScope,
+    relative: &str,
+    content: &str,
+    executable: Option<bool>,
+) -> Result<SkillFile, String> {
+    let (dir, path) = skill_file_path(name, scope, relative)?;
+    if path.is_dir() {
+        return Err(format!("{} is a directory", relative));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+
+    #[cfg(unix)]
+    if let Some(executable) = executable {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&path)
+            .map_err(|e| e.to_string())?
+            .permissions();
+        let mode = perms.mode();
+        perms.set_mode(if executable {
+            mode | 0o111
+        } else {
+            mode & !0o111
+        });
+        fs::set_permissions(&path, perms).map_err(|e| e.to_string())?;
+    }
+    #[cfg(not(unix))]
+    let _ = executable;
+
+    let metadata = fs::metadata(&path).map_err(|e| e.to_string())?;
+    Ok(SkillFile {
+        path: path
+            .strip_prefix(&dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/"),
+        is_dir: false,
+        size: metadata.len(),
+        executable: is_executable(&metadata),
+        preview: preview(&path),
+    })
+}
+
+/// Move a file or folder of the skill `name` to the skill trash; SKILL.md stays
+pub fn delete_file(
+    name: &str,
+    scope: &CommandScope,
+    relative: &str,
+) -> Result<DeletedSkill, String> {
+    let (dir, path) = skill_file_path(name, scope, relative)?;
+    if path == dir.join("SKILL.md") {
+        return Err("SKILL.md can't be removed; delete the skill instead".to_string());
+    }
+    if fs::symlink_metadata(&path).is_err() {
+        return Err(format!("{} not found in skill '{}'", relative, name));
+    }
+    crate::command_files::move_to_trash(
+        &get_trash_dir(),
+        format!("{}/{}", name, relative.trim()),
+        &path,
+        &[&path],
+    )
+}
+
+/// Move the skill `name` of `scope`, with all its files, to the lovcode trash
+pub fn delete(name: &str, scope: &CommandScope) -> Result<DeletedSkill, String> {
+    let dir = skill_dir(name, scope)?;
+    let deleted = move_to_trash(&dir)?;
+    crate::skill_templates::forget(&dir)?;
+    Ok(deleted)
+}
+
+/// Deleted skills, most recently deleted first
+pub fn list_deleted() -> Vec<DeletedSkill> {
+    crate::command_files::list_trash(&get_trash_dir())
+}
+
+/// Move a deleted skill back to where it was
+pub fn restore_deleted(id: &str) -> Result<DeletedSkill, String> {
+    crate::command_files::restore_from_trash(&get_trash_dir(), id)
+}
+
+/// Permanently delete a trashed skill
+pub fn purge_deleted(id: &str) -> Result<(), String> {
+    crate::command_files::purge_from_trash(&get_trash_dir(), id)
+}
+
+/// Permanently delete trashed skills past their expiry, returns how many were removed
+pub fn purge_expired() -> usize {
+    crate::command_files::purge_expired_in(&get_trash_dir())
+}
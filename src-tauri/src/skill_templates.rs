@@ -0,0 +1,211 @@
+//! Marketplace skills installed with their whole directory
+//!
+//! Skills from plugin sources are directories: SKILL.md plus the scripts,
+//! references and assets it points to. Installing copies the directory into
+//! ~/.claude/skills (through a staging dir, so a failed copy leaves nothing
+//! half-installed) and records where it came from in
+//! ~/.lovstudio/lovcode/skill-installs.json. Installing the same skill again
+//! replaces the copy, moving the old one to the skill trash; a different
+//! skill with the same name gets the first free `<name>-<n>`, which its
+//! frontmatter `name` follows. Deleting an installed skill forgets its record.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the store file
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillInstall {
+    /// Marketplace source id, e.g. "anthropic"
+    pub source_id: String,
+    /// Plugin the skill ships in, when the source has several
+    pub plugin: Option<String>,
+    /// SKILL.md (or skill directory) path the catalog listed it under
+    pub source_path: String,
+    /// Skill name it was installed as
+    pub name: String,
+    /// Installed skill directory
+    pub path: String,
+    pub installed_at: u64,
+    pub updated_at: u64,
+}
+
+fn get_store_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("skill-installs.json")
+}
+
+/// Install records; a missing file is empty, an unreadable one an error
+fn load() -> Result<Vec<SkillInstall>, String> {
+    let path = get_store_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read skill installs: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse skill installs: {}", e))
+}
+
+fn save(installs: &[SkillInstall]) -> Result<(), String> {
+    let path = get_store_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(installs).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The skill directory `path` (the dir or its SKILL.md) names inside the source's tree
+fn source_skill_dir(
+    app_handle: Option<&tauri::AppHandle>,
+    source_id: &str,
+    path: &str,
+) -> Result<(PathBuf, Option<String>), String> {
+    let source = crate::PLUGIN_SOURCES
+        .iter()
+        .find(|s| s.id == source_id)
+        .ok_or_else(|| format!("Unknown marketplace source: {}", source_id))?;
+    let base = crate::resolve_source_path(app_handle, source.path)
+        .filter(|p| p.is_dir())
+        .ok_or_else(|| format!("Source '{}' has no skill directories", source_id))?;
+    let base = base.canonicalize().map_err(|e| e.to_string())?;
+
+    let path = Path::new(path);
+    let dir = if path.file_name().is_some_and(|n| n == "SKILL.md") {
+        path.parent().unwrap_or(path)
+    } else {
+        path
+    };
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", dir.display(), e))?;
+    if !dir.starts_with(&base) || !dir.join("SKILL.md").is_file() {
+        return Err(format!(
+            "{} is not a skill of source '{}'",
+            dir.display(),
+            source_id
+        ));
+    }
+
+    // <base>/[external_]plugins/<plugin>/skills/<name> in multi-plugin sources
+    let plugin = dir.strip_prefix(&base).ok().and_then(|rel| {
+        let parts: Vec<_> = rel.components().collect();
+        (parts.len() == 4).then(|| parts[1].as_os_str().to_string_lossy().to_string())
+    });
+    Ok((dir, plugin))
+}
+
+/// Copy the skill directory at `path` in marketplace source `source_id` into
+/// ~/.claude/skills, or refresh a previous install of it
+pub fn install(
+    app_handle: Option<&tauri::AppHandle>,
+    source_id: &str,
+    path: &str,
+) -> Result<SkillInstall, String> {
+    let (source_dir, plugin) = source_skill_dir(app_handle, source_id, path)?;
+    let source_path = path.to_string();
+    let base_name = source_dir
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let skills_dir = crate::skill_files::skills_dir(&crate::command_files::CommandScope::Global)?;
+
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut installs = load()?;
+    let previous = installs
+        .iter()
+        .position(|i| i.source_id == source_id && i.source_path == source_path)
+        .filter(|&i| Path::new(&installs[i].path).is_dir());
+
+    let name = match previous {
+        Some(index) => installs[index].name.clone(),
+        None => {
+            let mut name = base_name.clone();
+            let mut n = 2;
+            while skills_dir.join(&name).exists() {
+                name = format!("{}-{}", base_name, n);
+                n += 1;
+            }
+            name
+        }
+    };
+
+    let dest = skills_dir.join(&name);
+    let staging = skills_dir.join(format!(".{}.installing", name));
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    fs::create_dir_all(&skills_dir).map_err(|e| e.to_string())?;
+    let staged = crate::trash::copy_recursive(&source_dir, &staging)
+        .map_err(|e| e.to_string())
+        .and_then(|_| {
+            if name == base_name {
+                return Ok(());
+            }
+            let skill_md = staging.join("SKILL.md");
+            let content = fs::read_to_string(&skill_md).map_err(|e| e.to_string())?;
+            let renamed = crate::frontmatter::set_field(&content, "name", Some(&name))?;
+            fs::write(&skill_md, renamed).map_err(|e| e.to_string())
+        });
+    if let Err(e) = staged {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    if previous.is_some() {
+        if let Err(e) = crate::skill_files::move_to_trash(&dest) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    }
+    fs::rename(&staging, &dest).map_err(|e| e.to_string())?;
+
+    let now = now_secs();
+    let install = SkillInstall {
+        source_id: source_id.to_string(),
+        plugin,
+        source_path: source_path.clone(),
+        name,
+        path: dest.to_string_lossy().to_string(),
+        installed_at: previous.map(|i| installs[i].installed_at).unwrap_or(now),
+        updated_at: now,
+    };
+    installs.retain(|i| !(i.source_id == source_id && i.source_path == source_path));
+    installs.push(install.clone());
+    save(&installs)?;
+    Ok(install)
+}
+
+/// Recorded installs whose directory still exists
+pub fn list() -> Result<Vec<SkillInstall>, String> {
+    Ok(load()?
+        .into_iter()
+        .filter(|install| Path::new(&install.path).is_dir())
+        .collect())
+}
+
+/// Drop the install record of the skill directory `dir`, if any
+pub fn forget(dir: &Path) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut installs = load()?;
+    let count = installs.len();
+    installs.retain(|i| Path::new(&i.path) != dir);
+    if installs.len() != count {
+        save(&installs)?;
+    }
+    Ok(())
+}
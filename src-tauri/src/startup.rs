@@ -0,0 +1,106 @@
+//! Staged startup and phase timings
+//!
+//! `setup` only does what the first paint needs; watchers, cache refreshes and
+//! cleanup run once the frontend emits "app-ready" (or after a fallback delay if
+//! it never does). Each phase is timed relative to process start and exposed via
+//! `get_startup_profile` for diagnostics.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Listener};
+
+/// Deferred init runs anyway if the frontend hasn't signalled readiness by then
+const READY_FALLBACK: Duration = Duration::from_secs(10);
+
+static PROCESS_START: LazyLock<Instant> = LazyLock::new(Instant::now);
+static PROFILE: Mutex<Vec<StartupPhase>> = Mutex::new(Vec::new());
+static APP_READY_MS: Mutex<Option<u64>> = Mutex::new(None);
+static DEFERRED_STARTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupPhase {
+    pub name: String,
+    /// Milliseconds since process start
+    pub start_ms: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupProfile {
+    pub phases: Vec<StartupPhase>,
+    /// When the frontend reported its first paint, if it has
+    pub app_ready_ms: Option<u64>,
+    /// False when deferred init was triggered by the fallback timer
+    pub ready_signalled: bool,
+    pub uptime_ms: u64,
+}
+
+fn since_start(at: Instant) -> u64 {
+    at.saturating_duration_since(*PROCESS_START).as_millis() as u64
+}
+
+/// Pin the process start time; call first thing in `run`
+pub fn mark_process_start() {
+    LazyLock::force(&PROCESS_START);
+}
+
+/// Run `f` and record how long it took
+pub fn measure<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    if let Ok(mut phases) = PROFILE.lock() {
+        phases.push(StartupPhase {
+            name: name.to_string(),
+            start_ms: since_start(started),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+    result
+}
+
+/// Run `init` once, after the frontend emits "app-ready" or after a fallback delay
+pub fn on_app_ready(app_handle: &AppHandle, init: impl FnOnce() + Send + 'static) {
+    let init = std::sync::Arc::new(Mutex::new(Some(init)));
+
+    let run = {
+        let init = init.clone();
+        move |signalled: bool| {
+            if DEFERRED_STARTED.swap(true, Ordering::SeqCst) {
+                return;
+            }
+            if signalled {
+                if let Ok(mut ready) = APP_READY_MS.lock() {
+                    *ready = Some(since_start(Instant::now()));
+                }
+            }
+            let task = init.lock().ok().and_then(|mut i| i.take());
+            if let Some(task) = task {
+                measure("deferred_init", task);
+            }
+        }
+    };
+
+    let on_event = run.clone();
+    app_handle.once("app-ready", move |_| on_event(true));
+    std::thread::spawn(move || {
+        std::thread::sleep(READY_FALLBACK);
+        run(false);
+    });
+}
+
+/// Whether deferred init (watchers, monitors) has been kicked off
+pub fn deferred_started() -> bool {
+    DEFERRED_STARTED.load(Ordering::SeqCst)
+}
+
+pub fn profile() -> StartupProfile {
+    let app_ready_ms = APP_READY_MS.lock().ok().and_then(|r| *r);
+    StartupProfile {
+        phases: PROFILE.lock().map(|p| p.clone()).unwrap_or_default(),
+        app_ready_ms,
+        ready_signalled: app_ready_ms.is_some(),
+        uptime_ms: since_start(Instant::now()),
+    }
+}
@@ -0,0 +1,165 @@
+//! Structured tool calls
+//!
+//! Assistant lines carry `tool_use` blocks and the following user lines carry the
+//! matching `tool_result` blocks (linked by `tool_use_id`). `ToolPairing` collects
+//! results while a session is streamed and attaches them to the calls afterwards,
+//! with the status and the time between the two lines.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Results longer than this are truncated before being sent to the UI
+const MAX_RESULT_CHARS: usize = 20_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input: serde_json::Value,
+    /// "pending" | "success" | "error"
+    pub status: String,
+    pub result: Option<String>,
+    #[serde(default)]
+    pub result_truncated: bool,
+    /// Time from the tool_use line to its tool_result line
+    pub duration_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct ToolResult {
+    content: String,
+    truncated: bool,
+    is_error: bool,
+    timestamp: String,
+}
+
+fn blocks<'a>(
+    content: &'a Option<serde_json::Value>,
+    kind: &'a str,
+) -> impl Iterator<Item = &'a serde_json::Map<String, serde_json::Value>> + 'a {
+    content
+        .as_ref()
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_object())
+        .filter(move |obj| obj.get("type").and_then(|t| t.as_str()) == Some(kind))
+}
+
+/// `tool_use` blocks of a message content array, all pending
+pub fn tool_uses(content: &Option<serde_json::Value>) -> Vec<ToolCall> {
+    blocks(content, "tool_use")
+        .map(|obj| ToolCall {
+            id: obj
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            name: obj
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            input: obj.get("input").cloned().unwrap_or(serde_json::Value::Null),
+            status: "pending".to_string(),
+            result: None,
+            result_truncated: false,
+            duration_ms: None,
+        })
+        .collect()
+}
+
+/// Text of a tool_result `content`, which is either a string or a block array
+fn result_text(value: Option<&serde_json::Value>) -> String {
+    match value {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item.get("type").and_then(|t| t.as_str()) {
+                Some("text") => item.get("text").and_then(|t| t.as_str()).map(String::from),
+                Some("image") => Some("[image]".to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn elapsed_ms(from: &str, to: &str) -> Option<i64> {
+    let from = chrono::DateTime::parse_from_rfc3339(from).ok()?;
+    let to = chrono::DateTime::parse_from_rfc3339(to).ok()?;
+    Some((to - from).num_milliseconds())
+}
+
+/// Pairs tool calls with their results across session lines
+#[derive(Debug, Default)]
+pub struct ToolPairing {
+    /// Tool use id -> timestamp of the line that issued it
+    wanted: HashMap<String, String>,
+    results: HashMap<String, ToolResult>,
+}
+
+impl ToolPairing {
+    /// Remember calls whose results should be kept
+    pub fn want(&mut self, calls: &[ToolCall], timestamp: &str) {
+        for call in calls {
+            self.wanted.insert(call.id.clone(), timestamp.to_string());
+        }
+    }
+
+    /// Forget calls that are no longer displayed
+    pub fn forget(&mut self, calls: &[ToolCall]) {
+        for call in calls {
+            self.wanted.remove(&call.id);
+            self.results.remove(&call.id);
+        }
+    }
+
+    /// Record the `tool_result` blocks of a line for wanted calls
+    pub fn add_results(&mut self, content: &Option<serde_json::Value>, timestamp: &str) {
+        for obj in blocks(content, "tool_result") {
+            let Some(id) = obj.get("tool_use_id").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if !self.wanted.contains_key(id) {
+                continue;
+            }
+            let text = result_text(obj.get("content"));
+            let truncated = text.chars().count() > MAX_RESULT_CHARS;
+            let content = if truncated {
+                text.chars().take(MAX_RESULT_CHARS).collect()
+            } else {
+                text
+            };
+            self.results.insert(
+                id.to_string(),
+                ToolResult {
+                    content,
+                    truncated,
+                    is_error: obj
+                        .get("is_error")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                    timestamp: timestamp.to_string(),
+                },
+            );
+        }
+    }
+
+    /// Fill in result, status and duration of the given calls
+    pub fn attach(&self, calls: &mut [ToolCall]) {
+        for call in calls {
+            let Some(result) = self.results.get(&call.id) else {
+                continue;
+            };
+            call.status = if result.is_error { "error" } else { "success" }.to_string();
+            call.result = Some(result.content.clone());
+            call.result_truncated = result.truncated;
+            call.duration_ms = self
+                .wanted
+                .get(&call.id)
+                .and_then(|started| elapsed_ms(started, &result.timestamp));
+        }
+    }
+}
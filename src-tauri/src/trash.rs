@@ -0,0 +1,168 @@
+//! Session trash
+//!
+//! Deleted sessions are moved (not removed) into ~/.lovstudio/lovcode/trash/<id>/
+//! together with a manifest.json, so they can be restored until they expire.
+//! Expired entries are purged on startup.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Days a deleted session stays restorable
+pub const DEFAULT_RETENTION_DAYS: u64 = 30;
+
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    pub project_id: String,
+    pub session_id: String,
+    pub summary: Option<String>,
+    /// File and directory names as they were inside the project dir
+    pub files: Vec<String>,
+    pub deleted_at: u64,
+    pub expires_at: u64,
+}
+
+fn get_trash_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("trash")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Copy a file or directory tree; fs::copy keeps permission bits, executable ones included
+pub fn copy_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(src, dst).map(|_| ())
+    }
+}
+
+/// Rename, falling back to copy + remove when crossing filesystems
+pub fn move_path(src: &Path, dst: &Path) -> Result<(), String> {
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+    copy_recursive(src, dst).map_err(|e| e.to_string())?;
+    if src.is_dir() {
+        fs::remove_dir_all(src).map_err(|e| e.to_string())
+    } else {
+        fs::remove_file(src).map_err(|e| e.to_string())
+    }
+}
+
+/// Move a session's files out of its project dir into a new trash entry
+pub fn move_to_trash(
+    project_id: &str,
+    session_id: &str,
+    summary: Option<String>,
+    paths: &[PathBuf],
+) -> Result<TrashEntry, String> {
+    let deleted_at = now_secs();
+    let id = format!("{}-{}", deleted_at, session_id);
+    let entry_dir = get_trash_dir().join(&id);
+    fs::create_dir_all(&entry_dir).map_err(|e| e.to_string())?;
+
+    let mut entry = TrashEntry {
+        id,
+        project_id: project_id.to_string(),
+        session_id: session_id.to_string(),
+        summary,
+        files: Vec::new(),
+        deleted_at,
+        expires_at: deleted_at + DEFAULT_RETENTION_DAYS * 24 * 60 * 60,
+    };
+
+    let mut result = Ok(());
+    for path in paths {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        result = move_path(path, &entry_dir.join(name));
+        if result.is_err() {
+            break;
+        }
+        entry.files.push(name.to_string_lossy().to_string());
+    }
+
+    // Record whatever was moved, even on failure, so nothing becomes unreachable
+    let manifest = serde_json::to_string_pretty(&entry).map_err(|e| e.to_string())?;
+    fs::write(entry_dir.join(MANIFEST), manifest).map_err(|e| e.to_string())?;
+    result?;
+
+    Ok(entry)
+}
+
+/// Trash entries, most recently deleted first
+pub fn list() -> Vec<TrashEntry> {
+    let Ok(dirs) = fs::read_dir(get_trash_dir()) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<TrashEntry> = dirs
+        .flatten()
+        .filter_map(|d| fs::read_to_string(d.path().join(MANIFEST)).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    entries
+}
+
+pub fn get(id: &str) -> Result<TrashEntry, String> {
+    if id.contains(['/', '\\']) || id.contains("..") {
+        return Err(format!("Invalid trash id: {}", id));
+    }
+    let content = fs::read_to_string(get_trash_dir().join(id).join(MANIFEST))
+        .map_err(|_| format!("Trash entry '{}' not found", id))?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+/// Move a trashed session back into `project_dir`
+pub fn restore(id: &str, project_dir: &Path) -> Result<TrashEntry, String> {
+    let entry = get(id)?;
+    let entry_dir = get_trash_dir().join(id);
+
+    if let Some(existing) = entry.files.iter().find(|f| project_dir.join(f).exists()) {
+        return Err(format!("Cannot restore: {} already exists", existing));
+    }
+
+    fs::create_dir_all(project_dir).map_err(|e| e.to_string())?;
+    for file in &entry.files {
+        move_path(&entry_dir.join(file), &project_dir.join(file))?;
+    }
+    fs::remove_dir_all(&entry_dir).map_err(|e| e.to_string())?;
+
+    Ok(entry)
+}
+
+/// Permanently delete a trash entry
+pub fn purge(id: &str) -> Result<(), String> {
+    get(id)?;
+    fs::remove_dir_all(get_trash_dir().join(id)).map_err(|e| e.to_string())
+}
+
+/// Permanently delete entries past their expiry, returns how many were removed
+pub fn purge_expired() -> usize {
+    let now = now_secs();
+    list()
+        .into_iter()
+        .filter(|e| e.expires_at <= now)
+        .filter(|e| purge(&e.id).is_ok())
+        .count()
+}
@@ -0,0 +1,208 @@
+//! Token usage and cost estimates
+//!
+//! Assistant lines carry a `usage` block; Claude Code writes one line per content
+//! block with the same message id and usage, so totals are deduplicated by id.
+//! Costs come from a per-model price table (USD per million tokens) that can be
+//! overridden in ~/.lovstudio/lovcode/pricing.json
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+    }
+}
+
+/// USD per million tokens
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+impl ModelPrice {
+    fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens as f64 * self.cache_write
+            + usage.cache_read_input_tokens as f64 * self.cache_read)
+            / 1_000_000.0
+    }
+}
+
+/// Model id substring -> price; the longest matching key wins
+pub type PriceTable = HashMap<String, ModelPrice>;
+
+const DEFAULT_PRICES: &[(&str, f64, f64, f64, f64)] = &[
+    ("opus", 15.0, 75.0, 18.75, 1.5),
+    ("opus-4-5", 5.0, 25.0, 6.25, 0.5),
+    ("sonnet", 3.0, 15.0, 3.75, 0.3),
+    ("haiku", 0.8, 4.0, 1.0, 0.08),
+    ("haiku-4-5", 1.0, 5.0, 1.25, 0.1),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub usage: TokenUsage,
+    /// None when no price is known for the model
+    pub cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionUsage {
+    pub total: TokenUsage,
+    /// Estimated cost of all priced models
+    pub cost: f64,
+    pub by_model: Vec<ModelUsage>,
+}
+
+fn get_pricing_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("pricing.json")
+}
+
+pub fn default_prices() -> PriceTable {
+    DEFAULT_PRICES
+        .iter()
+        .map(|(model, input, output, cache_write, cache_read)| {
+            (
+                model.to_string(),
+                ModelPrice {
+                    input: *input,
+                    output: *output,
+                    cache_write: *cache_write,
+                    cache_read: *cache_read,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Default prices merged with the user's overrides
+pub fn load_prices() -> PriceTable {
+    let mut prices = default_prices();
+    let overrides: PriceTable = fs::read_to_string(get_pricing_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    prices.extend(overrides);
+    prices
+}
+
+/// Persist user price overrides (replaces previous overrides)
+pub fn save_prices(prices: &PriceTable) -> Result<(), String> {
+    let path = get_pricing_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(prices).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+fn price_for<'a>(prices: &'a PriceTable, model: &str) -> Option<&'a ModelPrice> {
+    let model = model.to_lowercase();
+    prices
+        .iter()
+        .filter(|(key, _)| model.contains(&key.to_lowercase()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, price)| price)
+}
+
+/// Collects usage from session lines, counting each message id once
+#[derive(Debug, Default)]
+pub struct UsageAccumulator {
+    by_message: HashMap<String, (String, TokenUsage)>,
+    /// Lines without a message id can't be deduplicated
+    unkeyed: Vec<(String, TokenUsage)>,
+}
+
+impl UsageAccumulator {
+    pub fn add(&mut self, message_id: Option<&str>, model: Option<&str>, usage: &TokenUsage) {
+        let model = model.unwrap_or("unknown").to_string();
+        match message_id {
+            Some(id) => {
+                self.by_message.insert(id.to_string(), (model, *usage));
+            }
+            None => self.unkeyed.push((model, *usage)),
+        }
+    }
+
+    /// Forget a message, e.g. when it is counted elsewhere
+    pub fn remove(&mut self, message_id: &str) {
+        self.by_message.remove(message_id);
+    }
+
+    /// Add the usage of a raw session line, if it has any
+    pub fn add_line(&mut self, line: &serde_json::Value) {
+        let Some(message) = line.get("message") else {
+            return;
+        };
+        let Some(usage) = message
+            .get("usage")
+            .and_then(|u| serde_json::from_value::<TokenUsage>(u.clone()).ok())
+        else {
+            return;
+        };
+        self.add(
+            message.get("id").and_then(|v| v.as_str()),
+            message.get("model").and_then(|v| v.as_str()),
+            &usage,
+        );
+    }
+
+    /// Per-model totals
+    pub fn by_model(&self) -> HashMap<String, TokenUsage> {
+        let mut totals: HashMap<String, TokenUsage> = HashMap::new();
+        for (model, usage) in self.by_message.values().chain(self.unkeyed.iter()) {
+            // Synthetic entries (e.g. "<synthetic>") carry no real usage
+            if model.starts_with('<') {
+                continue;
+            }
+            totals.entry(model.clone()).or_default().add(usage);
+        }
+        totals
+    }
+}
+
+/// Totals and estimated cost from per-model usage
+pub fn summarize(by_model: &HashMap<String, TokenUsage>, prices: &PriceTable) -> SessionUsage {
+    let mut summary = SessionUsage::default();
+    for (model, usage) in by_model {
+        let cost = price_for(prices, model).map(|p| p.cost(usage));
+        summary.total.add(usage);
+        summary.cost += cost.unwrap_or(0.0);
+        summary.by_model.push(ModelUsage {
+            model: model.clone(),
+            usage: *usage,
+            cost,
+        });
+    }
+    summary
+        .by_model
+        .sort_by(|a, b| b.cost.unwrap_or(0.0).total_cmp(&a.cost.unwrap_or(0.0)));
+    summary
+}
@@ -0,0 +1,281 @@
+//! Workspace time machine
+//!
+//! Copies of workspace.json are kept in ~/.lovstudio/lovcode/workspace-snapshots/
+//! as `<unix secs>.json`. The file on disk is snapshotted before a save at most
+//! every ten minutes, and always before a save that drops projects or features,
+//! so an accidental deletion or a bad bulk edit can be rolled back. Only the
+//! newest MAX_SNAPSHOTS are kept.
+
+use crate::workspace_store::{self, Feature, FeatureStatus, WorkspaceData};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_SNAPSHOTS: usize = 50;
+const MIN_INTERVAL_SECS: u64 = 10 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotInfo {
+    pub timestamp: u64,
+    pub size: u64,
+    pub project_count: usize,
+    pub feature_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureRef {
+    pub project_id: String,
+    pub project_name: String,
+    pub feature_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureChange {
+    pub feature: FeatureRef,
+    /// e.g. "status: completed → pending"
+    pub changes: Vec<String>,
+}
+
+/// What restoring a snapshot would change in the current workspace
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnapshotDiff {
+    pub timestamp: u64,
+    /// Project names only in the snapshot
+    pub projects_restored: Vec<String>,
+    /// Project names only in the current workspace
+    pub projects_dropped: Vec<String>,
+    pub features_restored: Vec<FeatureRef>,
+    pub features_dropped: Vec<FeatureRef>,
+    pub features_changed: Vec<FeatureChange>,
+}
+
+fn get_snapshots_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".lovstudio")
+        .join("lovcode")
+        .join("workspace-snapshots")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Snapshot timestamps, oldest first
+fn timestamps() -> Vec<u64> {
+    let mut timestamps: Vec<u64> = fs::read_dir(get_snapshots_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .strip_suffix(".json")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    timestamps.sort_unstable();
+    timestamps
+}
+
+fn snapshot_path(timestamp: u64) -> PathBuf {
+    get_snapshots_dir().join(format!("{}.json", timestamp))
+}
+
+fn counts(data: &WorkspaceData) -> (usize, usize) {
+    let features = data.projects.iter().map(|p| p.features.len()).sum();
+    (data.projects.len(), features)
+}
+
+/// Copy workspace `content` into a new snapshot unless it equals the latest one
+fn write_snapshot(content: &str) -> Result<(), String> {
+    let existing = timestamps();
+    if let Some(latest) = existing.last() {
+        if fs::read_to_string(snapshot_path(*latest)).is_ok_and(|c| c == content) {
+            return Ok(());
+        }
+    }
+
+    let dir = get_snapshots_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    fs::write(snapshot_path(now_secs()), content).map_err(|e| e.to_string())?;
+
+    let mut all = timestamps();
+    let excess = all.len().saturating_sub(MAX_SNAPSHOTS);
+    for old in all.drain(..excess) {
+        let _ = fs::remove_file(snapshot_path(old));
+    }
+    Ok(())
+}
+
+/// Called by `save_workspace` with the file about to be overwritten
+pub fn before_save(current_path: &Path, next: &WorkspaceData) {
+    let Ok(content) = fs::read_to_string(current_path) else {
+        return;
+    };
+    let destructive = serde_json::from_str::<WorkspaceData>(&content).is_ok_and(|current| {
+        let (projects, features) = counts(&current);
+        let (next_projects, next_features) = counts(next);
+        next_projects < projects || next_features < features
+    });
+    let recent = timestamps()
+        .last()
+        .is_some_and(|latest| now_secs().saturating_sub(*latest) < MIN_INTERVAL_SECS);
+    if recent && !destructive {
+        return;
+    }
+    if let Err(e) = write_snapshot(&content) {
+        eprintln!("[Lovcode] Failed to snapshot workspace: {}", e);
+    }
+}
+
+/// Snapshots, newest first
+pub fn list() -> Vec<SnapshotInfo> {
+    timestamps()
+        .into_iter()
+        .rev()
+        .filter_map(|timestamp| {
+            let path = snapshot_path(timestamp);
+            let size = fs::metadata(&path).ok()?.len();
+            let data = load(timestamp).ok()?;
+            let (project_count, feature_count) = counts(&data);
+            Some(SnapshotInfo {
+                timestamp,
+                size,
+                project_count,
+                feature_count,
+            })
+        })
+        .collect()
+}
+
+fn load(timestamp: u64) -> Result<WorkspaceData, String> {
+    let content = fs::read_to_string(snapshot_path(timestamp))
+        .map_err(|_| format!("Workspace snapshot {} not found", timestamp))?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+/// Features of a workspace by id, with their project
+fn features_by_id(data: &WorkspaceData) -> HashMap<&str, (FeatureRef, &Feature)> {
+    data.projects
+        .iter()
+        .flat_map(|project| {
+            project.features.iter().map(move |feature| {
+                let feature_ref = FeatureRef {
+                    project_id: project.id.clone(),
+                    project_name: project.name.clone(),
+                    feature_id: feature.id.clone(),
+                    name: feature.name.clone(),
+                };
+                (feature.id.as_str(), (feature_ref, feature))
+            })
+        })
+        .collect()
+}
+
+/// Status as stored, e.g. "needs-review"
+fn status_label(status: &FeatureStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn describe_changes(current: &Feature, snapshot: &Feature) -> Vec<String> {
+    let mut changes = Vec::new();
+    if current.name != snapshot.name {
+        changes.push(format!("name: {} → {}", current.name, snapshot.name));
+    }
+    if current.status != snapshot.status {
+        changes.push(format!(
+            "status: {} → {}",
+            status_label(&current.status),
+            status_label(&snapshot.status)
+        ));
+    }
+    if current.archived.unwrap_or(false) != snapshot.archived.unwrap_or(false) {
+        changes.push(if snapshot.archived.unwrap_or(false) {
+            "archived".to_string()
+        } else {
+            "unarchived".to_string()
+        });
+    }
+    if current.description != snapshot.description {
+        changes.push("description".to_string());
+    }
+    if current.panels.len() != snapshot.panels.len() {
+        changes.push(format!(
+            "panels: {} → {}",
+            current.panels.len(),
+            snapshot.panels.len()
+        ));
+    }
+    changes
+}
+
+/// Compare the current workspace with a snapshot
+pub fn preview(timestamp: u64) -> Result<SnapshotDiff, String> {
+    let snapshot = load(timestamp)?;
+    let current = workspace_store::load_workspace()?;
+    let mut diff = SnapshotDiff {
+        timestamp,
+        ..Default::default()
+    };
+
+    let has_project = |data: &WorkspaceData, id: &str| data.projects.iter().any(|p| p.id == id);
+    for project in &snapshot.projects {
+        if !has_project(&current, &project.id) {
+            diff.projects_restored.push(project.name.clone());
+        }
+    }
+    for project in &current.projects {
+        if !has_project(&snapshot, &project.id) {
+            diff.projects_dropped.push(project.name.clone());
+        }
+    }
+
+    let current_features = features_by_id(&current);
+    let snapshot_features = features_by_id(&snapshot);
+    for (id, (feature_ref, feature)) in &snapshot_features {
+        match current_features.get(id) {
+            None => diff.features_restored.push(feature_ref.clone()),
+            Some((_, now)) => {
+                let changes = describe_changes(now, feature);
+                if !changes.is_empty() {
+                    diff.features_changed.push(FeatureChange {
+                        feature: feature_ref.clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    for (id, (feature_ref, _)) in &current_features {
+        if !snapshot_features.contains_key(id) {
+            diff.features_dropped.push(feature_ref.clone());
+        }
+    }
+
+    let order = |f: &FeatureRef| (f.project_name.clone(), f.name.clone());
+    diff.features_restored.sort_by_key(order);
+    diff.features_dropped.sort_by_key(order);
+    diff.features_changed.sort_by_key(|c| order(&c.feature));
+    Ok(diff)
+}
+
+/// Replace the workspace with a snapshot; the current state is snapshotted first
+/// so the restore itself can be undone
+pub fn restore(timestamp: u64) -> Result<WorkspaceData, String> {
+    let data = load(timestamp)?;
+    if let Some(current) = workspace_store::current_content() {
+        write_snapshot(&current)?;
+    }
+    workspace_store::save_workspace(&data)?;
+    Ok(data)
+}
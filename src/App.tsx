@@ -21,10 +21,12 @@ import { listen } from "@tauri-apps/api/event";
 // Modular imports
 import type {
   FeatureType, FeatureConfig, View, LocalCommand,
-  TemplatesCatalog, UserProfile,
+  TemplatesCatalog, UserProfile, LaunchTarget, CustomizationsChanged,
+  ProjectsChanged, SessionUpdated,
 } from "./types";
+import { useQueryClient } from "./hooks";
 import { useAtom } from "jotai";
-import { sidebarCollapsedAtom, marketplaceCategoryAtom, shortenPathsAtom, profileAtom, navigationStateAtom, viewAtom, viewHistoryAtom, historyIndexAtom } from "./store";
+import { sidebarCollapsedAtom, marketplaceCategoryAtom, shortenPathsAtom, accessibleModeAtom, profileAtom, memoryBudgetAtom, navigationStateAtom, viewAtom, viewHistoryAtom, historyIndexAtom } from "./store";
 import { AppConfigContext, useAppConfig, type AppConfig } from "./context";
 import { FEATURES, FEATURE_ICONS } from "./constants";
 // Modular views
@@ -106,6 +108,8 @@ function App() {
   const [catalog, setCatalog] = useState<TemplatesCatalog | null>(null);
   const [homeDir, setHomeDir] = useState("");
   const [shortenPaths, setShortenPaths] = useAtom(shortenPathsAtom);
+  const [accessibleMode, setAccessibleMode] = useAtom(accessibleModeAtom);
+  const [memoryBudget, setMemoryBudget] = useAtom(memoryBudgetAtom);
   const [showSettings, setShowSettings] = useState(false);
   const [profile, setProfile] = useAtom(profileAtom);
   const [showProfileDialog, setShowProfileDialog] = useState(false);
@@ -122,6 +126,51 @@ function App() {
     return () => { unlisten.then(fn => fn()); };
   }, []);
 
+  // Project/session passed on the command line, or opened from Finder while running
+  useEffect(() => {
+    const openLaunchTarget = () => {
+      invoke<LaunchTarget | null>("take_launch_target").then((target) => {
+        if (target?.type === "project") {
+          navigate({ type: "workspace", projectId: target.workspace_project_id });
+        } else if (target?.type === "session") {
+          navigate({ type: "chat-messages", projectId: target.project_id, sessionId: target.session_id, summary: target.summary });
+        }
+      }).catch(() => {});
+    };
+    openLaunchTarget();
+    const unlisten = listen("launch-target", openLaunchTarget);
+    return () => { unlisten.then(fn => fn()); };
+  }, [navigate]);
+
+  // Commands/agents/skills edited in an external editor
+  const queryClient = useQueryClient();
+  useEffect(() => {
+    const unlisten = listen<CustomizationsChanged>("customizations-changed", (event) => {
+      for (const kind of event.payload.kinds) {
+        queryClient.invalidateQueries({ queryKey: [kind] });
+      }
+    });
+    return () => { unlisten.then(fn => fn()); };
+  }, [queryClient]);
+
+  // Sessions written by Claude Code outside the app
+  useEffect(() => {
+    const unlistenProjects = listen<ProjectsChanged>("projects-changed", () => {
+      queryClient.invalidateQueries({ queryKey: ["projects"] });
+      queryClient.invalidateQueries({ queryKey: ["sessions"] });
+      queryClient.invalidateQueries({ queryKey: ["chats"] });
+    });
+    const unlistenSession = listen<SessionUpdated>("session-updated", (event) => {
+      queryClient.invalidateQueries({ queryKey: ["sessions"], exact: true });
+      queryClient.invalidateQueries({ queryKey: ["sessions", event.payload.project_id] });
+      queryClient.invalidateQueries({ queryKey: ["chats"] });
+    });
+    return () => {
+      unlistenProjects.then(fn => fn());
+      unlistenSession.then(fn => fn());
+    };
+  }, [queryClient]);
+
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
       if ((e.metaKey || e.ctrlKey) && e.key === "r") {
@@ -141,7 +190,20 @@ function App() {
     return path;
   }, [shortenPaths, homeDir]);
 
-  const appConfig: AppConfig = { homeDir, shortenPaths, setShortenPaths, formatPath };
+  const appConfig: AppConfig = {
+    homeDir,
+    shortenPaths,
+    setShortenPaths,
+    accessibleMode,
+    setAccessibleMode,
+    memoryBudget,
+    setMemoryBudget,
+    formatPath,
+  };
+
+  useEffect(() => {
+    invoke("set_memory_budget", { budget: memoryBudget }).catch(() => {});
+  }, [memoryBudget]);
 
   useEffect(() => {
     invoke<TemplatesCatalog>("get_templates_catalog").then(setCatalog).catch(() => {});
@@ -250,7 +312,7 @@ function App() {
           <Home
             onFeatureClick={handleFeatureClick}
             onProjectClick={(p) => navigate({ type: "chat-sessions", projectId: p.id, projectPath: p.path })}
-            onSessionClick={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.summary })}
+            onSessionClick={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.title ?? s.summary })}
             onSearch={() => navigate({ type: "chat-projects" })}
           />
         )}
@@ -259,7 +321,7 @@ function App() {
         {view.type === "chat-projects" && (
           <ProjectList
             onSelectProject={(p) => navigate({ type: "chat-sessions", projectId: p.id, projectPath: p.path })}
-            onSelectSession={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.summary })}
+            onSelectSession={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.title ?? s.summary })}
             onSelectChat={(c) => navigate({ type: "chat-messages", projectId: c.project_id, sessionId: c.session_id, summary: c.session_summary })}
           />
         )}
@@ -268,7 +330,7 @@ function App() {
             projectId={view.projectId}
             projectPath={view.projectPath}
             onBack={() => navigate({ type: "chat-projects" })}
-            onSelect={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.summary })}
+            onSelect={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.title ?? s.summary })}
           />
         )}
         {view.type === "chat-messages" && (
@@ -574,7 +636,7 @@ function App() {
           <Home
             onFeatureClick={handleFeatureClick}
             onProjectClick={(p) => navigate({ type: "chat-sessions", projectId: p.id, projectPath: p.path })}
-            onSessionClick={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.summary })}
+            onSessionClick={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.title ?? s.summary })}
             onSearch={() => navigate({ type: "chat-projects" })}
           />
         )}
@@ -584,7 +646,7 @@ function App() {
         {view.type === "chat-projects" && (
           <ProjectList
             onSelectProject={(p) => navigate({ type: "chat-sessions", projectId: p.id, projectPath: p.path })}
-            onSelectSession={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.summary })}
+            onSelectSession={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.title ?? s.summary })}
             onSelectChat={(c) => navigate({ type: "chat-messages", projectId: c.project_id, sessionId: c.session_id, summary: c.session_summary })}
           />
         )}
@@ -594,7 +656,7 @@ function App() {
             projectId={view.projectId}
             projectPath={view.projectPath}
             onBack={() => navigate({ type: "chat-projects" })}
-            onSelect={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.summary })}
+            onSelect={(s) => navigate({ type: "chat-messages", projectId: s.project_id, sessionId: s.id, summary: s.title ?? s.summary })}
           />
         )}
 
@@ -772,7 +834,7 @@ function App() {
 // ============================================================================
 
 function AppSettingsDialog({ open, onClose }: { open: boolean; onClose: () => void }) {
-  const { shortenPaths, setShortenPaths } = useAppConfig();
+  const { shortenPaths, setShortenPaths, accessibleMode, setAccessibleMode } = useAppConfig();
   const [autoCopy, setAutoCopy] = useState(getAutoCopyOnSelect);
 
   const handleAutoCopyChange = (checked: boolean) => {
@@ -801,6 +863,13 @@ function AppSettingsDialog({ open, onClose }: { open: boolean; onClose: () => vo
               </div>
               <Switch checked={shortenPaths} onCheckedChange={setShortenPaths} />
             </div>
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="text-sm font-medium text-ink">Screen reader mode</p>
+                <p className="text-xs text-muted-foreground">Plain-text transcripts and data tables</p>
+              </div>
+              <Switch checked={accessibleMode} onCheckedChange={setAccessibleMode} />
+            </div>
           </div>
           {/* Terminal */}
           <div className="space-y-3">
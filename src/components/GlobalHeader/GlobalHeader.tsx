@@ -10,6 +10,7 @@ import { Avatar, AvatarImage, AvatarFallback } from "../ui/avatar";
 import { Popover, PopoverTrigger, PopoverContent } from "../ui/popover";
 import { sidebarCollapsedAtom, profileAtom, workspaceDataAtom, primaryFeatureAtom } from "@/store";
 import { GlobalFeatureTabs } from "./GlobalFeatureTabs";
+import { HealthIndicator } from "./HealthIndicator";
 import type { View, FeatureType } from "@/types";
 
 interface GlobalHeaderProps {
@@ -178,7 +179,8 @@ function ProfileMenu({
   onFeatureClick?: (feature: FeatureType) => void;
 }) {
   return (
-    <div className="pr-4">
+    <div className="pr-4 flex items-center gap-2">
+      <HealthIndicator />
       <Popover>
         <PopoverTrigger className="rounded-full hover:ring-2 hover:ring-primary/50 transition-all">
           <Avatar className="h-6 w-6 cursor-pointer">
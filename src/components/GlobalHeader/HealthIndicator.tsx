@@ -0,0 +1,36 @@
+import { useQuery } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { Popover, PopoverTrigger, PopoverContent } from "../ui/popover";
+import type { RuntimeHealth } from "@/types";
+
+/** Amber dot in the header while any background subsystem is degraded */
+export function HealthIndicator() {
+  const { data: health } = useQuery({
+    queryKey: ["runtimeHealth"],
+    queryFn: () => invoke<RuntimeHealth>("get_runtime_health"),
+    refetchInterval: 60_000,
+  });
+
+  if (!health || health.status === "ok") return null;
+
+  return (
+    <Popover>
+      <PopoverTrigger
+        className="p-1.5 rounded-md hover:bg-card-alt"
+        title="Some background services need attention"
+      >
+        <span className="block w-2 h-2 rounded-full bg-amber-500" />
+      </PopoverTrigger>
+      <PopoverContent align="end" className="w-72 p-3">
+        <p className="text-sm font-medium text-ink mb-2">Degraded</p>
+        <ul className="space-y-1.5">
+          {health.degraded.map((item, i) => (
+            <li key={i} className="text-xs text-muted-foreground">
+              <span className="font-mono text-ink">{item.subsystem}</span> · {item.message}
+            </li>
+          ))}
+        </ul>
+      </PopoverContent>
+    </Popover>
+  );
+}
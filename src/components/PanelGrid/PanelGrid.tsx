@@ -22,6 +22,8 @@ export interface PanelState {
 
 export interface PanelGridProps {
   panels: PanelState[];
+  /** Feature the panels belong to; new terminals get its env overrides */
+  featureId?: string;
   layout?: LayoutNode;
   activePanelId?: string;
   onPanelFocus?: (id: string) => void;
@@ -44,6 +46,7 @@ export interface PanelGridProps {
 function LayoutRenderer({
   node,
   panels,
+  featureId,
   activePanelId,
   onPanelFocus,
   onPanelClose,
@@ -57,6 +60,7 @@ function LayoutRenderer({
 }: {
   node: LayoutNode;
   panels: PanelState[];
+  featureId?: string;
   activePanelId?: string;
   onPanelFocus?: (id: string) => void;
   onPanelClose: (id: string) => void;
@@ -81,6 +85,7 @@ function LayoutRenderer({
         <SessionPanel
           isActive={isActive}
           panel={panel}
+          featureId={featureId}
           showSplitActions
           onPanelSplit={(dir) => onPanelSplit(panel.id, dir)}
           onPanelClose={() => onPanelClose(panel.id)}
@@ -102,6 +107,7 @@ function LayoutRenderer({
         <LayoutRenderer
           node={node.first}
           panels={panels}
+          featureId={featureId}
           activePanelId={activePanelId}
           onPanelFocus={onPanelFocus}
           onPanelClose={onPanelClose}
@@ -118,6 +124,7 @@ function LayoutRenderer({
         <LayoutRenderer
           node={node.second}
           panels={panels}
+          featureId={featureId}
           activePanelId={activePanelId}
           onPanelFocus={onPanelFocus}
           onPanelClose={onPanelClose}
@@ -137,6 +144,7 @@ function LayoutRenderer({
 export function PanelGrid({
   panels,
   layout,
+  featureId,
   activePanelId: controlledActivePanelId,
   onPanelFocus: controlledOnPanelFocus,
   onPanelClose,
@@ -189,6 +197,7 @@ export function PanelGrid({
         <LayoutRenderer
           node={layout}
           panels={panels}
+          featureId={featureId}
           activePanelId={activePanelId}
           onPanelFocus={handlePanelFocus}
           onPanelClose={onPanelClose}
@@ -218,6 +227,7 @@ export function PanelGrid({
               <SessionPanel
                 isActive={isActive}
                 panel={panel}
+                featureId={featureId}
                 showSplitActions
                 onPanelSplit={(dir) => onPanelSplit(panel.id, dir)}
                 onPanelClose={() => onPanelClose(panel.id)}
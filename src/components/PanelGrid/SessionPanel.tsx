@@ -67,6 +67,8 @@ function EditableTabTitle({
 
 export interface SessionPanelProps {
   panel: PanelState;
+  /** Feature owning the panel, passed to new PTYs for its env overrides */
+  featureId?: string;
   /** Whether this panel has global focus */
   isActive?: boolean;
   /** Show expand/collapse toggle */
@@ -94,6 +96,7 @@ export interface SessionPanelProps {
 
 export const SessionPanel = memo(function SessionPanel({
   panel,
+  featureId,
   isActive = false,
   collapsible: _collapsible = false,
   isExpanded = true,
@@ -221,6 +224,7 @@ export const SessionPanel = memo(function SessionPanel({
                 ptyId={session.ptyId}
                 cwd={panel.cwd}
                 command={session.command}
+                featureId={featureId}
                 autoFocus={session.id === panel.activeSessionId && isActive}
                 onTitleChange={handleTitleChange(session.id)}
                 onExit={() => onSessionClose(session.id)}
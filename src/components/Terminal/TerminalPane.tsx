@@ -26,6 +26,8 @@ export interface TerminalPaneProps {
   cwd: string;
   /** Optional command to run instead of shell */
   command?: string;
+  /** Feature whose env overrides are injected into the shell */
+  featureId?: string;
   /** Auto focus terminal when ready */
   autoFocus?: boolean;
   /** Callback when terminal is ready */
@@ -42,6 +44,7 @@ export function TerminalPane({
   ptyId,
   cwd,
   command,
+  featureId,
   autoFocus = false,
   onReady,
   onExit,
@@ -51,6 +54,7 @@ export function TerminalPane({
   const containerRef = useRef<HTMLDivElement>(null);
   const cwdRef = useRef(cwd);
   const commandRef = useRef(command);
+  const featureIdRef = useRef(featureId);
   const autoFocusRef = useRef(autoFocus);
   const onReadyRef = useRef(onReady);
   const onExitRef = useRef(onExit);
@@ -141,7 +145,12 @@ export function TerminalPane({
 
         if (!exists) {
           console.log('[DEBUG][TerminalPane] initPty: creating new PTY');
-          await invoke("pty_create", { id: sessionId, cwd: cwdRef.current, command: commandRef.current });
+          await invoke("pty_create", {
+            id: sessionId,
+            cwd: cwdRef.current,
+            command: commandRef.current,
+            featureId: featureIdRef.current,
+          });
         }
 
         // Replay scrollback buffer (works for both page refresh and app restart)
@@ -94,7 +94,7 @@ export function RecentActivity({
               <MessageSquare className="w-4 h-4 text-muted-foreground/70 shrink-0" />
               <div className="flex-1 min-w-0">
                 <p className="text-sm text-foreground truncate group-hover:text-primary transition-colors">
-                  {session.summary || "Untitled session"}
+                  {session.title || session.summary || "Untitled session"}
                 </p>
                 <p className="text-xs text-muted-foreground">
                   {session.message_count} messages
@@ -1,9 +1,16 @@
 import { createContext, useContext } from "react";
+import type { MemoryBudget } from "@/types";
 
 export interface AppConfig {
   homeDir: string;
   shortenPaths: boolean;
   setShortenPaths: (value: boolean) => void;
+  /** Screen-reader friendly rendering; commands return text/table shapes */
+  accessibleMode: boolean;
+  setAccessibleMode: (value: boolean) => void;
+  /** Cache limits enforced by the backend memory monitor */
+  memoryBudget: MemoryBudget;
+  setMemoryBudget: (value: MemoryBudget) => void;
   formatPath: (path: string) => string;
 }
 
@@ -11,6 +18,10 @@ export const AppConfigContext = createContext<AppConfig>({
   homeDir: "",
   shortenPaths: true,
   setShortenPaths: () => {},
+  accessibleMode: false,
+  setAccessibleMode: () => {},
+  memoryBudget: { total_mb: 1024, scrollback_mb: 32, session_cache_mb: 64 },
+  setMemoryBudget: () => {},
   formatPath: (p) => p,
 });
 
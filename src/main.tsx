@@ -1,6 +1,7 @@
 import ReactDOM from "react-dom/client";
 import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
 import { loader } from "@monaco-editor/react";
+import { emit } from "@tauri-apps/api/event";
 import App from "./App";
 import "./index.css";
 
@@ -23,3 +24,10 @@ ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
     <App />
   </QueryClientProvider>,
 );
+
+// Let the backend start watchers and cache refreshes once the first frame is painted
+requestAnimationFrame(() => {
+  requestAnimationFrame(() => {
+    emit("app-ready");
+  });
+});
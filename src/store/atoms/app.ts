@@ -1,5 +1,5 @@
 import { atomWithStorage } from "jotai/utils";
-import type { TemplateCategory, UserProfile } from "@/types";
+import type { MemoryBudget, TemplateCategory, UserProfile } from "@/types";
 
 // 侧边栏折叠状态
 export const sidebarCollapsedAtom = atomWithStorage("lovcode:sidebarCollapsed", false);
@@ -10,5 +10,11 @@ export const marketplaceCategoryAtom = atomWithStorage<TemplateCategory>("lovcod
 // 路径缩短显示
 export const shortenPathsAtom = atomWithStorage("lovcode:shortenPaths", true);
 
+// 屏幕阅读器友好模式
+export const accessibleModeAtom = atomWithStorage("lovcode:accessibleMode", false);
+
 // 用户档案
 export const profileAtom = atomWithStorage<UserProfile>("lovcode:profile", { nickname: "", avatarUrl: "" });
+
+// 内存预算
+export const memoryBudgetAtom = atomWithStorage<MemoryBudget>("lovcode:memoryBudget", { total_mb: 1024, scrollback_mb: 32, session_cache_mb: 64 });
@@ -12,5 +12,5 @@ export const userPromptsOnlyAtom = atomWithStorage("lovcode:userPromptsOnly", fa
 
 // ProjectList
 export const chatViewModeAtom = atomWithStorage<"projects" | "sessions" | "chats">("lovcode:chatViewMode", "projects");
-export const allProjectsSortByAtom = atomWithStorage<"name" | "recent" | "sessions">("lovcode:allProjects:sortBy", "recent");
+export const allProjectsSortByAtom = atomWithStorage<"name" | "recent" | "sessions" | "size">("lovcode:allProjects:sortBy", "recent");
 export const hideEmptySessionsAllAtom = atomWithStorage("lovcode-hide-empty-sessions-all", false);
@@ -35,6 +35,57 @@ export interface Project {
   path: string;
   session_count: number;
   last_active: number;
+  pinned: boolean;
+  hidden: boolean;
+  archived: boolean;
+}
+
+/** `list_projects` leaves out hidden and archived projects unless asked */
+export interface ProjectFilters {
+  include_hidden?: boolean;
+  include_archived?: boolean;
+  /** Only archived projects */
+  archived_only?: boolean;
+}
+
+export interface ProjectUserMeta {
+  pinned: boolean;
+  hidden: boolean;
+  archived: boolean;
+  updated_at: number;
+}
+
+export interface RelocationResult {
+  old_project_id: string;
+  new_project_id: string;
+  new_path: string;
+  /** Files and dirs moved into the new project dir */
+  moved: number;
+  /** Names already in the new project dir, left in the old one */
+  conflicts: string[];
+  /** The new project dir already had history */
+  merged: boolean;
+}
+
+/** Sidebar category of Claude projects (not a workspace group) */
+export interface ProjectGroup {
+  id: string;
+  name: string;
+  color: string | null;
+  collapsed: boolean;
+  project_ids: string[];
+  created_at: number;
+}
+
+export interface ProjectGroupListing {
+  group: ProjectGroup;
+  projects: Project[];
+}
+
+export interface GroupedProjects {
+  groups: ProjectGroupListing[];
+  /** Projects in no group */
+  ungrouped: Project[];
 }
 
 export interface Session {
@@ -44,6 +95,148 @@ export interface Session {
   summary: string | null;
   message_count: number;
   last_modified: number;
+  /** Session file size on disk */
+  size_bytes: number;
+  /** Rough token count of message content (~4 chars per token) */
+  estimated_tokens: number;
+  topics: string[];
+  language: string | null;
+  starred: boolean;
+  tags: string[];
+  note: string | null;
+  /** Custom title; show instead of summary when set */
+  title: string | null;
+  annotation_count: number;
+  usage: SessionUsage | null;
+  /** Sessions this one continues, is continued in, or forks from */
+  related_sessions: RelatedSession[];
+}
+
+export type SessionRelation = "continued_from" | "continued_in" | "fork";
+
+export interface RelatedSession {
+  session_id: string;
+  relation: SessionRelation;
+  /** "resumed", "shared summary" or "same opening" */
+  reason: string;
+}
+
+export interface TokenUsage {
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_input_tokens: number;
+  cache_read_input_tokens: number;
+}
+
+export interface ModelUsage {
+  model: string;
+  usage: TokenUsage;
+  /** null when no price is known for the model */
+  cost: number | null;
+}
+
+export interface SessionUsage {
+  total: TokenUsage;
+  /** Estimated cost in USD */
+  cost: number;
+  by_model: ModelUsage[];
+}
+
+export type UsagePeriod = "day" | "week" | "month" | "year" | "all";
+
+export interface DailyUsage {
+  /** Local date, YYYY-MM-DD */
+  date: string;
+  usage: TokenUsage;
+  cost: number;
+}
+
+export interface ProjectUsage {
+  project_id: string;
+  period: UsagePeriod;
+  /** Totals and cost by model over the period */
+  total: SessionUsage;
+  /** Days with usage, oldest first */
+  by_day: DailyUsage[];
+  /** Sessions with usage in the period */
+  sessions: number;
+}
+
+export interface NamedCount {
+  name: string;
+  count: number;
+}
+
+export interface ProjectOverview {
+  project_id: string;
+  project_path: string;
+  session_count: number;
+  /** Sessions covered by usage, commands and tools */
+  analyzed_sessions: number;
+  total_messages: number;
+  estimated_tokens: number;
+  usage: SessionUsage;
+  /** By number of sessions invoking the command */
+  top_commands: NamedCount[];
+  /** By number of calls */
+  top_tools: NamedCount[];
+  last_active: number;
+  /** CLAUDE.md in the project root or .claude/, if any */
+  claude_md: string | null;
+}
+
+/** USD per million tokens */
+export interface ModelPrice {
+  input: number;
+  output: number;
+  cache_write: number;
+  cache_read: number;
+}
+
+export interface SessionUserMeta {
+  starred: boolean;
+  tags: string[];
+  note: string | null;
+  title: string | null;
+  updated_at: number;
+}
+
+export interface TagCount {
+  tag: string;
+  count: number;
+}
+
+export interface SessionFilters {
+  topic?: string;
+  has_errors?: boolean;
+  used_command?: string;
+  model?: string;
+  longer_than?: number;
+  touched_file?: string;
+  untagged?: boolean;
+  starred?: boolean;
+  tag?: string;
+  /** Only sessions with (true) or without (false) annotations */
+  annotated?: boolean;
+}
+
+/** Review comment on a message, stored outside the session file */
+export interface Annotation {
+  id: string;
+  message_uuid: string;
+  text: string;
+  created_at: number;
+  updated_at: number;
+}
+
+/** Server-side filter for get_session_messages; paging counts filtered messages */
+export interface MessageFilter {
+  roles?: string[];
+  /** default true */
+  include_meta?: boolean;
+  /** default true; false also drops tool calls from kept messages */
+  include_tools?: boolean;
+  text_only?: boolean;
 }
 
 export interface Message {
@@ -54,6 +247,133 @@ export interface Message {
   is_meta: boolean;
   is_tool: boolean;
   line_number: number;
+  /** Tool calls issued by this message */
+  tools: ToolCall[];
+  /** Extended-thinking text, present when requested with includeThinking */
+  thinking: string | null;
+  /** Summary a compacted conversation continues from */
+  is_compact_summary: boolean;
+  /** Images, documents and `@file` references (filled for session pages) */
+  attachments: Attachment[];
+}
+
+export type AttachmentKind = "image" | "document" | "file";
+
+/** Inline data is cached as a file under ~/.lovstudio/lovcode/attachments */
+export interface Attachment {
+  kind: AttachmentKind;
+  media_type: string | null;
+  /** Cached copy of inline data, or the referenced file */
+  path: string | null;
+  name: string;
+  size_bytes: number | null;
+  /** Inside a tool result rather than the message itself */
+  from_tool: boolean;
+  exists: boolean;
+}
+
+export interface AttachmentCacheStats {
+  files: number;
+  bytes: number;
+}
+
+export interface ToolCall {
+  id: string;
+  name: string;
+  input: unknown;
+  status: "pending" | "success" | "error";
+  result: string | null;
+  result_truncated: boolean;
+  /** Time from the tool_use line to its tool_result line */
+  duration_ms: number | null;
+}
+
+export interface MessagesPage {
+  items: Message[];
+  total: number;
+  offset: number;
+  usage: SessionUsage;
+  /** Items as labelled plain text, when requested with accessible: true */
+  transcript: string | null;
+  /** Stretches of the whole session between compactions */
+  segments: SessionSegment[];
+}
+
+export interface CompactBoundary {
+  line_number: number;
+  timestamp: string | null;
+  /** "auto" or "manual" */
+  trigger: string | null;
+  /** Context size when it was compacted */
+  pre_tokens: number | null;
+  /** Start of the summary the conversation continued from */
+  summary: string | null;
+}
+
+export interface SessionSegment {
+  index: number;
+  /** null for the first segment */
+  boundary: CompactBoundary | null;
+  /** Index of the first message of the segment in the (filtered) session */
+  start_index: number;
+  start_line: number;
+  message_count: number;
+  user_prompts: number;
+  tool_calls: number;
+  output_tokens: number;
+  first_timestamp: string | null;
+  last_timestamp: string | null;
+}
+
+export interface TreeNode {
+  uuid: string;
+  parent_uuid: string | null;
+  children: string[];
+  kind: "user" | "assistant" | "tool" | "meta" | "other";
+  preview: string;
+  timestamp: string | null;
+  line_number: number;
+  on_active_path: boolean;
+}
+
+/** Message DAG of a session built from parentUuid links */
+export interface ConversationTree {
+  /** Nodes in file order */
+  nodes: TreeNode[];
+  roots: string[];
+  /** Nodes with more than one child */
+  branch_points: string[];
+  leaves: string[];
+  /** Root-to-leaf uuids of the branch written last */
+  active_path: string[];
+}
+
+/** Subagent transcript spawned from a main session */
+export interface SessionSidechain {
+  /** File stem, e.g. "agent-a1b2c3" */
+  id: string;
+  parent_session_id: string;
+  /** First prompt given to the agent */
+  summary: string | null;
+  message_count: number;
+  first_timestamp: string | null;
+  last_modified: number;
+}
+
+/** Payload of the "session-message-appended" event */
+export interface SessionAppendedEvent {
+  project_id: string;
+  session_id: string;
+  messages: Message[];
+}
+
+/** Payload of the "session-messages-batch" event */
+export interface SessionMessagesBatch {
+  stream_id: string;
+  items: Message[];
+  loaded: number;
+  done: boolean;
+  error: string | null;
 }
 
 export interface ChatMessage {
@@ -77,11 +397,40 @@ export interface SearchResult {
   session_summary: string | null;
   timestamp: string;
   score: number;
+  parent_session_id: string | null;
+}
+
+/** Result of locate_message */
+export interface MessageLocation {
+  uuid: string;
+  project_id: string;
+  /** Main session; the parent when the message is inside a sidechain */
+  session_id: string;
+  sidechain_id: string | null;
+  /** Offset for get_session_messages; null for lines not shown as messages */
+  index: number | null;
+  line_number: number;
+  byte_offset: number;
+}
+
+export interface QueryClause {
+  field?: string;
+  text: string;
+  phrase?: boolean;
+}
+
+export interface StructuredQuery {
+  must?: QueryClause[];
+  should?: QueryClause[];
+  must_not?: QueryClause[];
 }
 
 export interface ChatsResponse {
   items: ChatMessage[];
+  /** Approximate number of messages across all sessions */
   total: number;
+  /** Pass back as `cursor` for the next page; null after the last one */
+  next_cursor: string | null;
 }
 
 export interface LocalCommand {
@@ -96,9 +445,159 @@ export interface LocalCommand {
   deprecated_by: string | null;
   changelog: string | null;
   aliases: string[];
+  /** From the `tags:` frontmatter field */
+  tags: string[];
   frontmatter: string | null;
 }
 
+export interface CommandSearchResult {
+  command: LocalCommand;
+  score: number;
+  /** Best matching passage of the body, empty when only other fields matched */
+  snippet: string;
+}
+
+/** Filters for list_local_commands / list_commands_by_tag; all commands by default */
+export interface CommandFilters {
+  /** Commands with any of these tags (case-insensitive) */
+  tags?: string[];
+  status?: "active" | "deprecated" | "archived";
+}
+
+export interface CommandsByTag {
+  groups: { tag: string; commands: LocalCommand[] }[];
+  untagged: LocalCommand[];
+}
+
+/** Changes for update_command; omitted fields are left as is, "" removes a field */
+export interface CommandUpdate {
+  description?: string;
+  allowed_tools?: string;
+  argument_hint?: string;
+  version?: string;
+  body?: string;
+}
+
+/** Previous revision of a command, from list_command_versions */
+export interface CommandVersion {
+  path: string;
+  /** snapshot: taken automatically on save; archived: via archive_command */
+  kind: "snapshot" | "archived";
+  version: string | null;
+  /** Unix ms */
+  created_at: number;
+  size_bytes: number;
+}
+
+export interface CommandIssue {
+  severity: "error" | "warning" | "hint";
+  /** Stable identifier, e.g. "unknown_key" */
+  code: string;
+  message: string;
+  /** 1-based line in the file */
+  line: number | null;
+}
+
+/** Result of validate_command / validate_agent; hints are not counted */
+export interface CommandValidation {
+  path: string;
+  issues: CommandIssue[];
+  errors: number;
+  warnings: number;
+}
+
+/** Usage of one command from get_command_usage */
+export interface CommandUsage {
+  /** Without the leading slash, as in get_command_stats */
+  name: string;
+  total: number;
+  by_project: Record<string, number>;
+  /** ISO week ("2025-W07") → uses */
+  by_week: Record<string, number>;
+  /** Month ("2025-02") → uses */
+  by_month: Record<string, number>;
+  /** Previous name (alias) → uses under it, included in total */
+  by_alias: Record<string, number>;
+  first_used: number;
+  last_used: number;
+}
+
+/** Launches of one subagent type from get_agent_stats; by_alias is empty */
+export type AgentUsage = CommandUsage;
+
+/** Command returned by find_stale_commands */
+export interface StaleCommand {
+  command: LocalCommand;
+  total: number;
+  /** Unix seconds; null when never used or the time is unknown */
+  last_used: number | null;
+}
+
+/** Shell snippet (!`...`) of a command preview */
+export interface ShellSnippet {
+  command: string;
+  /** stdout then stderr; null in a dry run */
+  output: string | null;
+  exit_code: number | null;
+  timed_out: boolean;
+}
+
+/** File referenced with @ in a command preview */
+export interface IncludedFile {
+  reference: string;
+  path: string | null;
+  content: string | null;
+  error: string | null;
+}
+
+/** Result of render_command_preview */
+export interface CommandPreview {
+  name: string;
+  prompt: string;
+  shell: ShellSnippet[];
+  includes: IncludedFile[];
+  dry_run: boolean;
+}
+
+/** One of the commands answering to a conflicting name */
+export interface CommandSource {
+  kind: "project" | "global" | "plugin";
+  plugin: string | null;
+  name: string;
+  path: string;
+}
+
+/** Result of find_command_conflicts; sources[0] is the one Claude Code runs */
+export interface CommandConflict {
+  invoked_as: string;
+  sources: CommandSource[];
+}
+
+/** Where a command file is created */
+export type CommandScope =
+  | { type: "global" }
+  | { type: "project"; project_path: string };
+
+/** Command moved to the lovcode trash by delete_command */
+export interface DeletedCommand {
+  id: string;
+  /** Command name when it was deleted, e.g. /foo/bar */
+  name: string;
+  /** Where the .md file was; restore puts it back here */
+  path: string;
+  files: TrashedFile[];
+  deleted_at: number;
+  expires_at: number;
+}
+
+/** A file of a trash entry */
+export interface TrashedFile {
+  /** Original path; restore puts it back here */
+  path: string;
+  /** Name inside the trash entry */
+  stored: string;
+}
+
 export interface LocalAgent {
   name: string;
   path: string;
@@ -106,13 +605,115 @@ export interface LocalAgent {
   model: string | null;
   tools: string | null;
   content: string;
+  frontmatter: string | null;
+  status: "active" | "deprecated";
+  deprecated_by: string | null;
+  changelog: string | null;
+}
+
+/** Agents of one scope from list_agents_by_scope */
+export interface ScopedAgents {
+  scope: CommandScope;
+  /** "Global", or the workspace project's name */
+  label: string;
+  agents: LocalAgent[];
+}
+
+/** New agent for create_agent; optional fields are omitted when empty */
+export interface AgentDraft {
+  name: string;
+  description: string;
+  tools?: string | null;
+  /** sonnet, opus, haiku, inherit or a claude-* model id */
+  model?: string | null;
+  color?: string | null;
+  body: string;
+}
+
+/** Fields to change with update_agent; omitted fields stay, "" removes one */
+export interface AgentUpdate {
+  description?: string;
+  tools?: string;
+  model?: string;
+  color?: string;
+  body?: string;
 }
 
+/** Marketplace agent installed with install_agent_template */
+export interface AgentInstall {
+  template: string;
+  /** Agent name it was installed as, `<template>-<n>` on a name clash */
+  name: string;
+  path: string;
+  scope: CommandScope;
+  content_hash: number;
+  installed_at: number;
+  updated_at: number;
+  /** Edited since it was installed */
+  modified: boolean;
+}
+
+/** Agent moved to the lovcode trash by delete_agent */
+export type DeletedAgent = DeletedCommand;
+
+/** Marketplace skill directory installed with install_skill_template */
+export interface SkillInstall {
+  source_id: string;
+  plugin: string | null;
+  /** Template path it was installed from */
+  source_path: string;
+  /** Skill name it was installed as, `<name>-<n>` on a name clash */
+  name: string;
+  /** Installed skill directory */
+  path: string;
+  installed_at: number;
+  updated_at: number;
+}
+
+/** Skill directory moved to the lovcode trash by delete_skill */
+export type DeletedSkill = DeletedCommand;
+
 export interface LocalSkill {
   name: string;
   path: string;
   description: string | null;
   content: string;
+  frontmatter: string | null;
+  scope: CommandScope;
+}
+
+/** Skills of one scope from list_skills_by_scope */
+export interface ScopedSkills {
+  scope: CommandScope;
+  /** "Global", or the workspace project's name */
+  label: string;
+  skills: LocalSkill[];
+}
+
+/** A file or folder inside a skill directory */
+export interface SkillFile {
+  /** Relative to the skill directory, `/`-separated */
+  path: string;
+  is_dir: boolean;
+  size: number;
+  executable: boolean;
+  /** Start of text files; null for folders and binary files */
+  preview: string | null;
+}
+
+/** Result of get_skill_details */
+export interface SkillDetails {
+  skill: LocalSkill;
+  dir: string;
+  files: SkillFile[];
+}
+
+/** Commands, agents and skills from a project's own .claude dir */
+export interface ProjectAssets {
+  project_path: string;
+  commands: LocalCommand[];
+  agents: LocalAgent[];
+  skills: LocalSkill[];
 }
 
 export interface DistillDocument {
@@ -121,6 +722,15 @@ export interface DistillDocument {
   title: string;
   tags: string[];
   session: string | null;
+  /** Project of the linked session, absent if the session no longer exists */
+  project_id?: string;
+}
+
+export interface NotesImportSummary {
+  imported: DistillDocument[];
+  /** Source paths skipped because a note with the same title exists */
+  duplicates: string[];
+  failed: string[];
 }
 
 export interface McpServer {
@@ -146,6 +756,21 @@ export interface ContextFile {
   last_modified: number;
 }
 
+export interface ContextBackup {
+  /** Unix ms the backup was taken; identifies it for restore */
+  id: string;
+  path: string;
+  created_at: number;
+  size_bytes: number;
+}
+
+export interface SavedContextFile {
+  path: string;
+  last_modified: number;
+  /** Backup of the content that was replaced; null for a new file or no change */
+  backup: ContextBackup | null;
+}
+
 export interface TemplateComponent {
   name: string;
   path: string;
@@ -274,3 +899,438 @@ export interface ClaudeCodeVersionInfo {
   available_versions: VersionWithDownloads[];
   autoupdater_disabled: boolean;
 }
+
+/** Options for the backend `render_markdown` command */
+export interface MarkdownRenderOptions {
+  gfm?: boolean;
+  highlight?: boolean;
+  theme?: string;
+  hard_breaks?: boolean;
+  allow_html?: boolean;
+}
+
+/** Options for `export_session_html` / `export_session_pdf` */
+export interface HtmlExportOptions {
+  include_tools?: boolean;
+  include_meta?: boolean;
+  expand?: boolean;
+  title?: string;
+}
+
+/** export_session_share_page options; also the settings of the "share_page" export format */
+export interface SharePageOptions extends HtmlExportOptions {
+  /** Mask secrets before rendering; default on */
+  redact?: boolean;
+  /** Extra redaction regexes for this export */
+  patterns?: string[];
+  /** Inline images attached to messages; default on */
+  include_images?: boolean;
+}
+
+export interface SharePageResult {
+  output_path: string;
+  bytes: number;
+  messages: number;
+  /** null when redaction was turned off */
+  redaction: RedactionReport | null;
+}
+
+export interface SessionDiagram {
+  id: string;
+  kind: "mermaid" | "plantuml";
+  code: string;
+  title: string | null;
+  context: string;
+  prompt: string | null;
+  message_uuid: string;
+  line_number: number;
+  timestamp: string;
+}
+
+export interface ArchiveSummary {
+  path: string;
+  projects: number;
+  sessions: number;
+  entries: number;
+  bytes: number;
+}
+
+export interface DistillTemplate {
+  id: string;
+  name: string;
+  description: string | null;
+  tags: string[];
+  body: string;
+  builtin: boolean;
+}
+
+export interface TrashEntry {
+  id: string;
+  project_id: string;
+  session_id: string;
+  summary: string | null;
+  files: string[];
+  deleted_at: number;
+  expires_at: number;
+}
+
+export interface ArchivedFile {
+  /** Relative to the project dir */
+  path: string;
+  original_size: number;
+  compressed_size: number;
+}
+
+/** Session gzipped into ~/.lovstudio/lovcode/archive */
+export interface ArchivedSession {
+  project_id: string;
+  session_id: string;
+  summary: string | null;
+  message_count: number;
+  last_modified: number;
+  archived_at: number;
+  files: ArchivedFile[];
+}
+
+export interface ArchiveReport {
+  archived: ArchivedSession[];
+  /** "<project_id>/<session_id>: <error>" */
+  failed: string[];
+  original_bytes: number;
+  compressed_bytes: number;
+  reclaimed_bytes: number;
+}
+
+export interface ArchiveStats {
+  sessions: number;
+  original_bytes: number;
+  compressed_bytes: number;
+  reclaimed_bytes: number;
+}
+
+/** Sessions selected by `cleanup_sessions`; every criterion set must match */
+export interface CleanupPolicy {
+  project_id?: string;
+  older_than_days?: number;
+  /** No user/assistant message with text */
+  empty_only?: boolean;
+  min_size_bytes?: number;
+  action?: "trash" | "archive";
+  /** Only report what would be removed; default true */
+  dry_run?: boolean;
+}
+
+export interface CleanupCandidate {
+  project_id: string;
+  session_id: string;
+  summary: string | null;
+  size_bytes: number;
+  last_modified: number;
+  reasons: string[];
+}
+
+export interface CleanupReport {
+  dry_run: boolean;
+  action: "trash" | "archive";
+  sessions: CleanupCandidate[];
+  failed: string[];
+  total_bytes: number;
+}
+
+/** Project whose decoded path no longer exists */
+export interface OrphanedProject {
+  project_id: string;
+  /** The path that no longer exists */
+  decoded_path: string;
+  session_count: number;
+  size_bytes: number;
+  last_active: number;
+}
+
+export interface ArchiveHit {
+  project_id: string;
+  session_id: string;
+  summary: string | null;
+  line_number: number;
+  role: string;
+  snippet: string;
+}
+
+export interface DiffLine {
+  op: "equal" | "insert" | "delete";
+  text: string;
+}
+
+export interface SectionDiff {
+  heading: string;
+  level: number;
+  status: "added" | "removed" | "changed" | "unchanged";
+  lines: DiffLine[];
+}
+
+export interface DocumentDiff {
+  sections: SectionDiff[];
+  stats: { added: number; removed: number; changed: number; unchanged: number };
+}
+
+/** Line numbers are 1-based */
+export interface DiffHunk {
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: DiffLine[];
+}
+
+export interface UnifiedDiff {
+  /** diff -u text, empty when equal */
+  unified: string;
+  hunks: DiffHunk[];
+  insertions: number;
+  deletions: number;
+}
+
+/** One side of diff_sessions; start/end are message indices (end exclusive) */
+export interface SessionRange {
+  project_id: string;
+  session_id: string;
+  start?: number;
+  end?: number;
+}
+
+export interface SessionDiffSide {
+  session_id: string;
+  models: string[];
+  turns: number;
+  usage: SessionUsage;
+}
+
+export interface TurnDiff {
+  status: "identical" | "changed" | "only_a" | "only_b";
+  prompt: string;
+  index_a: number | null;
+  index_b: number | null;
+  lines: DiffLine[];
+  tools_a: string[];
+  tools_b: string[];
+}
+
+export interface SessionDiff {
+  a: SessionDiffSide;
+  b: SessionDiffSide;
+  turns: TurnDiff[];
+  stats: { identical: number; changed: number; only_a: number; only_b: number };
+}
+
+export type ExportTarget =
+  | { type: "session"; project_id: string; session_id: string }
+  | { type: "sessions"; project_id?: string; session_ids?: string[] }
+  | { type: "collection"; collection_id: string }
+  | { type: "knowledge_base" };
+
+export interface ExportOptions {
+  /** File to write, or directory for multi-file formats */
+  output_path: string;
+  /** Format-specific settings, e.g. include_tools for transcripts */
+  settings?: Record<string, unknown>;
+}
+
+export interface ExportFormatInfo {
+  id: string;
+  label: string;
+  /** null when the output is a directory */
+  extension: string | null;
+  targets: ExportTarget["type"][];
+}
+
+export interface ExportResult {
+  format: string;
+  path: string;
+  items: number;
+}
+
+export type KbExportFormat = "obsidian" | "mdbook";
+
+export interface KbExportSummary {
+  dest: string;
+  format: KbExportFormat;
+  documents: number;
+  sections: number;
+  /** Source files listed for export that no longer exist */
+  missing: string[];
+}
+
+export interface StartupPhase {
+  name: string;
+  /** Milliseconds since process start */
+  start_ms: number;
+  duration_ms: number;
+}
+
+export interface StartupProfile {
+  phases: StartupPhase[];
+  app_ready_ms: number | null;
+  /** False when deferred init was triggered by the fallback timer */
+  ready_signalled: boolean;
+  uptime_ms: number;
+}
+
+export interface MemoryBudget {
+  /** Process RSS above which idle caches are dropped */
+  total_mb: number;
+  scrollback_mb: number;
+  session_cache_mb: number;
+}
+
+export interface CacheUsage {
+  name: string;
+  bytes: number;
+  entries: number;
+  limit_bytes: number;
+  /** Evictions since launch */
+  evictions: number;
+}
+
+export interface MemoryReport {
+  rss_bytes: number | null;
+  budget: MemoryBudget;
+  caches: CacheUsage[];
+  over_budget: boolean;
+  /** Unix time of the last background check */
+  last_check: number | null;
+}
+
+export interface ResourceUsage {
+  /** null where descriptors can't be counted (Windows) */
+  open_fds: number | null;
+  soft_limit: number | null;
+  hard_limit: number | null;
+  ptys: number;
+  /** Live file watchers by kind */
+  watchers: Record<string, number>;
+}
+
+/** Payload of the "resource-limit-warning" event */
+export interface ResourceWarning {
+  usage: ResourceUsage;
+  message: string;
+}
+
+/** Chart data as a table for screen readers */
+export interface DataTable {
+  title: string;
+  columns: string[];
+  rows: string[][];
+}
+
+/** Target given at launch (`lovcode <path>`, `lovcode --session <id>`) or opened since */
+export type LaunchTarget =
+  | { type: "project"; workspace_project_id: string; path: string }
+  | { type: "session"; project_id: string; session_id: string; summary: string | null };
+
+/** Payload of "customizations-changed": files changed under ~/.claude commands/agents/skills */
+export interface CustomizationsChanged {
+  kinds: ("commands" | "agents" | "skills")[];
+  paths: string[];
+}
+
+/** Payload of "projects-changed": project dirs or their set of sessions changed */
+export interface ProjectsChanged {
+  project_ids: string[];
+}
+
+/** Payload of "session-updated", emitted per session file once writes settle */
+export interface SessionUpdated {
+  project_id: string;
+  session_id: string;
+  change: "created" | "modified" | "removed";
+}
+
+export interface CollectionEntry {
+  project_id: string;
+  session_id: string;
+  /** Why the session was picked */
+  note: string | null;
+  added_at: number;
+}
+
+/** Curated group of exemplary sessions */
+export interface Collection {
+  id: string;
+  name: string;
+  description: string | null;
+  entries: CollectionEntry[];
+  created_at: number;
+  updated_at: number;
+}
+
+export interface CollectionSummary {
+  id: string;
+  name: string;
+  description: string | null;
+  session_count: number;
+  updated_at: number;
+}
+
+export interface CollectionDetail extends Collection {
+  sessions: Session[];
+  /** Entries whose session file is gone */
+  missing: CollectionEntry[];
+}
+
+export type MaintenanceTrigger = "scheduled" | "manual";
+
+export interface MaintenanceTask {
+  /** "index" | "session_cache" | "stats" | "retention" | "backup" */
+  name: string;
+  ok: boolean;
+  /** What was done, or the error */
+  detail: string;
+  duration_ms: number;
+}
+
+/** A logged run of the nightly maintenance job (get_maintenance_history) */
+export interface MaintenanceRun {
+  started_at: number;
+  finished_at: number;
+  trigger: MaintenanceTrigger;
+  tasks: MaintenanceTask[];
+}
+
+/** Result of export_session_redacted */
+export interface RedactionReport {
+  output_path: string;
+  redactions: number;
+  /** Masked values per rule, e.g. "anthropic_key", "custom: <pattern>" */
+  by_rule: Record<string, number>;
+}
+
+export interface IndexHealth {
+  built: boolean;
+  /** Last commit to the global index (unix secs) */
+  updated_at: number | null;
+  partitions: number;
+  /** Session files modified after the last index update */
+  stale_sessions: number;
+}
+
+export interface DegradedSubsystem {
+  /** "watcher" | "index" | "memory" | "file_descriptors" | "maintenance" */
+  subsystem: string;
+  message: string;
+}
+
+/** Result of get_runtime_health */
+export interface RuntimeHealth {
+  status: "ok" | "degraded";
+  uptime_ms: number;
+  watchers: Record<string, number>;
+  index: IndexHealth;
+  caches: CacheUsage[];
+  rss_bytes: number | null;
+  ptys: number;
+  open_fds: number | null;
+  fd_limit: number | null;
+  last_maintenance: MaintenanceRun | null;
+  degraded: DegradedSubsystem[];
+}
@@ -0,0 +1,49 @@
+import { invoke, convertFileSrc } from "@tauri-apps/api/core";
+import { FileText, Paperclip } from "lucide-react";
+import { formatFileSize } from "./utils";
+import type { Attachment } from "../../types";
+
+interface AttachmentListProps {
+  attachments: Attachment[];
+}
+
+export function AttachmentList({ attachments }: AttachmentListProps) {
+  if (attachments.length === 0) return null;
+
+  const open = (path: string | null) => {
+    if (path) invoke("open_path", { path });
+  };
+
+  return (
+    <div className="mt-3 flex flex-wrap gap-2">
+      {attachments.map((attachment, i) =>
+        attachment.kind === "image" && attachment.exists && attachment.path ? (
+          <button
+            key={`${attachment.name}-${i}`}
+            onClick={() => open(attachment.path)}
+            className="rounded-lg border border-border overflow-hidden hover:opacity-90"
+            title={attachment.from_tool ? "Image from tool result" : attachment.name}
+          >
+            <img
+              src={convertFileSrc(attachment.path)}
+              alt={attachment.name}
+              className="max-h-48 max-w-xs object-contain bg-card-alt"
+            />
+          </button>
+        ) : (
+          <button
+            key={`${attachment.name}-${i}`}
+            onClick={() => open(attachment.path)}
+            disabled={!attachment.exists}
+            className="flex items-center gap-1.5 rounded-md border border-border px-2 py-1 text-xs text-muted-foreground hover:text-ink disabled:opacity-50 disabled:hover:text-muted-foreground"
+            title={attachment.exists ? attachment.path ?? undefined : "File not found"}
+          >
+            {attachment.kind === "file" ? <Paperclip size={12} /> : <FileText size={12} />}
+            <span className="font-mono">{attachment.name}</span>
+            {attachment.size_bytes != null && <span>· {formatFileSize(attachment.size_bytes)}</span>}
+          </button>
+        )
+      )}
+    </div>
+  );
+}
@@ -1,4 +1,4 @@
-import { useState, useEffect, useMemo } from "react";
+import { Fragment, useState, useEffect, useMemo } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { DotsHorizontalIcon, ExternalLinkIcon, DownloadIcon } from "@radix-ui/react-icons";
 import { FolderOpen, Copy, FileCode } from "lucide-react";
@@ -20,10 +20,12 @@ import {
 } from "../../components/ui/dropdown-menu";
 import { useAtom } from "jotai";
 import { originalChatAtom, markdownPreviewAtom } from "../../store";
+import { useAppConfig } from "../../context";
 import { CollapsibleContent } from "./CollapsibleContent";
 import { ExportDialog } from "./ExportDialog";
+import { AttachmentList } from "./AttachmentList";
 import { restoreSlashCommand } from "./utils";
-import type { Message } from "../../types";
+import type { Message, MessagesPage, SessionSegment } from "../../types";
 
 interface MessageViewProps {
   projectId: string;
@@ -40,15 +42,22 @@ export function MessageView({ projectId, sessionId, summary, onBack }: MessageVi
   const [selectedIds, setSelectedIds] = useState<Set<string>>(new Set());
   const [exportDialogOpen, setExportDialogOpen] = useState(false);
   const [sessionFilePath, setSessionFilePath] = useState("");
+  const [transcript, setTranscript] = useState<string | null>(null);
+  const [segments, setSegments] = useState<SessionSegment[]>([]);
+  const { accessibleMode } = useAppConfig();
 
   useEffect(() => {
-    invoke<Message[]>("get_session_messages", { projectId, sessionId })
-      .then(setMessages)
+    invoke<MessagesPage>("get_session_messages", { projectId, sessionId, accessible: accessibleMode })
+      .then((page) => {
+        setMessages(page.items);
+        setTranscript(page.transcript);
+        setSegments(page.segments);
+      })
       .finally(() => setLoading(false));
     invoke<string>("get_session_file_path", { projectId, sessionId })
       .then(setSessionFilePath)
       .catch(() => {});
-  }, [projectId, sessionId]);
+  }, [projectId, sessionId, accessibleMode]);
 
   const processContent = (content: string) => {
     return originalChat ? restoreSlashCommand(content) : content;
@@ -75,6 +84,18 @@ export function MessageView({ projectId, sessionId, summary, onBack }: MessageVi
     [messages, originalChat]
   );
 
+  // Compaction separators go before the first shown message after each boundary
+  const segmentStarts = useMemo(() => {
+    const starts = new Map<string, SessionSegment>();
+    for (const segment of segments) {
+      if (!segment.boundary) continue;
+      const line = segment.boundary.line_number;
+      const first = filteredMessages.find((m) => m.line_number >= line);
+      if (first && !starts.has(first.uuid)) starts.set(first.uuid, segment);
+    }
+    return starts;
+  }, [segments, filteredMessages]);
+
   if (loading) {
     return (
       <div className="flex items-center justify-center h-full">
@@ -165,36 +186,57 @@ export function MessageView({ projectId, sessionId, summary, onBack }: MessageVi
         </div>
       </header>
 
-      <div className="space-y-4">
+      {transcript && (
+        <section aria-label="Transcript" className="sr-only whitespace-pre-wrap">
+          {transcript}
+        </section>
+      )}
+
+      <div className="space-y-4" aria-hidden={transcript ? true : undefined}>
         {filteredMessages.map((msg) => {
           const displayContent = processContent(msg.content);
+          const segment = segmentStarts.get(msg.uuid);
           return (
-            <div
-              key={msg.uuid}
-              className={`group relative rounded-xl p-4 ${
-                msg.role === "user" ? "bg-card-alt" : "bg-card border border-border"
-              }`}
-            >
-              <DropdownMenu>
-                <DropdownMenuTrigger asChild>
-                  <button className="absolute top-3 right-3 p-1.5 rounded-md bg-card-alt/80 hover:bg-card-alt text-muted-foreground hover:text-ink transition-opacity opacity-0 group-hover:opacity-100">
-                    <DotsHorizontalIcon width={16} />
-                  </button>
-                </DropdownMenuTrigger>
-                <DropdownMenuContent align="end">
-                  <DropdownMenuItem onClick={() => handleCopyContent(displayContent)}>
-                    <Copy size={14} />
-                    Copy Content
-                  </DropdownMenuItem>
-                  <DropdownMenuItem onClick={() => handleCopyFileLine(msg.line_number)}>
-                    <FileCode size={14} />
-                    Copy file:line
-                  </DropdownMenuItem>
-                </DropdownMenuContent>
-              </DropdownMenu>
-              <p className="text-xs text-muted-foreground-foreground mb-2 uppercase tracking-wide">{msg.role}</p>
-              <CollapsibleContent content={displayContent} markdown={markdownPreview} />
-            </div>
+            <Fragment key={msg.uuid}>
+              {segment?.boundary && (
+                <div role="separator" className="flex items-center gap-3 py-2 text-xs text-muted-foreground">
+                  <div className="flex-1 border-t border-dashed border-border" />
+                  <span title={segment.boundary.summary ?? undefined}>
+                    Context compacted here
+                    {segment.boundary.trigger && ` (${segment.boundary.trigger})`}
+                    {segment.boundary.pre_tokens != null && ` · ${segment.boundary.pre_tokens.toLocaleString()} tokens before`}
+                    {` · next: ${segment.message_count} messages, ${segment.tool_calls} tool calls`}
+                  </span>
+                  <div className="flex-1 border-t border-dashed border-border" />
+                </div>
+              )}
+              <div
+                className={`group relative rounded-xl p-4 ${
+                  msg.role === "user" ? "bg-card-alt" : "bg-card border border-border"
+                }`}
+              >
+                <DropdownMenu>
+                  <DropdownMenuTrigger asChild>
+                    <button className="absolute top-3 right-3 p-1.5 rounded-md bg-card-alt/80 hover:bg-card-alt text-muted-foreground hover:text-ink transition-opacity opacity-0 group-hover:opacity-100">
+                      <DotsHorizontalIcon width={16} />
+                    </button>
+                  </DropdownMenuTrigger>
+                  <DropdownMenuContent align="end">
+                    <DropdownMenuItem onClick={() => handleCopyContent(displayContent)}>
+                      <Copy size={14} />
+                      Copy Content
+                    </DropdownMenuItem>
+                    <DropdownMenuItem onClick={() => handleCopyFileLine(msg.line_number)}>
+                      <FileCode size={14} />
+                      Copy file:line
+                    </DropdownMenuItem>
+                  </DropdownMenuContent>
+                </DropdownMenu>
+                <p className="text-xs text-muted-foreground-foreground mb-2 uppercase tracking-wide">{msg.role}</p>
+                <CollapsibleContent content={displayContent} markdown={markdownPreview} />
+                <AttachmentList attachments={msg.attachments ?? []} />
+              </div>
+            </Fragment>
           );
         })}
       </div>
@@ -6,7 +6,7 @@ import { useAtom } from "jotai";
 import { chatViewModeAtom, allProjectsSortByAtom, hideEmptySessionsAllAtom } from "../../store";
 import { useAppConfig } from "../../context";
 import { VirtualChatList } from "./VirtualChatList";
-import { formatRelativeTime } from "./utils";
+import { formatRelativeTime, formatFileSize } from "./utils";
 import { useInvokeQuery } from "../../hooks";
 import type { Project, Session, ChatMessage, SearchResult, ChatsResponse } from "../../types";
 
@@ -27,12 +27,21 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
 
   // Local state for pagination (chats loaded beyond initial fetch)
   const [extraChats, setExtraChats] = useState<ChatMessage[]>([]);
+  const [chatsCursor, setChatsCursor] = useState<string | null | undefined>(undefined);
   const [loadingMoreChats, setLoadingMoreChats] = useState(false);
 
   const allChats = chatsResponse ? [...chatsResponse.items, ...extraChats] : null;
   const totalChats = chatsResponse?.total ?? 0;
+  // undefined until a page beyond the first is loaded
+  const nextChatsCursor = chatsCursor === undefined ? chatsResponse?.next_cursor ?? null : chatsCursor;
   const CHATS_PAGE_SIZE = 50;
 
+  // A refetched first page starts a new cursor chain
+  useEffect(() => {
+    setExtraChats([]);
+    setChatsCursor(undefined);
+  }, [chatsResponse]);
+
   const [sortBy, setSortBy] = useAtom(allProjectsSortByAtom);
   const [hideEmptySessions, setHideEmptySessions] = useAtom(hideEmptySessionsAllAtom);
   const [searchQuery, setSearchQuery] = useState("");
@@ -43,18 +52,19 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
   const [indexBuilt, setIndexBuilt] = useState(false);
 
   const loadMoreChats = useCallback(async () => {
-    if (loadingMoreChats || !allChats || allChats.length >= totalChats) return;
+    if (loadingMoreChats || !nextChatsCursor) return;
     setLoadingMoreChats(true);
     try {
       const res = await invoke<ChatsResponse>("list_all_chats", {
         limit: CHATS_PAGE_SIZE,
-        offset: allChats.length,
+        cursor: nextChatsCursor,
       });
       setExtraChats((prev) => [...prev, ...res.items]);
+      setChatsCursor(res.next_cursor);
     } finally {
       setLoadingMoreChats(false);
     }
-  }, [allChats, totalChats, loadingMoreChats]);
+  }, [nextChatsCursor, loadingMoreChats]);
 
   const loading =
     viewMode === "projects" ? loadingProjects : viewMode === "sessions" ? loadingSessions : loadingChats;
@@ -108,6 +118,7 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
       case "sessions":
         return b.session_count - a.session_count;
       case "name":
+      case "size":
         return a.path.localeCompare(b.path);
     }
   });
@@ -123,7 +134,9 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
       case "sessions":
         return b.message_count - a.message_count;
       case "name":
-        return (a.summary || "").localeCompare(b.summary || "");
+        return (a.title || a.summary || "").localeCompare(b.title || b.summary || "");
+      case "size":
+        return b.size_bytes - a.size_bytes;
     }
   });
 
@@ -188,6 +201,7 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
                 ["recent", "Recent"],
                 ["sessions", viewMode === "projects" ? "Sessions" : "Messages"],
                 ["name", "Name"],
+                ...(viewMode === "sessions" ? [["size", "Size"] as const] : []),
               ] as const
             ).map(([key, label]) => (
               <button
@@ -270,12 +284,12 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
               onClick={() => onSelectSession(session)}
               className="w-full text-left bg-card rounded-xl p-4 border border-border hover:border-primary transition-colors"
             >
-              <p className="font-medium text-ink line-clamp-2">{session.summary || "Untitled session"}</p>
+              <p className="font-medium text-ink line-clamp-2">{session.title || session.summary || "Untitled session"}</p>
               <p className="text-sm text-muted-foreground mt-1 truncate">
                 {session.project_path ? formatPath(session.project_path) : session.project_id}
               </p>
               <p className="text-xs text-muted-foreground mt-1">
-                {session.message_count} messages · {formatRelativeTime(session.last_modified)}
+                {session.message_count} messages · {formatFileSize(session.size_bytes)} · ~{session.estimated_tokens.toLocaleString()} tokens · {formatRelativeTime(session.last_modified)}
               </p>
             </button>
           ))}
@@ -285,7 +299,7 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
           chats={searchResults !== null ? searchResults : allChats || []}
           onSelectChat={onSelectChat}
           formatPath={formatPath}
-          hasMore={searchResults === null && (allChats?.length || 0) < totalChats}
+          hasMore={searchResults === null && !!nextChatsCursor}
           loadMore={loadMoreChats}
           loadingMore={loadingMoreChats}
         />
@@ -9,7 +9,7 @@ import { sessionContextTabAtom, sessionSelectModeAtom, hideEmptySessionsAtom, us
 import { useAppConfig } from "../../context";
 import { formatDate } from "./utils";
 import { useInvokeQuery } from "../../hooks";
-import type { Session, ContextFile, Message, SearchResult } from "../../types";
+import type { Session, ContextFile, MessagesPage, SearchResult } from "../../types";
 
 interface SessionListProps {
   projectId: string;
@@ -108,7 +108,7 @@ generator: "Lovcode"
           .replace(/\s+/g, "-");
       const toc = selected
         .map((s, i) => {
-          const title = `Session ${i + 1}: ${s.summary || "Untitled"}`;
+          const title = `Session ${i + 1}: ${s.title || s.summary || "Untitled"}`;
           return `- [${title}](#${toAnchor(title)})`;
         })
         .join("\n");
@@ -116,8 +116,11 @@ generator: "Lovcode"
       const parts: string[] = [];
       for (let i = 0; i < selected.length; i++) {
         const session = selected[i];
-        const allMessages = await invoke<Message[]>("get_session_messages", { projectId, sessionId: session.id });
-        const messages = userPromptsOnly ? allMessages.filter((m) => m.role === "user") : allMessages;
+        const { items: messages } = await invoke<MessagesPage>("get_session_messages", {
+          projectId,
+          sessionId: session.id,
+          filter: userPromptsOnly ? { roles: ["user"] } : undefined,
+        });
         const sessionMd = messages
           .map((m) => {
             const role = m.role.charAt(0).toUpperCase() + m.role.slice(1);
@@ -126,7 +129,7 @@ generator: "Lovcode"
           .join("\n\n---\n\n");
         const msgCountLabel = userPromptsOnly ? `${messages.length} prompts` : `${session.message_count} messages`;
         const meta = `_${msgCountLabel} · ${formatDate(session.last_modified)}_`;
-        parts.push(`## Session ${i + 1}: ${session.summary || "Untitled"}\n\n${meta}\n\n${sessionMd}`);
+        parts.push(`## Session ${i + 1}: ${session.title || session.summary || "Untitled"}\n\n${meta}\n\n${sessionMd}`);
       }
       const body = parts.join("\n\n<br>\n\n---\n\n<br>\n\n");
       const header = `# ${projectName}
@@ -315,7 +318,7 @@ generator: "Lovcode"
                 >
                   <div className="flex items-start justify-between gap-2">
                     <div className="flex-1 min-w-0">
-                      <p className="font-medium text-ink line-clamp-2">{session.summary || "Untitled session"}</p>
+                      <p className="font-medium text-ink line-clamp-2">{session.title || session.summary || "Untitled session"}</p>
                       <p className="text-sm text-muted-foreground mt-2">
                         {session.message_count} messages · {formatDate(session.last_modified)}
                       </p>
@@ -5,5 +5,6 @@ export { ExportDialog } from "./ExportDialog";
 export { MessageView } from "./MessageView";
 export { CollapsibleContent } from "./CollapsibleContent";
 export { CopyButton } from "./CopyButton";
+export { AttachmentList } from "./AttachmentList";
 export { restoreSlashCommand, formatRelativeTime, formatDate } from "./utils";
 export type { SortKey, ChatViewMode, ExportFormat, MarkdownStyle } from "./types";
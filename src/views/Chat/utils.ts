@@ -16,6 +16,12 @@ export function formatRelativeTime(ts: number): string {
   return new Date(ts * 1000).toLocaleDateString();
 }
 
+export function formatFileSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 export function formatDate(ts: number): string {
   return new Date(ts * 1000).toLocaleString();
 }
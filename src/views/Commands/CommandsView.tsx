@@ -89,14 +89,8 @@ export function CommandsView({
   const [activeDragId, setActiveDragId] = useState<string | null>(null);
   const { search, setSearch, filtered } = useSearch(commands, ["name", "description"]);
 
-  const getUsageCount = (cmd: LocalCommand) => {
-    const mainCount = commandStats[cmd.name.slice(1)] || 0;
-    const aliasCount = cmd.aliases.reduce((sum, alias) => {
-      const key = alias.startsWith("/") ? alias.slice(1) : alias;
-      return sum + (commandStats[key] || 0);
-    }, 0);
-    return mainCount + aliasCount;
-  };
+  // Uses under previous names (aliases) are already counted by the backend
+  const getUsageCount = (cmd: LocalCommand) => commandStats[cmd.name.slice(1)] || 0;
 
   const refreshCommands = () => {
     queryClient.invalidateQueries({ queryKey: ["commands"] });
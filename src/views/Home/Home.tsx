@@ -1,8 +1,9 @@
 import { useMemo } from "react";
-import type { FeatureType, Project, Session, LocalCommand } from "../../types";
+import type { FeatureType, Project, Session, LocalCommand, DataTable } from "../../types";
 import { FEATURES, FEATURE_ICONS } from "../../constants";
 import { ActivityHeatmap, RecentActivity, QuickActions } from "../../components/home";
 import { useInvokeQuery } from "../../hooks";
+import { useAppConfig } from "../../context";
 
 interface HomeProps {
   onFeatureClick: (feature: FeatureType) => void;
@@ -15,13 +16,19 @@ interface ActivityStats {
   daily: Record<string, number>;
   hourly: Record<string, number>;
   detailed: Record<string, number>;
+  tables: DataTable[] | null;
 }
 
 export function Home({ onFeatureClick, onProjectClick, onSessionClick, onSearch }: HomeProps) {
   const { data: projects } = useInvokeQuery<Project[]>(["projects"], "list_projects");
   const { data: sessions } = useInvokeQuery<Session[]>(["sessions"], "list_all_sessions");
   const { data: commands } = useInvokeQuery<LocalCommand[]>(["commands"], "list_local_commands");
-  const { data: activityStats } = useInvokeQuery<ActivityStats>(["activityStats"], "get_activity_stats");
+  const { accessibleMode } = useAppConfig();
+  const { data: activityStats } = useInvokeQuery<ActivityStats>(
+    ["activityStats", accessibleMode],
+    "get_activity_stats",
+    { accessible: accessibleMode },
+  );
 
   const data = projects && sessions && commands ? { projects, sessions, commands } : null;
 
@@ -77,6 +84,23 @@ export function Home({ onFeatureClick, onProjectClick, onSessionClick, onSearch
                 detailed={activityStats.detailed}
               />
             )}
+            {activityStats?.tables?.map((table) => (
+              <table key={table.title} className="sr-only">
+                <caption>{table.title}</caption>
+                <thead>
+                  <tr>
+                    {table.columns.map((col) => <th key={col} scope="col">{col}</th>)}
+                  </tr>
+                </thead>
+                <tbody>
+                  {table.rows.map((row) => (
+                    <tr key={row[0]}>
+                      {row.map((cell, i) => <td key={i}>{cell}</td>)}
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            ))}
             {/* Inline Stats */}
             {stats && (
               <div className="flex items-center gap-6 mt-4 pt-4 border-t border-border/40 text-sm text-muted-foreground">
@@ -38,7 +38,7 @@ export function DistillDetailView({
       sessionId: document.session,
     });
     if (session) {
-      onNavigateSession(session.project_id, session.id, session.summary);
+      onNavigateSession(session.project_id, session.id, session.title ?? session.summary);
     }
   };
 
@@ -17,6 +17,8 @@ interface ReferenceDoc {
   name: string;
   path: string;
   group: string | null;
+  /** Unix seconds of the last upstream fetch, if tracked */
+  fetched_at: number | null;
 }
 
 function ReferenceDocTree({
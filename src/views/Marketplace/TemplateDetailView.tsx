@@ -2,7 +2,7 @@ import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import Markdown from "react-markdown";
 import { StarFilledIcon, HeartFilledIcon, GlobeIcon } from "@radix-ui/react-icons";
-import type { TemplateComponent, TemplateCategory } from "../../types";
+import type { TemplateComponent, TemplateCategory, AgentInstall, SkillInstall } from "../../types";
 import { TEMPLATE_CATEGORIES } from "../../constants";
 import { DetailCard, ConfigPage } from "../../components/config";
 import { CodePreview } from "../../components/shared";
@@ -36,22 +36,42 @@ export function TemplateDetailView({
   const [installing, setInstalling] = useState(false);
   const [uninstalling, setUninstalling] = useState(false);
   const [installed, setInstalled] = useState(false);
+  const [skillInstall, setSkillInstall] = useState<SkillInstall | null>(null);
   const [error, setError] = useState<string | null>(null);
+  // Skills from plugin directories install with their scripts and references
+  const isSkillDir = category === "skills" && !!template.source_id && template.path.endsWith("SKILL.md");
 
   useEffect(() => {
     if (category === "mcps") {
       invoke<boolean>("check_mcp_installed", { name: template.name }).then(setInstalled);
+    } else if (category === "agents") {
+      invoke<AgentInstall[]>("list_agent_installs").then((installs) =>
+        setInstalled(installs.some((i) => i.template === template.name && i.scope.type === "global"))
+      );
+    } else if (isSkillDir) {
+      invoke<SkillInstall[]>("list_skill_installs").then((installs) => {
+        const install = installs.find((i) => i.source_id === template.source_id && i.source_path === template.path);
+        setSkillInstall(install ?? null);
+        setInstalled(!!install);
+      });
     }
-  }, [category, template.name]);
+  }, [category, template.name, template.path, template.source_id, isSkillDir]);
 
   const handleUninstall = async () => {
-    if (category !== "mcps") return;
+    if (category !== "mcps" && category !== "agents" && !skillInstall) return;
 
     setUninstalling(true);
     setError(null);
 
     try {
-      await invoke("uninstall_mcp_template", { name: template.name });
+      if (skillInstall) {
+        await invoke("delete_skill", { name: skillInstall.name });
+        setSkillInstall(null);
+      } else if (category === "agents") {
+        await invoke("uninstall_agent_template", { name: template.name });
+      } else {
+        await invoke("uninstall_mcp_template", { name: template.name });
+      }
       setInstalled(false);
     } catch (e) {
       setError(String(e));
@@ -70,15 +90,24 @@ export function TemplateDetailView({
     setError(null);
 
     try {
+      if (isSkillDir) {
+        setSkillInstall(
+          await invoke<SkillInstall>("install_skill_template", { sourceId: template.source_id, path: template.path })
+        );
+        setInstalled(true);
+        return;
+      }
       switch (category) {
         case "commands":
-        case "agents":
         case "skills":
           await invoke("install_command_template", {
             name: template.name,
             content: template.content,
           });
           break;
+        case "agents":
+          await invoke("install_agent_template", { name: template.name, content: template.content });
+          break;
         case "mcps":
           await invoke("install_mcp_template", { name: template.name, config: template.content });
           break;
@@ -165,7 +194,7 @@ export function TemplateDetailView({
                 View
               </button>
             )}
-            {installed && category === "mcps" ? (
+            {installed && (category === "mcps" || category === "agents" || skillInstall) ? (
               <button
                 onClick={handleUninstall}
                 disabled={uninstalling}
@@ -1,13 +1,27 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { LocalSkill } from "../../types";
-import { DetailHeader, ContentCard, ConfigPage } from "../../components/config";
+import type { LocalSkill, SkillDetails } from "../../types";
+import { DetailHeader, DetailCard, ContentCard, ConfigPage } from "../../components/config";
+import { useInvokeQuery } from "../../hooks";
 
 interface SkillDetailViewProps {
   skill: LocalSkill;
   onBack: () => void;
 }
 
+function formatSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 export function SkillDetailView({ skill, onBack }: SkillDetailViewProps) {
+  const { data: details } = useInvokeQuery<SkillDetails>(["skill-details", skill.path], "get_skill_details", {
+    name: skill.name,
+    scope: skill.scope,
+  });
+  // SKILL.md is shown as the content below
+  const files = details?.files.filter((f) => f.path !== "SKILL.md") ?? [];
+
   return (
     <ConfigPage>
       <DetailHeader
@@ -19,6 +33,30 @@ export function SkillDetailView({ skill, onBack }: SkillDetailViewProps) {
         onOpenPath={(p) => invoke("open_in_editor", { path: p })}
       />
       <div className="space-y-4">
+        {details && files.length > 0 && (
+          <DetailCard label={`Files (${files.filter((f) => !f.is_dir).length})`}>
+            <ul className="mt-2 space-y-1">
+              {files.map((file) => (
+                <li key={file.path}>
+                  <button
+                    onClick={() => invoke("open_in_editor", { path: `${details.dir}/${file.path}` })}
+                    className="w-full flex items-center justify-between gap-3 px-2 py-1 text-sm rounded hover:bg-card-alt text-left"
+                    title={file.preview ?? undefined}
+                  >
+                    <span className="font-mono text-ink truncate">
+                      {file.path}
+                      {file.is_dir ? "/" : ""}
+                      {file.executable && <span className="ml-2 text-xs text-muted-foreground">exec</span>}
+                    </span>
+                    {!file.is_dir && (
+                      <span className="text-xs text-muted-foreground shrink-0">{formatSize(file.size)}</span>
+                    )}
+                  </button>
+                </li>
+              ))}
+            </ul>
+          </DetailCard>
+        )}
         <ContentCard label="Content" content={skill.content} />
       </div>
     </ConfigPage>
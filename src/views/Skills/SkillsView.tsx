@@ -1,6 +1,7 @@
+import { useMemo } from "react";
 import { TargetIcon } from "@radix-ui/react-icons";
 import { Store } from "lucide-react";
-import type { LocalSkill } from "../../types";
+import type { LocalSkill, ScopedSkills } from "../../types";
 import {
   LoadingState,
   EmptyState,
@@ -41,8 +42,18 @@ export function SkillsView({
   onMarketplaceSelect,
   onBrowseMore,
 }: SkillsViewProps) {
-  const { data: skills = [], isLoading } = useInvokeQuery<LocalSkill[]>(["skills"], "list_local_skills");
-  const { search, setSearch, filtered } = useSearch(skills, ["name", "description"]);
+  const { data: groups = [], isLoading } = useInvokeQuery<ScopedSkills[]>(
+    ["skills", "by-scope"],
+    "list_skills_by_scope"
+  );
+  const skills = useMemo(
+    () =>
+      groups.flatMap((g, index) =>
+        g.skills.map((skill) => ({ ...skill, scope_index: index, scope_label: g.label }))
+      ),
+    [groups]
+  );
+  const { search, setSearch, filtered } = useSearch(skills, ["name", "description", "scope_label"]);
 
   if (isLoading) return <LoadingState message="Loading skills..." />;
 
@@ -50,7 +61,7 @@ export function SkillsView({
     <ConfigPage>
       <PageHeader
         title="Skills"
-        subtitle={`${skills.length} skills in ~/.claude/skills`}
+        subtitle={`${skills.length} skills in ~/.claude and workspace projects`}
         action={<BrowseMarketplaceButton onClick={onBrowseMore} />}
       />
       <SearchInput
@@ -59,24 +70,31 @@ export function SkillsView({
         onChange={setSearch}
       />
 
-      {filtered.length > 0 && (
-        <div className="space-y-2">
-          {filtered.map((skill) => (
-            <ItemCard
-              key={skill.name}
-              name={skill.name}
-              description={skill.description}
-              onClick={() => onSelect(skill)}
-            />
-          ))}
-        </div>
-      )}
+      {groups.map((group, index) => {
+        const scoped = filtered.filter((skill) => skill.scope_index === index);
+        if (scoped.length === 0) return null;
+        return (
+          <div key={index} className="space-y-2">
+            {groups.length > 1 && (
+              <h3 className="text-xs font-medium uppercase tracking-wide text-muted-foreground">{group.label}</h3>
+            )}
+            {scoped.map((skill) => (
+              <ItemCard
+                key={skill.path}
+                name={skill.name}
+                description={skill.description}
+                onClick={() => onSelect(skill)}
+              />
+            ))}
+          </div>
+        );
+      })}
 
       {filtered.length === 0 && !search && (
         <EmptyState
           icon={TargetIcon}
           message="No skills found"
-          hint="Skills are stored as SKILL.md in ~/.claude/skills/"
+          hint="Skills are stored as SKILL.md in .claude/skills, globally or in a project"
         />
       )}
 
@@ -1,6 +1,7 @@
+import { useMemo } from "react";
 import { PersonIcon } from "@radix-ui/react-icons";
 import { Store } from "lucide-react";
-import type { LocalAgent } from "../../types";
+import type { LocalAgent, ScopedAgents } from "../../types";
 import {
   LoadingState,
   EmptyState,
@@ -41,8 +42,18 @@ export function SubAgentsView({
   onMarketplaceSelect,
   onBrowseMore,
 }: SubAgentsViewProps) {
-  const { data: agents = [], isLoading } = useInvokeQuery<LocalAgent[]>(["agents"], "list_local_agents");
-  const { search, setSearch, filtered } = useSearch(agents, ["name", "description", "model"]);
+  const { data: groups = [], isLoading } = useInvokeQuery<ScopedAgents[]>(
+    ["agents", "by-scope"],
+    "list_agents_by_scope"
+  );
+  const agents = useMemo(
+    () =>
+      groups.flatMap((g, index) =>
+        g.agents.map((agent) => ({ ...agent, scope_index: index, scope_label: g.label }))
+      ),
+    [groups]
+  );
+  const { search, setSearch, filtered } = useSearch(agents, ["name", "description", "model", "scope_label"]);
 
   if (isLoading) return <LoadingState message="Loading sub-agents..." />;
 
@@ -50,7 +61,7 @@ export function SubAgentsView({
     <ConfigPage>
       <PageHeader
         title="Sub Agents"
-        subtitle={`${agents.length} sub-agents in ~/.claude/commands`}
+        subtitle={`${agents.length} sub-agents in ~/.claude and workspace projects`}
         action={<BrowseMarketplaceButton onClick={onBrowseMore} />}
       />
       <SearchInput
@@ -59,25 +70,32 @@ export function SubAgentsView({
         onChange={setSearch}
       />
 
-      {filtered.length > 0 && (
-        <div className="space-y-2">
-          {filtered.map((agent) => (
-            <ItemCard
-              key={agent.name}
-              name={agent.name}
-              description={agent.description}
-              badge={agent.model}
-              onClick={() => onSelect(agent)}
-            />
-          ))}
-        </div>
-      )}
+      {groups.map((group, index) => {
+        const scoped = filtered.filter((agent) => agent.scope_index === index);
+        if (scoped.length === 0) return null;
+        return (
+          <div key={index} className="space-y-2">
+            {groups.length > 1 && (
+              <h3 className="text-xs font-medium uppercase tracking-wide text-muted-foreground">{group.label}</h3>
+            )}
+            {scoped.map((agent) => (
+              <ItemCard
+                key={agent.path}
+                name={agent.name}
+                description={agent.description}
+                badge={agent.model}
+                onClick={() => onSelect(agent)}
+              />
+            ))}
+          </div>
+        );
+      })}
 
       {filtered.length === 0 && !search && (
         <EmptyState
           icon={PersonIcon}
           message="No sub-agents found"
-          hint="Sub-agents are commands with a model field in frontmatter"
+          hint="Sub-agents live in .claude/agents, globally or in a project"
         />
       )}
 
@@ -939,6 +939,7 @@ export function WorkspaceView() {
                 >
                   <PanelGrid
                     panels={featurePanels}
+                    featureId={feature.id}
                     layout={feature.layout}
                     activePanelId={activePanelId}
                     onPanelFocus={setActivePanelId}
@@ -42,6 +42,10 @@ export interface Feature {
   layout_direction?: "horizontal" | "vertical";
   /** Tree-based layout for tmux-style splits */
   layout?: LayoutNode;
+  /** Env var overrides for PTYs of this feature (secrets arrive masked) */
+  env?: Record<string, string>;
+  /** Ids of features in the same project that must be completed first */
+  blocked_by?: string[];
   created_at: number;
 }
 
@@ -59,15 +63,41 @@ export interface WorkspaceProject {
   active_feature_id?: string;
   feature_counter?: number;
   view_mode?: ProjectViewMode;
+  /** Group (folder) this project belongs to */
+  group_id?: string;
+  /** Custom fields, e.g. client name, priority, due date */
+  metadata?: Record<string, MetadataValue>;
   created_at: number;
 }
 
+/** Project group (folder) for organizing the sidebar */
+export interface ProjectGroup {
+  id: string;
+  name: string;
+  color?: string;
+  collapsed?: boolean;
+  created_at: number;
+}
+
+/** Aggregate stats for a group (group_id null = ungrouped) */
+export interface GroupStats {
+  group_id: string | null;
+  name: string;
+  project_count: number;
+  feature_count: number;
+  pending: number;
+  running: number;
+  completed: number;
+  needs_review: number;
+}
+
 /** Complete workspace data */
 export interface WorkspaceData {
   projects: WorkspaceProject[];
   active_project_id?: string;
   /** Global feature counter across all projects */
   feature_counter?: number;
+  groups?: ProjectGroup[];
 }
 
 // ============================================================================
@@ -124,3 +154,121 @@ export interface FileLineCount {
   file: string;
   lines: number;
 }
+
+/** Listening port opened by a process inside a feature's PTY */
+export interface ListeningPort {
+  port: number;
+  pid: number;
+  process_name: string;
+  pty_id: string;
+  url: string;
+}
+
+/** Result of resume_session_in_terminal and test_agent */
+export interface ResumedTerminal {
+  pty_id: string;
+  cwd: string;
+  /** Set when attached to a feature */
+  panel: PanelState | null;
+}
+
+/** Saved copy of workspace.json (list_workspace_snapshots) */
+export interface WorkspaceSnapshotInfo {
+  /** Unix seconds; also the snapshot id */
+  timestamp: number;
+  size: number;
+  project_count: number;
+  feature_count: number;
+}
+
+export interface SnapshotFeatureRef {
+  project_id: string;
+  project_name: string;
+  feature_id: string;
+  name: string;
+}
+
+/** What restore_workspace_snapshot would change */
+export interface WorkspaceSnapshotDiff {
+  timestamp: number;
+  projects_restored: string[];
+  projects_dropped: string[];
+  features_restored: SnapshotFeatureRef[];
+  features_dropped: SnapshotFeatureRef[];
+  features_changed: { feature: SnapshotFeatureRef; changes: string[] }[];
+}
+
+export interface FeatureNode {
+  id: string;
+  seq: number;
+  name: string;
+  status: FeatureStatus;
+  archived: boolean;
+  blocked_by: string[];
+  blocks: string[];
+  /** Blockers neither completed nor archived */
+  open_blockers: string[];
+}
+
+/** Result of get_feature_graph */
+export interface FeatureGraph {
+  project_id: string;
+  nodes: FeatureNode[];
+  /** Unfinished features that can start now */
+  unblocked: string[];
+}
+
+export type TimerMode = "stopwatch" | "pomodoro";
+
+/** Feature timer state; also the payload of "timer-tick" (array) and "timer-finished" */
+export interface TimerStatus {
+  project_id: string;
+  feature_id: string;
+  mode: TimerMode;
+  running: boolean;
+  elapsed_secs: number;
+  /** Pomodoro time left */
+  remaining_secs: number | null;
+}
+
+export interface FeatureTime {
+  project_id: string;
+  feature_id: string;
+  /** null when the feature was deleted */
+  feature_name: string | null;
+  seconds: number;
+  intervals: number;
+  pomodoros_completed: number;
+}
+
+/** Result of get_time_report */
+export interface TimeReport {
+  since: number | null;
+  until: number | null;
+  total_secs: number;
+  features: FeatureTime[];
+}
+
+/** Typed value of a custom project field; dates are YYYY-MM-DD */
+export type MetadataValue =
+  | { type: "text"; value: string }
+  | { type: "number"; value: number }
+  | { type: "date"; value: string }
+  | { type: "bool"; value: boolean };
+
+export type MetadataOp = "exists" | "missing" | "eq" | "ne" | "lt" | "gt" | "contains";
+
+export interface MetadataFilter {
+  key: string;
+  op: MetadataOp;
+  /** Unused by exists/missing */
+  value?: MetadataValue;
+}
+
+/** Optional argument of workspace_list_projects; all filters must match */
+export interface ProjectQuery {
+  filters?: MetadataFilter[];
+  /** Metadata key to sort by; projects without it come last */
+  sort_by?: string;
+  descending?: boolean;
+}